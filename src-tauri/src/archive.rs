@@ -0,0 +1,778 @@
+//! アーカイブ形式の抽象化
+//!
+//! エクスポート・インポートで、ZIP・tar.gz・ローカルフォルダーを同じ方法で読み書きします。
+//! 読み込みは展開前の検証に使うエントリの一覧と、エントリを順に読み込むカーソルを提供します。
+//! 書き込みは出力先と同じディレクトリの一時ファイル（フォルダーの場合は一時フォルダー）に書き込み、
+//! 完了時に出力先へ移動します。完了せずに破棄した場合は一時ファイルを削除し、出力先は変更しません。
+
+use crate::archive_safety;
+use crate::config::ArchiveImportLimits;
+use crate::types::ArchiveFormat;
+use crate::utils::{content_version_token, is_excluded_zip_entry, temp_path_for};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tar::{Archive as TarArchive, Builder as TarBuilder, EntryType, Header as TarHeader};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// フォルダーから読み込む際に辿らないディレクトリ
+const SKIPPED_DIRECTORIES: &[&str] = &[".git"];
+
+/// tar.gzの読み込み
+type TarGzArchive = TarArchive<GzDecoder<BufReader<File>>>;
+
+/// エントリの内容の読み込み
+pub type EntryReader<'a> = Box<dyn Read + 'a>;
+
+/// アーカイブ内のエントリ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryMeta {
+    /// アーカイブ内のパス（`/` 区切り）
+    pub name: String,
+    /// 展開後のサイズ（バイト、ヘッダーに記録された値）
+    pub size: u64,
+    /// 圧縮後のサイズ（エントリごとに圧縮する形式のみ）
+    pub compressed_size: Option<u64>,
+    /// ディレクトリか
+    pub is_dir: bool,
+    /// シンボリックリンク・ハードリンク・デバイスファイルなど、通常のファイル以外か
+    pub is_special: bool,
+}
+
+/// パスから形式を判定
+///
+/// # Returns
+///
+/// 既存のフォルダーの場合はフォルダー、それ以外は拡張子（`.zip`・`.tar.gz`・`.tgz`）で判定した形式
+pub fn detect_format(path: &Path) -> Option<ArchiveFormat> {
+    if path.is_dir() {
+        return Some(ArchiveFormat::Directory);
+    }
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else if name.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else {
+        None
+    }
+}
+
+/// ZIPファイルのエントリの一覧（内容は展開しない）
+///
+/// # Errors
+///
+/// 中央ディレクトリを読み込めない場合はエラーを返します。
+pub fn zip_entries<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<Vec<EntryMeta>, String> {
+    (0..archive.len())
+        .map(|i| {
+            let file = archive
+                .by_index_raw(i)
+                .map_err(|e| format!("Failed to read ZIP entry: {e}"))?;
+            Ok(EntryMeta {
+                name: file.name().to_string(),
+                size: file.size(),
+                compressed_size: Some(file.compressed_size()),
+                is_dir: file.is_dir(),
+                is_special: file.is_symlink(),
+            })
+        })
+        .collect()
+}
+
+/// tarのエントリをエントリの情報に変換
+///
+/// パス名はpax拡張ヘッダー・GNUの長いパス名を反映した、正規化前の値です（検証で `..` などを拒否する）。
+/// `tar -C dir .` で作成したアーカイブの `./` の接頭辞は取り除きます。
+/// ルートディレクトリ（`./`）自体は `None` を返します。
+fn tar_entry<R: Read>(entry: &tar::Entry<'_, R>) -> Option<EntryMeta> {
+    let path = entry.path_bytes();
+    let full_name = String::from_utf8_lossy(&path);
+    let mut name = full_name.as_ref();
+    while let Some(rest) = name.strip_prefix("./") {
+        name = rest;
+    }
+    let entry_type = entry.header().entry_type();
+    let is_dir = entry_type == EntryType::Directory;
+    if is_dir && (name.is_empty() || name == ".") {
+        return None;
+    }
+    Some(EntryMeta {
+        name: name.to_string(),
+        size: if is_dir { 0 } else { entry.size() },
+        compressed_size: None,
+        is_dir,
+        is_special: !is_dir && !entry_type.is_file(),
+    })
+}
+
+/// tar.gzファイルを開く
+fn open_tar_gz(path: &Path) -> Result<TarGzArchive, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {e}"))?;
+    Ok(TarArchive::new(GzDecoder::new(BufReader::new(file))))
+}
+
+/// フォルダー内のエントリの一覧（名前順）
+///
+/// `.git` と、除外ディレクトリ・除外globに一致するディレクトリの中は辿りません。
+/// `limit` を超えた時点で列挙を止めます（検証でエントリ数の上限超過として拒否される）。
+fn directory_entries(root: &Path, limit: Option<usize>) -> Result<Vec<EntryMeta>, String> {
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(root)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            if !e.file_type().is_dir() {
+                return true;
+            }
+            if SKIPPED_DIRECTORIES.contains(&e.file_name().to_string_lossy().as_ref()) {
+                return false;
+            }
+            let relative = e.path().strip_prefix(root).unwrap_or(e.path());
+            !is_excluded_zip_entry(&relative.to_string_lossy())
+        })
+    {
+        let entry = entry.map_err(|e| format!("Failed to read folder: {e}"))?;
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let file_type = entry.file_type();
+        let size = if file_type.is_file() {
+            entry.metadata().map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        entries.push(EntryMeta {
+            name,
+            size,
+            compressed_size: None,
+            is_dir: file_type.is_dir(),
+            is_special: !file_type.is_file() && !file_type.is_dir(),
+        });
+        if limit.is_some_and(|limit| entries.len() > limit) {
+            break;
+        }
+    }
+    Ok(entries)
+}
+
+/// インポート元のアーカイブ（ZIP・tar.gz・フォルダー）
+pub enum ArchiveReader {
+    /// ZIPファイル
+    Zip(ZipArchive<File>),
+    /// tar.gzファイル（読み込むたびに先頭から展開する）
+    TarGz {
+        path: PathBuf,
+        /// 読み込み中のアーカイブ（カーソルが借用する）
+        archive: Option<Box<TarGzArchive>>,
+    },
+    /// ローカルフォルダー
+    Directory(PathBuf),
+}
+
+impl ArchiveReader {
+    /// アーカイブを開く
+    ///
+    /// フォルダーの場合はフォルダー、拡張子が `.tar.gz`・`.tgz` の場合はtar.gz、
+    /// それ以外はZIPファイルとして開きます。
+    ///
+    /// # Errors
+    ///
+    /// 開けない場合、ZIPファイルとして読み込めない場合はエラーを返します。
+    pub fn open(path: &str) -> Result<Self, String> {
+        let path = Path::new(path);
+        match detect_format(path).unwrap_or(ArchiveFormat::Zip) {
+            ArchiveFormat::Directory => Ok(Self::Directory(path.to_path_buf())),
+            ArchiveFormat::TarGz => {
+                File::open(path).map_err(|e| format!("Failed to open archive: {e}"))?;
+                Ok(Self::TarGz {
+                    path: path.to_path_buf(),
+                    archive: None,
+                })
+            }
+            ArchiveFormat::Zip => {
+                let file = File::open(path).map_err(|e| format!("Failed to open ZIP file: {e}"))?;
+                let archive =
+                    ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP file: {e}"))?;
+                Ok(Self::Zip(archive))
+            }
+        }
+    }
+
+    /// 形式
+    pub fn format(&self) -> ArchiveFormat {
+        match self {
+            Self::Zip(_) => ArchiveFormat::Zip,
+            Self::TarGz { .. } => ArchiveFormat::TarGz,
+            Self::Directory(_) => ArchiveFormat::Directory,
+        }
+    }
+
+    /// エントリの一覧（内容は展開しない）
+    ///
+    /// tar.gzとフォルダーは、エントリ数または合計サイズが上限を超えた時点で列挙を止めます
+    /// （`validate` で上限超過として拒否される）。
+    ///
+    /// # Errors
+    ///
+    /// アーカイブを読み込めない場合はエラーを返します。
+    pub fn list(&mut self, limits: &ArchiveImportLimits) -> Result<Vec<EntryMeta>, String> {
+        match self {
+            Self::Zip(archive) => zip_entries(archive),
+            Self::TarGz { path, .. } => {
+                let mut archive = open_tar_gz(path)?;
+                let mut entries = Vec::new();
+                let mut total: u64 = 0;
+                for entry in archive
+                    .entries()
+                    .map_err(|e| format!("Failed to read archive: {e}"))?
+                {
+                    let entry = entry.map_err(|e| format!("Failed to read archive: {e}"))?;
+                    total = total.saturating_add(entry.size());
+                    entries.extend(tar_entry(&entry));
+                    // 上限を超えるデータは展開しない
+                    if entries.len() > limits.max_entries as usize || total > limits.max_total_bytes
+                    {
+                        break;
+                    }
+                }
+                Ok(entries)
+            }
+            Self::Directory(root) => directory_entries(root, Some(limits.max_entries as usize)),
+        }
+    }
+
+    /// すべてのエントリを展開前に検証
+    ///
+    /// # Returns
+    ///
+    /// エントリの一覧
+    ///
+    /// # Errors
+    ///
+    /// 読み込めない場合、上限を超える・安全でないエントリを含む場合はエラーを返します。
+    pub fn validate(&mut self, limits: &ArchiveImportLimits) -> Result<Vec<EntryMeta>, String> {
+        let entries = self.list(limits)?;
+        let archive_bytes = match self {
+            Self::TarGz { path, .. } => fs::metadata(path).ok().map(|m| m.len()),
+            Self::Zip(_) | Self::Directory(_) => None,
+        };
+        archive_safety::validate_entries(&entries, limits, archive_bytes)?;
+        Ok(entries)
+    }
+
+    /// エントリを順に読み込むカーソル
+    ///
+    /// # Errors
+    ///
+    /// アーカイブを読み込めない場合はエラーを返します。
+    pub fn entries(&mut self) -> Result<EntryCursor<'_>, String> {
+        Ok(match self {
+            Self::Zip(archive) => EntryCursor::Zip { archive, index: 0 },
+            Self::TarGz { path, archive } => EntryCursor::TarGz(
+                archive
+                    .insert(Box::new(open_tar_gz(path)?))
+                    .entries()
+                    .map_err(|e| format!("Failed to read archive: {e}"))?,
+            ),
+            Self::Directory(root) => EntryCursor::Directory {
+                entries: directory_entries(root, None)?.into_iter(),
+                root: root.clone(),
+            },
+        })
+    }
+}
+
+/// アーカイブのエントリを順に読み込むカーソル
+pub enum EntryCursor<'a> {
+    /// ZIPファイル
+    Zip {
+        archive: &'a mut ZipArchive<File>,
+        index: usize,
+    },
+    /// tar.gzファイル
+    TarGz(tar::Entries<'a, GzDecoder<BufReader<File>>>),
+    /// ローカルフォルダー
+    Directory {
+        root: PathBuf,
+        entries: std::vec::IntoIter<EntryMeta>,
+    },
+}
+
+impl EntryCursor<'_> {
+    /// 次のエントリと内容
+    ///
+    /// ディレクトリと通常のファイル以外のエントリの内容は空です。
+    ///
+    /// # Errors
+    ///
+    /// エントリを読み込めない場合はエラーを返します。
+    pub fn next_entry(&mut self) -> Result<Option<(EntryMeta, EntryReader<'_>)>, String> {
+        match self {
+            Self::Zip { archive, index } => {
+                if *index >= archive.len() {
+                    return Ok(None);
+                }
+                let file = archive
+                    .by_index(*index)
+                    .map_err(|e| format!("Failed to read ZIP entry: {e}"))?;
+                *index += 1;
+                let meta = EntryMeta {
+                    name: file.name().to_string(),
+                    size: file.size(),
+                    compressed_size: Some(file.compressed_size()),
+                    is_dir: file.is_dir(),
+                    is_special: file.is_symlink(),
+                };
+                Ok(Some((meta, Box::new(file))))
+            }
+            Self::TarGz(entries) => {
+                let (meta, entry) = loop {
+                    let Some(entry) = entries.next() else {
+                        return Ok(None);
+                    };
+                    let entry = entry.map_err(|e| format!("Failed to read archive: {e}"))?;
+                    if let Some(meta) = tar_entry(&entry) {
+                        break (meta, entry);
+                    }
+                };
+                if meta.is_dir || meta.is_special {
+                    return Ok(Some((meta, Box::new(io::empty()))));
+                }
+                Ok(Some((meta, Box::new(entry))))
+            }
+            Self::Directory { root, entries } => {
+                let Some(meta) = entries.next() else {
+                    return Ok(None);
+                };
+                if meta.is_dir || meta.is_special {
+                    return Ok(Some((meta, Box::new(io::empty()))));
+                }
+                let path = root.join(&meta.name);
+                // 一覧の作成後にシンボリックリンクへ置き換えられたファイルは読み込まない
+                let is_file = fs::symlink_metadata(&path).is_ok_and(|m| m.file_type().is_file());
+                if !is_file {
+                    return Err(format!("{}: not a regular file", meta.name));
+                }
+                let file =
+                    File::open(&path).map_err(|e| format!("Failed to read {}: {e}", meta.name))?;
+                Ok(Some((meta, Box::new(file))))
+            }
+        }
+    }
+}
+
+/// アーカイブ内の1つのファイルを読み込む
+///
+/// # Arguments
+///
+/// * `path` - アーカイブのパス
+/// * `entry` - アーカイブ内のパス
+/// * `limit` - 読み込むサイズの上限（バイト）
+///
+/// # Errors
+///
+/// アーカイブを読み込めない場合、ファイルが見つからない場合、上限を超える場合はエラーを返します。
+pub fn read_entry(path: &str, entry: &str, limit: u64) -> Result<Vec<u8>, String> {
+    let mut archive = ArchiveReader::open(path)?;
+    let mut cursor = archive.entries()?;
+    while let Some((meta, reader)) = cursor.next_entry()? {
+        if meta.name != entry || meta.is_dir || meta.is_special {
+            continue;
+        }
+        let mut contents = Vec::new();
+        archive_safety::copy_limited(reader, &mut contents, limit)
+            .map_err(|e| format!("Failed to read {entry}: {e}"))?;
+        return Ok(contents);
+    }
+    Err(format!("{entry} was not found in the archive"))
+}
+
+/// アーカイブの変更を検出するためのトークン
+///
+/// ファイルの場合はサイズと更新日時、フォルダーの場合は含まれるファイルのパス・サイズ・更新日時から作成します。
+///
+/// # Errors
+///
+/// アーカイブが存在しない場合はエラーを返します。
+pub fn fingerprint(path: &str) -> Result<String, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("Failed to open archive: {e}"))?;
+    let modified = |metadata: &fs::Metadata| {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos())
+    };
+    if !metadata.is_dir() {
+        return Ok(format!("{}:{}", metadata.len(), modified(&metadata)));
+    }
+
+    let mut listing = String::new();
+    for entry in directory_entries(Path::new(path), None)? {
+        let file_metadata = fs::symlink_metadata(Path::new(path).join(&entry.name))
+            .map_err(|e| format!("Failed to read folder: {e}"))?;
+        listing.push_str(&format!(
+            "{}\t{}\t{}\n",
+            entry.name,
+            entry.size,
+            modified(&file_metadata)
+        ));
+    }
+    Ok(content_version_token(listing.as_bytes()))
+}
+
+/// 書き込み先
+enum ArchiveSink {
+    Zip(Box<ZipWriter<File>>),
+    TarGz(TarBuilder<GzEncoder<BufWriter<File>>>),
+    Directory,
+}
+
+/// エクスポート先のアーカイブ（ZIP・tar.gz・フォルダー）
+///
+/// `finish` を呼び出さずに破棄した場合（キャンセル・失敗）は一時ファイルを削除します。
+pub struct ArchiveWriter {
+    /// 出力先
+    dest: PathBuf,
+    /// 書き込み中の一時ファイル（フォルダーの場合は一時フォルダー）
+    temp: PathBuf,
+    /// 書き込み先（完了後は`None`）
+    sink: Option<ArchiveSink>,
+    /// 出力先へ移動したか
+    finished: bool,
+    /// 既存のフォルダーを丸ごと置き換えるか（フォルダーのみ）
+    replace_folder: bool,
+    /// tarに記録する更新日時（UNIX時刻）
+    mtime: u64,
+}
+
+impl ArchiveWriter {
+    /// 書き込みを開始
+    ///
+    /// 出力先の親ディレクトリが存在しない場合は作成します。
+    ///
+    /// # Arguments
+    ///
+    /// * `dest` - 出力先のパス
+    /// * `format` - 形式
+    ///
+    /// # Errors
+    ///
+    /// 形式と出力先の種類（ファイル・フォルダー）が一致しない場合、一時ファイルを作成できない場合はエラーを返します。
+    pub fn create(dest: &Path, format: ArchiveFormat) -> Result<Self, String> {
+        if format == ArchiveFormat::Directory && dest.is_file() {
+            return Err(format!("Destination is not a folder: {}", dest.display()));
+        }
+        if format != ArchiveFormat::Directory && dest.is_dir() {
+            return Err(format!("Destination is a folder: {}", dest.display()));
+        }
+        if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !parent.exists() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create destination directory: {e}"))?;
+            }
+        }
+
+        let temp = temp_path_for(dest).map_err(|e| format!("Invalid destination path: {e}"))?;
+        let create_file =
+            || File::create(&temp).map_err(|e| format!("Failed to create archive: {e}"));
+        let sink = match format {
+            ArchiveFormat::Zip => ArchiveSink::Zip(Box::new(ZipWriter::new(create_file()?))),
+            ArchiveFormat::TarGz => ArchiveSink::TarGz(TarBuilder::new(GzEncoder::new(
+                BufWriter::new(create_file()?),
+                Compression::default(),
+            ))),
+            ArchiveFormat::Directory => {
+                fs::create_dir(&temp).map_err(|e| format!("Failed to create folder: {e}"))?;
+                ArchiveSink::Directory
+            }
+        };
+        Ok(Self {
+            dest: dest.to_path_buf(),
+            temp,
+            sink: Some(sink),
+            finished: false,
+            replace_folder: false,
+            mtime: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        })
+    }
+
+    /// 既存のフォルダーへの書き込みで、同じパスのファイルのみではなくフォルダーを丸ごと置き換える
+    ///
+    /// 書き込まなかったファイルを出力先に残さない場合に使用します（フォルダー以外の形式では常に置き換える）。
+    pub fn replace_existing_folder(&mut self) {
+        self.replace_folder = true;
+    }
+
+    /// ファイルを追加
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - アーカイブ内のパス（`/` 区切りの相対パス）
+    /// * `contents` - 内容
+    ///
+    /// # Errors
+    ///
+    /// 書き込みに失敗した場合、パスが不正な場合はエラーを返します。
+    pub fn add_file(&mut self, name: &str, contents: &[u8]) -> Result<(), String> {
+        let write_error = |e: &dyn std::fmt::Display| format!("Failed to write {name}: {e}");
+        match self.sink.as_mut() {
+            Some(ArchiveSink::Zip(zip)) => {
+                let options = SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Deflated)
+                    .unix_permissions(0o644);
+                zip.start_file(name, options).map_err(|e| write_error(&e))?;
+                zip.write_all(contents).map_err(|e| write_error(&e))
+            }
+            Some(ArchiveSink::TarGz(tar)) => {
+                // ustarに収まらないパス名はGNUの長いパス名で記録される
+                let mut header = TarHeader::new_gnu();
+                header.set_entry_type(EntryType::Regular);
+                header.set_size(contents.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(self.mtime);
+                tar.append_data(&mut header, name, contents)
+                    .map_err(|e| write_error(&e))
+            }
+            Some(ArchiveSink::Directory) => {
+                let relative = archive_safety::normalize_entry_name(name)?;
+                let path = self.temp.join(relative);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| write_error(&e))?;
+                }
+                fs::write(&path, contents).map_err(|e| write_error(&e))
+            }
+            None => Err(format!(
+                "Failed to write {name}: the archive is already finished"
+            )),
+        }
+    }
+
+    /// 書き込みを完了し、出力先へ移動
+    ///
+    /// フォルダーの場合、出力先が存在しなければ一時フォルダーをリネームし、
+    /// 存在すればファイルごとに移動します（同じパスの既存ファイルは置き換える）。
+    /// [`ArchiveWriter::replace_existing_folder`] を呼び出した場合は既存のフォルダーを置き換えます。
+    ///
+    /// # Errors
+    ///
+    /// 書き込み・移動に失敗した場合はエラーを返します（一時ファイルは削除する）。
+    pub fn finish(mut self) -> Result<(), String> {
+        let finish_error = |e: &dyn std::fmt::Display| format!("Failed to finalize archive: {e}");
+        match self.sink.take() {
+            Some(ArchiveSink::Zip(zip)) => {
+                let file = zip.finish().map_err(|e| finish_error(&e))?;
+                file.sync_all().map_err(|e| finish_error(&e))?;
+            }
+            Some(ArchiveSink::TarGz(tar)) => {
+                let file = tar
+                    .into_inner()
+                    .and_then(GzEncoder::finish)
+                    .and_then(|w| w.into_inner().map_err(io::IntoInnerError::into_error))
+                    .map_err(|e| finish_error(&e))?;
+                file.sync_all().map_err(|e| finish_error(&e))?;
+            }
+            Some(ArchiveSink::Directory) => {
+                if self.dest.exists() {
+                    if self.replace_folder {
+                        replace_folder(&self.temp, &self.dest)
+                            .map_err(|e| format!("Failed to replace folder: {e}"))?;
+                    } else {
+                        move_files_into(&self.temp, &self.dest)
+                            .map_err(|e| format!("Failed to write to folder: {e}"))?;
+                        let _ = fs::remove_dir_all(&self.temp);
+                    }
+                    self.finished = true;
+                    return Ok(());
+                }
+            }
+            None => return Err("The archive is already finished".to_string()),
+        }
+        fs::rename(&self.temp, &self.dest).map_err(|e| format!("Failed to create archive: {e}"))?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+/// 一時フォルダー内のファイルを既存のフォルダーへ移動
+fn move_files_into(staging: &Path, dest: &Path) -> io::Result<()> {
+    for entry in WalkDir::new(staging).min_depth(1) {
+        let entry = entry.map_err(io::Error::other)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(staging).unwrap_or(entry.path());
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(entry.path(), &target)?;
+    }
+    Ok(())
+}
+
+/// 既存のフォルダーを一時フォルダーで置き換える（失敗した場合は元のフォルダーに戻す）
+fn replace_folder(staging: &Path, dest: &Path) -> io::Result<()> {
+    let previous = temp_path_for(dest)?;
+    fs::rename(dest, &previous)?;
+    if let Err(e) = fs::rename(staging, dest) {
+        let _ = fs::rename(&previous, dest);
+        return Err(e);
+    }
+    let _ = fs::remove_dir_all(&previous);
+    Ok(())
+}
+
+impl Drop for ArchiveWriter {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        // 書き込み中のファイルを閉じてから削除
+        drop(self.sink.take());
+        let _ = if self.temp.is_dir() {
+            fs::remove_dir_all(&self.temp)
+        } else {
+            fs::remove_file(&self.temp)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestClaudeDir;
+
+    #[test]
+    fn test_round_trip_in_every_format() {
+        let dir = TestClaudeDir::new();
+        let root = dir.path();
+        let files = [
+            ("CLAUDE.md", b"memory".to_vec()),
+            ("skills/lint/SKILL.md", b"lint".to_vec()),
+        ];
+        let limits = ArchiveImportLimits::default();
+
+        for (dest, format) in [
+            (root.join("out/a.zip"), ArchiveFormat::Zip),
+            (root.join("out/a.tar.gz"), ArchiveFormat::TarGz),
+            (root.join("out/folder"), ArchiveFormat::Directory),
+        ] {
+            let mut writer = ArchiveWriter::create(&dest, format).unwrap();
+            for (name, contents) in &files {
+                writer.add_file(name, contents).unwrap();
+            }
+            writer.finish().unwrap();
+            assert_eq!(detect_format(&dest), Some(format));
+
+            let path = dest.to_string_lossy().to_string();
+            let mut archive = ArchiveReader::open(&path).unwrap();
+            assert_eq!(archive.format(), format);
+            let entries = archive.validate(&limits).unwrap();
+            let names: Vec<&str> = entries
+                .iter()
+                .filter(|e| !e.is_dir)
+                .map(|e| e.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["CLAUDE.md", "skills/lint/SKILL.md"]);
+            assert_eq!(
+                read_entry(&path, "skills/lint/SKILL.md", 1024).unwrap(),
+                b"lint"
+            );
+            assert!(read_entry(&path, "missing.md", 1024).is_err());
+        }
+
+        // 既存のフォルダーへのエクスポートはファイルを追加・置き換える
+        let folder = root.join("out/folder");
+        dir.write("out/folder/keep.md", "keep");
+        let before = fingerprint(&folder.to_string_lossy()).unwrap();
+        let mut writer = ArchiveWriter::create(&folder, ArchiveFormat::Directory).unwrap();
+        writer.add_file("CLAUDE.md", b"updated").unwrap();
+        writer.finish().unwrap();
+        assert_eq!(fs::read_to_string(folder.join("keep.md")).unwrap(), "keep");
+        assert_eq!(
+            fs::read_to_string(folder.join("CLAUDE.md")).unwrap(),
+            "updated"
+        );
+        assert_ne!(fingerprint(&folder.to_string_lossy()).unwrap(), before);
+
+        // 完了せずに破棄した場合は何も残さない
+        let dest = root.join("out/cancelled.tar.gz");
+        let mut writer = ArchiveWriter::create(&dest, ArchiveFormat::TarGz).unwrap();
+        writer.add_file("CLAUDE.md", b"memory").unwrap();
+        drop(writer);
+        assert!(!dest.exists());
+        let leftovers = fs::read_dir(root.join("out"))
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .ends_with(crate::utils::TEMP_FILE_SUFFIX)
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn test_tar_gz_long_names_and_links() {
+        let dir = TestClaudeDir::new();
+        let dest = dir.path().join("out/long.tar.gz");
+        let long_name = format!("skills/{}/SKILL.md", "a".repeat(150));
+        let mut writer = ArchiveWriter::create(&dest, ArchiveFormat::TarGz).unwrap();
+        writer.add_file(&long_name, b"skill").unwrap();
+        writer.finish().unwrap();
+        let path = dest.to_string_lossy().to_string();
+        assert_eq!(read_entry(&path, &long_name, 1024).unwrap(), b"skill");
+
+        // シンボリックリンクを含むアーカイブは検証で拒否
+        let linked = dir.path().join("out/link.tar.gz");
+        let mut builder = TarBuilder::new(GzEncoder::new(
+            File::create(&linked).unwrap(),
+            Compression::default(),
+        ));
+        let mut header = TarHeader::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "CLAUDE.md", "/etc/passwd")
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        let mut archive = ArchiveReader::open(&linked.to_string_lossy()).unwrap();
+        let entries = archive.list(&ArchiveImportLimits::default()).unwrap();
+        assert!(entries[0].is_special);
+        assert!(archive
+            .validate(&ArchiveImportLimits::default())
+            .unwrap_err()
+            .contains("CLAUDE.md"));
+    }
+
+    #[test]
+    fn test_folder_skips_excluded_directories() {
+        let dir = TestClaudeDir::new();
+        let folder = dir.path().join("source");
+        for name in ["CLAUDE.md", "cache/a.json", "backups/b.md", ".git/config"] {
+            dir.write(&format!("source/{name}"), "x");
+        }
+
+        let names: Vec<String> = directory_entries(&folder, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["CLAUDE.md"]);
+    }
+}
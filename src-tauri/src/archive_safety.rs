@@ -0,0 +1,251 @@
+//! アーカイブの安全な展開
+//!
+//! ZIP・tar.gz・ローカルフォルダーのインポートで、展開前にすべてのエントリを検証し、
+//! エントリ数・展開後の合計サイズ・エントリごとのサイズ・圧縮率の上限を超えるアーカイブ（zip bomb）と、
+//! 絶対パス・`..` を含むパス・シンボリックリンク・重複したエントリ名を含むアーカイブを拒否します。
+//! ヘッダーに記録されたサイズは偽装できるため、展開時も実際に読み込んだバイト数で上限を確認します。
+
+use crate::archive::EntryMeta;
+use crate::config::ArchiveImportLimits;
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
+
+/// 圧縮率の上限を適用する展開後のサイズの下限（バイト）
+const RATIO_CHECK_MIN_BYTES: u64 = 1024 * 1024;
+
+/// エラーメッセージに含める違反の最大数
+const MAX_REPORTED_VIOLATIONS: usize = 10;
+
+/// エントリ名を検証し、`/` 区切りの相対パスに正規化
+///
+/// `\` も区切り文字として扱い、空の要素と `.` は取り除きます。
+///
+/// # Errors
+///
+/// 空の名前、NUL文字、絶対パス（`/`・`\` で始まる、ドライブ文字付き）、
+/// `..` を含むパスの場合はエラーを返します。
+pub fn normalize_entry_name(name: &str) -> Result<String, String> {
+    if name.contains('\0') {
+        return Err(format!("{name:?}: entry name contains NUL"));
+    }
+    if name.starts_with('/') || name.starts_with('\\') {
+        return Err(format!("{name}: absolute paths are not allowed"));
+    }
+    let bytes = name.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return Err(format!("{name}: absolute paths are not allowed"));
+    }
+
+    let mut components = Vec::new();
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => return Err(format!("{name}: '..' is not allowed in entry names")),
+            _ => components.push(component),
+        }
+    }
+    if components.is_empty() {
+        return Err(format!("{name:?}: empty entry name"));
+    }
+    Ok(components.join("/"))
+}
+
+/// アーカイブ内のすべてのエントリを展開前に検証
+///
+/// エントリの内容は展開せず、一覧の情報（名前・サイズ・種類）のみを確認します。
+/// 圧縮率はエントリごとの圧縮後のサイズ（ZIP）、またはアーカイブ全体のサイズ（tar.gz）で確認します。
+///
+/// # Arguments
+///
+/// * `entries` - アーカイブ内のエントリ
+/// * `limits` - 上限
+/// * `archive_bytes` - 圧縮後のアーカイブ全体のサイズ（エントリごとのサイズがない形式のみ）
+///
+/// # Errors
+///
+/// 上限を超える場合、または安全でないエントリを含む場合は、違反の一覧をエラーとして返します。
+pub fn validate_entries(
+    entries: &[EntryMeta],
+    limits: &ArchiveImportLimits,
+    archive_bytes: Option<u64>,
+) -> Result<(), String> {
+    if entries.len() > limits.max_entries as usize {
+        return Err(format!(
+            "The archive has too many entries: {} (limit {})",
+            entries.len(),
+            limits.max_entries
+        ));
+    }
+
+    let mut violations = Vec::new();
+    let mut seen = HashSet::new();
+    let mut total_bytes: u64 = 0;
+    for entry in entries {
+        let name = &entry.name;
+
+        match normalize_entry_name(name) {
+            // 大文字・小文字を区別しないファイルシステムでも衝突しないよう比較
+            Ok(normalized) => {
+                if !seen.insert(normalized.to_lowercase()) {
+                    violations.push(format!("{name}: duplicate entry name"));
+                }
+            }
+            Err(e) => violations.push(e),
+        }
+        if entry.is_special {
+            violations.push(format!(
+                "{name}: symbolic links and special files are not allowed"
+            ));
+        }
+
+        let size = entry.size;
+        if size > limits.max_entry_bytes {
+            violations.push(format!(
+                "{name}: entry is too large ({size} bytes, limit {})",
+                limits.max_entry_bytes
+            ));
+        }
+        total_bytes = total_bytes.saturating_add(size);
+        if let Some(compressed) = entry
+            .compressed_size
+            .filter(|_| size >= RATIO_CHECK_MIN_BYTES)
+        {
+            let ratio = size / compressed.max(1);
+            if ratio > u64::from(limits.max_compression_ratio) {
+                violations.push(format!(
+                    "{name}: compression ratio is too high ({ratio}:1, limit {}:1)",
+                    limits.max_compression_ratio
+                ));
+            }
+        }
+    }
+    if total_bytes > limits.max_total_bytes {
+        violations.push(format!(
+            "The archive is too large when extracted ({total_bytes} bytes, limit {})",
+            limits.max_total_bytes
+        ));
+    }
+    if let Some(compressed) = archive_bytes.filter(|_| total_bytes >= RATIO_CHECK_MIN_BYTES) {
+        let ratio = total_bytes / compressed.max(1);
+        if ratio > u64::from(limits.max_compression_ratio) {
+            violations.push(format!(
+                "The archive compression ratio is too high ({ratio}:1, limit {}:1)",
+                limits.max_compression_ratio
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let count = violations.len();
+    violations.truncate(MAX_REPORTED_VIOLATIONS);
+    let more = count - violations.len();
+    let mut message = format!("Unsafe archive rejected: {}", violations.join("; "));
+    if more > 0 {
+        message.push_str(&format!(" (and {more} more)"));
+    }
+    Err(message)
+}
+
+/// 上限までのバイト数をコピー
+///
+/// # Returns
+///
+/// コピーしたバイト数
+///
+/// # Errors
+///
+/// 読み書きに失敗した場合、または上限を超えるデータが続く場合はエラーを返します。
+pub fn copy_limited<R: Read, W: Write>(reader: R, writer: &mut W, limit: u64) -> io::Result<u64> {
+    let copied = io::copy(&mut reader.take(limit.saturating_add(1)), writer)?;
+    if copied > limit {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("entry exceeds the size limit of {limit} bytes"),
+        ));
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::zip_entries;
+    use std::io::Cursor;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipArchive;
+
+    fn build_zip(entries: &[(&str, Vec<u8>)]) -> Vec<EntryMeta> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        let mut archive = ZipArchive::new(zip.finish().unwrap()).unwrap();
+        zip_entries(&mut archive).unwrap()
+    }
+
+    #[test]
+    fn test_rejects_unsafe_archives() {
+        assert_eq!(
+            normalize_entry_name("agents\\./a.md").unwrap(),
+            "agents/a.md"
+        );
+        assert!(normalize_entry_name("../a.md").is_err());
+        assert!(normalize_entry_name("skills/../../a.md").is_err());
+        assert!(normalize_entry_name("/etc/passwd").is_err());
+        assert!(normalize_entry_name("C:/Windows/a.md").is_err());
+
+        let limits = ArchiveImportLimits::default();
+        let entries = build_zip(&[("a.md", b"a".to_vec()), ("skills/b.md", b"b".to_vec())]);
+        assert!(validate_entries(&entries, &limits, None).is_ok());
+
+        let entries = build_zip(&[("a.md", b"a".to_vec()), ("A.md", b"b".to_vec())]);
+        assert!(validate_entries(&entries, &limits, None)
+            .unwrap_err()
+            .contains("duplicate"));
+
+        // 高い圧縮率のエントリ（zip bomb）
+        let entries = build_zip(&[("bomb.md", vec![0; 4 * 1024 * 1024])]);
+        assert!(validate_entries(&entries, &limits, None)
+            .unwrap_err()
+            .contains("compression ratio"));
+
+        // エントリごとの圧縮後のサイズがない形式（tar.gz）はアーカイブ全体で確認
+        let entries = vec![EntryMeta {
+            name: "bomb.md".to_string(),
+            size: 4 * 1024 * 1024,
+            compressed_size: None,
+            is_dir: false,
+            is_special: false,
+        }];
+        assert!(validate_entries(&entries, &limits, None).is_ok());
+        assert!(validate_entries(&entries, &limits, Some(4096))
+            .unwrap_err()
+            .contains("compression ratio"));
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.add_symlink("link.md", "/etc/passwd", SimpleFileOptions::default())
+            .unwrap();
+        let mut archive = ZipArchive::new(zip.finish().unwrap()).unwrap();
+        assert!(
+            validate_entries(&zip_entries(&mut archive).unwrap(), &limits, None)
+                .unwrap_err()
+                .contains("symbolic links")
+        );
+
+        let small = ArchiveImportLimits {
+            max_entries: 1,
+            ..ArchiveImportLimits::default()
+        };
+        let entries = build_zip(&[("a.md", b"a".to_vec()), ("b.md", b"b".to_vec())]);
+        assert!(validate_entries(&entries, &small, None)
+            .unwrap_err()
+            .contains("too many entries"));
+
+        let mut out = Vec::new();
+        assert_eq!(copy_limited(&b"abc"[..], &mut out, 3).unwrap(), 3);
+        assert!(copy_limited(&b"abcd"[..], &mut Vec::new(), 3).is_err());
+    }
+}
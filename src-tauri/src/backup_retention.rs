@@ -0,0 +1,328 @@
+//! バックアップの保持ポリシー
+//!
+//! ダッシュボード設定の保持ポリシーから、削除するバックアップを決定します。
+//! 作成からの日数（`keepWithinDays`）、ファイルごとの世代数（`keepLast`）、
+//! 日次・週次・月次のチェックポイント（`keepDaily` / `keepWeekly` / `keepMonthly`）の
+//! いずれかに該当するバックアップとピン留めされたバックアップを残し、
+//! 最後に合計サイズの上限（`maxTotalBytes`）を超える分を古い順に削除します。
+
+use crate::config::RetentionPolicy;
+use crate::types::{BackupRecord, RetentionCandidate, RetentionPlan, RetentionReason};
+use chrono::{DateTime, Datelike, Duration, Local};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// チェックポイントの区切り（日・週・月）を求める関数
+type BucketKey = fn(&DateTime<Local>) -> (i32, u32);
+
+/// 日次チェックポイントの区切り
+fn daily_key(date: &DateTime<Local>) -> (i32, u32) {
+    (date.year(), date.ordinal())
+}
+
+/// 週次チェックポイントの区切り（ISO週）
+fn weekly_key(date: &DateTime<Local>) -> (i32, u32) {
+    let week = date.iso_week();
+    (week.year(), week.week())
+}
+
+/// 月次チェックポイントの区切り
+fn monthly_key(date: &DateTime<Local>) -> (i32, u32) {
+    (date.year(), date.month())
+}
+
+/// 保持ポリシーを適用した場合に削除されるバックアップを求める
+///
+/// # Arguments
+///
+/// * `records` - 記録されているすべてのバックアップ
+/// * `object_sizes` - ハッシュごとのオブジェクトのディスク使用量
+/// * `policy` - 保持ポリシー
+/// * `now` - 基準日時
+///
+/// # Returns
+///
+/// 削除対象のバックアップと適用前後の物理サイズ
+pub fn plan_retention(
+    records: &[BackupRecord],
+    object_sizes: &HashMap<String, u64>,
+    policy: &RetentionPolicy,
+    now: DateTime<Local>,
+) -> RetentionPlan {
+    let dates: Vec<Option<DateTime<Local>>> = records
+        .iter()
+        .map(|r| {
+            DateTime::parse_from_rfc3339(&r.created_at)
+                .ok()
+                .map(|d| d.with_timezone(&Local))
+        })
+        .collect();
+
+    // ファイルごとに新しい順に並べる
+    let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, record) in records.iter().enumerate() {
+        groups.entry(&record.relative_path).or_default().push(index);
+    }
+    for indices in groups.values_mut() {
+        indices.sort_by(|a, b| dates[*b].cmp(&dates[*a]));
+    }
+    let latest: HashSet<usize> = groups.values().filter_map(|g| g.first().copied()).collect();
+
+    let mut kept: HashSet<usize> = if policy.has_keep_rules() {
+        groups
+            .values()
+            .flat_map(|indices| retained_in_group(indices, &dates, policy, now))
+            .collect()
+    } else {
+        (0..records.len()).collect()
+    };
+    kept.extend((0..records.len()).filter(|i| records[*i].pinned));
+
+    let mut delete: Vec<(usize, RetentionReason)> = (0..records.len())
+        .filter(|i| !kept.contains(i))
+        .map(|i| (i, RetentionReason::NotRetained))
+        .collect();
+
+    let physical_size_before = physical_size(records, 0..records.len(), object_sizes);
+    let mut physical_size_after = physical_size(records, kept.iter().copied(), object_sizes);
+
+    if let Some(max_total_bytes) = policy.max_total_bytes {
+        let mut references: HashMap<&str, usize> = HashMap::new();
+        for index in &kept {
+            *references.entry(records[*index].hash.as_str()).or_default() += 1;
+        }
+
+        let mut candidates: Vec<usize> = kept
+            .iter()
+            .copied()
+            .filter(|i| !records[*i].pinned && !latest.contains(i))
+            .collect();
+        candidates.sort_by(|a, b| dates[*a].cmp(&dates[*b]));
+
+        for index in candidates {
+            if physical_size_after <= max_total_bytes {
+                break;
+            }
+            kept.remove(&index);
+            delete.push((index, RetentionReason::OverSizeLimit));
+
+            let hash = records[index].hash.as_str();
+            if let Some(count) = references.get_mut(hash) {
+                *count -= 1;
+                if *count == 0 {
+                    physical_size_after -= object_sizes.get(hash).copied().unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    delete.sort_by(|a, b| dates[a.0].cmp(&dates[b.0]));
+
+    RetentionPlan {
+        delete: delete
+            .into_iter()
+            .map(|(index, reason)| RetentionCandidate {
+                record: records[index].clone(),
+                reason,
+            })
+            .collect(),
+        kept_count: kept.len(),
+        pinned_count: kept.iter().filter(|i| records[**i].pinned).count(),
+        physical_size_before,
+        physical_size_after,
+        size_limit_exceeded: policy
+            .max_total_bytes
+            .is_some_and(|max| physical_size_after > max),
+    }
+}
+
+/// 指定したバックアップが参照するオブジェクトのディスク使用量の合計
+fn physical_size(
+    records: &[BackupRecord],
+    indices: impl Iterator<Item = usize>,
+    object_sizes: &HashMap<String, u64>,
+) -> u64 {
+    indices
+        .map(|i| records[i].hash.as_str())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter_map(|hash| object_sizes.get(hash))
+        .sum()
+}
+
+/// 1つのファイルのバックアップ（新しい順）のうち、保持ルールに該当するものを求める
+fn retained_in_group(
+    indices: &[usize],
+    dates: &[Option<DateTime<Local>>],
+    policy: &RetentionPolicy,
+    now: DateTime<Local>,
+) -> HashSet<usize> {
+    let mut retained = HashSet::new();
+
+    if let Some(count) = policy.keep_last {
+        retained.extend(indices.iter().take(count as usize).copied());
+    }
+
+    if let Some(days) = policy.keep_within_days {
+        let threshold = now - Duration::days(i64::from(days));
+        // 作成日時が読み取れないバックアップは削除しない
+        retained.extend(
+            indices
+                .iter()
+                .copied()
+                .filter(|i| dates[*i].map_or(true, |d| d >= threshold)),
+        );
+    }
+
+    let checkpoints: [(Option<u32>, BucketKey); 3] = [
+        (policy.keep_daily, daily_key),
+        (policy.keep_weekly, weekly_key),
+        (policy.keep_monthly, monthly_key),
+    ];
+    for (count, key) in checkpoints {
+        let Some(count) = count else {
+            continue;
+        };
+        let mut buckets = HashSet::new();
+        for index in indices {
+            if buckets.len() >= count as usize {
+                break;
+            }
+            let Some(date) = &dates[*index] else {
+                continue;
+            };
+            // 各区切りで最も新しいバックアップを残す
+            if buckets.insert(key(date)) {
+                retained.insert(*index);
+            }
+        }
+    }
+
+    retained
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BackupOperation;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap()
+    }
+
+    fn record(id: &str, path: &str, hours_ago: i64, hash: &str) -> BackupRecord {
+        BackupRecord {
+            id: id.to_string(),
+            relative_path: path.to_string(),
+            created_at: (now() - Duration::hours(hours_ago)).to_rfc3339(),
+            size: 100,
+            hash: hash.to_string(),
+            operation: BackupOperation::Write,
+            pinned: false,
+        }
+    }
+
+    fn deleted_ids(plan: &RetentionPlan) -> Vec<&str> {
+        plan.delete.iter().map(|c| c.record.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_keep_last_per_file_and_pinning() {
+        let mut records: Vec<BackupRecord> = (0..5)
+            .map(|i| record(&format!("a{i}"), "a.md", i * 24, &format!("h{i}")))
+            .collect();
+        records.push(record("b0", "b.md", 24 * 100, "hb"));
+        records[3].pinned = true;
+
+        let policy = RetentionPolicy {
+            keep_within_days: None,
+            keep_last: Some(2),
+            ..RetentionPolicy::default()
+        };
+        let plan = plan_retention(&records, &HashMap::new(), &policy, now());
+
+        // 古い順に並び、ピン留めと各ファイルの最新2件は残る
+        assert_eq!(deleted_ids(&plan), vec!["a4", "a2"]);
+        assert!(plan
+            .delete
+            .iter()
+            .all(|c| c.reason == RetentionReason::NotRetained));
+        assert_eq!(plan.kept_count, 4);
+        assert_eq!(plan.pinned_count, 1);
+
+        // 保持ルールが1つもなければ削除しない
+        let keep_all = RetentionPolicy {
+            keep_within_days: None,
+            ..RetentionPolicy::default()
+        };
+        assert!(plan_retention(&records, &HashMap::new(), &keep_all, now())
+            .delete
+            .is_empty());
+    }
+
+    #[test]
+    fn test_daily_and_monthly_checkpoints() {
+        let records = vec![
+            record("today", "a.md", 0, "h1"),
+            record("today-earlier", "a.md", 1, "h2"),
+            record("yesterday", "a.md", 24, "h3"),
+            record("yesterday-earlier", "a.md", 25, "h4"),
+            record("february", "a.md", 24 * 30, "h5"),
+            record("february-earlier", "a.md", 24 * 31, "h6"),
+            record("january", "a.md", 24 * 60, "h7"),
+        ];
+        let policy = RetentionPolicy {
+            keep_within_days: None,
+            keep_daily: Some(2),
+            keep_monthly: Some(2),
+            ..RetentionPolicy::default()
+        };
+        let plan = plan_retention(&records, &HashMap::new(), &policy, now());
+
+        assert_eq!(
+            deleted_ids(&plan),
+            vec!["january", "february-earlier", "yesterday-earlier", "today-earlier"]
+        );
+    }
+
+    #[test]
+    fn test_size_limit_counts_shared_objects_once() {
+        let mut records = vec![
+            record("old-shared", "a.md", 72, "shared"),
+            record("old", "a.md", 48, "old"),
+            record("pinned", "a.md", 36, "pinned"),
+            record("latest-a", "a.md", 24, "latest"),
+            record("latest-b", "b.md", 96, "shared"),
+        ];
+        records[2].pinned = true;
+        let sizes: HashMap<String, u64> = [("shared", 10), ("old", 20), ("pinned", 30), ("latest", 40)]
+            .into_iter()
+            .map(|(hash, size)| (hash.to_string(), size))
+            .collect();
+
+        let policy = RetentionPolicy {
+            max_total_bytes: Some(80),
+            ..RetentionPolicy::default()
+        };
+        let plan = plan_retention(&records, &sizes, &policy, now());
+
+        // "old-shared" を消しても "latest-b" が同じ内容を参照しているため、"old" まで削除される
+        assert_eq!(deleted_ids(&plan), vec!["old-shared", "old"]);
+        assert!(plan
+            .delete
+            .iter()
+            .all(|c| c.reason == RetentionReason::OverSizeLimit));
+        assert_eq!(plan.physical_size_before, 100);
+        assert_eq!(plan.physical_size_after, 80);
+        assert!(!plan.size_limit_exceeded);
+
+        // ピン留めと各ファイルの最新は上限を超えても残す
+        let strict = RetentionPolicy {
+            max_total_bytes: Some(10),
+            ..policy
+        };
+        let plan = plan_retention(&records, &sizes, &strict, now());
+        assert_eq!(plan.kept_count, 3);
+        assert!(plan.size_limit_exceeded);
+    }
+}
@@ -0,0 +1,852 @@
+//! バックアップストア
+//!
+//! 設定ルートの `backups/` に内容アドレス方式でバックアップを保存します。
+//! 内容は SHA-256 ハッシュ名の gzip 圧縮オブジェクト（`backups/objects/ab/<hash>.gz`）として
+//! 一度だけ保存され、各バックアップのメタデータ（元の相対パス、作成日時、サイズ、ハッシュ、
+//! 作成した操作）は `backups/manifest.json` に記録されます。
+//!
+//! 旧形式（`backups/` 直下に `<ファイル名>_<日時>` で保存したコピー）のバックアップは、
+//! 初回アクセス時にオブジェクトへ移行されます。
+//!
+//! オブジェクトはスナップショット（`snapshot_store`）からも参照されるため、
+//! どちらからも参照されなくなったものだけを削除します。
+
+use crate::snapshot_store;
+use crate::types::{BackupOperation, BackupRecord};
+use crate::utils::{content_version_token, get_claude_dir, write_file_atomic, TEMP_FILE_SUFFIX};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// バックアップディレクトリ名
+pub const BACKUP_DIR_NAME: &str = "backups";
+
+/// マニフェストファイル名
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// オブジェクトを保存するディレクトリ名（`backups/` からの相対パス）
+pub const OBJECTS_DIR: &str = "objects";
+
+/// マニフェスト形式のバージョン
+///
+/// - 1: `backups/` 直下のコピーを記録
+/// - 2: 内容アドレス方式のオブジェクトを記録
+const MANIFEST_VERSION: u32 = 2;
+
+/// 旧形式のバックアップ名（`<ファイル名>_<YYYYmmdd_HHMMSS>[_<連番>]`）
+static LEGACY_NAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+)_(\d{8}_\d{6})(?:_\d+)?$").expect("valid regex"));
+
+/// マニフェストの読み書きを直列化するロック
+static MANIFEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 移行済みのバックアップディレクトリ
+static MIGRATED_DIRS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// マニフェストに保存するバックアップ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StoredRecord {
+    /// メタデータ
+    #[serde(flatten)]
+    record: BackupRecord,
+    /// 旧形式のバックアップファイル名（バージョン1のマニフェストのみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    /// 移行元の旧形式のバックアップファイル名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    migrated_from: Option<String>,
+}
+
+/// バックアップのマニフェスト
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+    /// 形式のバージョン
+    version: u32,
+    /// バックアップ一覧（作成順）
+    records: Vec<StoredRecord>,
+}
+
+impl Default for BackupManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            records: Vec::new(),
+        }
+    }
+}
+
+/// バックアップストアの使用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreUsage {
+    /// バックアップ数
+    pub record_count: usize,
+    /// オブジェクト数
+    pub object_count: usize,
+    /// 論理サイズ（各バックアップの元ファイルサイズの合計）
+    pub logical_size: u64,
+    /// 物理サイズ（オブジェクトのディスク使用量の合計）
+    pub physical_size: u64,
+}
+
+/// バックアップ削除の結果
+#[derive(Debug, Clone, Default)]
+pub struct RemovalOutcome {
+    /// 削除したバックアップ
+    pub removed: Vec<BackupRecord>,
+    /// 参照されなくなり削除したオブジェクトのディスク使用量
+    pub freed_bytes: u64,
+    /// エラーメッセージ
+    pub errors: Vec<String>,
+}
+
+/// アクティブな設定ルートのバックアップディレクトリを取得
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合はエラーを返します。
+pub fn get_backup_dir() -> Result<PathBuf, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    Ok(claude_dir.join(BACKUP_DIR_NAME))
+}
+
+/// マニフェストのロックを取得
+///
+/// オブジェクトの追加・削除はこのロックを取得した状態で行います。
+pub(crate) fn lock_manifest() -> MutexGuard<'static, ()> {
+    MANIFEST_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// ハッシュからオブジェクトのパスを取得
+pub(crate) fn object_path(backup_dir: &Path, hash: &str) -> PathBuf {
+    let prefix = hash.get(..2).unwrap_or("00");
+    backup_dir
+        .join(OBJECTS_DIR)
+        .join(prefix)
+        .join(format!("{hash}.gz"))
+}
+
+/// 内容をオブジェクトとして保存（同じ内容のオブジェクトが既にあれば何もしない）
+pub(crate) fn write_object(backup_dir: &Path, hash: &str, bytes: &[u8]) -> Result<(), String> {
+    let path = object_path(backup_dir, hash);
+    if path.is_file() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup object directory: {e}"))?;
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .map_err(|e| format!("Failed to compress backup: {e}"))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress backup: {e}"))?;
+
+    write_file_atomic(&path, &compressed).map_err(|e| format!("Failed to write backup object: {e}"))
+}
+
+/// オブジェクトを読み込んで展開
+pub(crate) fn read_object(backup_dir: &Path, hash: &str) -> Result<Vec<u8>, String> {
+    let file = fs::File::open(object_path(backup_dir, hash))
+        .map_err(|e| format!("Failed to open backup object {hash}: {e}"))?;
+    let mut bytes = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to decompress backup object {hash}: {e}"))?;
+
+    // 破損したオブジェクトを誤って復元しないようハッシュを検証
+    if content_version_token(&bytes) != hash {
+        return Err(format!("Backup object {hash} is corrupt"));
+    }
+    Ok(bytes)
+}
+
+/// マニフェストを読み込む
+///
+/// 破損している場合は退避したうえで空のマニフェストから始めます
+/// （退避したファイルが残っている間はガベージコレクションを行わない）。
+///
+/// # Errors
+///
+/// マニフェストが存在するのに読み込めない場合はエラーを返します。
+fn read_manifest(backup_dir: &Path) -> Result<BackupManifest, String> {
+    let path = backup_dir.join(MANIFEST_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(BackupManifest::default());
+        }
+        Err(e) => return Err(format!("Failed to read backup manifest: {e}")),
+    };
+
+    match serde_json::from_str::<BackupManifest>(&content) {
+        Ok(manifest) => Ok(manifest),
+        Err(e) => {
+            let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+            let corrupt = backup_dir.join(format!("{MANIFEST_FILE}.corrupt_{timestamp}"));
+            warn!(
+                "Backup manifest is corrupt ({e}), moving it to {}",
+                corrupt.display()
+            );
+            if let Err(e) = fs::rename(&path, &corrupt) {
+                warn!("Failed to move corrupt backup manifest: {e}");
+            }
+            Ok(BackupManifest::default())
+        }
+    }
+}
+
+/// マニフェストを保存
+fn save_manifest(backup_dir: &Path, manifest: &BackupManifest) -> Result<(), String> {
+    fs::create_dir_all(backup_dir)
+        .map_err(|e| format!("Failed to create backup directory: {e}"))?;
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize backup manifest: {e}"))?;
+    write_file_atomic(&backup_dir.join(MANIFEST_FILE), json.as_bytes())
+        .map_err(|e| format!("Failed to save backup manifest: {e}"))
+}
+
+/// `backups/` 直下の旧形式のバックアップファイルかどうか
+fn is_legacy_backup_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    path.is_file()
+        && !name.starts_with(MANIFEST_FILE)
+        && !name.ends_with(TEMP_FILE_SUFFIX)
+        && !name.starts_with('.')
+}
+
+/// 旧形式のバックアップ名から元のファイル名と作成日時を推測
+fn parse_legacy_name(name: &str, path: &Path) -> (String, String) {
+    let from_mtime = || {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|t| DateTime::<Local>::from(t).to_rfc3339())
+            .unwrap_or_else(|_| Local::now().to_rfc3339())
+    };
+
+    match LEGACY_NAME_PATTERN.captures(name) {
+        Some(caps) => {
+            let created_at = NaiveDateTime::parse_from_str(&caps[2], "%Y%m%d_%H%M%S")
+                .ok()
+                .and_then(|naive| Local.from_local_datetime(&naive).single())
+                .map_or_else(from_mtime, |dt| dt.to_rfc3339());
+            (caps[1].to_string(), created_at)
+        }
+        None => (name.to_string(), from_mtime()),
+    }
+}
+
+/// 旧形式のバックアップをオブジェクトへ移行
+///
+/// マニフェストを保存してから旧ファイルを削除するため、途中で中断しても
+/// 次回の移行で重複した記録は作られません。
+fn migrate(backup_dir: &Path, manifest: &mut BackupManifest) -> Result<(), String> {
+    let mut changed = manifest.version < MANIFEST_VERSION;
+
+    // バージョン1で記録されたコピー
+    for stored in &mut manifest.records {
+        let Some(file) = stored.file.take() else {
+            continue;
+        };
+        let legacy_path = backup_dir.join(&file);
+        match fs::read(&legacy_path) {
+            Ok(bytes) => {
+                let hash = content_version_token(&bytes);
+                write_object(backup_dir, &hash, &bytes)?;
+                stored.record.hash = hash;
+                stored.record.size = bytes.len() as u64;
+            }
+            Err(e) => warn!("Backup file {file} is missing, keeping its record only: {e}"),
+        }
+        stored.migrated_from = Some(file);
+        changed = true;
+    }
+
+    // 記録のない旧形式のコピー
+    let migrated: HashSet<String> = manifest
+        .records
+        .iter()
+        .filter_map(|r| r.migrated_from.clone())
+        .collect();
+    let mut legacy_files: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| is_legacy_backup_file(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    legacy_files.sort();
+
+    let mut leftovers = Vec::new();
+    for path in legacy_files {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if migrated.contains(&name) {
+            leftovers.push(path);
+            continue;
+        }
+
+        let bytes = fs::read(&path).map_err(|e| format!("Failed to read {name}: {e}"))?;
+        let hash = content_version_token(&bytes);
+        write_object(backup_dir, &hash, &bytes)?;
+
+        let (relative_path, created_at) = parse_legacy_name(&name, &path);
+        let id = generate_backup_id(manifest, &hash, &created_at);
+        manifest.records.push(StoredRecord {
+            record: BackupRecord {
+                id,
+                relative_path,
+                created_at,
+                size: bytes.len() as u64,
+                hash,
+                operation: BackupOperation::Unknown,
+                pinned: false,
+            },
+            file: None,
+            migrated_from: Some(name),
+        });
+        leftovers.push(path);
+        changed = true;
+    }
+
+    if !changed && leftovers.is_empty() {
+        return Ok(());
+    }
+
+    manifest.version = MANIFEST_VERSION;
+    manifest
+        .records
+        .sort_by(|a, b| a.record.created_at.cmp(&b.record.created_at));
+    save_manifest(backup_dir, manifest)?;
+
+    for path in &leftovers {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Failed to remove migrated backup {}: {e}", path.display());
+        }
+    }
+    if !leftovers.is_empty() {
+        info!(
+            "Migrated {} legacy backup(s) to the object store",
+            leftovers.len()
+        );
+    }
+    Ok(())
+}
+
+/// マニフェストを読み込む（未移行の場合は旧形式のバックアップを移行）
+fn load_manifest(backup_dir: &Path) -> Result<BackupManifest, String> {
+    let mut manifest = read_manifest(backup_dir)?;
+
+    let mut migrated = MIGRATED_DIRS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if !migrated.contains(backup_dir) {
+        if backup_dir.is_dir() {
+            migrate(backup_dir, &mut manifest)?;
+        }
+        migrated.insert(backup_dir.to_path_buf());
+    }
+
+    Ok(manifest)
+}
+
+/// バックアップIDを生成（作成日時とハッシュの先頭から生成し、重複時は連番を付与）
+fn generate_backup_id(manifest: &BackupManifest, hash: &str, created_at: &str) -> String {
+    let timestamp = DateTime::parse_from_rfc3339(created_at)
+        .map(|dt| dt.format("%Y%m%d%H%M%S%3f").to_string())
+        .unwrap_or_else(|_| Local::now().format("%Y%m%d%H%M%S%3f").to_string());
+    let base = format!("{timestamp}-{}", hash.get(..8).unwrap_or(hash));
+    let mut id = base.clone();
+    let mut suffix = 1;
+    while manifest.records.iter().any(|r| r.record.id == id) {
+        id = format!("{base}-{suffix}");
+        suffix += 1;
+    }
+    id
+}
+
+/// ファイルのバックアップを作成してメタデータを記録
+///
+/// 同じファイルの直前のバックアップと内容が同じ場合は新たに記録せず、直前の記録を返します。
+/// 異なるファイルでも内容が同じであればオブジェクトは共有されます。
+///
+/// # Arguments
+///
+/// * `source` - バックアップするファイルの正規化済みパス
+/// * `relative_path` - 設定ルートからの相対パス
+/// * `operation` - バックアップを作成した操作
+///
+/// # Returns
+///
+/// バックアップのメタデータ
+///
+/// # Errors
+///
+/// ファイルの読み込み、オブジェクトの書き込み、マニフェストの保存に失敗した場合はエラーを返します。
+pub fn store_backup(
+    source: &Path,
+    relative_path: &str,
+    operation: BackupOperation,
+) -> Result<BackupRecord, String> {
+    let backup_dir = get_backup_dir()?;
+    let bytes = fs::read(source).map_err(|e| format!("Failed to read file: {e}"))?;
+    let hash = content_version_token(&bytes);
+    let relative_path = relative_path.replace('\\', "/");
+
+    let _guard = lock_manifest();
+    let mut manifest = load_manifest(&backup_dir)?;
+
+    let latest = manifest
+        .records
+        .iter()
+        .rev()
+        .find(|r| r.record.relative_path == relative_path);
+    if let Some(latest) = latest {
+        if latest.record.hash == hash && object_path(&backup_dir, &hash).is_file() {
+            return Ok(latest.record.clone());
+        }
+    }
+
+    write_object(&backup_dir, &hash, &bytes)?;
+
+    let created_at = Local::now().to_rfc3339();
+    let record = BackupRecord {
+        id: generate_backup_id(&manifest, &hash, &created_at),
+        relative_path,
+        created_at,
+        size: bytes.len() as u64,
+        hash,
+        operation,
+        pinned: false,
+    };
+    manifest.records.push(StoredRecord {
+        record: record.clone(),
+        file: None,
+        migrated_from: None,
+    });
+    save_manifest(&backup_dir, &manifest)?;
+
+    Ok(record)
+}
+
+/// 記録されているすべてのバックアップを取得（新しい順）
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合、移行に失敗した場合はエラーを返します。
+pub fn list_backups() -> Result<Vec<BackupRecord>, String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let manifest = load_manifest(&backup_dir)?;
+
+    Ok(manifest
+        .records
+        .into_iter()
+        .rev()
+        .map(|r| r.record)
+        .collect())
+}
+
+/// 指定した相対パスのファイルのバックアップを取得（新しい順）
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合、移行に失敗した場合はエラーを返します。
+pub fn list_backups_for(relative_path: &str) -> Result<Vec<BackupRecord>, String> {
+    let relative_path = relative_path.replace('\\', "/");
+    Ok(list_backups()?
+        .into_iter()
+        .filter(|r| r.relative_path == relative_path)
+        .collect())
+}
+
+/// IDからバックアップのメタデータと内容を取得
+///
+/// # Errors
+///
+/// IDが見つからない場合、オブジェクトが存在しないか破損している場合はエラーを返します。
+pub fn read_backup(id: &str) -> Result<(BackupRecord, Vec<u8>), String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let manifest = load_manifest(&backup_dir)?;
+
+    let record = manifest
+        .records
+        .into_iter()
+        .map(|r| r.record)
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("Backup not found: {id}"))?;
+    let bytes = read_object(&backup_dir, &record.hash)?;
+    Ok((record, bytes))
+}
+
+/// バックアップストアの使用量を取得
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合、移行に失敗した場合はエラーを返します。
+pub fn usage() -> Result<StoreUsage, String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let manifest = load_manifest(&backup_dir)?;
+
+    let hashes: HashSet<&str> = manifest
+        .records
+        .iter()
+        .map(|r| r.record.hash.as_str())
+        .collect();
+    let physical_size = hashes
+        .iter()
+        .filter_map(|hash| fs::metadata(object_path(&backup_dir, hash)).ok())
+        .map(|m| m.len())
+        .sum();
+
+    Ok(StoreUsage {
+        record_count: manifest.records.len(),
+        object_count: hashes.len(),
+        logical_size: manifest.records.iter().map(|r| r.record.size).sum(),
+        physical_size,
+    })
+}
+
+/// 記録されているオブジェクトごとのディスク使用量を取得
+///
+/// # Returns
+///
+/// ハッシュからオブジェクトのサイズ（バイト）へのマップ（存在しないオブジェクトは含まない）
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合、移行に失敗した場合はエラーを返します。
+pub fn object_sizes() -> Result<HashMap<String, u64>, String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let manifest = load_manifest(&backup_dir)?;
+
+    Ok(manifest
+        .records
+        .iter()
+        .map(|r| r.record.hash.as_str())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter_map(|hash| {
+            let size = fs::metadata(object_path(&backup_dir, hash)).ok()?.len();
+            Some((hash.to_string(), size))
+        })
+        .collect())
+}
+
+/// バックアップのピン留めを設定
+///
+/// # Arguments
+///
+/// * `id` - バックアップID
+/// * `pinned` - ピン留めするかどうか
+///
+/// # Returns
+///
+/// 更新後のメタデータ
+///
+/// # Errors
+///
+/// IDが見つからない場合、マニフェストの保存に失敗した場合はエラーを返します。
+pub fn set_pinned(id: &str, pinned: bool) -> Result<BackupRecord, String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let mut manifest = load_manifest(&backup_dir)?;
+
+    let stored = manifest
+        .records
+        .iter_mut()
+        .find(|r| r.record.id == id)
+        .ok_or_else(|| format!("Backup not found: {id}"))?;
+    if stored.record.pinned == pinned {
+        return Ok(stored.record.clone());
+    }
+    stored.record.pinned = pinned;
+    let record = stored.record.clone();
+    save_manifest(&backup_dir, &manifest)?;
+
+    Ok(record)
+}
+
+/// 条件に一致するバックアップを削除し、参照されなくなったオブジェクトを削除
+///
+/// # Arguments
+///
+/// * `should_remove` - 削除するかどうかを判定する関数
+///
+/// # Errors
+///
+/// マニフェストの読み込み・保存に失敗した場合はエラーを返します。
+pub fn remove_backups<F>(mut should_remove: F) -> Result<RemovalOutcome, String>
+where
+    F: FnMut(&BackupRecord) -> bool,
+{
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let mut manifest = load_manifest(&backup_dir)?;
+
+    let mut outcome = RemovalOutcome::default();
+    let (removed, kept): (Vec<StoredRecord>, Vec<StoredRecord>) = manifest
+        .records
+        .into_iter()
+        .partition(|r| should_remove(&r.record));
+    manifest.records = kept;
+    if removed.is_empty() {
+        return Ok(outcome);
+    }
+    save_manifest(&backup_dir, &manifest)?;
+
+    outcome.removed = removed.into_iter().map(|r| r.record).collect();
+    match collect_garbage(&backup_dir, &manifest) {
+        Ok((freed_bytes, errors)) => {
+            outcome.freed_bytes = freed_bytes;
+            outcome.errors = errors;
+        }
+        Err(e) => {
+            warn!("Skipped deleting unreferenced backup objects: {e}");
+            outcome.errors.push(e);
+        }
+    }
+    Ok(outcome)
+}
+
+/// バックアップからもスナップショットからも参照されていないオブジェクトを削除
+///
+/// # Returns
+///
+/// 解放したバイト数とエラーメッセージ
+///
+/// # Errors
+///
+/// マニフェストの読み込みに失敗した場合、参照されているオブジェクトを確定できない場合は
+/// 何も削除せずにエラーを返します。
+pub fn collect_unreferenced_objects() -> Result<(u64, Vec<String>), String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    let manifest = load_manifest(&backup_dir)?;
+    collect_garbage(&backup_dir, &manifest)
+}
+
+/// ディレクトリのエントリのパス（存在しない場合は空）
+fn read_dir_paths(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {e}", dir.display())),
+    };
+    entries
+        .map(|e| {
+            e.map(|e| e.path())
+                .map_err(|e| format!("Failed to read {}: {e}", dir.display()))
+        })
+        .collect()
+}
+
+/// 参照されていないオブジェクトを削除（マニフェストのロックを取得した状態で呼び出す）
+///
+/// スナップショットが参照するオブジェクトは削除しません。
+/// 誤って削除したオブジェクトは復旧できないため、退避した破損マニフェストが残っている場合、
+/// スナップショットのマニフェストやオブジェクトのディレクトリを1つでも読み込めない場合は何も削除しません。
+///
+/// # Returns
+///
+/// 解放したバイト数とエラーメッセージ
+///
+/// # Errors
+///
+/// 参照されているオブジェクトを確定できない場合はエラーを返します（何も削除しない）。
+fn collect_garbage(
+    backup_dir: &Path,
+    manifest: &BackupManifest,
+) -> Result<(u64, Vec<String>), String> {
+    let corrupt_prefix = format!("{MANIFEST_FILE}.corrupt_");
+    if let Some(corrupt) = read_dir_paths(backup_dir)?.iter().find(|p| {
+        p.file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with(&corrupt_prefix))
+    }) {
+        return Err(format!(
+            "A corrupt backup manifest was set aside at {}; remove it after recovering its backups",
+            corrupt.display()
+        ));
+    }
+    let snapshot_hashes = snapshot_store::referenced_hashes(backup_dir)?;
+    let referenced: HashSet<&str> = manifest
+        .records
+        .iter()
+        .map(|r| r.record.hash.as_str())
+        .chain(snapshot_hashes.iter().map(String::as_str))
+        .collect();
+
+    // 削除を始める前にすべてのオブジェクトを列挙する
+    let mut prefixes = Vec::new();
+    for prefix in read_dir_paths(&backup_dir.join(OBJECTS_DIR))? {
+        let objects = read_dir_paths(&prefix)?;
+        prefixes.push((prefix, objects));
+    }
+
+    let mut freed = 0;
+    let mut errors = Vec::new();
+    for (prefix, objects) in prefixes {
+        for object in objects {
+            let hash = object
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".gz"));
+            if hash.is_some_and(|h| referenced.contains(h)) {
+                continue;
+            }
+            let size = fs::metadata(&object).map(|m| m.len()).unwrap_or(0);
+            match fs::remove_file(&object) {
+                Ok(()) => freed += size,
+                Err(e) => errors.push(format!("Failed to delete {}: {e}", object.display())),
+            }
+        }
+        // 空になったディレクトリは削除（失敗しても問題ない）
+        let _ = fs::remove_dir(&prefix);
+    }
+
+    Ok((freed, errors))
+}
+
+/// オブジェクトが存在しない記録をマニフェストから取り除く
+///
+/// # Returns
+///
+/// 取り除いた記録
+///
+/// # Errors
+///
+/// マニフェストの保存に失敗した場合はエラーを返します。
+pub fn prune_missing_records() -> Result<Vec<BackupRecord>, String> {
+    let backup_dir = get_backup_dir()?;
+    let missing: HashSet<String> = {
+        let _guard = lock_manifest();
+        load_manifest(&backup_dir)?
+            .records
+            .iter()
+            .filter(|r| !object_path(&backup_dir, &r.record.hash).is_file())
+            .map(|r| r.record.id.clone())
+            .collect()
+    };
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(remove_backups(|r| missing.contains(&r.id))?.removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backups_are_recorded_per_path_and_deduplicated() {
+        let root = crate::utils::TestClaudeDir::new();
+        let a = root.write("skills/a/SKILL.md", "# same");
+        let b = root.write("skills/b/SKILL.md", "# same");
+
+        let first = store_backup(&a, "skills/a/SKILL.md", BackupOperation::Write).unwrap();
+        // 内容が変わっていなければ記録を増やさない
+        let again = store_backup(&a, "skills/a/SKILL.md", BackupOperation::Write).unwrap();
+        assert_eq!(first, again);
+        store_backup(&b, "skills/b/SKILL.md", BackupOperation::Replace).unwrap();
+
+        assert_eq!(list_backups_for("skills/a/SKILL.md").unwrap(), vec![first.clone()]);
+        let usage = usage().unwrap();
+        assert_eq!(usage.record_count, 2);
+        assert_eq!(usage.object_count, 1);
+        assert_eq!(usage.logical_size, 12);
+
+        let (record, bytes) = read_backup(&first.id).unwrap();
+        assert_eq!(record, first);
+        assert_eq!(bytes, b"# same");
+
+        // 共有されているオブジェクトは片方を削除しても残る
+        let outcome = remove_backups(|r| r.id == first.id).unwrap();
+        assert_eq!(outcome.removed.len(), 1);
+        assert_eq!(outcome.freed_bytes, 0);
+        let outcome = remove_backups(|_| true).unwrap();
+        assert!(outcome.freed_bytes > 0);
+        assert!(!object_path(&root.path().join(BACKUP_DIR_NAME), &first.hash).exists());
+    }
+
+    #[test]
+    fn test_legacy_backups_are_migrated() {
+        let root = crate::utils::TestClaudeDir::new();
+        let backup_dir = root.path().join(BACKUP_DIR_NAME);
+        root.write("backups/settings.json_20260101_120000", "{}");
+        root.write("backups/settings.json_20260101_120000_1", "{}");
+        root.write("backups/CLAUDE.md_20260102_080000", "# memo");
+        fs::create_dir_all(backup_dir.join("trash")).unwrap();
+
+        let backups = list_backups().unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].relative_path, "CLAUDE.md");
+        assert_eq!(backups[0].operation, BackupOperation::Unknown);
+        assert!(backups[0].created_at.starts_with("2026-01-02T08:00:00"));
+
+        // 旧ファイルは削除され、同じ内容は1つのオブジェクトにまとめられる
+        let remaining: Vec<_> = fs::read_dir(&backup_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| is_legacy_backup_file(&e.path()))
+            .collect();
+        assert!(remaining.is_empty());
+        assert_eq!(usage().unwrap().object_count, 2);
+        assert!(backup_dir.join("trash").is_dir());
+    }
+
+    #[test]
+    fn test_prune_missing_records_returns_pruned_records() {
+        let root = crate::utils::TestClaudeDir::new();
+        let a = root.write("CLAUDE.md", "# a");
+        let b = root.write("settings.json", "{}");
+        let lost = store_backup(&a, "CLAUDE.md", BackupOperation::Write).unwrap();
+        let kept = store_backup(&b, "settings.json", BackupOperation::Write).unwrap();
+
+        fs::remove_file(object_path(&root.path().join(BACKUP_DIR_NAME), &lost.hash)).unwrap();
+        assert_eq!(prune_missing_records().unwrap(), vec![lost]);
+        assert_eq!(list_backups().unwrap(), vec![kept]);
+        assert!(prune_missing_records().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_manifest_blocks_garbage_collection() {
+        let root = crate::utils::TestClaudeDir::new();
+        let a = root.write("CLAUDE.md", "# a");
+        let record = store_backup(&a, "CLAUDE.md", BackupOperation::Write).unwrap();
+        let backup_dir = root.path().join(BACKUP_DIR_NAME);
+        fs::write(backup_dir.join(MANIFEST_FILE), "{\"records\": [").unwrap();
+
+        // 破損したマニフェストは退避され、記録は空から始まる
+        assert!(list_backups().unwrap().is_empty());
+        // 退避したマニフェストが参照していたオブジェクトは削除しない
+        assert!(collect_unreferenced_objects()
+            .unwrap_err()
+            .contains("corrupt"));
+        assert!(object_path(&backup_dir, &record.hash).is_file());
+    }
+}
@@ -1,14 +1,34 @@
 //! バックアップ操作コマンド
 //!
-//! ファイルのバックアップ作成、一覧取得、復元、クリーンアップ機能を提供します。
-
+//! ファイルのバックアップ作成、一覧取得、復元、ピン留め、保持ポリシーによるクリーンアップ機能を提供します。
+//! 各バックアップには元の相対パス・作成日時・サイズ・ハッシュ・作成した操作が記録され、
+//! 内容は重複排除・圧縮してバックアップストアに保存されます。
+
+use crate::backup_retention::plan_retention;
+use crate::backup_store::{self, RemovalOutcome};
+use crate::commands::fileops::empty_trash;
+use crate::config::{self, RetentionPolicy};
 use crate::error::AppResult;
-use crate::types::{CleanupResult, FileNode};
-use crate::utils::{generate_backup_name, get_claude_dir, validate_path_security};
+use crate::git_history;
+use crate::security::AccessPolicy;
+use crate::types::{BackupList, BackupOperation, BackupRecord, CleanupResult, RetentionPlan};
+use crate::utils::write_file_atomic;
+use chrono::{DateTime, Duration, Local};
 use log::{info, warn};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// ゴミ箱のエントリを保持する日数
+const TRASH_RETENTION_DAYS: u32 = 30;
+
+/// 保持ポリシーの適用間隔を確認する間隔
+const SCHEDULER_TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// 定期適用のスレッドを起動済みか
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
 
 /// バックアップを作成
 ///
@@ -18,120 +38,185 @@ use std::time::{Duration, SystemTime};
 ///
 /// # Returns
 ///
-/// 作成されたバックアップファイルのパス
+/// 作成されたバックアップのメタデータ（ファイルが存在しない場合は`None`）
 #[tauri::command]
-pub fn create_backup(path: &str) -> AppResult<String> {
-    create_backup_internal(path)
+pub fn create_backup(path: &str) -> AppResult<Option<BackupRecord>> {
+    create_backup_internal(path, BackupOperation::Manual)
 }
 
 /// 内部用バックアップ作成関数
 ///
 /// Tauriコマンドと内部処理の両方から使用されます。
-pub fn create_backup_internal(path: &str) -> AppResult<String> {
-    let path_buf = PathBuf::from(path);
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-
-    if !path_buf.exists() {
-        return Ok("File does not exist, no backup needed".to_string());
-    }
-
-    let backup_dir = claude_dir.join("backups");
-    if !backup_dir.exists() {
-        fs::create_dir_all(&backup_dir)
-            .map_err(|e| format!("Failed to create backup directory: {e}"))?;
+/// バックアップ元は設定ルート配下のファイルのみ許可されます。
+/// 直前のバックアップと内容が同じ場合は新たに記録せず、直前のバックアップを返します。
+///
+/// # Arguments
+///
+/// * `path` - バックアップするファイルのパス
+/// * `operation` - バックアップを作成した操作
+///
+/// # Returns
+///
+/// バックアップのメタデータ（ファイルが存在しない場合は`None`）
+pub fn create_backup_internal(
+    path: &str,
+    operation: BackupOperation,
+) -> AppResult<Option<BackupRecord>> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let path_buf = policy
+        .resolve(&PathBuf::from(path))
+        .map_err(|e| e.to_string())?;
+
+    if !path_buf.is_file() {
+        return Ok(None);
     }
 
-    let file_name = path_buf
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("unknown");
-
-    let backup_name = generate_backup_name(file_name);
-    let backup_path = backup_dir.join(&backup_name);
-
-    fs::copy(&path_buf, &backup_path).map_err(|e| format!("Failed to create backup: {e}"))?;
-
-    Ok(backup_path.to_string_lossy().to_string())
+    let relative = path_buf
+        .strip_prefix(policy.root())
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    backup_store::store_backup(&path_buf, &relative, operation).map(Some)
 }
 
 /// バックアップ一覧を取得
 ///
 /// # Returns
 ///
-/// バックアップファイルの一覧（新しい順）
+/// バックアップの一覧（新しい順）と、論理サイズ・物理サイズ
 #[tauri::command]
-pub fn get_backups() -> AppResult<Vec<FileNode>> {
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-    let backup_dir = claude_dir.join("backups");
-
-    if !backup_dir.exists() {
-        return Ok(Vec::new());
-    }
-
-    let mut backups: Vec<FileNode> = Vec::new();
-
-    if let Ok(entries) = fs::read_dir(&backup_dir) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_file() {
-                let name = path
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
+pub fn get_backups() -> AppResult<BackupList> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let backups = backup_store::list_backups()?;
+    let usage = backup_store::usage()?;
+
+    Ok(BackupList {
+        root: policy.root().to_string_lossy().to_string(),
+        backups,
+        logical_size: usage.logical_size,
+        physical_size: usage.physical_size,
+        object_count: usage.object_count,
+    })
+}
 
-                backups.push(FileNode::new_file(name, path.to_string_lossy().to_string()));
-            }
-        }
+/// 指定したファイルのバックアップ一覧を取得
+///
+/// # Arguments
+///
+/// * `path` - 対象ファイルのパス（絶対パスまたは設定ルートからの相対パス）
+///
+/// # Returns
+///
+/// 指定したファイルのバックアップの一覧（新しい順）
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_backups_for(path: String) -> AppResult<Vec<BackupRecord>> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let resolved = if Path::new(&path).is_absolute() {
+        policy.resolve(Path::new(&path))
+    } else {
+        policy.resolve_relative(&path)
     }
+    .map_err(|e| e.to_string())?;
+    let relative = resolved
+        .strip_prefix(policy.root())
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    backup_store::list_backups_for(&relative)
+}
 
-    // 新しい順にソート
-    backups.sort_by(|a, b| b.name.cmp(&a.name));
-
-    Ok(backups)
+/// バックアップの内容を取得
+///
+/// # Arguments
+///
+/// * `backup_id` - バックアップID
+///
+/// # Returns
+///
+/// バックアップの内容
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_backup_content(backup_id: String) -> AppResult<String> {
+    let (_, bytes) = backup_store::read_backup(&backup_id)?;
+    String::from_utf8(bytes).map_err(|e| format!("Backup is not valid UTF-8: {e}"))
 }
 
-/// バックアップを復元
+/// バックアップIDを指定して元のパスに復元
+///
+/// 復元前に現在のファイルをバックアップします（操作は `restore` として記録）。
 ///
 /// # Arguments
 ///
-/// * `backup_path` - 復元するバックアップファイルのパス
-/// * `target_path` - 復元先のパス
+/// * `backup_id` - バックアップID
+///
+/// # Returns
+///
+/// 復元したファイルの絶対パス
 ///
 /// # Security
 ///
-/// バックアップはbackupsディレクトリから、復元先は~/.claude/配下のみ許可
+/// 復元先は記録された相対パスから解決し、~/.claude/配下のみ許可
 #[tauri::command]
-pub fn restore_backup(backup_path: String, target_path: String) -> AppResult<()> {
-    let backup_buf = PathBuf::from(&backup_path);
-    let target_buf = PathBuf::from(&target_path);
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-
-    // セキュリティチェック
-    if !backup_buf.starts_with(claude_dir.join("backups")) {
-        return Err("Invalid backup path".to_string());
-    }
-    validate_path_security(&target_buf, &claude_dir).map_err(|e| e.to_string())?;
+#[allow(clippy::needless_pass_by_value)]
+pub fn restore_backup_by_id(backup_id: String) -> AppResult<String> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let (record, bytes) = backup_store::read_backup(&backup_id)?;
+    let target = policy
+        .resolve_relative(&record.relative_path)
+        .map_err(|e| e.to_string())?;
+
+    restore_bytes(&target, &bytes)?;
+    git_history::record_change_or_warn(
+        std::slice::from_ref(&target),
+        &format!("Restore {} from backup {backup_id}", record.relative_path),
+    );
+
+    info!("Restored backup {backup_id} to {}", record.relative_path);
+    Ok(target.to_string_lossy().to_string())
+}
 
-    // 現在のファイルをバックアップ
-    create_backup(&target_path)?;
+/// 現在のファイルをバックアップしてから内容を書き込む
+fn restore_bytes(target: &Path, bytes: &[u8]) -> AppResult<()> {
+    create_backup_internal(&target.to_string_lossy(), BackupOperation::Restore)?;
 
-    fs::copy(&backup_buf, &target_buf).map_err(|e| format!("Failed to restore backup: {e}"))?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory: {e}"))?;
+    }
+    write_file_atomic(target, bytes).map_err(|e| format!("Failed to restore backup: {e}"))
+}
 
-    Ok(())
+/// バックアップのピン留めを設定
+///
+/// ピン留めされたバックアップは保持ポリシーやクリーンアップで削除されません。
+///
+/// # Arguments
+///
+/// * `backup_id` - バックアップID
+/// * `pinned` - ピン留めするかどうか
+///
+/// # Returns
+///
+/// 更新後のバックアップのメタデータ
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn set_backup_pinned(backup_id: String, pinned: bool) -> AppResult<BackupRecord> {
+    backup_store::set_pinned(&backup_id, pinned)
 }
 
 /// 古いバックアップをクリーンアップ
 ///
-/// 指定日数以上古いバックアップファイルを削除します。
+/// 指定日数以上古いバックアップを削除します（ピン留めされたバックアップを除く）。
 ///
 /// # Arguments
 ///
-/// * `days` - この日数より古いファイルを削除
+/// * `days` - この日数より古いバックアップを削除
 ///
 /// # Returns
 ///
-/// クリーンアップ結果（削除数、サイズ、エラー）
+/// クリーンアップ結果（削除数、解放したサイズ、エラー）
 #[tauri::command]
 pub fn cleanup_old_backups(days: u32) -> AppResult<CleanupResult> {
     cleanup_old_backups_internal(days, true)
@@ -139,107 +224,138 @@ pub fn cleanup_old_backups(days: u32) -> AppResult<CleanupResult> {
 
 /// 内部用クリーンアップ関数
 ///
+/// 古いバックアップの記録を削除し、どの記録からも参照されなくなった内容を削除します。
+///
 /// # Arguments
 ///
-/// * `days` - この日数より古いファイルを削除
+/// * `days` - この日数より古いバックアップを削除
 /// * `verbose` - ログ出力を行うかどうか
 pub fn cleanup_old_backups_internal(days: u32, verbose: bool) -> AppResult<CleanupResult> {
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-    let backup_dir = claude_dir.join("backups");
+    let threshold = Local::now() - Duration::days(i64::from(days));
 
-    let mut result = CleanupResult::default();
+    let outcome = backup_store::remove_backups(|record| {
+        !record.pinned
+            && DateTime::parse_from_rfc3339(&record.created_at)
+                .is_ok_and(|created| created < threshold)
+    })?;
 
-    if !backup_dir.exists() {
-        if verbose {
-            info!("Backup directory does not exist, nothing to clean up");
-        }
-        return Ok(result);
-    }
+    Ok(finish_cleanup(outcome, verbose))
+}
+
+/// 保持ポリシーの適用結果をプレビュー（ドライラン）
+///
+/// ダッシュボード設定の `backupRetention` を適用した場合に削除されるバックアップを返します。
+/// バックアップは削除しません。
+///
+/// # Returns
+///
+/// 削除対象のバックアップ（古い順）と、適用前後の物理サイズ
+#[tauri::command]
+pub fn preview_backup_retention() -> AppResult<RetentionPlan> {
+    plan_with_policy(&retention_policy()?)
+}
 
-    let threshold = SystemTime::now()
-        .checked_sub(Duration::from_secs(u64::from(days) * 24 * 60 * 60))
-        .ok_or("Failed to calculate threshold time")?;
+/// ダッシュボード設定の保持ポリシー
+///
+/// 設定ファイルを読み込めない場合、既定値のポリシーで意図しないバックアップを削除しないよう
+/// 保持ポリシーを適用しません。
+///
+/// # Errors
+///
+/// ダッシュボード設定の読み込みに失敗している場合はエラーを返します。
+fn retention_policy() -> AppResult<RetentionPolicy> {
+    let (resolved, error) = config::current_with_status();
+    if let Some(e) = error {
+        return Err(format!("Backup retention is skipped: {e}"));
+    }
+    Ok(resolved.config.backup_retention.clone())
+}
 
-    let entries = fs::read_dir(&backup_dir)
-        .map_err(|e| format!("Failed to read backup directory: {e}"))?;
+/// 保持ポリシーを適用
+///
+/// ダッシュボード設定の `backupRetention` に従ってバックアップを削除します。
+///
+/// # Returns
+///
+/// クリーンアップ結果（削除数、解放したサイズ、エラー）
+///
+/// # Errors
+///
+/// ダッシュボード設定の読み込みに失敗している場合はエラーを返します（何も削除しない）。
+#[tauri::command]
+pub fn apply_backup_retention() -> AppResult<CleanupResult> {
+    apply_backup_retention_internal(true)
+}
 
-    for entry in entries.filter_map(|e| e.ok()) {
-        let path = entry.path();
+/// 内部用の保持ポリシー適用関数
+///
+/// # Arguments
+///
+/// * `verbose` - ログ出力を行うかどうか
+pub fn apply_backup_retention_internal(verbose: bool) -> AppResult<CleanupResult> {
+    let plan = plan_with_policy(&retention_policy()?)?;
+    let ids: HashSet<&str> = plan.delete.iter().map(|c| c.record.id.as_str()).collect();
+
+    // 計画後にピン留めされたバックアップは削除しない
+    let outcome = if ids.is_empty() {
+        RemovalOutcome::default()
+    } else {
+        backup_store::remove_backups(|record| !record.pinned && ids.contains(record.id.as_str()))?
+    };
+
+    Ok(finish_cleanup(outcome, verbose))
+}
 
-        if !path.is_file() {
-            continue;
-        }
+/// 現在のバックアップに保持ポリシーを適用した場合の結果を求める
+fn plan_with_policy(policy: &RetentionPolicy) -> AppResult<RetentionPlan> {
+    let records = backup_store::list_backups()?;
+    let object_sizes = backup_store::object_sizes()?;
+    Ok(plan_retention(&records, &object_sizes, policy, Local::now()))
+}
 
-        // ファイルの更新日時を取得
-        let metadata = match fs::metadata(&path) {
-            Ok(m) => m,
-            Err(e) => {
-                if verbose {
-                    result
-                        .errors
-                        .push(format!("Failed to get metadata for {}: {e}", path.display()));
-                }
-                continue;
-            }
-        };
-
-        let modified = match metadata.modified() {
-            Ok(t) => t,
-            Err(e) => {
-                if verbose {
-                    result.errors.push(format!(
-                        "Failed to get modification time for {}: {e}",
-                        path.display()
-                    ));
-                }
-                continue;
-            }
-        };
-
-        // 古いファイルを削除
-        if modified < threshold {
-            let file_size = metadata.len();
-            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
-
-            match fs::remove_file(&path) {
-                Ok(()) => {
-                    result.deleted_count += 1;
-                    result.deleted_size_bytes += file_size;
-                    if verbose {
-                        info!("Deleted old backup: {file_name} ({file_size} bytes)");
-                    }
-                }
-                Err(e) => {
-                    if verbose {
-                        result
-                            .errors
-                            .push(format!("Failed to delete {}: {e}", path.display()));
-                        warn!("Failed to delete old backup {file_name}: {e}");
-                    }
-                }
-            }
-        }
+/// 削除結果をクリーンアップ結果に変換
+///
+/// 内容（オブジェクト）が失われた記録もマニフェストから取り除き、
+/// 取り除いた記録を `pruned_records` として結果に含めます。
+fn finish_cleanup(outcome: RemovalOutcome, verbose: bool) -> CleanupResult {
+    let mut result = CleanupResult {
+        deleted_count: outcome.removed.len() as u32,
+        deleted_size_bytes: outcome.freed_bytes,
+        errors: outcome.errors,
+        pruned_records: Vec::new(),
+    };
+
+    match backup_store::prune_missing_records() {
+        Ok(pruned) => result.pruned_records = pruned,
+        Err(e) => result.errors.push(e),
     }
 
     if verbose {
+        for record in &result.pruned_records {
+            warn!(
+                "Removed backup record {} of {}: its content is missing",
+                record.id, record.relative_path
+            );
+        }
+        for error in &result.errors {
+            warn!("Backup cleanup error: {error}");
+        }
         info!(
-            "Backup cleanup completed: {} files deleted, {} bytes freed, {} errors",
+            "Backup cleanup completed: {} backups deleted, {} bytes freed, {} errors",
             result.deleted_count,
             result.deleted_size_bytes,
             result.errors.len()
         );
     }
 
-    Ok(result)
+    result
 }
 
 /// アプリ起動時のバックアップクリーンアップ
 ///
-/// デフォルトで30日以上古いバックアップを削除します。
+/// ダッシュボード設定の保持ポリシーを適用し、30日以上前のゴミ箱のエントリを削除します。
 pub fn perform_startup_cleanup() {
-    const DEFAULT_DAYS: u32 = 30;
-
-    match cleanup_old_backups_internal(DEFAULT_DAYS, false) {
+    match apply_backup_retention_internal(false) {
         Ok(result) => {
             if result.deleted_count > 0 {
                 info!(
@@ -249,7 +365,96 @@ pub fn perform_startup_cleanup() {
             }
         }
         Err(e) => {
-            warn!("Startup cleanup failed: {e}");
+            warn!("Startup backup retention skipped: {e}");
         }
     }
+
+    match empty_trash(Some(TRASH_RETENTION_DAYS)) {
+        Ok(result) => {
+            if result.deleted_count > 0 {
+                info!(
+                    "Startup cleanup: deleted {} old trash entries ({} bytes)",
+                    result.deleted_count, result.deleted_size_bytes
+                );
+            }
+        }
+        Err(e) => {
+            warn!("Trash cleanup failed: {e}");
+        }
+    }
+}
+
+/// 保持ポリシーを定期的に適用するスレッドを起動
+///
+/// 適用間隔（`backupRetention.intervalHours`）は毎回ダッシュボード設定から読み込むため、
+/// 設定の変更は再起動せずに反映されます。未設定または `0` の場合、設定ファイルを読み込めない場合は適用しません。
+/// 複数回呼び出しても起動するスレッドは1つだけです。
+pub fn start_retention_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(|| {
+        let mut last_run = Instant::now();
+        let mut config_error: Option<String> = None;
+        loop {
+            std::thread::sleep(SCHEDULER_TICK);
+
+            // 設定ファイルを読み込めない間は既定値のポリシーで削除しない（警告は変化した時のみ）
+            let (resolved, error) = config::current_with_status();
+            if error != config_error {
+                if let Some(e) = &error {
+                    warn!("Scheduled backup retention skipped: {e}");
+                }
+                config_error = error;
+            }
+            if config_error.is_some() {
+                continue;
+            }
+            let Some(interval) = resolved.config.backup_retention.interval() else {
+                continue;
+            };
+            if last_run.elapsed() < interval {
+                continue;
+            }
+            last_run = Instant::now();
+
+            match apply_backup_retention_internal(false) {
+                Ok(result) if result.deleted_count > 0 => info!(
+                    "Scheduled cleanup: deleted {} old backups ({} bytes)",
+                    result.deleted_count, result.deleted_size_bytes
+                ),
+                Ok(_) => {}
+                Err(e) => warn!("Scheduled cleanup failed: {e}"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DASHBOARD_CONFIG_FILE;
+
+    #[test]
+    fn test_retention_is_skipped_when_config_is_malformed() {
+        let root = crate::utils::TestClaudeDir::new();
+        // 既定のポリシー（30日）なら削除される古いバックアップ
+        root.write("backups/CLAUDE.md_20200101_120000", "# old");
+        root.write(
+            DASHBOARD_CONFIG_FILE,
+            r#"{"backupRetention": {"keepLast": 10,}"#,
+        );
+        config::invalidate();
+
+        assert!(apply_backup_retention()
+            .unwrap_err()
+            .contains(DASHBOARD_CONFIG_FILE));
+        assert!(preview_backup_retention().is_err());
+        assert_eq!(backup_store::list_backups().unwrap().len(), 1);
+
+        fs::write(root.path().join(DASHBOARD_CONFIG_FILE), "{}").unwrap();
+        config::invalidate();
+        assert_eq!(apply_backup_retention().unwrap().deleted_count, 1);
+    }
 }
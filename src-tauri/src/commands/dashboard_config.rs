@@ -0,0 +1,100 @@
+//! ダッシュボード設定コマンド
+//!
+//! 除外glob・許可する拡張子・エディタモードなどのダッシュボード設定の取得と保存を提供します。
+//! 設定データは ~/.claude/dashboard-config.json に保存されます。
+
+use crate::config::{self, DashboardConfig, ResolvedConfig};
+use crate::error::AppResult;
+use crate::search_index::request_full_sync;
+use crate::security::resolve_path;
+use crate::utils::write_file_atomic;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// ダッシュボード設定の取得結果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardConfigInfo {
+    /// 現在有効な設定（読み込みに失敗した場合はデフォルト値）
+    pub config: DashboardConfig,
+    /// 設定ファイルのパス
+    pub path: String,
+    /// 設定ファイルが存在するか
+    pub exists: bool,
+    /// 読み込みエラー（デフォルト値を使用中の場合）
+    pub error: Option<String>,
+}
+
+/// ダッシュボード設定を取得
+///
+/// # Returns
+///
+/// 現在有効な設定と設定ファイルの状態
+#[tauri::command]
+pub fn get_dashboard_config() -> AppResult<DashboardConfigInfo> {
+    let path = config::get_config_file_path()
+        .ok_or_else(|| "Failed to resolve dashboard config path".to_string())?;
+    let (resolved, error) = config::current_with_status();
+
+    Ok(DashboardConfigInfo {
+        config: resolved.config.clone(),
+        exists: path.exists(),
+        path: path.to_string_lossy().to_string(),
+        error,
+    })
+}
+
+/// ダッシュボード設定を保存
+///
+/// 拡張子の表記を揃え（先頭の `.` を除去、小文字化）、除外globを検証してから保存します。
+/// 保存した設定は直ちにファイルツリー・検索・エクスポート・インポート・統計に反映されます。
+///
+/// # Arguments
+///
+/// * `config` - 保存する設定
+///
+/// # Returns
+///
+/// 正規化後の保存された設定
+///
+/// # Errors
+///
+/// 不正なglobが含まれている場合や、書き込みに失敗した場合はエラーを返します。
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn save_dashboard_config(config: DashboardConfig) -> AppResult<DashboardConfig> {
+    let path = config::get_config_file_path()
+        .ok_or_else(|| "Failed to resolve dashboard config path".to_string())?;
+    let resolved = ResolvedConfig::new(config)?;
+
+    let json = serde_json::to_string_pretty(&resolved.config)
+        .map_err(|e| format!("Failed to serialize dashboard config: {e}"))?;
+    write_file_atomic(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to save dashboard config: {e}"))?;
+    config::invalidate();
+    request_full_sync();
+
+    info!("Saved dashboard config: {}", path.display());
+    Ok(resolved.config)
+}
+
+/// ファイルのエディタモードを取得
+///
+/// # Arguments
+///
+/// * `path` - 対象ファイルのパス
+///
+/// # Returns
+///
+/// `editorModes` に基づくエディタモード（未設定の拡張子は `plaintext`）
+///
+/// # Security
+///
+/// ~/.claude/ 配下のパスのみ受け付けます。
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_editor_mode(path: String) -> AppResult<String> {
+    let resolved = resolve_path(&PathBuf::from(&path)).map_err(|e| e.to_string())?;
+    Ok(config::current().editor_mode(&resolved))
+}
@@ -0,0 +1,286 @@
+//! 差分操作コマンド
+//!
+//! 現在のファイル・バックアップ・スナップショット・Gitの変更履歴・ZIPのエントリ・任意のテキストの
+//! いずれか2つを比較し、ハンクまたは統合diff形式の差分を返します。
+//! 比較した内容のうち選択したハンクのみを現在のファイルに戻すこともできます。
+
+use crate::archive;
+use crate::backup_store;
+use crate::commands::backup::create_backup_internal;
+use crate::commands::files::check_expected_version;
+use crate::diff_engine::{self, DEFAULT_CONTEXT_LINES};
+use crate::error::AppResult;
+use crate::git_history;
+use crate::security::AccessPolicy;
+use crate::snapshot_store;
+use crate::types::{
+    BackupOperation, DiffOptions, DiffResult, DiffSource, FileVersion, WriteResult,
+};
+use crate::utils::{
+    content_version_token, modified_millis, normalize_claude_path, write_file_atomic,
+};
+use log::info;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 差分の比較に読み込む内容の上限（バイト）
+const MAX_DIFF_SOURCE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// 比較する現在のファイルのパスを検証
+///
+/// 設定ルートからの相対パス、`~/.claude/` で始まるパス、設定ルート配下の絶対パスを受け付けます。
+fn resolve_current_path(policy: &AccessPolicy, path: &str) -> AppResult<PathBuf> {
+    let resolved = if Path::new(path).is_absolute() || path.starts_with("~/") {
+        let normalized = normalize_claude_path(path, policy.root())
+            .ok_or_else(|| "パスの正規化に失敗しました".to_string())?;
+        policy.resolve(&normalized)
+    } else {
+        policy.resolve_relative(path)
+    };
+    resolved.map_err(|e| e.to_string())
+}
+
+/// 読み込んだ内容をUTF-8のテキストに変換
+fn into_text(bytes: Vec<u8>, label: &str) -> AppResult<String> {
+    if bytes.len() as u64 > MAX_DIFF_SOURCE_BYTES {
+        return Err(format!("{label} is too large to compare"));
+    }
+    String::from_utf8(bytes).map_err(|_| format!("{label} is not a UTF-8 text file"))
+}
+
+/// 比較する内容と表示名を読み込む
+fn load_source(policy: &AccessPolicy, source: &DiffSource) -> AppResult<(String, String)> {
+    match source {
+        DiffSource::Current { path } => {
+            let resolved = resolve_current_path(policy, path)?;
+            let label = git_history::display_path(&resolved);
+            if !resolved.exists() {
+                return Ok((String::new(), format!("{label} (missing)")));
+            }
+            let size = fs::metadata(&resolved).map(|m| m.len()).unwrap_or(0);
+            if size > MAX_DIFF_SOURCE_BYTES {
+                return Err(format!("{label} is too large to compare"));
+            }
+            let bytes = fs::read(&resolved).map_err(|e| format!("Failed to read {label}: {e}"))?;
+            let content = into_text(bytes, &label)?;
+            Ok((content, label))
+        }
+        DiffSource::Backup { backup_id } => {
+            let (record, bytes) = backup_store::read_backup(backup_id)?;
+            let label = format!("{} (backup {backup_id})", record.relative_path);
+            Ok((into_text(bytes, &label)?, label))
+        }
+        DiffSource::Snapshot { snapshot_id, path } => {
+            let (info, files) = snapshot_store::load_snapshot(snapshot_id)?;
+            let label = format!(
+                "{path} (snapshot {})",
+                info.name.as_deref().unwrap_or(&info.id)
+            );
+            let Some(file) = files.iter().find(|f| f.relative_path == *path) else {
+                return Ok((String::new(), format!("{label} (missing)")));
+            };
+            let bytes = snapshot_store::read_file_content(file)?;
+            Ok((into_text(bytes, &label)?, label))
+        }
+        DiffSource::GitRevision { revision, path } => {
+            let (bytes, commit) = git_history::read_file_at(path, revision)?;
+            let label = format!("{path} ({})", commit.short_id);
+            Ok((into_text(bytes, &label)?, label))
+        }
+        DiffSource::ZipEntry { zip_path, entry } => {
+            let archive_name = Path::new(zip_path)
+                .file_name()
+                .map_or_else(|| zip_path.clone(), |n| n.to_string_lossy().to_string());
+            let label = format!("{archive_name}:{entry}");
+
+            let bytes = archive::read_entry(zip_path, entry, MAX_DIFF_SOURCE_BYTES)
+                .map_err(|e| format!("{label}: {e}"))?;
+            Ok((into_text(bytes, &label)?, label))
+        }
+        DiffSource::Text { content, label } => Ok((
+            content.clone(),
+            label.clone().unwrap_or_else(|| "(text)".to_string()),
+        )),
+    }
+}
+
+/// 2つの内容の差分を計算
+///
+/// # Arguments
+///
+/// * `old` - 比較元
+/// * `new` - 比較先
+/// * `options` - 出力形式・前後の行数・JSONとして比較するか（省略時は構造化したハンク）
+///
+/// # Returns
+///
+/// ハンク・統計と、指定に応じて統合diff形式のテキストやJSONの値の変更
+///
+/// # Security
+///
+/// 現在のファイルは~/.claude/配下のみ比較できます。
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn compute_diff(
+    old: DiffSource,
+    new: DiffSource,
+    options: Option<DiffOptions>,
+) -> AppResult<DiffResult> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let (old_content, old_label) = load_source(&policy, &old)?;
+    let (new_content, new_label) = load_source(&policy, &new)?;
+
+    Ok(diff_engine::diff_texts(
+        &old_content,
+        &new_content,
+        &old_label,
+        &new_label,
+        &options.unwrap_or_default(),
+    ))
+}
+
+/// 選択したハンクのみを比較元の内容に戻す
+///
+/// `compute_diff` で比較元に `source`、比較先に現在のファイルを指定した場合の
+/// ハンクの番号を指定します（テキストとしての比較、同じ `context_lines`）。
+/// 書き込み前に現在のファイルをバックアップします（操作は `restore` として記録）。
+///
+/// # Arguments
+///
+/// * `path` - 戻すファイルのパス
+/// * `source` - 戻す内容の取得元（バックアップなど）
+/// * `hunks` - 戻すハンクの番号
+/// * `context_lines` - 差分を計算した際の前後の行数（省略時は3行）
+/// * `expected_version` - 差分を計算した時点のバージョントークン（指定時は変更を検出）
+///
+/// # Returns
+///
+/// 書き込み後のバージョン（`WriteResult::Written`）。
+/// 差分の計算後にファイルが変更されていた場合は書き込まずに `WriteResult::Conflict` を返します。
+///
+/// # Errors
+///
+/// 存在しないハンクが指定された場合はエラーを返します。
+///
+/// # Security
+///
+/// ~/.claude/ 配下のファイルのみ変更できます。
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn restore_diff_hunks(
+    path: String,
+    source: DiffSource,
+    hunks: Vec<usize>,
+    context_lines: Option<usize>,
+    expected_version: Option<String>,
+) -> AppResult<WriteResult> {
+    if hunks.is_empty() {
+        return Err("No hunks selected".to_string());
+    }
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let target = resolve_current_path(&policy, &path)?;
+    if let Some(expected_version) = expected_version {
+        if let Some(conflict) = check_expected_version(&target, &path, expected_version) {
+            return Ok(WriteResult::Conflict(conflict));
+        }
+    }
+
+    let (source_content, source_label) = load_source(&policy, &source)?;
+    let (current_content, label) =
+        load_source(&policy, &DiffSource::Current { path: path.clone() })?;
+    let selected: HashSet<usize> = hunks.into_iter().collect();
+    let restored = diff_engine::revert_hunks(
+        &source_content,
+        &current_content,
+        context_lines.unwrap_or(DEFAULT_CONTEXT_LINES),
+        &selected,
+    )?;
+
+    create_backup_internal(&target.to_string_lossy(), BackupOperation::Restore)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory: {e}"))?;
+    }
+    write_file_atomic(&target, restored.as_bytes())
+        .map_err(|e| format!("Failed to write file: {e}"))?;
+
+    info!(
+        "Restored {} hunk(s) of {label} from {source_label}",
+        selected.len()
+    );
+    git_history::record_change_or_warn(
+        std::slice::from_ref(&target),
+        &format!(
+            "Restore {} hunk(s) of {label} from {source_label}",
+            selected.len()
+        ),
+    );
+
+    Ok(WriteResult::Written(FileVersion {
+        version_token: content_version_token(restored.as_bytes()),
+        modified: fs::metadata(&target).ok().and_then(|m| modified_millis(&m)),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestClaudeDir;
+
+    #[test]
+    fn test_restore_selected_hunks_from_backup() {
+        let dir = TestClaudeDir::new();
+        let path = dir.path().join("CLAUDE.md");
+        let original: String = (1..=12).map(|i| format!("line {i}\n")).collect();
+        fs::write(&path, &original).unwrap();
+        let backup = create_backup_internal(&path.to_string_lossy(), BackupOperation::Write)
+            .unwrap()
+            .unwrap();
+
+        let edited = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "line eleven\n");
+        fs::write(&path, &edited).unwrap();
+
+        let source = DiffSource::Backup {
+            backup_id: backup.id.clone(),
+        };
+        let current = DiffSource::Current {
+            path: "CLAUDE.md".to_string(),
+        };
+        let diff = compute_diff(source.clone(), current, None).unwrap();
+        assert_eq!(diff.stats.hunks, 2);
+        assert_eq!(diff.new_label, "CLAUDE.md");
+
+        // 古いバージョントークンでは書き込まない
+        let stale = restore_diff_hunks(
+            "CLAUDE.md".to_string(),
+            source.clone(),
+            vec![1],
+            None,
+            Some(content_version_token(original.as_bytes())),
+        );
+        assert!(matches!(stale, Ok(WriteResult::Conflict(_))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+
+        let result = restore_diff_hunks(
+            "CLAUDE.md".to_string(),
+            source,
+            vec![1],
+            None,
+            Some(content_version_token(edited.as_bytes())),
+        )
+        .unwrap();
+        let WriteResult::Written(version) = result else {
+            panic!("書き込まれませんでした");
+        };
+        let restored = fs::read_to_string(&path).unwrap();
+        assert_eq!(restored, original.replace("line 2\n", "line two\n"));
+        assert_eq!(
+            version.version_token,
+            content_version_token(restored.as_bytes())
+        );
+    }
+}
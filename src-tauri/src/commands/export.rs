@@ -1,21 +1,34 @@
 //! エクスポート操作コマンド
 //!
 //! ファイルのエクスポート機能を提供します。
-//! 単一ファイルのエクスポートとZIPによる一括エクスポートに対応。
-
+//! 単一ファイルのエクスポートと、アーカイブ（ZIP・tar.gz・ローカルフォルダー）による
+//! 一括・選択したファイルのエクスポートに対応。
+//! アーカイブにはファイル一覧とハッシュを記録した `.ccsd-export.json` を含めます。
+//! APIトークンなどの秘密情報はデフォルトでプレースホルダーに置き換え、マニフェストに記録します。
+//! アーカイブのエクスポートは進捗の通知とキャンセルに対応したバックグラウンドジョブとしても実行できます。
+
+use crate::archive::{self, ArchiveWriter};
+use crate::commands::replace::{build_glob_set, collect_target_files};
+use crate::commands::version::get_claude_version;
 use crate::error::AppResult;
+use crate::jobs::{self, JobContext};
+use crate::redaction::Redactor;
+use crate::security::resolve_path;
+use crate::types::{
+    ArchiveFormat, ExportManifest, ExportManifestEntry, ExportSelection, FileKind, JobKind,
+    RedactedSecret,
+};
 use crate::utils::{
-    get_claude_dir, is_allowed_extension, is_excluded_path, validate_path_security,
-    ALLOWED_EXTENSIONS, EXCLUDED_DIRS,
+    classify_file_kind, content_version_token, extract_frontmatter_list, get_claude_dir,
+    is_allowed_extension, is_excluded_relative_path,
 };
+use chrono::Local;
 use log::info;
-use std::fs::File;
-use std::io::{Read, Write};
-use std::path::PathBuf;
-use std::{fs, io};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
 use walkdir::WalkDir;
-use zip::write::SimpleFileOptions;
-use zip::ZipWriter;
 
 /// 単一ファイルをエクスポート
 ///
@@ -29,12 +42,10 @@ use zip::ZipWriter;
 /// ソースファイルは~/.claude/配下のみ許可されます。
 #[tauri::command]
 pub fn export_file(source_path: String, dest_path: String) -> AppResult<()> {
-    let source_buf = PathBuf::from(&source_path);
     let dest_buf = PathBuf::from(&dest_path);
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
 
     // セキュリティチェック
-    validate_path_security(&source_buf, &claude_dir).map_err(|e| e.to_string())?;
+    let source_buf = resolve_path(&PathBuf::from(&source_path)).map_err(|e| e.to_string())?;
 
     if !source_buf.exists() {
         return Err("Source file does not exist".to_string());
@@ -54,110 +65,282 @@ pub fn export_file(source_path: String, dest_path: String) -> AppResult<()> {
     Ok(())
 }
 
-/// ~/.claude/ 全体をZIPでエクスポート
+/// エクスポートしたアーカイブに含めるマニフェストのファイル名（アーカイブのルート）
+///
+/// 設定ルート直下の `manifest.json` などのユーザーのファイルと衝突しない名前にしています。
+pub const EXPORT_MANIFEST_FILE: &str = ".ccsd-export.json";
+
+/// 以前のバージョンがアーカイブのルートに作成していたマニフェストのファイル名
+pub const LEGACY_EXPORT_MANIFEST_FILE: &str = "manifest.json";
+
+/// 以前のバージョンのマニフェストか判定（`formatVersion` を含むJSONオブジェクト）
+///
+/// ユーザーの `manifest.json` と区別するために使用します。
+pub fn is_legacy_manifest(contents: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(contents)
+        .is_ok_and(|value| value.get("formatVersion").is_some())
+}
+
+/// マニフェストの形式のバージョン
+pub const EXPORT_MANIFEST_VERSION: u32 = 1;
+
+/// 出力先の形式を決定
+///
+/// 指定がない場合は出力先のパスから判定し、判定できない場合はZIPとします。
+fn output_format(dest_path: &str, format: Option<ArchiveFormat>) -> ArchiveFormat {
+    format
+        .or_else(|| archive::detect_format(Path::new(dest_path)))
+        .unwrap_or(ArchiveFormat::Zip)
+}
+
+/// ~/.claude/ 全体をアーカイブでエクスポート
+///
+/// 秘密情報はプレースホルダーに置き換えます（インポート時に値を指定して戻せます）。
 ///
 /// # Arguments
 ///
-/// * `dest_path` - ZIPファイルの出力先パス
+/// * `dest_path` - 出力先パス（ZIP・tar.gzファイル、またはフォルダー）
+/// * `format` - 形式（省略時は出力先のパスから判定し、判定できない場合はZIP）
 ///
 /// # Returns
 ///
 /// エクスポートしたファイル数
 #[tauri::command]
-pub fn export_all_zip(dest_path: String) -> AppResult<u32> {
+pub fn export_all_zip(dest_path: String, format: Option<ArchiveFormat>) -> AppResult<u32> {
+    let manifest = export_selected_zip(dest_path, ExportSelection::default(), format)?;
+    Ok(manifest.files.len() as u32)
+}
+
+/// 選択したファイルをアーカイブでエクスポート
+///
+/// アーカイブのルートに、ファイル一覧・SHA-256ハッシュ・ダッシュボードとClaude Codeのバージョン・
+/// エクスポート日時を記録した `.ccsd-export.json` を含めます。
+/// `redact_secrets` が有効な場合は、秘密情報を `${REDACTED:NAME}` に置き換えた内容を書き込み、
+/// 置き換えた位置と名前をマニフェストの `redactions` に記録します（値は記録しません）。
+/// フォルダーへのエクスポートでは、出力先の既存のファイルのうち同じパスのファイルのみ置き換えます。
+///
+/// # Arguments
+///
+/// * `dest_path` - 出力先パス（ZIP・tar.gzファイル、またはフォルダー）
+/// * `selection` - 対象にするglob・除くglob・ファイル種別・秘密情報を置き換えるか
+/// * `format` - 形式（省略時は出力先のパスから判定し、判定できない場合はZIP）
+///
+/// # Returns
+///
+/// アーカイブに含めたマニフェスト
+///
+/// # Security
+///
+/// ~/.claude/ 配下の、ダッシュボード設定で除外されていないファイルのみエクスポートします。
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_selected_zip(
+    dest_path: String,
+    selection: ExportSelection,
+    format: Option<ArchiveFormat>,
+) -> AppResult<ExportManifest> {
+    export_archive(
+        &dest_path,
+        output_format(&dest_path, format),
+        &selection,
+        &mut JobContext::new(JobKind::Export, None),
+    )
+}
+
+/// 選択したファイルをアーカイブでエクスポートするジョブを開始
+///
+/// 処理は別スレッドで実行し、進捗を `job:progress`、完了を `job:finished` イベントで通知します
+/// （完了時の結果はマニフェスト）。`cancel_job` でキャンセルした場合、出力先は作成されません。
+///
+/// # Arguments
+///
+/// * `dest_path` - 出力先パス（ZIP・tar.gzファイル、またはフォルダー）
+/// * `selection` - 対象にするglob・除くglob・ファイル種別（省略時はすべてのファイル）
+/// * `format` - 形式（省略時は出力先のパスから判定し、判定できない場合はZIP）
+///
+/// # Returns
+///
+/// ジョブID
+#[tauri::command]
+pub fn start_export_job(
+    app: AppHandle,
+    dest_path: String,
+    selection: Option<ExportSelection>,
+    format: Option<ArchiveFormat>,
+) -> AppResult<String> {
+    let format = output_format(&dest_path, format);
+    Ok(jobs::spawn(app, JobKind::Export, move |context| {
+        export_archive(&dest_path, format, &selection.unwrap_or_default(), context)
+    }))
+}
+
+/// 選択したファイルをアーカイブに書き出す
+///
+/// 出力先と同じディレクトリの一時ファイル（フォルダーの場合は一時フォルダー）に書き込み、
+/// 完了後に出力先へ移動します。キャンセルされた場合や失敗した場合は一時ファイルを削除し、出力先は変更しません。
+pub(crate) fn export_archive(
+    dest_path: &str,
+    format: ArchiveFormat,
+    selection: &ExportSelection,
+    context: &mut JobContext,
+) -> AppResult<ExportManifest> {
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
-    let dest_buf = PathBuf::from(&dest_path);
+    let dest_buf = PathBuf::from(dest_path);
 
     if !claude_dir.exists() {
         return Err("~/.claude directory not found".to_string());
     }
 
-    // 親ディレクトリが存在しない場合は作成
-    if let Some(parent) = dest_buf.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create destination directory: {e}"))?;
-        }
-    }
-
-    // ZIPファイルを作成
-    let zip_file = File::create(&dest_buf).map_err(|e| format!("Failed to create ZIP file: {e}"))?;
-    let mut zip = ZipWriter::new(zip_file);
+    let files = select_export_files(&claude_dir, selection)?;
+    let total_bytes = files
+        .iter()
+        .filter_map(|(path, _)| fs::metadata(path).ok())
+        .map(|m| m.len())
+        .sum();
+    context.set_totals(files.len() as u32, total_bytes);
+
+    // 完了せずに破棄した場合（キャンセル・失敗）は一時ファイルを削除
+    let mut writer = ArchiveWriter::create(&dest_buf, format)?;
+    let manifest = write_archive(&mut writer, &files, selection, context)?;
+    writer.finish()?;
+
+    info!(
+        "Exported {} files as {format:?} ({} secret(s) redacted): {dest_path}",
+        manifest.files.len(),
+        manifest.redactions.len()
+    );
+    Ok(manifest)
+}
 
-    let options = SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated)
-        .unix_permissions(0o644);
+/// ファイルとマニフェストをアーカイブに書き込む
+fn write_archive(
+    writer: &mut ArchiveWriter,
+    files: &[(PathBuf, String)],
+    selection: &ExportSelection,
+    context: &mut JobContext,
+) -> AppResult<ExportManifest> {
+    let mut redactor = selection.redact_secrets.then(Redactor::new);
+    let mut entries = Vec::with_capacity(files.len());
+    for (path, relative) in files {
+        context.check_cancelled()?;
+
+        let mut contents =
+            fs::read(path).map_err(|e| format!("Failed to read file {}: {e}", path.display()))?;
+        let size = contents.len() as u64;
+        if let Some(redactor) = redactor.as_mut() {
+            contents = redactor.redact(relative, contents);
+        }
 
-    let file_count = write_directory_to_zip(&mut zip, &claude_dir, &claude_dir, options)?;
+        writer.add_file(relative, &contents)?;
 
-    zip.finish()
-        .map_err(|e| format!("Failed to finalize ZIP file: {e}"))?;
+        entries.push(manifest_entry(relative, &contents));
+        context.advance(relative, size);
+    }
 
-    info!("Exported {file_count} files to ZIP: {dest_path}");
-    Ok(file_count)
+    let redactions = redactor.map(Redactor::into_report).unwrap_or_default();
+    let manifest = build_manifest(entries, redactions);
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {e}"))?;
+    writer.add_file(EXPORT_MANIFEST_FILE, manifest_json.as_bytes())?;
+    Ok(manifest)
 }
 
-/// ディレクトリの内容をZIPに書き込む
-fn write_directory_to_zip<W: Write + io::Seek>(
-    zip: &mut ZipWriter<W>,
-    dir: &PathBuf,
-    base_dir: &PathBuf,
-    options: SimpleFileOptions,
-) -> AppResult<u32> {
-    let mut file_count: u32 = 0;
-
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        let path_str = path.to_string_lossy();
-
-        // 除外ディレクトリをスキップ
-        if is_excluded_path(&path_str) {
-            continue;
-        }
+/// 選択条件に一致するファイルを確認
+///
+/// # Arguments
+///
+/// * `selection` - 対象にするglob・除くglob・ファイル種別
+///
+/// # Returns
+///
+/// エクスポートされるファイル（相対パス順、サイズとハッシュは秘密情報を置き換えた後の内容）
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn preview_export_selection(selection: ExportSelection) -> AppResult<Vec<ExportManifestEntry>> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let mut redactor = selection.redact_secrets.then(Redactor::new);
+    select_export_files(&claude_dir, &selection)?
+        .iter()
+        .map(|(path, relative)| {
+            let contents = fs::read(path)
+                .map_err(|e| format!("Failed to read file {}: {e}", path.display()))?;
+            let contents = match redactor.as_mut() {
+                Some(redactor) => redactor.redact(relative, contents),
+                None => contents,
+            };
+            Ok(manifest_entry(relative, &contents))
+        })
+        .collect()
+}
 
-        // ディレクトリ名自体が除外対象かチェック
-        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if EXCLUDED_DIRS.contains(&name) && path.is_dir() {
-                continue;
+/// 選択条件に一致するファイルを列挙（相対パス順）
+fn select_export_files(
+    root: &Path,
+    selection: &ExportSelection,
+) -> AppResult<Vec<(PathBuf, String)>> {
+    let include = build_glob_set(&selection.include_globs)?;
+    let exclude = build_glob_set(&selection.exclude_globs)?;
+    let select_all = include.is_none() && selection.kinds.is_empty();
+
+    let candidates = collect_target_files(root, None, exclude.as_ref());
+    let mut selected: BTreeMap<String, PathBuf> = candidates
+        .iter()
+        .filter(|(_, relative)| {
+            select_all
+                || include.as_ref().is_some_and(|set| set.is_match(relative))
+                || selection
+                    .kinds
+                    .contains(&classify_file_kind(Path::new(relative)))
+        })
+        .map(|(path, relative)| (relative.clone(), path.clone()))
+        .collect();
+
+    if selection.include_referenced_skills {
+        let skills: HashSet<String> = selected
+            .iter()
+            .filter(|(relative, _)| classify_file_kind(Path::new(relative)) == FileKind::Agent)
+            .filter_map(|(_, path)| fs::read_to_string(path).ok())
+            .flat_map(|content| extract_frontmatter_list(&content, "skills"))
+            .collect();
+        for (path, relative) in candidates {
+            let skill = relative
+                .strip_prefix("skills/")
+                .and_then(|rest| rest.split('/').next());
+            if skill.is_some_and(|name| skills.contains(name)) {
+                selected.insert(relative, path);
             }
         }
+    }
 
-        // 相対パスを計算
-        let relative_path = path
-            .strip_prefix(base_dir)
-            .map_err(|e| format!("Failed to calculate relative path: {e}"))?;
-
-        let relative_str = relative_path.to_string_lossy();
-
-        if path.is_file() {
-            // 許可された拡張子のファイルのみエクスポート
-            if !is_allowed_extension(path) {
-                continue;
-            }
+    Ok(selected
+        .into_iter()
+        .map(|(relative, path)| (path, relative))
+        .collect())
+}
 
-            // ファイルをZIPに追加
-            zip.start_file(relative_str.to_string(), options)
-                .map_err(|e| format!("Failed to add file to ZIP: {e}"))?;
-
-            let mut file =
-                File::open(path).map_err(|e| format!("Failed to open file {path_str}: {e}"))?;
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer)
-                .map_err(|e| format!("Failed to read file {path_str}: {e}"))?;
-
-            zip.write_all(&buffer)
-                .map_err(|e| format!("Failed to write file to ZIP: {e}"))?;
-
-            file_count += 1;
-        } else if path.is_dir() && !relative_str.is_empty() {
-            // ディレクトリをZIPに追加
-            let dir_name = format!("{relative_str}/");
-            zip.add_directory(dir_name, options)
-                .map_err(|e| format!("Failed to add directory to ZIP: {e}"))?;
-        }
+/// マニフェストに記録するファイルの情報
+fn manifest_entry(relative: &str, contents: &[u8]) -> ExportManifestEntry {
+    ExportManifestEntry {
+        path: relative.to_string(),
+        kind: classify_file_kind(Path::new(relative)),
+        size: contents.len() as u64,
+        sha256: content_version_token(contents),
     }
+}
 
-    Ok(file_count)
+/// エクスポートのマニフェストを作成
+fn build_manifest(
+    files: Vec<ExportManifestEntry>,
+    redactions: Vec<RedactedSecret>,
+) -> ExportManifest {
+    ExportManifest {
+        format_version: EXPORT_MANIFEST_VERSION,
+        dashboard_version: env!("CARGO_PKG_VERSION").to_string(),
+        claude_code_version: get_claude_version().ok(),
+        exported_at: Local::now().to_rfc3339(),
+        files,
+        redactions,
+    }
 }
 
 /// エクスポート対象のファイル数を取得
@@ -172,23 +355,84 @@ pub fn get_export_file_count() -> AppResult<u32> {
 
     for entry in WalkDir::new(&claude_dir)
         .into_iter()
+        .filter_entry(|e| {
+            e.path()
+                .strip_prefix(&claude_dir)
+                .map_or(true, |rel| !is_excluded_relative_path(rel))
+        })
         .filter_map(|e| e.ok())
         .filter(|e| e.path().is_file())
     {
-        let path = entry.path();
-        let path_str = path.to_string_lossy();
-
-        // 除外ディレクトリをスキップ
-        if is_excluded_path(&path_str) {
-            continue;
-        }
-
         // 許可された拡張子のファイルのみカウント
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if ALLOWED_EXTENSIONS.contains(&ext) {
+        if is_allowed_extension(entry.path()) {
             count += 1;
         }
     }
 
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestClaudeDir;
+    use std::fs::File;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_export_selected_agents_with_referenced_skills() {
+        let dir = TestClaudeDir::new();
+        let root = dir.path();
+        for (path, content) in [
+            (
+                "agents/reviewer.md",
+                "---\nname: reviewer\nskills: lint\n---\nbody",
+            ),
+            ("agents/writer.md", "---\nname: writer\n---\n"),
+            ("skills/lint/SKILL.md", "lint"),
+            ("skills/lint/reference.md", "ref"),
+            ("skills/other/SKILL.md", "other"),
+            ("settings.json", "{}"),
+            ("CLAUDE.md", "memory"),
+        ] {
+            dir.write(path, content);
+        }
+
+        let selection = ExportSelection {
+            include_globs: vec!["agents/reviewer.md".to_string()],
+            kinds: vec![FileKind::Settings],
+            exclude_globs: vec!["**/reference.md".to_string()],
+            include_referenced_skills: true,
+            redact_secrets: false,
+        };
+        let dest = root.join("out/export.zip");
+        let manifest =
+            export_selected_zip(dest.to_string_lossy().to_string(), selection, None).unwrap();
+
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "agents/reviewer.md",
+                "settings.json",
+                "skills/lint/SKILL.md"
+            ]
+        );
+        assert_eq!(manifest.files[1].kind, FileKind::Settings);
+        assert_eq!(manifest.files[1].sha256, content_version_token(b"{}"));
+
+        // マニフェストはアーカイブのルートに含まれる
+        let mut archive = ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        assert_eq!(archive.len(), 4);
+        let mut json = String::new();
+        archive
+            .by_name(EXPORT_MANIFEST_FILE)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let stored: ExportManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(stored, manifest);
+        assert_eq!(stored.dashboard_version, env!("CARGO_PKG_VERSION"));
+    }
+}
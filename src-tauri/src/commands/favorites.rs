@@ -4,11 +4,11 @@
 //! お気に入りデータは ~/.claude/dashboard-favorites.json に保存されます。
 
 use crate::error::AppResult;
-use crate::utils::get_claude_dir;
+use crate::utils::{get_claude_dir, write_file_atomic};
 use log::info;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// お気に入りアイテム
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -56,12 +56,56 @@ fn save_favorites(data: &FavoritesData) -> Result<(), String> {
     let json = serde_json::to_string_pretty(data)
         .map_err(|e| format!("Failed to serialize favorites: {e}"))?;
 
-    fs::write(&file_path, json)
+    write_file_atomic(&file_path, json.as_bytes())
         .map_err(|e| format!("Failed to save favorites: {e}"))?;
 
     Ok(())
 }
 
+/// 移動・名前変更に合わせてお気に入りのパスを更新
+///
+/// `from` 自体、または `from` 配下のパスで登録されているアイテムを `to` 配下に置き換えます。
+///
+/// # Arguments
+///
+/// * `from` - 移動元のパス
+/// * `to` - 移動先のパス
+///
+/// # Returns
+///
+/// 更新したアイテム数
+pub(crate) fn update_favorite_paths(from: &Path, to: &Path) -> Result<usize, String> {
+    if !get_favorites_file_path()?.exists() {
+        return Ok(0);
+    }
+
+    let mut data = load_favorites()?;
+    let mut updated = 0;
+
+    for item in &mut data.favorites {
+        let Ok(relative) = Path::new(&item.path).strip_prefix(from) else {
+            continue;
+        };
+        let new_path = if relative.as_os_str().is_empty() {
+            // 対象自体が移動した場合は表示名も新しいファイル名に更新
+            if let Some(name) = to.file_name() {
+                item.name = name.to_string_lossy().to_string();
+            }
+            to.to_path_buf()
+        } else {
+            to.join(relative)
+        };
+        item.path = new_path.to_string_lossy().to_string();
+        updated += 1;
+    }
+
+    if updated > 0 {
+        save_favorites(&data)?;
+    }
+
+    Ok(updated)
+}
+
 /// お気に入り一覧を取得
 ///
 /// # Returns
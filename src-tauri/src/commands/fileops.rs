@@ -0,0 +1,645 @@
+//! ファイル管理コマンド
+//!
+//! ファイル/ディレクトリの削除、名前変更、移動、複製と、ゴミ箱からの復元を提供します。
+//! 削除したファイルや上書きされる移動先は `~/.claude/backups/trash/` に退避されるため、
+//! 誤操作しても `restore_from_trash` で元に戻せます。
+
+use crate::commands::favorites::update_favorite_paths;
+use crate::error::{AccessDeniedReason, AppError, AppResult};
+use crate::security::AccessPolicy;
+use crate::types::CleanupResult;
+use crate::utils::{get_claude_dir, is_excluded_relative_path, normalize_claude_path_strict};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// ゴミ箱のエントリ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    /// エントリID（`trash/<id>/` ディレクトリ名）
+    pub id: String,
+    /// 元の絶対パス
+    pub original_path: String,
+    /// ファイル/ディレクトリ名
+    pub name: String,
+    /// ディレクトリかどうか
+    pub is_directory: bool,
+    /// 削除日時（ISO 8601形式）
+    pub deleted_at: String,
+    /// 削除の理由（`delete` または `overwrite`）
+    pub reason: String,
+}
+
+/// ゴミ箱ディレクトリのパスを取得
+fn get_trash_dir() -> Result<PathBuf, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    Ok(claude_dir.join("backups").join("trash"))
+}
+
+/// フロントエンドから渡されたパスを検証して正規化
+///
+/// 設定ルート自体と除外ディレクトリ（backupsなど）配下は操作対象外です。
+fn resolve_target(policy: &AccessPolicy, path: &str) -> AppResult<PathBuf> {
+    // 設定ルートはシンボリックリンク経由の場合があるため、正規化前後の両方で判定
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let path_buf = normalize_claude_path_strict(path, &claude_dir)
+        .or_else(|| normalize_claude_path_strict(path, policy.root()))
+        .ok_or_else(|| {
+            AppError::AccessDenied(AccessDeniedReason::OutsideRoot(path.to_string())).to_string()
+        })?;
+    let resolved = policy.resolve(&path_buf).map_err(|e| e.to_string())?;
+
+    let relative = resolved
+        .strip_prefix(policy.root())
+        .map_err(|e| e.to_string())?;
+    if relative.as_os_str().is_empty() {
+        return Err(AppError::AccessDenied(AccessDeniedReason::Other(
+            "the config root itself cannot be modified".to_string(),
+        ))
+        .to_string());
+    }
+    if is_excluded_relative_path(relative) {
+        return Err(AppError::AccessDenied(AccessDeniedReason::Other(format!(
+            "files in excluded directories cannot be modified: {path}"
+        )))
+        .to_string());
+    }
+
+    Ok(resolved)
+}
+
+/// ファイル名として有効か検証（区切り文字や `.`/`..` を含まない）
+fn validate_file_name(name: &str) -> AppResult<()> {
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains('/')
+        || name.contains('\\')
+    {
+        return Err(format!("Invalid file name: {name}"));
+    }
+    Ok(())
+}
+
+/// ファイル/ディレクトリをゴミ箱に移動
+///
+/// エントリは `trash/<id>/<元の名前>` に移動され、メタデータは `trash/<id>.json` に保存されます。
+fn move_to_trash(path: &Path, reason: &str) -> AppResult<TrashEntry> {
+    let trash_dir = get_trash_dir()?;
+    fs::create_dir_all(&trash_dir).map_err(|e| format!("Failed to create trash directory: {e}"))?;
+
+    let now = chrono::Local::now();
+    let base_id = now.format("%Y%m%d_%H%M%S_%3f").to_string();
+    let mut id = base_id.clone();
+    let mut suffix = 1;
+    while trash_dir.join(&id).exists() {
+        id = format!("{base_id}_{suffix}");
+        suffix += 1;
+    }
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let entry_dir = trash_dir.join(&id);
+    fs::create_dir_all(&entry_dir).map_err(|e| format!("Failed to create trash entry: {e}"))?;
+
+    let entry = TrashEntry {
+        id: id.clone(),
+        original_path: path.to_string_lossy().to_string(),
+        name: name.clone(),
+        is_directory: path.is_dir(),
+        deleted_at: now.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+        reason: reason.to_string(),
+    };
+
+    move_path_internal(path, &entry_dir.join(&name))?;
+
+    let json = serde_json::to_string_pretty(&entry)
+        .map_err(|e| format!("Failed to serialize trash entry: {e}"))?;
+    fs::write(trash_dir.join(format!("{id}.json")), json)
+        .map_err(|e| format!("Failed to save trash entry: {e}"))?;
+
+    info!("Moved to trash: {} ({id})", path.display());
+    Ok(entry)
+}
+
+/// ファイル/ディレクトリを再帰的にコピー
+///
+/// 設定ルート外を指すシンボリックリンクはコピーしません。
+fn copy_recursive(policy: &AccessPolicy, from: &Path, to: &Path) -> AppResult<()> {
+    if from.is_file() {
+        fs::copy(from, to).map_err(|e| format!("Failed to copy file: {e}"))?;
+        return Ok(());
+    }
+
+    for entry in WalkDir::new(from).into_iter().filter_map(|e| e.ok()) {
+        let source = entry.path();
+        let relative = source.strip_prefix(from).map_err(|e| e.to_string())?;
+        let target = to.join(relative);
+
+        if entry.path_is_symlink() && policy.resolve(source).is_err() {
+            warn!("Skipped symlink outside the config root: {}", source.display());
+            continue;
+        }
+
+        if source.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| format!("Failed to create directory: {e}"))?;
+        } else {
+            fs::copy(source, &target).map_err(|e| format!("Failed to copy file: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// ファイル/ディレクトリを移動（異なるデバイス間の場合はコピー後に削除）
+fn move_path_internal(from: &Path, to: &Path) -> AppResult<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+
+    let Err(rename_error) = fs::rename(from, to) else {
+        return Ok(());
+    };
+
+    // renameできない場合（別デバイスなど）はコピーしてから元を削除
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    if let Err(e) = copy_recursive(&policy, from, to) {
+        let _ = if to.is_dir() {
+            fs::remove_dir_all(to)
+        } else {
+            fs::remove_file(to)
+        };
+        return Err(format!("Failed to move {}: {rename_error} ({e})", from.display()));
+    }
+
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+    .map_err(|e| format!("Failed to remove {} after copying: {e}", from.display()))
+}
+
+/// 移動/複製先を準備（既存の場合は上書き指定時のみゴミ箱へ退避）
+fn prepare_destination(destination: &Path, overwrite: bool) -> AppResult<()> {
+    if fs::symlink_metadata(destination).is_err() {
+        return Ok(());
+    }
+    if !overwrite {
+        return Err(format!(
+            "Destination already exists: {}. Set overwrite to replace it.",
+            destination.display()
+        ));
+    }
+    move_to_trash(destination, "overwrite").map(|_| ())
+}
+
+/// 移動処理本体（名前変更と移動で共通）
+fn move_and_update_favorites(
+    source_input: &str,
+    source: &Path,
+    destination: &Path,
+    overwrite: bool,
+) -> AppResult<String> {
+    if !source.exists() {
+        return Err(AppError::FileNotFound(source_input.to_string()).to_string());
+    }
+    if source == destination {
+        return Ok(destination.to_string_lossy().to_string());
+    }
+    if destination.starts_with(source) {
+        return Err("Cannot move a directory into itself".to_string());
+    }
+
+    // 大文字小文字を区別しないファイルシステムでの大文字小文字のみの変更は上書きとして扱わない
+    let same_file = dunce::canonicalize(destination)
+        .is_ok_and(|d| dunce::canonicalize(source).is_ok_and(|s| s == d));
+    if !same_file {
+        prepare_destination(destination, overwrite)?;
+    }
+    move_path_internal(source, destination)?;
+
+    // お気に入りのパスを更新（正規化前のパスで登録されている場合も対象）
+    let mut updated = update_favorite_paths(source, destination)?;
+    if Path::new(source_input) != source {
+        updated += update_favorite_paths(Path::new(source_input), destination)?;
+    }
+    if updated > 0 {
+        info!("Updated {updated} favorite(s) after move");
+    }
+
+    info!("Moved: {} -> {}", source.display(), destination.display());
+    Ok(destination.to_string_lossy().to_string())
+}
+
+/// 複製先の名前を生成（`name copy.ext`, `name copy 2.ext`, ...）
+fn generate_copy_path(source: &Path) -> PathBuf {
+    let parent = source.parent().unwrap_or_else(|| Path::new(""));
+    let (stem, extension) = if source.is_dir() {
+        (
+            source.file_name().map(|n| n.to_string_lossy().to_string()),
+            None,
+        )
+    } else {
+        (
+            source.file_stem().map(|n| n.to_string_lossy().to_string()),
+            source.extension().map(|e| e.to_string_lossy().to_string()),
+        )
+    };
+    let stem = stem.unwrap_or_default();
+
+    (1..)
+        .map(|n| {
+            let suffix = if n == 1 {
+                " copy".to_string()
+            } else {
+                format!(" copy {n}")
+            };
+            let name = match &extension {
+                Some(ext) => format!("{stem}{suffix}.{ext}"),
+                None => format!("{stem}{suffix}"),
+            };
+            parent.join(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| parent.join(format!("{stem} copy")))
+}
+
+/// ファイル/ディレクトリを削除（ゴミ箱へ移動）
+///
+/// # Arguments
+///
+/// * `path` - 削除するパス（~/.claude/で始まる形式も可）
+///
+/// # Returns
+///
+/// ゴミ箱のエントリ（`restore_from_trash` で復元可能）
+///
+/// # Errors
+///
+/// - 設定ルート外、設定ルート自体、除外ディレクトリ配下の場合
+/// - ファイルが存在しない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn delete_path(path: String) -> AppResult<TrashEntry> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let resolved = resolve_target(&policy, &path)?;
+
+    if fs::symlink_metadata(&resolved).is_err() {
+        return Err(AppError::FileNotFound(path).to_string());
+    }
+
+    move_to_trash(&resolved, "delete")
+}
+
+/// ファイル/ディレクトリの名前を変更
+///
+/// # Arguments
+///
+/// * `path` - 対象のパス
+/// * `new_name` - 新しい名前（パス区切りを含まないファイル名）
+/// * `overwrite` - 同名のファイルが存在する場合に上書きするか（既存はゴミ箱へ退避）
+///
+/// # Returns
+///
+/// 変更後の絶対パス
+///
+/// # Errors
+///
+/// - 名前が不正な場合
+/// - 同名のファイルが存在し、`overwrite` が指定されていない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn rename_path(path: String, new_name: String, overwrite: Option<bool>) -> AppResult<String> {
+    validate_file_name(&new_name)?;
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let source = resolve_target(&policy, &path)?;
+    let parent = source
+        .parent()
+        .ok_or_else(|| format!("Invalid path: {path}"))?;
+    let destination = resolve_target(&policy, &parent.join(&new_name).to_string_lossy())?;
+
+    move_and_update_favorites(
+        &path,
+        &source,
+        &destination,
+        overwrite.unwrap_or(false),
+    )
+}
+
+/// ファイル/ディレクトリを移動
+///
+/// 移動先の親ディレクトリが存在しない場合は作成します。
+/// お気に入りに登録されているパスも移動先に更新されます。
+///
+/// # Arguments
+///
+/// * `source` - 移動元のパス
+/// * `destination` - 移動先のパス（移動後のファイル/ディレクトリのパス）
+/// * `overwrite` - 移動先が存在する場合に上書きするか（既存はゴミ箱へ退避）
+///
+/// # Returns
+///
+/// 移動後の絶対パス
+///
+/// # Errors
+///
+/// - 移動元または移動先が設定ルート外の場合
+/// - ディレクトリを自身の配下に移動しようとした場合
+/// - 移動先が存在し、`overwrite` が指定されていない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn move_path(source: String, destination: String, overwrite: Option<bool>) -> AppResult<String> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let source_buf = resolve_target(&policy, &source)?;
+    let destination_buf = resolve_target(&policy, &destination)?;
+
+    move_and_update_favorites(
+        &source,
+        &source_buf,
+        &destination_buf,
+        overwrite.unwrap_or(false),
+    )
+}
+
+/// ファイル/ディレクトリを複製
+///
+/// # Arguments
+///
+/// * `source` - 複製元のパス
+/// * `destination` - 複製先のパス（省略時は同じディレクトリに `name copy.ext` を作成）
+/// * `overwrite` - 複製先が存在する場合に上書きするか（既存はゴミ箱へ退避）
+///
+/// # Returns
+///
+/// 複製先の絶対パス
+///
+/// # Errors
+///
+/// - 複製元が存在しない場合
+/// - 複製先が存在し、`overwrite` が指定されていない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn duplicate_path(
+    source: String,
+    destination: Option<String>,
+    overwrite: Option<bool>,
+) -> AppResult<String> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let source_buf = resolve_target(&policy, &source)?;
+
+    if !source_buf.exists() {
+        return Err(AppError::FileNotFound(source).to_string());
+    }
+
+    let destination_buf = match destination {
+        Some(destination) => resolve_target(&policy, &destination)?,
+        None => generate_copy_path(&source_buf),
+    };
+    if destination_buf.starts_with(&source_buf) {
+        return Err("Cannot copy a directory into itself".to_string());
+    }
+
+    prepare_destination(&destination_buf, overwrite.unwrap_or(false))?;
+    if let Some(parent) = destination_buf.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {e}"))?;
+    }
+    copy_recursive(&policy, &source_buf, &destination_buf)?;
+
+    info!(
+        "Duplicated: {} -> {}",
+        source_buf.display(),
+        destination_buf.display()
+    );
+    Ok(destination_buf.to_string_lossy().to_string())
+}
+
+/// ゴミ箱のエントリ一覧を取得
+///
+/// # Returns
+///
+/// ゴミ箱のエントリ一覧（新しい順）
+#[tauri::command]
+pub fn get_trash_entries() -> AppResult<Vec<TrashEntry>> {
+    let trash_dir = get_trash_dir()?;
+    if !trash_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries: Vec<TrashEntry> = fs::read_dir(&trash_dir)
+        .map_err(|e| format!("Failed to read trash directory: {e}"))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|content| serde_json::from_str::<TrashEntry>(&content).ok())
+        .collect();
+
+    entries.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(entries)
+}
+
+/// ゴミ箱から元の場所に復元
+///
+/// # Arguments
+///
+/// * `id` - ゴミ箱のエントリID
+/// * `overwrite` - 元の場所にファイルが存在する場合に上書きするか（既存はゴミ箱へ退避）
+///
+/// # Returns
+///
+/// 復元先の絶対パス
+///
+/// # Errors
+///
+/// - エントリが存在しない場合
+/// - 元の場所が設定ルート外になった場合（設定ルートを切り替えた場合など）
+/// - 元の場所にファイルが存在し、`overwrite` が指定されていない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn restore_from_trash(id: String, overwrite: Option<bool>) -> AppResult<String> {
+    validate_file_name(&id)?;
+
+    let trash_dir = get_trash_dir()?;
+    let meta_path = trash_dir.join(format!("{id}.json"));
+    let content = fs::read_to_string(&meta_path)
+        .map_err(|_| format!("Trash entry not found: {id}"))?;
+    let entry: TrashEntry = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse trash entry: {e}"))?;
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let destination = resolve_target(&policy, &entry.original_path)?;
+    let stored = trash_dir.join(&id).join(&entry.name);
+    if fs::symlink_metadata(&stored).is_err() {
+        return Err(format!("Trash entry data not found: {id}"));
+    }
+
+    prepare_destination(&destination, overwrite.unwrap_or(false))?;
+    move_path_internal(&stored, &destination)?;
+
+    let _ = fs::remove_dir_all(trash_dir.join(&id));
+    let _ = fs::remove_file(&meta_path);
+
+    info!("Restored from trash: {} ({id})", destination.display());
+    Ok(destination.to_string_lossy().to_string())
+}
+
+/// ゴミ箱を空にする
+///
+/// # Arguments
+///
+/// * `older_than_days` - 指定した場合はこの日数より古いエントリのみ削除
+///
+/// # Returns
+///
+/// クリーンアップ結果（削除数、サイズ、エラー）
+#[tauri::command]
+pub fn empty_trash(older_than_days: Option<u32>) -> AppResult<CleanupResult> {
+    let trash_dir = get_trash_dir()?;
+    let mut result = CleanupResult::default();
+
+    if !trash_dir.exists() {
+        return Ok(result);
+    }
+
+    let threshold = match older_than_days {
+        Some(days) => Some(
+            SystemTime::now()
+                .checked_sub(Duration::from_secs(u64::from(days) * 24 * 60 * 60))
+                .ok_or("Failed to calculate threshold time")?,
+        ),
+        None => None,
+    };
+
+    let entries = fs::read_dir(&trash_dir)
+        .map_err(|e| format!("Failed to read trash directory: {e}"))?;
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        // ゴミ箱に移動した時刻（エントリディレクトリの作成時刻）で判定
+        let expired = threshold.map_or(true, |threshold| {
+            fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified < threshold)
+        });
+        if !expired {
+            continue;
+        }
+
+        let size: u64 = WalkDir::new(&path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+            .filter(std::fs::Metadata::is_file)
+            .map(|m| m.len())
+            .sum();
+
+        match fs::remove_dir_all(&path) {
+            Ok(()) => {
+                let _ = fs::remove_file(path.with_extension("json"));
+                result.deleted_count += 1;
+                result.deleted_size_bytes += size;
+            }
+            Err(e) => result
+                .errors
+                .push(format!("Failed to delete {}: {e}", path.display())),
+        }
+    }
+
+    if result.deleted_count > 0 {
+        info!(
+            "Trash emptied: {} entries deleted, {} bytes freed",
+            result.deleted_count, result.deleted_size_bytes
+        );
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::TestClaudeDir;
+
+    #[test]
+    fn test_validate_file_name() {
+        assert!(validate_file_name("SKILL.md").is_ok());
+        assert!(validate_file_name("").is_err());
+        assert!(validate_file_name("..").is_err());
+        assert!(validate_file_name("a/b.md").is_err());
+    }
+
+    #[test]
+    fn test_generate_copy_path() {
+        let claude = TestClaudeDir::new();
+        let file = claude.path().join("agent.md");
+        fs::write(&file, "x").unwrap();
+        assert_eq!(generate_copy_path(&file), claude.path().join("agent copy.md"));
+
+        claude.write("agent copy.md", "x");
+        assert_eq!(generate_copy_path(&file), claude.path().join("agent copy 2.md"));
+    }
+
+    #[test]
+    fn test_move_refuses_overwrite_and_updates_favorites() {
+        let claude = TestClaudeDir::new();
+        let root = claude.path();
+        claude.write("agents/a.md", "a");
+        claude.write("agents/b.md", "b");
+        crate::commands::favorites::add_favorite(
+            root.join("agents/a.md").to_string_lossy().to_string(),
+            "a.md".to_string(),
+        )
+        .unwrap();
+
+        let source = root.join("agents/a.md").to_string_lossy().to_string();
+        let destination = root.join("agents/b.md").to_string_lossy().to_string();
+        assert!(move_path(source.clone(), destination.clone(), None).is_err());
+
+        let moved = move_path(source, destination, Some(true)).unwrap();
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "a");
+
+        // 上書きされたファイルはゴミ箱に退避される
+        let trash = get_trash_entries().unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].reason, "overwrite");
+
+        let favorites = crate::commands::favorites::get_favorites().unwrap();
+        assert_eq!(favorites[0].name, "b.md");
+        assert!(favorites[0].path.ends_with("agents/b.md"));
+    }
+
+    #[test]
+    fn test_delete_and_restore_directory() {
+        let claude = TestClaudeDir::new();
+        let skill_dir = claude.path().join("skills/demo");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "demo").unwrap();
+
+        let entry = delete_path(skill_dir.to_string_lossy().to_string()).unwrap();
+        assert!(entry.is_directory);
+        assert!(!skill_dir.exists());
+
+        // 設定ルート自体と除外ディレクトリは削除できない
+        assert!(delete_path(claude.path().to_string_lossy().to_string()).is_err());
+        assert!(delete_path(
+            claude.path().join("backups/trash").to_string_lossy().to_string()
+        )
+        .is_err());
+
+        restore_from_trash(entry.id, None).unwrap();
+        assert_eq!(fs::read_to_string(skill_dir.join("SKILL.md")).unwrap(), "demo");
+        assert!(get_trash_entries().unwrap().is_empty());
+    }
+}
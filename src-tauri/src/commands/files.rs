@@ -1,80 +1,135 @@
 //! ファイル操作コマンド
 //!
-//! ファイルツリーの取得、ファイルの読み書き、検索などの機能を提供します。
-
-use crate::error::AppResult;
-use crate::types::{FileContent, FileNode, ReplaceResult};
+//! ファイルツリーの取得（一括・遅延読み込み）、ファイルの読み書き、検索などの機能を提供します。
+
+use crate::commands::backup::create_backup_internal;
+use crate::error::{AccessDeniedReason, AppError, AppResult};
+use crate::git_history;
+use crate::security::{resolve_path, AccessPolicy};
+use crate::types::{
+    BackupOperation, DirectoryEntry, DirectoryListing, FileContent, FileNode, FileVersion,
+    ReplaceResult, WriteConflict, WriteResult,
+};
 use crate::utils::{
-    get_claude_dir, is_allowed_extension, is_excluded_path, normalize_claude_path,
-    normalize_claude_path_strict, validate_path_security, ALLOWED_EXTENSIONS, EXCLUDED_DIRS,
+    classify_file_kind, content_version_token, get_claude_dir, is_allowed_extension,
+    is_excluded_path, is_excluded_relative_path, modified_millis, normalize_claude_path,
+    normalize_claude_path_strict, write_file_atomic,
 };
 use log::info;
 use regex::{Regex, RegexBuilder};
 use std::cmp::Ordering;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+/// 一覧のデフォルト取得件数
+const DEFAULT_LIST_LIMIT: usize = 500;
+
+/// 表示対象のディレクトリエントリ
+struct VisibleEntry {
+    path: PathBuf,
+    name: String,
+    is_dir: bool,
+}
+
+/// ディレクトリ直下の表示対象エントリを列挙（順序は `read_dir` のまま）
+///
+/// 除外ディレクトリと許可されていない拡張子のファイルは含まれません。
+/// 種別は `read_dir` の結果から判定するため、エントリごとの追加の `stat` は
+/// シンボリックリンクの場合のみ発生します。
+///
+/// # Arguments
+///
+/// * `dir` - 対象ディレクトリ（読み込めない場合は空）
+/// * `root` - `dir` と同じ形式（正規化の有無）の設定ルート。除外globの判定に使用
+fn visible_entries(dir: &Path, root: &Path) -> impl Iterator<Item = VisibleEntry> {
+    let config = crate::config::current();
+    let root = root.to_path_buf();
+
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(move |entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_str()?.to_string();
+            let file_type = entry.file_type().ok()?;
+            let is_dir = if file_type.is_symlink() {
+                path.is_dir()
+            } else {
+                file_type.is_dir()
+            };
+
+            // 除外するディレクトリ・glob、許可されていない拡張子のファイル
+            if is_dir && config.is_excluded_dir_name(&name) {
+                return None;
+            }
+            if !is_dir && !config.is_allowed_extension(&path) {
+                return None;
+            }
+            let relative = path.strip_prefix(&root).ok();
+            if relative.is_some_and(|rel| config.is_excluded_relative(rel)) {
+                return None;
+            }
+
+            Some(VisibleEntry { path, name, is_dir })
+        })
+}
+
+/// ディレクトリ直下の表示対象エントリを名前順に取得
+///
+/// # Arguments
+///
+/// * `dir` - 対象ディレクトリ
+/// * `root` - `dir` と同じ形式の設定ルート
+/// * `dirs_first` - ディレクトリを先に並べるか（`false` の場合はファイルが先）
+fn read_visible_entries(dir: &Path, root: &Path, dirs_first: bool) -> Vec<VisibleEntry> {
+    let mut visible: Vec<VisibleEntry> = visible_entries(dir, root).collect();
+    visible.sort_by(|a, b| match (a.is_dir, b.is_dir) {
+        (true, false) if dirs_first => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (false, true) if dirs_first => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => a.name.cmp(&b.name),
+    });
+
+    visible
+}
+
 /// ファイルツリーを再帰的に構築
 ///
 /// # Arguments
 ///
-/// * `path` - 構築を開始するパス
+/// * `entry` - 構築を開始するエントリ
+/// * `root` - 設定ルート
 /// * `max_depth` - 最大深度
 /// * `current_depth` - 現在の深度
 ///
 /// # Returns
 ///
-/// ファイルノード。除外対象や深度超過の場合は`None`
-fn build_file_tree(path: &PathBuf, max_depth: usize, current_depth: usize) -> Option<FileNode> {
+/// ファイルノード。深度超過の場合は`None`
+fn build_file_tree(
+    entry: VisibleEntry,
+    root: &Path,
+    max_depth: usize,
+    current_depth: usize,
+) -> Option<FileNode> {
     if current_depth > max_depth {
         return None;
     }
 
-    let name = path.file_name()?.to_str()?.to_string();
-
-    // 除外するディレクトリ
-    if EXCLUDED_DIRS.contains(&name.as_str()) {
-        return None;
-    }
+    let path = entry.path.to_string_lossy().to_string();
 
-    if path.is_dir() {
-        let mut children: Vec<FileNode> = Vec::new();
-
-        if let Ok(entries) = fs::read_dir(path) {
-            let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-            entries.sort_by(|a, b| {
-                let a_is_dir = a.path().is_dir();
-                let b_is_dir = b.path().is_dir();
-                match (a_is_dir, b_is_dir) {
-                    (true, false) => Ordering::Less,
-                    (false, true) => Ordering::Greater,
-                    _ => a.file_name().cmp(&b.file_name()),
-                }
-            });
-
-            for entry in entries {
-                if let Some(child) = build_file_tree(&entry.path(), max_depth, current_depth + 1) {
-                    children.push(child);
-                }
-            }
-        }
+    if entry.is_dir {
+        let children: Vec<FileNode> = read_visible_entries(&entry.path, root, true)
+            .into_iter()
+            .filter_map(|child| build_file_tree(child, root, max_depth, current_depth + 1))
+            .collect();
 
-        Some(FileNode::new_directory(
-            name,
-            path.to_string_lossy().to_string(),
-            children,
-        ))
+        Some(FileNode::new_directory(entry.name, path, children))
     } else {
-        // .md, .json, .jsonl ファイルのみ
-        if is_allowed_extension(path) {
-            Some(FileNode::new_file(
-                name,
-                path.to_string_lossy().to_string(),
-            ))
-        } else {
-            None
-        }
+        Some(FileNode::new_file(entry.name, path))
     }
 }
 
@@ -82,6 +137,9 @@ fn build_file_tree(path: &PathBuf, max_depth: usize, current_depth: usize) -> Op
 ///
 /// ~/.claude/ ディレクトリ配下のファイル構造を返します。
 /// 除外ディレクトリ（cache, backupsなど）は含まれません。
+///
+/// 大きなディレクトリでは `list_directory` による遅延読み込みを推奨します。
+/// このコマンドは互換性のために残されています。
 #[tauri::command]
 pub fn get_file_tree() -> AppResult<Vec<FileNode>> {
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
@@ -90,30 +148,107 @@ pub fn get_file_tree() -> AppResult<Vec<FileNode>> {
         return Err("~/.claude directory not found".to_string());
     }
 
-    let mut nodes: Vec<FileNode> = Vec::new();
-
-    // トップレベルのファイルとディレクトリを取得
-    let entries = fs::read_dir(&claude_dir)
-        .map_err(|e| format!("Failed to read ~/.claude directory: {e}"))?;
+    // トップレベルはファイルを先に表示
+    Ok(read_visible_entries(&claude_dir, &claude_dir, false)
+        .into_iter()
+        .filter_map(|entry| build_file_tree(entry, &claude_dir, 10, 0))
+        .collect())
+}
 
-    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-    entries.sort_by(|a, b| {
-        let a_is_dir = a.path().is_dir();
-        let b_is_dir = b.path().is_dir();
-        match (a_is_dir, b_is_dir) {
-            (true, false) => Ordering::Greater,
-            (false, true) => Ordering::Less,
-            _ => a.file_name().cmp(&b.file_name()),
+/// ディレクトリ直下の子要素をメタデータ付きで取得（遅延読み込み用）
+///
+/// # Arguments
+///
+/// * `path` - 対象ディレクトリのパス（~/.claude/で始まる形式も可、省略時は設定ルート）
+/// * `offset` - 取得開始位置（デフォルト: 0）
+/// * `limit` - 取得件数（デフォルト: 500）
+///
+/// # Returns
+///
+/// 子要素の一覧（サイズ、更新日時、子要素数、種別付き）と総数
+///
+/// # Errors
+///
+/// - 設定ルート外のパスの場合
+/// - ディレクトリでない場合
+/// - 除外ディレクトリ配下の場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn list_directory(
+    path: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> AppResult<DirectoryListing> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let dir = match &path {
+        Some(p) => {
+            // パスを正規化（~/.claude/ プレフィックスをサポート）
+            let path_buf = normalize_claude_path(p, policy.root())
+                .ok_or_else(|| "パスの正規化に失敗しました".to_string())?;
+            policy.resolve(&path_buf).map_err(|e| e.to_string())?
         }
-    });
+        None => policy.root().to_path_buf(),
+    };
 
-    for entry in entries {
-        if let Some(node) = build_file_tree(&entry.path(), 10, 0) {
-            nodes.push(node);
-        }
+    if !dir.is_dir() {
+        return Err(format!("Not a directory: {}", dir.display()));
     }
+    let dir_relative = dir.strip_prefix(policy.root()).map_err(|e| e.to_string())?;
+    if is_excluded_relative_path(dir_relative) {
+        return Err(AppError::AccessDenied(AccessDeniedReason::Other(format!(
+            "excluded directory: {}",
+            dir.display()
+        )))
+        .to_string());
+    }
+
+    let config = crate::config::current();
+    // `dir` は正規化済みのため、除外globも正規化済みのルートからの相対パスで判定
+    let entries = read_visible_entries(&dir, policy.root(), true);
+    let total = entries.len();
+    let offset = offset.unwrap_or(0).min(total);
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT);
 
-    Ok(nodes)
+    // メタデータは要求されたページ分のみ取得
+    let page: Vec<DirectoryEntry> = entries
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|entry| {
+            let metadata = fs::metadata(&entry.path).ok();
+            let relative = entry
+                .path
+                .strip_prefix(policy.root())
+                .unwrap_or(&entry.path)
+                .to_path_buf();
+
+            DirectoryEntry {
+                path: entry.path.to_string_lossy().to_string(),
+                relative_path: relative.to_string_lossy().replace('\\', "/"),
+                is_directory: entry.is_dir,
+                size: if entry.is_dir {
+                    None
+                } else {
+                    metadata.as_ref().map(std::fs::Metadata::len)
+                },
+                modified: metadata.as_ref().and_then(modified_millis),
+                child_count: entry
+                    .is_dir
+                    .then(|| visible_entries(&entry.path, policy.root()).count()),
+                kind: classify_file_kind(&relative),
+                editor_mode: (!entry.is_dir).then(|| config.editor_mode(&entry.path)),
+                name: entry.name,
+            }
+        })
+        .collect();
+
+    Ok(DirectoryListing {
+        path: dir.to_string_lossy().to_string(),
+        has_more: offset + page.len() < total,
+        entries: page,
+        total,
+        offset,
+    })
 }
 
 /// ファイルを読み込む
@@ -124,21 +259,18 @@ pub fn get_file_tree() -> AppResult<Vec<FileNode>> {
 ///
 /// # Returns
 ///
-/// ファイルの内容とメタデータ
+/// ファイルの内容とメタデータ（`write_file` の競合検出に使うバージョントークンを含む）
 ///
 /// # Security
 ///
 /// ~/.claude/ 配下のファイルのみアクセス可能です。
+/// パスは正規化され、`..` やシンボリックリンクによるルート外へのアクセスは拒否されます。
 #[tauri::command]
 pub fn read_file(path: String) -> AppResult<FileContent> {
     let path_buf = PathBuf::from(&path);
-    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
 
     // セキュリティチェック
-    validate_path_security(&path_buf, &claude_dir).map_err(|e| e.to_string())?;
-
-    let content =
-        fs::read_to_string(&path_buf).map_err(|e| format!("Failed to read file: {e}"))?;
+    let resolved = resolve_path(&path_buf).map_err(|e| e.to_string())?;
 
     let name = path_buf
         .file_name()
@@ -146,22 +278,57 @@ pub fn read_file(path: String) -> AppResult<FileContent> {
         .unwrap_or("unknown")
         .to_string();
 
-    Ok(FileContent { path, content, name })
+    read_file_content(&resolved, path, name).map_err(|e| format!("Failed to read file: {e}"))
+}
+
+/// ファイルを読み込み、バージョントークンと更新日時を付与
+fn read_file_content(resolved: &Path, path: String, name: String) -> std::io::Result<FileContent> {
+    let bytes = fs::read(resolved)?;
+    let modified = fs::metadata(resolved)
+        .ok()
+        .and_then(|m| modified_millis(&m));
+    let version_token = content_version_token(&bytes);
+    let content = String::from_utf8(bytes)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    Ok(FileContent {
+        path,
+        content,
+        name,
+        version_token: Some(version_token),
+        modified,
+        editor_mode: Some(crate::config::current().editor_mode(resolved)),
+    })
 }
 
 /// ファイルを書き込む
 ///
+/// 一時ファイルに書き込んでからリネームするため、書き込み途中で中断しても
+/// ファイルが壊れることはありません。
+///
 /// # Arguments
 ///
 /// * `path` - 書き込むファイルのパス（~/.claude/で始まる形式も可）
 /// * `content` - 書き込む内容
+/// * `expected_version` - `read_file` で取得したバージョントークン。
+///   指定した場合、ディスク上の内容が変更されていれば書き込みを中止します
+///
+/// # Returns
+///
+/// 書き込み後のバージョン（`WriteResult::Written`）。
+/// 読み込み後にファイルが変更されていた場合は書き込まずに
+/// 現在の内容を含む `WriteResult::Conflict` を返します。
 ///
 /// # Security
 ///
 /// ~/.claude/ 配下のファイルのみアクセス可能です。
 /// 書き込み前に自動的にバックアップが作成されます。
 #[tauri::command]
-pub fn write_file(path: String, content: String) -> AppResult<()> {
+pub fn write_file(
+    path: String,
+    content: String,
+    expected_version: Option<String>,
+) -> AppResult<WriteResult> {
     let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
 
     // パスを正規化（~/.claude/ プレフィックスをサポート）
@@ -169,13 +336,66 @@ pub fn write_file(path: String, content: String) -> AppResult<()> {
         .ok_or_else(|| "パスの正規化に失敗しました".to_string())?;
 
     // セキュリティチェック
-    validate_path_security(&path_buf, &claude_dir).map_err(|e| e.to_string())?;
+    let resolved = resolve_path(&path_buf).map_err(|e| e.to_string())?;
+
+    // 読み込み後に他のプロセスが変更していないか確認
+    if let Some(expected_version) = expected_version {
+        if let Some(conflict) = check_expected_version(&resolved, &path, expected_version) {
+            return Ok(WriteResult::Conflict(conflict));
+        }
+    }
 
     // バックアップを作成（元のパスではなく正規化されたパスを使用）
-    let normalized_path = path_buf.to_string_lossy().to_string();
-    crate::commands::backup::create_backup(&normalized_path)?;
+    let normalized_path = resolved.to_string_lossy().to_string();
+    create_backup_internal(&normalized_path, BackupOperation::Write)?;
+
+    write_file_atomic(&resolved, content.as_bytes())
+        .map_err(|e| format!("Failed to write file: {e}"))?;
+    git_history::record_change_or_warn(
+        std::slice::from_ref(&resolved),
+        &format!("Update {}", git_history::display_path(&resolved)),
+    );
+
+    Ok(WriteResult::Written(FileVersion {
+        version_token: content_version_token(content.as_bytes()),
+        modified: fs::metadata(&resolved)
+            .ok()
+            .and_then(|m| modified_millis(&m)),
+    }))
+}
 
-    fs::write(&path_buf, content).map_err(|e| format!("Failed to write file: {e}"))
+/// ファイルが読み込み時から変更されていないか確認
+///
+/// # Arguments
+///
+/// * `resolved` - 検証済みのファイルの絶対パス
+/// * `path` - 呼び出し元が指定したパス（エラーに含める）
+/// * `expected_version` - 読み込み時のバージョントークン
+///
+/// # Returns
+///
+/// 現在のバージョンが異なる場合は、現在の内容を含む競合の詳細
+pub(crate) fn check_expected_version(
+    resolved: &Path,
+    path: &str,
+    expected_version: String,
+) -> Option<WriteConflict> {
+    let current_version = fs::read(resolved).ok().map(|b| content_version_token(&b));
+    if current_version.as_deref() == Some(expected_version.as_str()) {
+        return None;
+    }
+
+    let name = resolved
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+    let current = read_file_content(resolved, path.to_string(), name).ok();
+    Some(WriteConflict {
+        path: path.to_string(),
+        expected_version,
+        current,
+    })
 }
 
 /// 新規ファイルを作成
@@ -197,13 +417,8 @@ pub fn create_file(path: String, content: String) -> AppResult<()> {
     let path_buf = normalize_claude_path_strict(&path, &claude_dir)
         .ok_or_else(|| "アクセス拒否: ~/.claude/ 配下のファイルのみ許可されています".to_string())?;
 
-    // セキュリティチェック
-    validate_path_security(&path_buf, &claude_dir).map_err(|e| e.to_string())?;
-
-    // ファイルが既に存在する場合はエラー
-    if path_buf.exists() {
-        return Err("File already exists. Use write_file to update existing files.".to_string());
-    }
+    // セキュリティチェック（存在しないパスも祖先ディレクトリを基準に正規化）
+    let path_buf = resolve_path(&path_buf).map_err(|e| e.to_string())?;
 
     // 親ディレクトリを作成
     if let Some(parent) = path_buf.parent() {
@@ -213,8 +428,25 @@ pub fn create_file(path: String, content: String) -> AppResult<()> {
         }
     }
 
-    // ファイルを作成
-    fs::write(&path_buf, content).map_err(|e| format!("Failed to create file: {e}"))?;
+    // ファイルを作成（確認後に作成されたファイルも上書きしないよう、既存の場合は作成自体を失敗させる）
+    let mut file = match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path_buf)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            return Err(
+                "File already exists. Use write_file to update existing files.".to_string(),
+            );
+        }
+        Err(e) => return Err(format!("Failed to create file: {e}")),
+    };
+    if let Err(e) = file.write_all(content.as_bytes()).and_then(|()| file.sync_all()) {
+        drop(file);
+        let _ = fs::remove_file(&path_buf);
+        return Err(format!("Failed to create file: {e}"));
+    }
 
     info!("Created new file: {}", path_buf.display());
     Ok(())
@@ -245,8 +477,7 @@ pub fn search_files(query: String) -> AppResult<Vec<FileContent>> {
         let path = entry.path();
 
         // 許可された拡張子のファイルのみ検索
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        if !ALLOWED_EXTENSIONS.contains(&ext) {
+        if !is_allowed_extension(path) {
             continue;
         }
 
@@ -265,6 +496,9 @@ pub fn search_files(query: String) -> AppResult<Vec<FileContent>> {
                     path: path.to_string_lossy().to_string(),
                     content: content.chars().take(500).collect(),
                     name: name.to_string(),
+                    version_token: None,
+                    modified: None,
+                    editor_mode: None,
                 });
             }
             continue;
@@ -277,6 +511,9 @@ pub fn search_files(query: String) -> AppResult<Vec<FileContent>> {
                     path: path.to_string_lossy().to_string(),
                     content: content.chars().take(500).collect(),
                     name: name.to_string(),
+                    version_token: None,
+                    modified: None,
+                    editor_mode: None,
                 });
             }
         }
@@ -324,9 +561,10 @@ pub fn search_and_replace_in_file(
     };
 
     // セキュリティチェック
-    if let Err(e) = validate_path_security(&path_buf, &claude_dir) {
-        return Ok(ReplaceResult::error(e.to_string()));
-    }
+    let path_buf = match resolve_path(&path_buf) {
+        Ok(p) => p,
+        Err(e) => return Ok(ReplaceResult::error(e.to_string())),
+    };
 
     // ファイルが存在するか確認
     if !path_buf.exists() {
@@ -366,12 +604,12 @@ pub fn search_and_replace_in_file(
     if new_content != content {
         // バックアップを作成
         let normalized_path = path_buf.to_string_lossy().to_string();
-        if let Err(e) = crate::commands::backup::create_backup(&normalized_path) {
+        if let Err(e) = create_backup_internal(&normalized_path, BackupOperation::Replace) {
             return Ok(ReplaceResult::error(format!("Failed to create backup: {e}")));
         }
 
         // ファイルに書き込み
-        if let Err(e) = fs::write(&path_buf, &new_content) {
+        if let Err(e) = write_file_atomic(&path_buf, new_content.as_bytes()) {
             return Ok(ReplaceResult::error(format!("Failed to write file: {e}")));
         }
 
@@ -380,6 +618,10 @@ pub fn search_and_replace_in_file(
             replaced_count,
             path_buf.display()
         );
+        git_history::record_change_or_warn(
+            std::slice::from_ref(&path_buf),
+            &format!("Replace \"{search}\" in {}", git_history::display_path(&path_buf)),
+        );
     }
 
     Ok(ReplaceResult::success(replaced_count, new_content))
@@ -388,7 +630,8 @@ pub fn search_and_replace_in_file(
 /// 検索パターンを構築
 ///
 /// オプションに応じて正規表現パターンを構築します。
-fn build_search_pattern(
+/// ワークスペース一括置換でも同じオプション解釈を使用します。
+pub(crate) fn build_search_pattern(
     search: &str,
     case_sensitive: bool,
     whole_word: bool,
@@ -415,3 +658,125 @@ fn build_search_pattern(
         .case_insensitive(!case_sensitive)
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FileKind;
+    use crate::utils::TestClaudeDir;
+
+    #[test]
+    fn test_write_file_detects_conflict() {
+        let claude = TestClaudeDir::new();
+        let path = claude.path().join("settings.json");
+        fs::write(&path, "{}").unwrap();
+        let path_str = path.to_string_lossy().to_string();
+
+        let loaded = read_file(path_str.clone()).unwrap();
+        let token = loaded.version_token.expect("バージョントークンがありません");
+
+        // 読み込み後に外部で変更された場合は競合エラー
+        fs::write(&path, "{\"model\":\"opus\"}").unwrap();
+        let result = write_file(path_str.clone(), "{\"a\":1}".to_string(), Some(token)).unwrap();
+        let WriteResult::Conflict(conflict) = result else {
+            panic!("競合が検出されませんでした");
+        };
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"model\":\"opus\"}");
+        let current = conflict.current.expect("現在の内容がありません");
+        assert_eq!(current.content, "{\"model\":\"opus\"}");
+
+        // 最新のトークンを指定すれば書き込める
+        let token = current.version_token.unwrap();
+        let result = write_file(path_str, "{\"a\":1}".to_string(), Some(token)).unwrap();
+        let WriteResult::Written(version) = result else {
+            panic!("書き込まれませんでした");
+        };
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":1}");
+        assert_eq!(version.version_token, content_version_token(b"{\"a\":1}"));
+    }
+
+    #[test]
+    fn test_create_file_does_not_overwrite_existing_file() {
+        let claude = TestClaudeDir::new();
+        let path = claude.path().join("agents/new.md");
+        let path_str = path.to_string_lossy().to_string();
+
+        create_file(path_str.clone(), "first".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+        let error = create_file(path_str, "second".to_string()).unwrap_err();
+        assert!(error.contains("already exists"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+    }
+
+    #[test]
+    fn test_list_directory_paging_and_metadata() {
+        let claude = TestClaudeDir::new();
+        let root = claude.path();
+        claude.write("skills/a/SKILL.md", "skill");
+        fs::create_dir_all(root.join("backups")).unwrap();
+        claude.write("settings.json", "{}");
+        claude.write("CLAUDE.md", "memory");
+        claude.write("notes.txt", "ignored");
+
+        let listing = list_directory(None, None, None).unwrap();
+        assert_eq!(listing.total, 3);
+        let names: Vec<&str> = listing.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["skills", "CLAUDE.md", "settings.json"]);
+        assert_eq!(listing.entries[0].child_count, Some(1));
+        assert_eq!(listing.entries[0].kind, FileKind::Skill);
+        assert_eq!(listing.entries[1].kind, FileKind::Memory);
+        assert_eq!(listing.entries[2].size, Some(2));
+
+        let page = list_directory(None, Some(1), Some(1)).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].name, "CLAUDE.md");
+        assert!(page.has_more);
+
+        let backups = root.join("backups").to_string_lossy().to_string();
+        assert!(list_directory(Some(backups), None, None).is_err());
+
+        // 互換APIではトップレベルのファイルが先に並ぶ
+        let tree = get_file_tree().unwrap();
+        let names: Vec<&str> = tree.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["CLAUDE.md", "settings.json", "skills"]);
+    }
+
+    #[test]
+    fn test_list_directory_accepts_claude_prefix() {
+        let claude = TestClaudeDir::new();
+        claude.write("agents/reviewer.md", "agent");
+
+        let listing = list_directory(Some("~/.claude/agents".to_string()), None, None).unwrap();
+        assert_eq!(listing.total, 1);
+        assert_eq!(listing.entries[0].relative_path, "agents/reviewer.md");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_directory_applies_exclude_globs_under_symlinked_root() {
+        let claude = TestClaudeDir::new();
+        let real = claude.path().join("real");
+        fs::create_dir_all(real.join("agents")).unwrap();
+        fs::write(real.join("agents/reviewer.md"), "agent").unwrap();
+        fs::write(real.join("agents/draft-x.md"), "draft").unwrap();
+        fs::write(
+            real.join("dashboard-config.json"),
+            r#"{"excludeGlobs":["agents/draft-*.md"]}"#,
+        )
+        .unwrap();
+        let link = claude.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        crate::utils::set_active_claude_dir(Some((
+            link,
+            crate::types::ConfigRootSource::Override,
+        )));
+
+        let listing = list_directory(Some("~/.claude/agents".to_string()), None, None).unwrap();
+        let names: Vec<&str> = listing.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["reviewer.md"]);
+
+        let root = list_directory(None, None, None).unwrap();
+        assert_eq!(root.entries[0].child_count, Some(1));
+    }
+}
@@ -0,0 +1,155 @@
+//! 変更履歴操作コマンド
+//!
+//! Gitによる変更履歴の状態・コミット履歴・ファイルごとの履歴・差分の取得と、
+//! 過去のリビジョンからのファイルの復元、ダッシュボードの外で行われた変更のコミットを提供します。
+
+use crate::commands::backup::create_backup_internal;
+use crate::error::AppResult;
+use crate::git_history::{self, DEFAULT_LOG_LIMIT};
+use crate::security::AccessPolicy;
+use crate::types::{BackupOperation, GitCommitInfo, GitFileDiff, GitHistoryStatus};
+use crate::utils::write_file_atomic;
+use log::info;
+use std::fs;
+
+/// 変更履歴の状態を取得
+///
+/// # Returns
+///
+/// 有効かどうか、リポジトリの有無、最新のコミット、コミットされていない変更
+#[tauri::command]
+pub fn get_git_history_status() -> AppResult<GitHistoryStatus> {
+    git_history::status()
+}
+
+/// コミット履歴を取得
+///
+/// # Arguments
+///
+/// * `limit` - 最大件数（省略時は100件）
+/// * `offset` - 読み飛ばす件数
+///
+/// # Returns
+///
+/// コミットの一覧（新しい順）
+#[tauri::command]
+pub fn get_git_log(limit: Option<usize>, offset: Option<usize>) -> AppResult<Vec<GitCommitInfo>> {
+    git_history::log(
+        None,
+        limit.unwrap_or(DEFAULT_LOG_LIMIT),
+        offset.unwrap_or(0),
+    )
+}
+
+/// ファイルを変更したコミットの履歴を取得
+///
+/// # Arguments
+///
+/// * `path` - 設定ルートからの相対パス
+/// * `limit` - 最大件数（省略時は100件）
+///
+/// # Returns
+///
+/// コミットの一覧（新しい順）
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_git_file_history(path: String, limit: Option<usize>) -> AppResult<Vec<GitCommitInfo>> {
+    git_history::log(Some(&path), limit.unwrap_or(DEFAULT_LOG_LIMIT), 0)
+}
+
+/// 2つのコミット間、またはコミットと現在のファイルの差分を取得
+///
+/// # Arguments
+///
+/// * `from` - 比較元のコミットID
+/// * `to` - 比較先のコミットID（省略時は現在のファイル）
+/// * `path` - 指定した場合はこのファイル（設定ルートからの相対パス）のみ
+///
+/// # Returns
+///
+/// 変更されたファイルごとの統合diff形式のパッチ
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_git_diff(
+    from: String,
+    to: Option<String>,
+    path: Option<String>,
+) -> AppResult<Vec<GitFileDiff>> {
+    git_history::diff(&from, to.as_deref(), path.as_deref())
+}
+
+/// 指定したコミット時点のファイルの内容を取得
+///
+/// # Arguments
+///
+/// * `path` - 設定ルートからの相対パス
+/// * `revision` - コミットID
+///
+/// # Returns
+///
+/// ファイルの内容
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn get_git_file_at(path: String, revision: String) -> AppResult<String> {
+    let (bytes, _) = git_history::read_file_at(&path, &revision)?;
+    String::from_utf8(bytes).map_err(|e| format!("File is not valid UTF-8: {e}"))
+}
+
+/// 指定したコミット時点の内容でファイルを復元
+///
+/// 復元前に現在のファイルをバックアップし（操作は `restore` として記録）、
+/// 復元後の状態をコミットします。
+///
+/// # Arguments
+///
+/// * `path` - 設定ルートからの相対パス
+/// * `revision` - コミットID
+///
+/// # Returns
+///
+/// 復元を記録したコミットのID（変更がない場合は`None`）
+///
+/// # Security
+///
+/// 復元先は相対パスから解決し、~/.claude/配下のみ許可
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn checkout_git_file(path: String, revision: String) -> AppResult<Option<String>> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let target = policy.resolve_relative(&path).map_err(|e| e.to_string())?;
+    let (bytes, commit) = git_history::read_file_at(&path, &revision)?;
+
+    create_backup_internal(&target.to_string_lossy(), BackupOperation::Restore)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory: {e}"))?;
+    }
+    write_file_atomic(&target, &bytes).map_err(|e| format!("Failed to restore file: {e}"))?;
+
+    info!("Restored {path} from commit {}", commit.short_id);
+    Ok(git_history::record_change_or_warn(
+        &[target],
+        &format!("Restore {path} from {}", commit.short_id),
+    ))
+}
+
+/// ダッシュボードの外で行われた変更をコミット
+///
+/// リポジトリが存在しない場合は作成します。
+///
+/// # Arguments
+///
+/// * `message` - コミットメッセージ（省略時は自動生成）
+///
+/// # Returns
+///
+/// 作成したコミットのID（変更がない場合は`None`）
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn commit_git_changes(message: Option<String>) -> AppResult<Option<String>> {
+    let message = message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "Record external changes".to_string());
+    git_history::commit_all(&message)
+}
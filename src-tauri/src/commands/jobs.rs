@@ -0,0 +1,28 @@
+//! バックグラウンドジョブ操作コマンド
+//!
+//! `start_export_job`・`start_import_job` で開始したジョブのキャンセルを提供します。
+
+use crate::jobs;
+use log::info;
+
+/// 実行中のジョブをキャンセル
+///
+/// ジョブは処理中のファイルの完了後に中断し、変更を元に戻してから
+/// `job:finished` イベント（状態 `cancelled`）を通知します。
+///
+/// # Arguments
+///
+/// * `job_id` - ジョブID
+///
+/// # Returns
+///
+/// 実行中のジョブが見つかった場合は`true`
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn cancel_job(job_id: String) -> bool {
+    let found = jobs::cancel(&job_id);
+    if found {
+        info!("Cancellation requested for job {job_id}");
+    }
+    found
+}
@@ -3,24 +3,48 @@
 //! フロントエンドから呼び出されるコマンドを機能別に整理しています。
 
 pub mod backup;
+pub mod dashboard_config;
+pub mod diff;
 pub mod export;
 pub mod favorites;
+pub mod fileops;
 pub mod files;
+pub mod history;
 pub mod import;
+pub mod jobs;
+pub mod jsonl;
+pub mod plugin;
+pub mod profiles;
+pub mod replace;
+pub mod search;
+pub mod snapshot;
 pub mod stats;
 pub mod template;
 pub mod terminal;
 pub mod version;
+pub mod watcher;
 pub mod window;
 
 // 各モジュールからコマンドを再エクスポート
 pub use backup::*;
+pub use dashboard_config::*;
+pub use diff::*;
 pub use export::*;
 pub use favorites::*;
+pub use fileops::*;
 pub use files::*;
+pub use history::*;
 pub use import::*;
+pub use jobs::*;
+pub use jsonl::*;
+pub use plugin::*;
+pub use profiles::*;
+pub use replace::*;
+pub use search::*;
+pub use snapshot::*;
 pub use stats::*;
 pub use template::*;
 pub use terminal::*;
 pub use version::*;
+pub use watcher::*;
 pub use window::*;
@@ -0,0 +1,782 @@
+//! プラグインのエクスポートコマンド
+//!
+//! 選択したスキル・サブエージェント・スラッシュコマンドとフックを、Claude Codeのプラグインの構成
+//! （`.claude-plugin/plugin.json`・`skills/`・`agents/`・`commands/`・`hooks/hooks.json`）で書き出します。
+//! 書き出す前にメタデータと内容を検証し、指定した場合はローカルの `marketplace.json` にエントリを追加します。
+
+use crate::archive::{self, ArchiveWriter};
+use crate::commands::replace::collect_target_files;
+use crate::error::AppResult;
+use crate::redaction::Redactor;
+use crate::types::{
+    ArchiveFormat, PluginComponents, PluginExportResult, PluginMetadata, PluginSelection,
+    PluginValidation,
+};
+use crate::utils::{
+    extract_frontmatter_field, get_claude_dir, is_excluded_relative_path, write_file_atomic,
+};
+use log::info;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// プラグインのマニフェスト（プラグインのルートからの相対パス）
+pub const PLUGIN_MANIFEST_FILE: &str = ".claude-plugin/plugin.json";
+
+/// プラグインのフック設定（プラグインのルートからの相対パス）
+pub const PLUGIN_HOOKS_FILE: &str = "hooks/hooks.json";
+
+/// プラグイン名の最大長
+const MAX_PLUGIN_NAME_LEN: usize = 64;
+
+/// 新しく作成するマーケットプレイスの名前（フォルダー名から作れない場合）
+const DEFAULT_MARKETPLACE_NAME: &str = "local-plugins";
+
+/// プラグインに含めることができるスキル・サブエージェント・スラッシュコマンド・フックを取得
+///
+/// # Returns
+///
+/// スキル名と、サブエージェント・スラッシュコマンドの相対パス（それぞれ名前順）
+#[tauri::command]
+pub fn list_plugin_components() -> AppResult<PluginComponents> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let mut components = PluginComponents {
+        has_hooks: settings_hooks(&claude_dir).is_some(),
+        ..PluginComponents::default()
+    };
+    for (_, relative) in collect_target_files(&claude_dir, None, None) {
+        if let Some(name) = relative
+            .strip_prefix("skills/")
+            .and_then(|rest| rest.strip_suffix("/SKILL.md"))
+            .filter(|name| !name.contains('/'))
+        {
+            components.skills.push(name.to_string());
+        } else if is_component_file(&relative, "agents/") {
+            components.agents.push(relative);
+        } else if is_component_file(&relative, "commands/") {
+            components.commands.push(relative);
+        }
+    }
+    components.skills.sort();
+    components.agents.sort();
+    components.commands.sort();
+    Ok(components)
+}
+
+/// プラグインの内容を検証
+///
+/// # Arguments
+///
+/// * `metadata` - プラグイン名・バージョン・説明・作者
+/// * `selection` - 含めるスキル・サブエージェント・スラッシュコマンドとフック
+///
+/// # Returns
+///
+/// エラー・警告と、プラグインに含まれるファイル
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn validate_plugin(
+    metadata: PluginMetadata,
+    selection: PluginSelection,
+) -> AppResult<PluginValidation> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let plugin = build_plugin(&claude_dir, &normalize_metadata(&metadata), &selection)?;
+    Ok(PluginValidation {
+        files: plugin.files.into_keys().collect(),
+        errors: plugin.errors,
+        warnings: plugin.warnings,
+    })
+}
+
+/// プラグインとしてエクスポート
+///
+/// 検証でエラーがある場合は何も書き込みません。
+/// `marketplace_path` を指定した場合は、そのマーケットプレイスのフォルダー（`.claude-plugin/` の親）
+/// からの相対パスでプラグインを参照するエントリを追加します（同じ名前のエントリは置き換えます）。
+/// ファイルが存在しない場合は新しいマーケットプレイスとして作成します。
+///
+/// フォルダーへの出力では、選択から外した内容が残らないよう出力先を丸ごと置き換えます。
+/// 空でないフォルダーは、同じ名前のプラグインを以前に書き出したフォルダーのみ指定できます。
+///
+/// # Arguments
+///
+/// * `dest_path` - 出力先パス（フォルダー、またはZIP・tar.gzファイル）
+/// * `metadata` - プラグイン名・バージョン・説明・作者
+/// * `selection` - 含めるスキル・サブエージェント・スラッシュコマンドとフック
+/// * `format` - 形式（省略時は出力先のパスから判定し、判定できない場合はフォルダー）
+/// * `marketplace_path` - エントリを追加する `marketplace.json` のパス（フォルダーへの出力のみ）
+///
+/// # Returns
+///
+/// 書き込んだファイル・警告・置き換えた秘密情報
+///
+/// # Security
+///
+/// ~/.claude/ 配下の、ダッシュボード設定で除外されていないファイルのみ含めます。
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn export_plugin(
+    dest_path: String,
+    metadata: PluginMetadata,
+    selection: PluginSelection,
+    format: Option<ArchiveFormat>,
+    marketplace_path: Option<String>,
+) -> AppResult<PluginExportResult> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    let metadata = normalize_metadata(&metadata);
+    let plugin = build_plugin(&claude_dir, &metadata, &selection)?;
+    if !plugin.errors.is_empty() {
+        return Err(format!("Invalid plugin: {}", plugin.errors.join("; ")));
+    }
+
+    let dest_buf = PathBuf::from(&dest_path);
+    let format = format
+        .or_else(|| archive::detect_format(&dest_buf))
+        .unwrap_or(ArchiveFormat::Directory);
+    // 書き出す前にマーケットプレイスを検証し、失敗した場合は何も書き込まない
+    let marketplace = match marketplace_path.as_deref() {
+        Some(_) if format != ArchiveFormat::Directory => {
+            return Err(
+                "A marketplace entry can only reference a plugin exported to a folder".to_string(),
+            );
+        }
+        Some(path) => Some(prepare_marketplace(Path::new(path), &dest_buf, &metadata)?),
+        None => None,
+    };
+    if format == ArchiveFormat::Directory {
+        check_plugin_destination(&dest_buf, &metadata.name)?;
+    }
+
+    let mut redactor = selection.redact_secrets.then(Redactor::new);
+    let mut files = Vec::with_capacity(plugin.files.len());
+    let mut writer = ArchiveWriter::create(&dest_buf, format)?;
+    writer.replace_existing_folder();
+    for (relative, contents) in plugin.files {
+        let contents = match redactor.as_mut() {
+            Some(redactor) => redactor.redact(&relative, contents),
+            None => contents,
+        };
+        writer.add_file(&relative, &contents)?;
+        files.push(relative);
+    }
+    writer.finish()?;
+
+    if let Some((path, document)) = &marketplace {
+        write_marketplace(path, document)?;
+    }
+
+    info!(
+        "Exported plugin {} {} ({} files) as {format:?}: {dest_path}",
+        metadata.name,
+        metadata.version,
+        files.len()
+    );
+    Ok(PluginExportResult {
+        files,
+        warnings: plugin.warnings,
+        redactions: redactor.map(Redactor::into_report).unwrap_or_default(),
+        marketplace_path,
+    })
+}
+
+/// 出力先のフォルダーを置き換えてよいか確認
+///
+/// 存在しない・空のフォルダーと、同じ名前のプラグインのマニフェストがあるフォルダーのみ許可します。
+fn check_plugin_destination(dest: &Path, name: &str) -> AppResult<()> {
+    if !dest.is_dir() {
+        return Ok(());
+    }
+    let is_empty = fs::read_dir(dest)
+        .map_err(|e| format!("Failed to read destination folder: {e}"))?
+        .next()
+        .is_none();
+    if is_empty {
+        return Ok(());
+    }
+    let previous = fs::read_to_string(dest.join(PLUGIN_MANIFEST_FILE))
+        .ok()
+        .and_then(|c| serde_json::from_str::<Value>(&c).ok());
+    if previous.as_ref().and_then(|m| m["name"].as_str()) == Some(name) {
+        return Ok(());
+    }
+    Err(format!(
+        "{} is not empty and is not an export of the plugin {name}",
+        dest.display()
+    ))
+}
+
+/// 書き出すプラグインの内容
+#[derive(Debug, Default)]
+struct PluginContents {
+    /// プラグインのルートからの相対パスと内容
+    files: BTreeMap<String, Vec<u8>>,
+    /// エクスポートできない問題
+    errors: Vec<String>,
+    /// 確認が必要な問題
+    warnings: Vec<String>,
+}
+
+/// 選択した内容からプラグインのファイルを組み立て、検証する
+///
+/// スキル・サブエージェント・スラッシュコマンドは設定ルートと同じ相対パスでプラグインに含めます。
+fn build_plugin(
+    claude_dir: &Path,
+    metadata: &PluginMetadata,
+    selection: &PluginSelection,
+) -> AppResult<PluginContents> {
+    let mut plugin = PluginContents::default();
+    validate_metadata(metadata, &mut plugin.errors);
+
+    // 選択した名前・パスはファイルの一覧と照合し、一覧にないパスは読み込まない
+    let candidates: BTreeMap<String, PathBuf> = collect_target_files(claude_dir, None, None)
+        .into_iter()
+        .map(|(path, relative)| (relative, path))
+        .collect();
+    let read = |relative: &str, path: &Path| {
+        fs::read(path).map_err(|e| format!("Failed to read file {relative}: {e}"))
+    };
+
+    for name in selection.skills.iter().collect::<BTreeSet<_>>() {
+        let prefix = format!("skills/{name}/");
+        let skill_file = format!("{prefix}SKILL.md");
+        if name.is_empty() || name.contains(['/', '\\']) || !candidates.contains_key(&skill_file) {
+            plugin.errors.push(format!("{skill_file}: skill not found"));
+            continue;
+        }
+        // スクリプトなどを含めるため、スキルのフォルダーは拡張子に関係なくすべて含める
+        for (path, relative) in skill_files(claude_dir, name) {
+            let contents = read(&relative, &path)?;
+            if relative == skill_file {
+                check_frontmatter(
+                    &relative,
+                    &contents,
+                    &["name", "description"],
+                    &mut plugin.errors,
+                );
+            }
+            plugin.files.insert(relative, contents);
+        }
+    }
+
+    for (relative, directory, required) in selection
+        .agents
+        .iter()
+        .map(|r| (r, "agents/", &["name", "description"][..]))
+        .chain(selection.commands.iter().map(|r| (r, "commands/", &[][..])))
+    {
+        let Some(path) = candidates
+            .get(relative)
+            .filter(|_| is_component_file(relative, directory))
+        else {
+            plugin
+                .errors
+                .push(format!("{relative}: not found in {directory}"));
+            continue;
+        };
+        let contents = read(relative, path)?;
+        check_frontmatter(relative, &contents, required, &mut plugin.errors);
+        if directory == "commands/" {
+            check_frontmatter(relative, &contents, &["description"], &mut plugin.warnings);
+        }
+        plugin.files.insert(relative.clone(), contents);
+    }
+
+    if selection.include_hooks {
+        match settings_hooks(claude_dir) {
+            Some(hooks) => {
+                check_hook_commands(&hooks, claude_dir, &mut plugin.warnings);
+                let json = serde_json::to_vec_pretty(&json!({ "hooks": hooks }))
+                    .map_err(|e| format!("Failed to serialize hooks: {e}"))?;
+                plugin.files.insert(PLUGIN_HOOKS_FILE.to_string(), json);
+            }
+            None => plugin
+                .errors
+                .push("settings.json: no hooks are configured".to_string()),
+        }
+    }
+
+    if plugin.files.is_empty() {
+        plugin
+            .errors
+            .push("The plugin has no skills, agents, commands or hooks".to_string());
+    }
+
+    let manifest = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| format!("Failed to serialize plugin manifest: {e}"))?;
+    plugin
+        .files
+        .insert(PLUGIN_MANIFEST_FILE.to_string(), manifest);
+    Ok(plugin)
+}
+
+/// 前後の空白を除き、空のメールアドレスを省いたメタデータ
+fn normalize_metadata(metadata: &PluginMetadata) -> PluginMetadata {
+    let mut normalized = metadata.clone();
+    for field in [
+        &mut normalized.name,
+        &mut normalized.version,
+        &mut normalized.description,
+        &mut normalized.author.name,
+    ] {
+        *field = field.trim().to_string();
+    }
+    normalized.author.email = metadata
+        .author
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty())
+        .map(str::to_string);
+    normalized
+}
+
+/// メタデータを検証
+fn validate_metadata(metadata: &PluginMetadata, errors: &mut Vec<String>) {
+    if !is_kebab_case(&metadata.name) {
+        errors.push(format!(
+            "{PLUGIN_MANIFEST_FILE}: name must be lowercase letters, digits and hyphens (got {:?})",
+            metadata.name
+        ));
+    } else if metadata.name.len() > MAX_PLUGIN_NAME_LEN {
+        errors.push(format!(
+            "{PLUGIN_MANIFEST_FILE}: name is too long (limit {MAX_PLUGIN_NAME_LEN} characters)"
+        ));
+    }
+    if !is_semver(&metadata.version) {
+        errors.push(format!(
+            "{PLUGIN_MANIFEST_FILE}: version must be a semantic version such as 1.0.0 (got {:?})",
+            metadata.version
+        ));
+    }
+    if metadata.description.is_empty() {
+        errors.push(format!("{PLUGIN_MANIFEST_FILE}: description is required"));
+    }
+    if metadata.author.name.is_empty() {
+        errors.push(format!("{PLUGIN_MANIFEST_FILE}: author name is required"));
+    }
+    if let Some(email) = &metadata.author.email {
+        let valid = email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+        if !valid {
+            errors.push(format!(
+                "{PLUGIN_MANIFEST_FILE}: author email is invalid ({email})"
+            ));
+        }
+    }
+}
+
+/// 小文字の英数字をハイフンでつないだ名前か
+fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        })
+}
+
+/// セマンティックバージョニングの形式（`1.2.3`・`1.0.0-beta.1`・`1.0.0+build`）か
+fn is_semver(version: &str) -> bool {
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let identifiers_valid = |value: Option<&str>| {
+        value.map_or(true, |value| {
+            value.split('.').all(|id| {
+                !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+            })
+        })
+    };
+    let numbers: Vec<&str> = core.split('.').collect();
+    numbers.len() == 3
+        && numbers.iter().all(|n| {
+            !n.is_empty()
+                && n.bytes().all(|b| b.is_ascii_digit())
+                && (n.len() == 1 || !n.starts_with('0'))
+        })
+        && identifiers_valid(pre)
+        && identifiers_valid(build)
+}
+
+/// スキルのフォルダー配下のファイルを列挙
+///
+/// ダッシュボード設定で除外されたパスとシンボリックリンクは含めません。
+fn skill_files(claude_dir: &Path, name: &str) -> Vec<(PathBuf, String)> {
+    WalkDir::new(claude_dir.join("skills").join(name))
+        .into_iter()
+        .filter_entry(|e| {
+            e.path()
+                .strip_prefix(claude_dir)
+                .map(|rel| !is_excluded_relative_path(rel))
+                .unwrap_or(false)
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e
+                .path()
+                .strip_prefix(claude_dir)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            Some((e.into_path(), relative))
+        })
+        .collect()
+}
+
+/// 指定したディレクトリ配下のMarkdownファイルか
+fn is_component_file(relative: &str, directory: &str) -> bool {
+    relative.starts_with(directory) && relative.ends_with(".md")
+}
+
+/// frontmatterの必須の値がない場合に問題として記録
+fn check_frontmatter(relative: &str, contents: &[u8], keys: &[&str], problems: &mut Vec<String>) {
+    let text = String::from_utf8_lossy(contents);
+    for key in keys {
+        if extract_frontmatter_field(&text, key).map_or(true, |value| value.is_empty()) {
+            problems.push(format!("{relative}: frontmatter has no {key}"));
+        }
+    }
+}
+
+/// `settings.json` のフック設定（設定されていない場合は`None`）
+fn settings_hooks(claude_dir: &Path) -> Option<Value> {
+    let content = fs::read_to_string(claude_dir.join("settings.json")).ok()?;
+    let settings: Value = serde_json::from_str(&content).ok()?;
+    settings
+        .get("hooks")
+        .filter(|hooks| hooks.as_object().is_some_and(|map| !map.is_empty()))
+        .cloned()
+}
+
+/// 設定ルートのファイルを参照するフックのコマンドを警告として記録
+///
+/// プラグインのフックはインストール先の環境で実行されるため、
+/// 同梱するファイルは `${CLAUDE_PLUGIN_ROOT}` で参照する必要があります。
+fn check_hook_commands(hooks: &Value, claude_dir: &Path, warnings: &mut Vec<String>) {
+    let local_prefixes = [
+        "~/.claude".to_string(),
+        "$HOME/.claude".to_string(),
+        claude_dir.to_string_lossy().to_string(),
+    ];
+    let mut stack = vec![hooks];
+    while let Some(value) = stack.pop() {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    match child.as_str() {
+                        Some(command)
+                            if key == "command"
+                                && local_prefixes.iter().any(|p| command.contains(p)) =>
+                        {
+                            warnings.push(format!(
+                                "{PLUGIN_HOOKS_FILE}: hook command refers to the local config directory ({command}); use ${{CLAUDE_PLUGIN_ROOT}} for files bundled with the plugin"
+                            ));
+                        }
+                        _ => stack.push(child),
+                    }
+                }
+            }
+            Value::Array(items) => stack.extend(items),
+            _ => {}
+        }
+    }
+}
+
+/// `marketplace.json` にプラグインのエントリを追加した内容を作成
+///
+/// マーケットプレイスのルートは `marketplace.json` のあるフォルダー
+/// （`.claude-plugin/` 配下の場合はその親）で、プラグインはルート配下に出力する必要があります。
+fn prepare_marketplace(
+    marketplace_path: &Path,
+    plugin_dir: &Path,
+    metadata: &PluginMetadata,
+) -> AppResult<(PathBuf, Value)> {
+    let parent = marketplace_path
+        .parent()
+        .filter(|_| marketplace_path.is_absolute())
+        .ok_or_else(|| format!("Invalid marketplace path: {}", marketplace_path.display()))?;
+    let root = match parent.parent() {
+        Some(root) if parent.file_name().is_some_and(|n| n == ".claude-plugin") => root,
+        _ => parent,
+    };
+    let source = plugin_dir
+        .strip_prefix(root)
+        .ok()
+        .filter(|relative| {
+            relative.components().next().is_some()
+                && relative
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)))
+        })
+        .map(|relative| format!("./{}", relative.to_string_lossy().replace('\\', "/")))
+        .ok_or_else(|| {
+            format!(
+                "The plugin folder must be inside the marketplace folder: {}",
+                root.display()
+            )
+        })?;
+
+    let mut document = if marketplace_path.exists() {
+        let content = fs::read_to_string(marketplace_path)
+            .map_err(|e| format!("Failed to read marketplace: {e}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Invalid marketplace JSON: {e}"))?
+    } else {
+        json!({
+            "name": marketplace_name(root),
+            "owner": metadata.author,
+            "plugins": [],
+        })
+    };
+    let plugins = document
+        .as_object_mut()
+        .map(|map| map.entry("plugins").or_insert_with(|| json!([])))
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| "Invalid marketplace: plugins must be an array".to_string())?;
+
+    let entry = json!({
+        "name": metadata.name,
+        "source": source,
+        "description": metadata.description,
+        "version": metadata.version,
+        "author": metadata.author,
+    });
+    match plugins
+        .iter_mut()
+        .find(|plugin| plugin.get("name").and_then(Value::as_str) == Some(&metadata.name))
+    {
+        Some(existing) => *existing = entry,
+        None => plugins.push(entry),
+    }
+    Ok((marketplace_path.to_path_buf(), document))
+}
+
+/// フォルダー名から新しいマーケットプレイスの名前を作成
+fn marketplace_name(root: &Path) -> String {
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if name.is_empty() {
+        DEFAULT_MARKETPLACE_NAME.to_string()
+    } else {
+        name
+    }
+}
+
+/// `marketplace.json` を書き込む
+fn write_marketplace(path: &Path, document: &Value) -> AppResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create marketplace directory: {e}"))?;
+    }
+    let mut json = serde_json::to_string_pretty(document)
+        .map_err(|e| format!("Failed to serialize marketplace: {e}"))?;
+    json.push('\n');
+    write_file_atomic(path, json.as_bytes())
+        .map_err(|e| format!("Failed to write marketplace: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PluginAuthor;
+    use crate::utils::TestClaudeDir;
+
+    #[test]
+    fn test_export_plugin_with_marketplace_entry() {
+        let dir = TestClaudeDir::new();
+        let root = dir.path();
+        for (path, content) in [
+            (
+                "skills/lint/SKILL.md",
+                "---\nname: lint\ndescription: Lint code\n---\n",
+            ),
+            ("skills/lint/scripts/run.sh", "echo lint"),
+            (
+                "agents/reviewer.md",
+                "---\nname: reviewer\ndescription: Reviews\n---\n",
+            ),
+            ("agents/draft.md", "no frontmatter"),
+            ("commands/review.md", "Review the diff"),
+            (
+                "settings.json",
+                r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"~/.claude/notify.sh"}]}]}}"#,
+            ),
+        ] {
+            dir.write(path, content);
+        }
+
+        let components = list_plugin_components().unwrap();
+        assert_eq!(components.skills, vec!["lint"]);
+        assert_eq!(
+            components.agents,
+            vec!["agents/draft.md", "agents/reviewer.md"]
+        );
+        assert!(components.has_hooks);
+
+        let metadata = PluginMetadata {
+            name: "team-tools".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Shared tools".to_string(),
+            author: PluginAuthor {
+                name: "Team".to_string(),
+                email: Some(" ".to_string()),
+            },
+        };
+        let selection = PluginSelection {
+            skills: vec!["lint".to_string()],
+            agents: vec!["agents/reviewer.md".to_string()],
+            commands: vec!["commands/review.md".to_string()],
+            include_hooks: true,
+            ..PluginSelection::default()
+        };
+
+        // 不正なメタデータ・frontmatterのないサブエージェント・存在しないパスはエラー
+        let invalid = validate_plugin(
+            PluginMetadata {
+                name: "Team Tools".to_string(),
+                version: "1.0".to_string(),
+                ..metadata.clone()
+            },
+            PluginSelection {
+                agents: vec!["agents/draft.md".to_string(), "../secret.md".to_string()],
+                ..selection.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(invalid.errors.len(), 5, "{:?}", invalid.errors);
+
+        let out = root.join("out");
+        let dest = out.join("plugins/team-tools");
+        let marketplace = out.join(".claude-plugin/marketplace.json");
+        let result = export_plugin(
+            dest.to_string_lossy().to_string(),
+            metadata.clone(),
+            selection,
+            None,
+            Some(marketplace.to_string_lossy().to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            result.files,
+            vec![
+                PLUGIN_MANIFEST_FILE,
+                "agents/reviewer.md",
+                "commands/review.md",
+                PLUGIN_HOOKS_FILE,
+                "skills/lint/SKILL.md",
+                "skills/lint/scripts/run.sh",
+            ]
+        );
+        // ~/.claude を参照するフックと、descriptionのないスラッシュコマンドは警告
+        assert_eq!(result.warnings.len(), 2, "{:?}", result.warnings);
+
+        let manifest: Value =
+            serde_json::from_str(&fs::read_to_string(dest.join(PLUGIN_MANIFEST_FILE)).unwrap())
+                .unwrap();
+        assert_eq!(manifest["name"], "team-tools");
+        assert_eq!(manifest["author"], json!({ "name": "Team" }));
+        let hooks: Value =
+            serde_json::from_str(&fs::read_to_string(dest.join(PLUGIN_HOOKS_FILE)).unwrap())
+                .unwrap();
+        assert!(hooks["hooks"]["Stop"].is_array());
+
+        let document: Value =
+            serde_json::from_str(&fs::read_to_string(&marketplace).unwrap()).unwrap();
+        assert_eq!(document["name"], "out");
+        assert_eq!(document["plugins"][0]["source"], "./plugins/team-tools");
+
+        // 同じ名前のエントリは置き換える
+        let (_, updated) = prepare_marketplace(
+            &marketplace,
+            &dest,
+            &PluginMetadata {
+                version: "1.1.0".to_string(),
+                ..normalize_metadata(&metadata)
+            },
+        )
+        .unwrap();
+        assert_eq!(updated["plugins"].as_array().unwrap().len(), 1);
+        assert_eq!(updated["plugins"][0]["version"], "1.1.0");
+
+        // マーケットプレイスの外には出力できない
+        assert!(prepare_marketplace(&marketplace, &root.join("elsewhere"), &metadata).is_err());
+    }
+
+    #[test]
+    fn test_reexport_plugin_removes_deselected_components() {
+        let dir = TestClaudeDir::new();
+        dir.write("skills/lint/SKILL.md", "---\nname: lint\ndescription: Lint\n---\n");
+        dir.write("skills/old/SKILL.md", "---\nname: old\ndescription: Old\n---\n");
+        dir.write("agents/x.md", "---\nname: x\ndescription: X\n---\n");
+        dir.write(
+            "settings.json",
+            r#"{"hooks":{"Stop":[{"hooks":[{"type":"command","command":"echo"}]}]}}"#,
+        );
+        let metadata = PluginMetadata {
+            name: "team-tools".to_string(),
+            version: "1.0.0".to_string(),
+            description: "Shared tools".to_string(),
+            author: PluginAuthor {
+                name: "Team".to_string(),
+                email: None,
+            },
+        };
+        let dest = dir.path().join("out/team-tools");
+        let export = |selection: PluginSelection| {
+            export_plugin(
+                dest.to_string_lossy().to_string(),
+                metadata.clone(),
+                selection,
+                Some(ArchiveFormat::Directory),
+                None,
+            )
+        };
+
+        export(PluginSelection {
+            skills: vec!["lint".to_string(), "old".to_string()],
+            agents: vec!["agents/x.md".to_string()],
+            include_hooks: true,
+            ..PluginSelection::default()
+        })
+        .unwrap();
+        assert!(dest.join("agents/x.md").is_file());
+
+        // 選択から外したスキル・サブエージェント・フックは残らない
+        export(PluginSelection {
+            skills: vec!["lint".to_string()],
+            ..PluginSelection::default()
+        })
+        .unwrap();
+        assert!(dest.join("skills/lint/SKILL.md").is_file());
+        assert!(!dest.join("skills/old").exists());
+        assert!(!dest.join("agents").exists());
+        assert!(!dest.join(PLUGIN_HOOKS_FILE).exists());
+
+        // 別のプラグイン・無関係なファイルがあるフォルダーは置き換えない
+        let other = dir.write("out/other/notes.md", "keep");
+        assert!(export_plugin(
+            other.parent().unwrap().to_string_lossy().to_string(),
+            metadata.clone(),
+            PluginSelection::default(),
+            Some(ArchiveFormat::Directory),
+            None,
+        )
+        .is_err());
+        assert!(other.is_file());
+    }
+}
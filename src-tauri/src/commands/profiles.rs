@@ -0,0 +1,339 @@
+//! 設定ルート（プロファイル）操作コマンド
+//!
+//! 仕事用/個人用など複数のClaude設定ディレクトリを切り替える機能を提供します。
+//! プロファイル一覧はアプリの設定ディレクトリ（例: `~/.config/claude-setting-dashboard/profiles.json`）
+//! に保存されるため、設定ルートを切り替えても失われません。
+
+use crate::commands::watcher::start_watcher;
+use crate::error::AppResult;
+use crate::types::{ConfigProfile, ConfigRootInfo, ConfigRootSource};
+use crate::utils::{expand_home, resolve_claude_dir, set_active_claude_dir, write_file_atomic};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+/// 設定ルート切り替え時に送信するイベント名
+pub const CONFIG_ROOT_CHANGED_EVENT: &str = "config-root:changed";
+
+/// プロファイルデータ全体
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProfilesData {
+    /// 登録済みプロファイル一覧（登録順）
+    profiles: Vec<ConfigProfile>,
+    /// 選択中のプロファイル名（`None` の場合は環境変数/デフォルト）
+    active_profile: Option<String>,
+}
+
+/// プロファイルファイルのパスを取得
+fn get_profiles_file_path() -> Result<PathBuf, String> {
+    let config_dir =
+        dirs::config_dir().ok_or_else(|| "Could not find config directory".to_string())?;
+    Ok(config_dir
+        .join("claude-setting-dashboard")
+        .join("profiles.json"))
+}
+
+/// プロファイルデータを読み込み
+fn load_profiles() -> Result<ProfilesData, String> {
+    let file_path = get_profiles_file_path()?;
+
+    if !file_path.exists() {
+        return Ok(ProfilesData::default());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read profiles file: {e}"))?;
+
+    serde_json::from_str::<ProfilesData>(&content)
+        .map_err(|e| format!("Failed to parse profiles file: {e}"))
+}
+
+/// プロファイルデータを保存
+fn save_profiles(data: &ProfilesData) -> Result<(), String> {
+    let file_path = get_profiles_file_path()?;
+
+    if let Some(parent) = file_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create profiles directory: {e}"))?;
+        }
+    }
+
+    let json = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize profiles: {e}"))?;
+
+    write_file_atomic(&file_path, json.as_bytes())
+        .map_err(|e| format!("Failed to save profiles: {e}"))
+}
+
+/// 設定ルートとして使用するディレクトリを検証
+fn validate_root_dir(path: &str) -> Result<PathBuf, String> {
+    let path_buf = expand_home(path.trim()).map_err(|e| e.to_string())?;
+
+    if !path_buf.is_absolute() {
+        return Err(format!("Config root must be an absolute path: {path}"));
+    }
+    if !path_buf.is_dir() {
+        return Err(format!("Directory not found: {}", path_buf.display()));
+    }
+
+    Ok(path_buf)
+}
+
+/// 現在の設定ルート情報を構築
+fn current_root_info() -> Result<ConfigRootInfo, String> {
+    let (path, source) = resolve_claude_dir().map_err(|e| e.to_string())?;
+
+    let profile_name = if source == ConfigRootSource::Profile {
+        load_profiles()
+            .ok()
+            .and_then(|data| data.active_profile)
+    } else {
+        None
+    };
+
+    Ok(ConfigRootInfo {
+        exists: path.is_dir(),
+        path: path.to_string_lossy().to_string(),
+        source,
+        profile_name,
+    })
+}
+
+/// 設定ルートを切り替えてイベントを送信
+///
+/// ファイル監視も新しい設定ルートで再開します。
+fn apply_root(app: &AppHandle, root: Option<(PathBuf, ConfigRootSource)>) -> AppResult<ConfigRootInfo> {
+    set_active_claude_dir(root);
+
+    let info = current_root_info()?;
+    info!("Config root switched: {} ({:?})", info.path, info.source);
+
+    if let Err(e) = start_watcher(app) {
+        warn!("Failed to restart file watcher: {e}");
+    }
+
+    if let Err(e) = app.emit(CONFIG_ROOT_CHANGED_EVENT, info.clone()) {
+        warn!("Failed to emit {CONFIG_ROOT_CHANGED_EVENT}: {e}");
+    }
+
+    Ok(info)
+}
+
+/// アプリ起動時に前回選択していたプロファイルを復元
+///
+/// プロファイルのディレクトリが存在しない場合は環境変数/デフォルトにフォールバックします。
+pub fn restore_active_profile() {
+    let data = match load_profiles() {
+        Ok(d) => d,
+        Err(e) => {
+            warn!("Failed to load config profiles: {e}");
+            return;
+        }
+    };
+
+    let Some(active_name) = data.active_profile else {
+        return;
+    };
+
+    match data.profiles.iter().find(|p| p.name == active_name) {
+        Some(profile) if PathBuf::from(&profile.path).is_dir() => {
+            set_active_claude_dir(Some((
+                PathBuf::from(&profile.path),
+                ConfigRootSource::Profile,
+            )));
+            info!("Restored config profile: {} ({})", profile.name, profile.path);
+        }
+        _ => warn!("Config profile is unavailable, falling back to default: {active_name}"),
+    }
+}
+
+/// 現在アクティブな設定ルートを取得
+///
+/// # Returns
+///
+/// 設定ルートのパス、解決元（override/profile/env/default）、プロファイル名
+#[tauri::command]
+pub fn get_active_config_root() -> AppResult<ConfigRootInfo> {
+    current_root_info()
+}
+
+/// 登録済みプロファイル一覧を取得
+///
+/// # Returns
+///
+/// プロファイル一覧（登録順）
+#[tauri::command]
+pub fn get_config_profiles() -> AppResult<Vec<ConfigProfile>> {
+    Ok(load_profiles()?.profiles)
+}
+
+/// プロファイルを追加（同名の場合はパスを更新）
+///
+/// # Arguments
+///
+/// * `name` - プロファイル名
+/// * `path` - 設定ルートのパス（`~/` で始まる形式も可）
+///
+/// # Returns
+///
+/// 追加したプロファイル
+///
+/// # Errors
+///
+/// - 名前が空の場合
+/// - ディレクトリが存在しない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn add_config_profile(name: String, path: String) -> AppResult<ConfigProfile> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+
+    let path_buf = validate_root_dir(&path)?;
+    let profile = ConfigProfile {
+        name: name.clone(),
+        path: path_buf.to_string_lossy().to_string(),
+    };
+
+    let mut data = load_profiles()?;
+    match data.profiles.iter_mut().find(|p| p.name == name) {
+        Some(existing) => existing.path = profile.path.clone(),
+        None => data.profiles.push(profile.clone()),
+    }
+    save_profiles(&data)?;
+
+    info!("Saved config profile: {name} ({})", profile.path);
+
+    Ok(profile)
+}
+
+/// プロファイルを削除
+///
+/// 削除したプロファイルが選択中の場合は環境変数/デフォルトに戻します。
+///
+/// # Arguments
+///
+/// * `name` - 削除するプロファイル名
+///
+/// # Errors
+///
+/// - 指定したプロファイルが存在しない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn remove_config_profile(app: AppHandle, name: String) -> AppResult<()> {
+    let mut data = load_profiles()?;
+
+    let original_len = data.profiles.len();
+    data.profiles.retain(|p| p.name != name);
+
+    if data.profiles.len() == original_len {
+        return Err(format!("Profile not found: {name}"));
+    }
+
+    let was_active = data.active_profile.as_deref() == Some(name.as_str());
+    if was_active {
+        data.active_profile = None;
+    }
+    save_profiles(&data)?;
+
+    info!("Removed config profile: {name}");
+
+    if was_active {
+        apply_root(&app, None)?;
+    }
+
+    Ok(())
+}
+
+/// 設定ルートを切り替え
+///
+/// `profile` と `path` のどちらも指定しない場合は環境変数 `CLAUDE_CONFIG_DIR`
+/// またはデフォルトの `~/.claude` に戻します。
+/// 切り替え後に `config-root:changed` イベントを送信します。
+///
+/// # Arguments
+///
+/// * `profile` - 切り替え先のプロファイル名（選択は次回起動時も維持）
+/// * `path` - 切り替え先のパス（明示指定。現在のセッションのみ有効）
+///
+/// # Returns
+///
+/// 切り替え後の設定ルート情報
+///
+/// # Errors
+///
+/// - `profile` と `path` が両方指定された場合
+/// - プロファイルが存在しない場合
+/// - ディレクトリが存在しない場合
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn switch_config_root(
+    app: AppHandle,
+    profile: Option<String>,
+    path: Option<String>,
+) -> AppResult<ConfigRootInfo> {
+    let mut data = load_profiles()?;
+
+    let root = match (profile, path) {
+        (Some(_), Some(_)) => {
+            return Err("Specify either a profile or a path, not both".to_string());
+        }
+        (Some(name), None) => {
+            let target = data
+                .profiles
+                .iter()
+                .find(|p| p.name == name)
+                .ok_or_else(|| format!("Profile not found: {name}"))?;
+            let path_buf = validate_root_dir(&target.path)?;
+            data.active_profile = Some(name);
+            Some((path_buf, ConfigRootSource::Profile))
+        }
+        (None, Some(path)) => {
+            data.active_profile = None;
+            Some((validate_root_dir(&path)?, ConfigRootSource::Override))
+        }
+        (None, None) => {
+            data.active_profile = None;
+            None
+        }
+    };
+
+    save_profiles(&data)?;
+    apply_root(&app, root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_data_serialization() {
+        let data = ProfilesData {
+            profiles: vec![ConfigProfile {
+                name: "work".to_string(),
+                path: "/home/user/.claude-work".to_string(),
+            }],
+            active_profile: Some("work".to_string()),
+        };
+
+        let json = serde_json::to_string(&data)
+            .expect("ProfilesDataのシリアライズに失敗しました");
+        assert!(json.contains("\"activeProfile\":\"work\""));
+
+        let parsed: ProfilesData = serde_json::from_str(&json)
+            .expect("ProfilesDataのデシリアライズに失敗しました");
+        assert_eq!(parsed.profiles.len(), 1);
+        assert_eq!(parsed.profiles[0].path, "/home/user/.claude-work");
+    }
+
+    #[test]
+    fn test_validate_root_dir_rejects_missing_directory() {
+        assert!(validate_root_dir("/nonexistent/claude-config-root").is_err());
+        assert!(validate_root_dir("relative/path").is_err());
+    }
+}
@@ -0,0 +1,795 @@
+//! ワークスペース一括置換コマンド
+//!
+//! 設定ルート配下の全ファイルを対象に検索置換を行います。
+//! 置換前にマッチ一覧（行・列、置換前後のスニペット）をプレビューし、
+//! 全件または選択したマッチのみを適用できます。
+//! 適用時は変更するファイルごとに1つバックアップを作成し、一括で元に戻せます。
+
+use crate::backup_store;
+use crate::commands::backup::create_backup_internal;
+use crate::commands::snapshot::create_auto_snapshot;
+use crate::commands::files::build_search_pattern;
+use crate::error::AppResult;
+use crate::git_history;
+use crate::security::{resolve_path, AccessPolicy};
+use crate::types::{BackupOperation, SnapshotTrigger};
+use crate::utils::{
+    content_version_token, get_claude_dir, is_allowed_extension, is_excluded_relative_path,
+    write_file_atomic,
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::{info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use walkdir::WalkDir;
+
+/// プレビューで返すマッチ数の上限
+const MAX_PREVIEW_MATCHES: usize = 5000;
+
+/// スニペットとして返す1行あたりの最大文字数
+const MAX_SNIPPET_CHARS: usize = 240;
+
+/// 一括置換の操作記録を保存するディレクトリ（backups/ からの相対パス）
+const OPERATIONS_DIR: &str = "operations";
+
+/// 操作IDの連番（同じミリ秒に実行した操作の記録が上書きされないようにする）
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 一括置換のオプション
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceReplaceOptions {
+    /// 検索文字列（またはパターン）
+    pub search: String,
+    /// 置換文字列
+    pub replace: String,
+    /// 大文字小文字を区別するか
+    pub case_sensitive: bool,
+    /// 単語全体にマッチさせるか
+    pub whole_word: bool,
+    /// 正規表現を使用するか
+    pub use_regex: bool,
+    /// 対象に含めるglob（設定ルートからの相対パス。空の場合は全ファイル）
+    #[serde(default)]
+    pub include_globs: Vec<String>,
+    /// 対象から除外するglob（設定ルートからの相対パス）
+    #[serde(default)]
+    pub exclude_globs: Vec<String>,
+}
+
+/// 置換対象のマッチ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceMatch {
+    /// マッチ開始位置（バイトオフセット。適用時の選択に使用）
+    pub start: usize,
+    /// マッチ終了位置（バイトオフセット）
+    pub end: usize,
+    /// 行番号（1始まり）
+    pub line: usize,
+    /// 列番号（1始まり、文字単位）
+    pub column: usize,
+    /// マッチした文字列
+    pub matched_text: String,
+    /// 置換後の文字列
+    pub replacement: String,
+    /// 置換前の行スニペット
+    pub before: String,
+    /// 置換後の行スニペット
+    pub after: String,
+}
+
+/// ファイルごとのプレビュー
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplacePreview {
+    /// 絶対パス
+    pub path: String,
+    /// 設定ルートからの相対パス
+    pub relative_path: String,
+    /// マッチ一覧
+    pub matches: Vec<ReplaceMatch>,
+}
+
+/// 一括置換のプレビュー結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceReplacePreview {
+    /// マッチしたファイル一覧
+    pub files: Vec<FileReplacePreview>,
+    /// 総マッチ数
+    pub total_matches: usize,
+    /// 上限に達してプレビューを打ち切ったかどうか
+    pub truncated: bool,
+}
+
+/// 適用するマッチの選択
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceSelection {
+    /// ファイルの絶対パス
+    pub path: String,
+    /// 適用するマッチの開始位置（プレビューの `start`）
+    pub match_starts: Vec<usize>,
+}
+
+/// ファイルごとの適用結果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplaceOutcome {
+    /// 絶対パス
+    pub path: String,
+    /// 置換した回数
+    pub replaced_count: usize,
+    /// 作成したバックアップのID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+    /// 置換後の内容のSHA-256ハッシュ（元に戻す前に、その後の変更がないか確認する）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// 一括置換の適用結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceReplaceResult {
+    /// 操作ID（元に戻す際に使用。変更がない場合は`None`）
+    pub operation_id: Option<String>,
+    /// 変更したファイル一覧
+    pub files: Vec<FileReplaceOutcome>,
+    /// 総置換数
+    pub total_replaced: usize,
+    /// エラーメッセージ一覧
+    pub errors: Vec<String>,
+    /// 適用前の状態を保存したスナップショットのID（対象ファイルがない場合は`None`）
+    pub snapshot_id: Option<String>,
+}
+
+/// 一括置換の操作記録（元に戻す用）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ReplaceOperation {
+    /// 操作ID
+    id: String,
+    /// 実行日時（ISO 8601形式）
+    created_at: String,
+    /// 検索文字列
+    search: String,
+    /// 置換文字列
+    replace: String,
+    /// 変更したファイルとバックアップ
+    files: Vec<FileReplaceOutcome>,
+}
+
+/// glob一覧からGlobSetを構築
+pub(crate) fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid glob pattern {pattern}: {e}"))?;
+        builder.add(glob);
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Failed to build glob set: {e}"))
+}
+
+/// 一括置換・検索の対象ファイルを列挙
+///
+/// 許可された拡張子かつ除外ディレクトリ外のファイルのうち、
+/// include/exclude globに一致するものを返します。
+pub(crate) fn collect_target_files(
+    root: &Path,
+    include: Option<&GlobSet>,
+    exclude: Option<&GlobSet>,
+) -> Vec<(PathBuf, String)> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            e.path()
+                .strip_prefix(root)
+                .map(|rel| !is_excluded_relative_path(rel))
+                .unwrap_or(false)
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_allowed_extension(e.path()))
+        .filter_map(|e| {
+            let relative = e
+                .path()
+                .strip_prefix(root)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            if include.is_some_and(|set| !set.is_match(&relative)) {
+                return None;
+            }
+            if exclude.is_some_and(|set| set.is_match(&relative)) {
+                return None;
+            }
+            Some((e.into_path(), relative))
+        })
+        .collect()
+}
+
+/// バイトオフセットから行番号と列番号（共に1始まり）を計算
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = content[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
+/// 長すぎるスニペットを切り詰める
+fn truncate_snippet(snippet: &str) -> String {
+    if snippet.chars().count() <= MAX_SNIPPET_CHARS {
+        snippet.to_string()
+    } else {
+        let truncated: String = snippet.chars().take(MAX_SNIPPET_CHARS).collect();
+        format!("{truncated}…")
+    }
+}
+
+/// マッチを含む行の置換前後のスニペットを作成
+fn build_snippets(content: &str, start: usize, end: usize, replacement: &str) -> (String, String) {
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[end..].find('\n').map_or(content.len(), |i| end + i);
+
+    let before = &content[line_start..line_end];
+    let after = format!(
+        "{}{}{}",
+        &content[line_start..start],
+        replacement,
+        &content[end..line_end]
+    );
+
+    (truncate_snippet(before), truncate_snippet(&after))
+}
+
+/// ファイル内容からマッチ一覧を作成
+fn find_matches(content: &str, regex: &Regex, replace: &str, limit: usize) -> Vec<ReplaceMatch> {
+    regex
+        .captures_iter(content)
+        .filter_map(|caps| {
+            let m = caps.get(0)?;
+            if m.as_str().is_empty() {
+                return None;
+            }
+            let mut replacement = String::new();
+            caps.expand(replace, &mut replacement);
+            let (line, column) = line_and_column(content, m.start());
+            let (before, after) = build_snippets(content, m.start(), m.end(), &replacement);
+            Some(ReplaceMatch {
+                start: m.start(),
+                end: m.end(),
+                line,
+                column,
+                matched_text: m.as_str().to_string(),
+                replacement,
+                before,
+                after,
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// 選択されたマッチのみ置換した内容を作成
+///
+/// `selected` が`None`の場合は全マッチを置換します。
+///
+/// # Returns
+///
+/// 置換後の内容と置換数。選択されたマッチが現在の内容に存在しない場合はエラー。
+fn apply_matches(
+    content: &str,
+    regex: &Regex,
+    replace: &str,
+    selected: Option<&HashSet<usize>>,
+) -> Result<(String, usize), String> {
+    let mut result = String::with_capacity(content.len());
+    let mut last_end = 0;
+    let mut applied: HashSet<usize> = HashSet::new();
+
+    for caps in regex.captures_iter(content) {
+        let Some(m) = caps.get(0) else { continue };
+        if m.as_str().is_empty() {
+            continue;
+        }
+        if selected.is_some_and(|s| !s.contains(&m.start())) {
+            continue;
+        }
+        result.push_str(&content[last_end..m.start()]);
+        caps.expand(replace, &mut result);
+        last_end = m.end();
+        applied.insert(m.start());
+    }
+    result.push_str(&content[last_end..]);
+
+    if let Some(selected) = selected {
+        if selected.iter().any(|start| !applied.contains(start)) {
+            return Err("file has changed since the preview".to_string());
+        }
+    }
+
+    Ok((result, applied.len()))
+}
+
+/// 操作記録ディレクトリのパスを取得
+fn get_operations_dir() -> Result<PathBuf, String> {
+    let claude_dir = get_claude_dir().map_err(|e| e.to_string())?;
+    Ok(claude_dir.join("backups").join(OPERATIONS_DIR))
+}
+
+/// 操作IDから操作記録ファイルのパスを取得
+fn get_operation_file_path(operation_id: &str) -> Result<PathBuf, String> {
+    if operation_id.is_empty()
+        || !operation_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("Invalid operation id: {operation_id}"));
+    }
+    Ok(get_operations_dir()?.join(format!("{operation_id}.json")))
+}
+
+/// 新しい操作IDを作成（`replace_<日時>_<連番>`）
+fn new_operation_id(now: &chrono::DateTime<chrono::Local>) -> String {
+    format!(
+        "replace_{}_{}",
+        now.format("%Y%m%d_%H%M%S_%3f"),
+        NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// 全ファイルを対象に置換をプレビュー（ファイルは変更しない）
+///
+/// # Arguments
+///
+/// * `options` - 検索オプションとinclude/exclude glob
+///
+/// # Returns
+///
+/// ファイルごと・マッチごとの行/列と置換前後のスニペット
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn preview_workspace_replace(
+    options: WorkspaceReplaceOptions,
+) -> AppResult<WorkspaceReplacePreview> {
+    if options.search.is_empty() {
+        return Err("Search pattern must not be empty".to_string());
+    }
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let regex = build_search_pattern(
+        &options.search,
+        options.case_sensitive,
+        options.whole_word,
+        options.use_regex,
+    )
+    .map_err(|e| format!("Invalid search pattern: {e}"))?;
+    let include = build_glob_set(&options.include_globs)?;
+    let exclude = build_glob_set(&options.exclude_globs)?;
+
+    let mut preview = WorkspaceReplacePreview::default();
+
+    for (path, relative_path) in collect_target_files(policy.root(), include.as_ref(), exclude.as_ref()) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let remaining = MAX_PREVIEW_MATCHES - preview.total_matches;
+        let mut matches = find_matches(&content, &regex, &options.replace, remaining + 1);
+        if matches.len() > remaining {
+            matches.truncate(remaining);
+            preview.truncated = true;
+        }
+
+        if !matches.is_empty() {
+            preview.total_matches += matches.len();
+            preview.files.push(FileReplacePreview {
+                path: path.to_string_lossy().to_string(),
+                relative_path,
+                matches,
+            });
+        }
+
+        if preview.truncated {
+            break;
+        }
+    }
+
+    Ok(preview)
+}
+
+/// 全ファイルを対象に置換を適用
+///
+/// # Arguments
+///
+/// * `options` - 検索オプションとinclude/exclude glob
+/// * `selections` - 適用するマッチ（`None` の場合は全マッチを適用）
+///
+/// # Returns
+///
+/// 変更したファイル一覧と、一括で元に戻すための操作ID
+///
+/// # Security
+///
+/// 設定ルート配下の、include/exclude glob・拡張子・除外ディレクトリの条件に一致するファイルのみ変更します
+/// （`selections` に条件外のファイルが含まれる場合は何も変更せずにエラーを返します）。
+/// 変更するファイルごとに置換前のバックアップを1つ作成し、
+/// 適用前に設定全体のスナップショットを保存します。
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn apply_workspace_replace(
+    options: WorkspaceReplaceOptions,
+    selections: Option<Vec<ReplaceSelection>>,
+) -> AppResult<WorkspaceReplaceResult> {
+    if options.search.is_empty() {
+        return Err("Search pattern must not be empty".to_string());
+    }
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let regex = build_search_pattern(
+        &options.search,
+        options.case_sensitive,
+        options.whole_word,
+        options.use_regex,
+    )
+    .map_err(|e| format!("Invalid search pattern: {e}"))?;
+
+    // 対象ファイルと選択されたマッチを決定（選択したファイルも同じ条件の対象ファイルに限る）
+    let include = build_glob_set(&options.include_globs)?;
+    let exclude = build_glob_set(&options.exclude_globs)?;
+    let allowed: Vec<PathBuf> =
+        collect_target_files(policy.root(), include.as_ref(), exclude.as_ref())
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+    let targets: Vec<(PathBuf, Option<HashSet<usize>>)> = match selections {
+        Some(selections) => {
+            let allowed: HashSet<PathBuf> = allowed.into_iter().collect();
+            selections
+                .into_iter()
+                .filter(|s| !s.match_starts.is_empty())
+                .map(|s| {
+                    let path = policy
+                        .resolve(Path::new(&s.path))
+                        .map_err(|e| e.to_string())?;
+                    if !allowed.contains(&path) {
+                        return Err(format!("Not a replace target: {}", s.path));
+                    }
+                    Ok((path, Some(s.match_starts.into_iter().collect())))
+                })
+                .collect::<Result<_, String>>()?
+        }
+        None => allowed.into_iter().map(|path| (path, None)).collect(),
+    };
+
+    let mut result = WorkspaceReplaceResult::default();
+
+    // 適用前の状態をスナップショットとして保存（失敗した場合は適用しない）
+    if !targets.is_empty() {
+        result.snapshot_id = Some(create_auto_snapshot(SnapshotTrigger::BulkReplace)?);
+    }
+
+    for (path, selected) in targets {
+        let display = path.display().to_string();
+
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                result.errors.push(format!("Failed to read {display}: {e}"));
+                continue;
+            }
+        };
+
+        let (new_content, replaced_count) =
+            match apply_matches(&content, &regex, &options.replace, selected.as_ref()) {
+                Ok(r) => r,
+                Err(e) => {
+                    result.errors.push(format!("Skipped {display}: {e}"));
+                    continue;
+                }
+            };
+
+        if replaced_count == 0 || new_content == content {
+            continue;
+        }
+
+        let backup = create_backup_internal(&path.to_string_lossy(), BackupOperation::Replace);
+        let backup_id = match backup {
+            Ok(record) => record.map(|r| r.id),
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to create backup for {display}: {e}"));
+                continue;
+            }
+        };
+
+        if let Err(e) = write_file_atomic(&path, new_content.as_bytes()) {
+            result.errors.push(format!("Failed to write {display}: {e}"));
+            continue;
+        }
+
+        result.total_replaced += replaced_count;
+        result.files.push(FileReplaceOutcome {
+            path: path.to_string_lossy().to_string(),
+            replaced_count,
+            backup_id,
+            content_hash: Some(content_version_token(new_content.as_bytes())),
+        });
+    }
+
+    if !result.files.is_empty() {
+        let now = chrono::Local::now();
+        let operation = ReplaceOperation {
+            id: new_operation_id(&now),
+            created_at: now.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+            search: options.search.clone(),
+            replace: options.replace.clone(),
+            files: result.files.clone(),
+        };
+
+        match save_operation(&operation) {
+            Ok(()) => result.operation_id = Some(operation.id),
+            Err(e) => result.errors.push(e),
+        }
+    }
+
+    if !result.files.is_empty() {
+        let paths: Vec<PathBuf> = result.files.iter().map(|f| PathBuf::from(&f.path)).collect();
+        git_history::record_change_or_warn(
+            &paths,
+            &format!(
+                "Replace \"{}\" with \"{}\" in {} file(s)",
+                options.search,
+                options.replace,
+                paths.len()
+            ),
+        );
+    }
+
+    info!(
+        "Workspace replace: {} occurrence(s) in {} file(s), {} error(s)",
+        result.total_replaced,
+        result.files.len(),
+        result.errors.len()
+    );
+
+    Ok(result)
+}
+
+/// 操作記録を保存
+fn save_operation(operation: &ReplaceOperation) -> Result<(), String> {
+    let operations_dir = get_operations_dir()?;
+    if !operations_dir.exists() {
+        fs::create_dir_all(&operations_dir)
+            .map_err(|e| format!("Failed to create operations directory: {e}"))?;
+    }
+
+    let json = serde_json::to_string_pretty(operation)
+        .map_err(|e| format!("Failed to serialize replace operation: {e}"))?;
+
+    write_file_atomic(&get_operation_file_path(&operation.id)?, json.as_bytes())
+        .map_err(|e| format!("Failed to save replace operation: {e}"))
+}
+
+/// 一括置換を元に戻す
+///
+/// 操作時に作成したバックアップから、変更した全ファイルを復元します。
+/// 置換後に変更されたファイル（内容が置換直後のハッシュと一致しないファイル）は上書きせず、
+/// エラーとして報告します。
+///
+/// # Arguments
+///
+/// * `operation_id` - `apply_workspace_replace` が返した操作ID
+///
+/// # Returns
+///
+/// 復元したファイルのパス一覧
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn undo_workspace_replace(operation_id: String) -> AppResult<Vec<String>> {
+    let file_path = get_operation_file_path(&operation_id)?;
+    if !file_path.exists() {
+        return Err(format!("Replace operation not found: {operation_id}"));
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read replace operation: {e}"))?;
+    let operation: ReplaceOperation = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse replace operation: {e}"))?;
+
+    let mut restored = Vec::new();
+    let mut errors = Vec::new();
+
+    for file in &operation.files {
+        let target = match resolve_path(Path::new(&file.path)) {
+            Ok(p) => p,
+            Err(e) => {
+                errors.push(e.to_string());
+                continue;
+            }
+        };
+        let written = restore_replaced_file(&target, file);
+        match written {
+            Ok(()) => restored.push(file.path.clone()),
+            Err(e) => errors.push(format!("Failed to restore {}: {e}", file.path)),
+        }
+    }
+
+    if !restored.is_empty() {
+        let paths: Vec<PathBuf> = restored.iter().map(PathBuf::from).collect();
+        git_history::record_change_or_warn(
+            &paths,
+            &format!("Undo replace operation {operation_id}"),
+        );
+    }
+
+    if !errors.is_empty() {
+        warn!("Undo of {operation_id} finished with errors: {errors:?}");
+        return Err(errors.join("\n"));
+    }
+
+    fs::remove_file(&file_path)
+        .map_err(|e| format!("Failed to remove replace operation: {e}"))?;
+
+    info!("Undid replace operation {operation_id}: {} file(s)", restored.len());
+
+    Ok(restored)
+}
+
+/// 置換したファイルを置換前の内容に戻す
+///
+/// 既に置換前の内容の場合は何もしません（途中で失敗した取り消しのやり直し）。
+fn restore_replaced_file(target: &Path, file: &FileReplaceOutcome) -> Result<(), String> {
+    let backup_id = file
+        .backup_id
+        .as_deref()
+        .ok_or_else(|| "no backup was recorded".to_string())?;
+    let expected_hash = file
+        .content_hash
+        .as_deref()
+        .ok_or_else(|| "the replaced content was not recorded".to_string())?;
+    let (_, original) = backup_store::read_backup(backup_id)?;
+
+    let current = fs::read(target).map_err(|e| e.to_string())?;
+    if current == original {
+        return Ok(());
+    }
+    if content_version_token(&current) != expected_hash {
+        return Err("the file was modified after the replace; skipped".to_string());
+    }
+    write_file_atomic(target, &original).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_and_column() {
+        let content = "first\nsecond line\nthird";
+        assert_eq!(line_and_column(content, 0), (1, 1));
+        assert_eq!(line_and_column(content, 13), (2, 8));
+        assert_eq!(line_and_column("日本語\nテスト", 13), (2, 2));
+    }
+
+    #[test]
+    fn test_find_matches_builds_snippets() {
+        let regex = build_search_pattern("foo", false, true, false).unwrap();
+        let matches = find_matches("a foo b\nFOO food", &regex, "bar", 10);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].before, "a foo b");
+        assert_eq!(matches[0].after, "a bar b");
+        assert_eq!((matches[1].line, matches[1].column), (2, 1));
+        assert_eq!(matches[1].after, "bar food");
+    }
+
+    #[test]
+    fn test_apply_matches_with_selection() {
+        let regex = build_search_pattern(r"v(\d)", true, false, true).unwrap();
+        let content = "v1 v2 v3";
+
+        let (all, count) = apply_matches(content, &regex, "version$1", None).unwrap();
+        assert_eq!(all, "version1 version2 version3");
+        assert_eq!(count, 3);
+
+        let selected: HashSet<usize> = [3].into_iter().collect();
+        let (partial, count) = apply_matches(content, &regex, "version$1", Some(&selected)).unwrap();
+        assert_eq!(partial, "v1 version2 v3");
+        assert_eq!(count, 1);
+
+        let stale: HashSet<usize> = [1].into_iter().collect();
+        assert!(apply_matches(content, &regex, "x", Some(&stale)).is_err());
+    }
+
+    #[test]
+    fn test_collect_target_files_with_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("skills/a")).unwrap();
+        fs::create_dir_all(root.join("backups")).unwrap();
+        fs::write(root.join("skills/a/SKILL.md"), "x").unwrap();
+        fs::write(root.join("settings.json"), "x").unwrap();
+        fs::write(root.join("backups/SKILL.md_1"), "x").unwrap();
+        fs::write(root.join("notes.txt"), "x").unwrap();
+
+        let all = collect_target_files(root, None, None);
+        assert_eq!(all.len(), 2);
+
+        let include = build_glob_set(&["skills/**".to_string()]).unwrap();
+        let only_skills = collect_target_files(root, include.as_ref(), None);
+        assert_eq!(only_skills.len(), 1);
+        assert_eq!(only_skills[0].1, "skills/a/SKILL.md");
+
+        let exclude = build_glob_set(&["*.json".to_string()]).unwrap();
+        let no_json = collect_target_files(root, None, exclude.as_ref());
+        assert_eq!(no_json.len(), 1);
+    }
+
+    #[test]
+    fn test_operation_ids_are_unique_within_a_millisecond() {
+        let _dir = crate::utils::TestClaudeDir::new();
+        let now = chrono::Local::now();
+        let ids: HashSet<String> = (0..100).map(|_| new_operation_id(&now)).collect();
+        assert_eq!(ids.len(), 100);
+        assert!(ids.iter().all(|id| get_operation_file_path(id).is_ok()));
+    }
+
+    #[test]
+    fn test_apply_rejects_out_of_scope_selection_and_undo_skips_edited_files() {
+        let dir = crate::utils::TestClaudeDir::new();
+        let root = dir.path();
+        dir.write("skills/a/SKILL.md", "foo");
+        dir.write("CLAUDE.md", "foo");
+        let options = WorkspaceReplaceOptions {
+            search: "foo".to_string(),
+            replace: "bar".to_string(),
+            case_sensitive: true,
+            whole_word: false,
+            use_regex: false,
+            include_globs: vec!["skills/**".to_string()],
+            exclude_globs: Vec::new(),
+        };
+
+        // include globの対象外のファイルは選択しても変更しない
+        let outside = ReplaceSelection {
+            path: root.join("CLAUDE.md").to_string_lossy().to_string(),
+            match_starts: vec![0],
+        };
+        assert!(apply_workspace_replace(options.clone(), Some(vec![outside])).is_err());
+        assert_eq!(fs::read_to_string(root.join("CLAUDE.md")).unwrap(), "foo");
+
+        let result = apply_workspace_replace(options.clone(), None).unwrap();
+        assert_eq!(result.total_replaced, 1);
+        let operation_id = result.operation_id.unwrap();
+
+        // 置換後に変更したファイルは元に戻さない
+        dir.write("skills/a/SKILL.md", "edited");
+        assert!(undo_workspace_replace(operation_id.clone()).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("skills/a/SKILL.md")).unwrap(),
+            "edited"
+        );
+
+        dir.write("skills/a/SKILL.md", "bar");
+        assert_eq!(undo_workspace_replace(operation_id).unwrap().len(), 1);
+        assert_eq!(
+            fs::read_to_string(root.join("skills/a/SKILL.md")).unwrap(),
+            "foo"
+        );
+    }
+}
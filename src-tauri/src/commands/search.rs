@@ -0,0 +1,498 @@
+//! 構造化検索コマンド
+//!
+//! 設定ルート配下のファイルを検索し、マッチごとの行番号・列範囲・前後の文脈を返します。
+//! 結果は関連度（ファイル名 > frontmatter > 本文）順に並べ替えられ、
+//! 長時間の検索は `cancel_search` で中断できます。
+//! 大きな設定ツリー向けに、永続化した全文検索インデックスを使う `query_search_index` も提供します。
+
+use crate::commands::files::build_search_pattern;
+use crate::commands::replace::collect_target_files;
+use crate::error::AppResult;
+use crate::search_index::{self, IndexSearchResponse, SearchIndexStatus};
+use crate::security::AccessPolicy;
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 検索対象とするファイルサイズの上限（これより大きいファイルは内容検索しない）
+const MAX_SEARCH_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// ファイル名一致の関連度
+const SCORE_NAME: u32 = 3000;
+/// frontmatter一致の関連度
+const SCORE_FRONTMATTER: u32 = 2000;
+/// 本文一致の関連度
+const SCORE_BODY: u32 = 1000;
+
+/// 実行中の検索のキャンセルフラグ
+///
+/// キー: 検索ID、値: キャンセル要求フラグ
+static ACTIVE_SEARCHES: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 検索範囲
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchScope {
+    /// ファイル名と内容の両方
+    #[default]
+    All,
+    /// ファイル名のみ
+    FileName,
+    /// 内容のみ
+    Content,
+}
+
+/// 検索オプション
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchOptions {
+    /// 大文字小文字を区別するか
+    pub case_sensitive: bool,
+    /// 単語全体にマッチさせるか
+    pub whole_word: bool,
+    /// 正規表現を使用するか
+    pub use_regex: bool,
+    /// 検索範囲
+    pub scope: SearchScope,
+    /// マッチ前後に含める文脈の行数
+    pub context_lines: usize,
+    /// 返すファイル数の上限
+    pub max_results: usize,
+    /// 1ファイルあたりに返すマッチ数の上限
+    pub max_matches_per_file: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            whole_word: false,
+            use_regex: false,
+            scope: SearchScope::All,
+            context_lines: 2,
+            max_results: 200,
+            max_matches_per_file: 100,
+        }
+    }
+}
+
+/// ファイル内のマッチ
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    /// 行番号（1始まり）
+    pub line: usize,
+    /// 開始列（1始まり、文字単位）
+    pub column_start: usize,
+    /// 終了列（1始まり、文字単位、終端を含まない）
+    pub column_end: usize,
+    /// マッチした行のテキスト
+    pub line_text: String,
+    /// 直前の文脈行
+    pub context_before: Vec<String>,
+    /// 直後の文脈行
+    pub context_after: Vec<String>,
+    /// frontmatter内のマッチかどうか
+    pub in_frontmatter: bool,
+}
+
+/// ファイルごとの検索結果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultFile {
+    /// 絶対パス
+    pub path: String,
+    /// 設定ルートからの相対パス
+    pub relative_path: String,
+    /// ファイル名
+    pub name: String,
+    /// 関連度（大きいほど上位）
+    pub score: u32,
+    /// ファイル名が一致したかどうか
+    pub name_match: bool,
+    /// 内容のマッチ一覧（上限まで）
+    pub matches: Vec<SearchMatch>,
+    /// 内容の総マッチ数
+    pub total_matches: usize,
+}
+
+/// 検索結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    /// ファイルごとの結果（関連度順）
+    pub results: Vec<SearchResultFile>,
+    /// マッチしたファイル総数
+    pub total_files_matched: usize,
+    /// 上限に達して結果を打ち切ったかどうか
+    pub truncated: bool,
+    /// キャンセルされたかどうか（結果は途中まで）
+    pub cancelled: bool,
+}
+
+/// 検索のキャンセルフラグを登録し、終了時に解除するガード
+struct SearchRegistration {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl SearchRegistration {
+    fn new(id: &str) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut searches = ACTIVE_SEARCHES
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // 同じIDの検索が実行中の場合は置き換える（古い検索はキャンセル）
+        if let Some(previous) = searches.insert(id.to_string(), cancelled.clone()) {
+            previous.store(true, Ordering::Relaxed);
+        }
+        Self {
+            id: id.to_string(),
+            cancelled,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for SearchRegistration {
+    fn drop(&mut self) {
+        let mut searches = ACTIVE_SEARCHES
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if searches
+            .get(&self.id)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &self.cancelled))
+        {
+            searches.remove(&self.id);
+        }
+    }
+}
+
+/// frontmatter（先頭の `---` で囲まれた部分）のバイト範囲を取得
+fn frontmatter_range(content: &str) -> Option<std::ops::Range<usize>> {
+    let first_line_end = content.find('\n')?;
+    if content[..first_line_end].trim_end() != "---" {
+        return None;
+    }
+
+    let mut offset = first_line_end + 1;
+    for line in content[offset..].split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some(0..offset + line.len());
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// ファイル内容を検索してマッチ一覧と総マッチ数を返す
+fn search_content(
+    content: &str,
+    regex: &Regex,
+    context_lines: usize,
+    max_matches: usize,
+) -> (Vec<SearchMatch>, usize) {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let lines: Vec<&str> = content.lines().collect();
+    let frontmatter = frontmatter_range(content);
+
+    let mut matches = Vec::new();
+    let mut total = 0;
+
+    for m in regex.find_iter(content) {
+        if m.as_str().is_empty() {
+            continue;
+        }
+        total += 1;
+        if matches.len() >= max_matches {
+            continue;
+        }
+
+        let line_index = line_starts.partition_point(|&start| start <= m.start()) - 1;
+        let line_start = line_starts[line_index];
+        let line_text = lines.get(line_index).copied().unwrap_or("");
+        let line_end = line_start + line_text.len();
+
+        let column_start = content[line_start..m.start()].chars().count() + 1;
+        let column_end = column_start + content[m.start()..m.end().min(line_end)].chars().count();
+
+        let context_from = line_index.saturating_sub(context_lines);
+        let context_to = (line_index + 1 + context_lines).min(lines.len());
+
+        matches.push(SearchMatch {
+            line: line_index + 1,
+            column_start,
+            column_end,
+            line_text: line_text.to_string(),
+            context_before: lines[context_from..line_index]
+                .iter()
+                .map(|l| (*l).to_string())
+                .collect(),
+            context_after: lines[(line_index + 1).min(context_to)..context_to]
+                .iter()
+                .map(|l| (*l).to_string())
+                .collect(),
+            in_frontmatter: frontmatter
+                .as_ref()
+                .is_some_and(|range| range.contains(&m.start())),
+        });
+    }
+
+    (matches, total)
+}
+
+/// 関連度を計算（ファイル名 > frontmatter > 本文、同順位ではマッチ数が多い方を優先）
+fn calculate_score(name_match: bool, matches: &[SearchMatch], total_matches: usize) -> u32 {
+    let tier = if name_match {
+        SCORE_NAME
+    } else if matches.iter().any(|m| m.in_frontmatter) {
+        SCORE_FRONTMATTER
+    } else {
+        SCORE_BODY
+    };
+    tier + u32::try_from(total_matches.min(999)).unwrap_or(999)
+}
+
+/// 設定ルート配下を構造化検索
+///
+/// # Arguments
+///
+/// * `query` - 検索文字列（またはパターン）
+/// * `options` - 検索オプション（大文字小文字、単語単位、正規表現、範囲、上限など）
+/// * `search_id` - 検索ID（`cancel_search` で使用。同じIDの実行中の検索は置き換えられる）
+///
+/// # Returns
+///
+/// 関連度順のファイルごとの結果（マッチごとの行番号・列範囲・文脈付き）
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn search_workspace(
+    query: String,
+    options: Option<SearchOptions>,
+    search_id: Option<String>,
+) -> AppResult<SearchResponse> {
+    let options = options.unwrap_or_default();
+    if query.is_empty() {
+        return Ok(SearchResponse::default());
+    }
+
+    let regex = build_search_pattern(
+        &query,
+        options.case_sensitive,
+        options.whole_word,
+        options.use_regex,
+    )
+    .map_err(|e| format!("Invalid search pattern: {e}"))?;
+
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let registration = SearchRegistration::new(search_id.as_deref().unwrap_or("default"));
+
+    let mut response = SearchResponse::default();
+
+    for (path, relative_path) in collect_target_files(policy.root(), None, None) {
+        if registration.is_cancelled() {
+            response.cancelled = true;
+            break;
+        }
+
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let name_match = options.scope != SearchScope::Content && regex.is_match(&name);
+
+        let (matches, total_matches) = if options.scope == SearchScope::FileName {
+            (Vec::new(), 0)
+        } else {
+            let too_large = fs::metadata(&path)
+                .map(|m| m.len() > MAX_SEARCH_FILE_SIZE)
+                .unwrap_or(true);
+            match (too_large, fs::read_to_string(&path)) {
+                (false, Ok(content)) => search_content(
+                    &content,
+                    &regex,
+                    options.context_lines,
+                    options.max_matches_per_file,
+                ),
+                _ => (Vec::new(), 0),
+            }
+        };
+
+        if !name_match && total_matches == 0 {
+            continue;
+        }
+
+        response.total_files_matched += 1;
+        response.results.push(SearchResultFile {
+            path: path.to_string_lossy().to_string(),
+            relative_path,
+            name,
+            score: calculate_score(name_match, &matches, total_matches),
+            name_match,
+            matches,
+            total_matches,
+        });
+    }
+
+    response
+        .results
+        .sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+
+    if response.results.len() > options.max_results {
+        response.results.truncate(options.max_results);
+        response.truncated = true;
+    }
+
+    info!(
+        "Search completed: {} file(s) matched{}",
+        response.total_files_matched,
+        if response.cancelled { " (cancelled)" } else { "" }
+    );
+
+    Ok(response)
+}
+
+/// 実行中の検索をキャンセル
+///
+/// # Arguments
+///
+/// * `search_id` - `search_workspace` に渡した検索ID
+///
+/// # Returns
+///
+/// 実行中の検索が見つかった場合は`true`
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn cancel_search(search_id: String) -> bool {
+    let searches = ACTIVE_SEARCHES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match searches.get(&search_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 全文検索インデックスで検索
+///
+/// インデックスは初回実行時に作成され、以降は変更されたファイルのみ更新されます。
+///
+/// # Arguments
+///
+/// * `query` - クエリ文字列（`"phrase"`、`prefix*`、`name:`、`description:`、`kind:skill` などに対応）
+/// * `limit` - 返すヒット数の上限（デフォルト: 50）
+///
+/// # Returns
+///
+/// 関連度順のヒット一覧
+///
+/// # Errors
+///
+/// - クエリの構文が不正な場合
+/// - 設定ルートが存在しない場合
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn query_search_index(query: String, limit: Option<usize>) -> AppResult<IndexSearchResponse> {
+    search_index::search(&query, limit.unwrap_or(50))
+}
+
+/// 全文検索インデックスを作り直す
+///
+/// # Returns
+///
+/// 作り直した後のインデックスの状態
+#[tauri::command(async)]
+pub fn rebuild_search_index() -> AppResult<SearchIndexStatus> {
+    search_index::refresh(true)
+}
+
+/// 全文検索インデックスの状態を取得（未反映の変更がある場合は反映）
+///
+/// # Returns
+///
+/// インデックス済みファイル数、語の数、インデックスファイルのパス
+#[tauri::command(async)]
+pub fn get_search_index_status() -> AppResult<SearchIndexStatus> {
+    search_index::refresh(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontmatter_range() {
+        let content = "---\nname: foo\n---\nbody";
+        let range = frontmatter_range(content).expect("frontmatterが検出されませんでした");
+        assert_eq!(&content[range], "---\nname: foo\n---\n");
+        assert!(frontmatter_range("# no frontmatter\n---\n").is_none());
+    }
+
+    #[test]
+    fn test_search_content_lines_and_context() {
+        let content = "---\ndescription: deploy helper\n---\nline a\nrun deploy now\nline c";
+        let regex = build_search_pattern("deploy", false, false, false).unwrap();
+        let (matches, total) = search_content(content, &regex, 1, 10);
+
+        assert_eq!(total, 2);
+        assert!(matches[0].in_frontmatter);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].column_start, 14);
+        assert_eq!(matches[0].column_end, 20);
+
+        assert!(!matches[1].in_frontmatter);
+        assert_eq!(matches[1].line, 5);
+        assert_eq!(matches[1].context_before, vec!["line a"]);
+        assert_eq!(matches[1].context_after, vec!["line c"]);
+    }
+
+    #[test]
+    fn test_calculate_score_ranking() {
+        let body = SearchMatch {
+            line: 5,
+            column_start: 1,
+            column_end: 2,
+            line_text: String::new(),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            in_frontmatter: false,
+        };
+        let frontmatter = SearchMatch {
+            in_frontmatter: true,
+            ..body.clone()
+        };
+
+        let name_score = calculate_score(true, &[], 0);
+        let frontmatter_score = calculate_score(false, std::slice::from_ref(&frontmatter), 1);
+        let body_score = calculate_score(false, &[body.clone(), body], 50);
+
+        assert!(name_score > frontmatter_score);
+        assert!(frontmatter_score > body_score);
+    }
+
+    #[test]
+    fn test_cancel_search() {
+        let registration = SearchRegistration::new("test-cancel");
+        assert!(!registration.is_cancelled());
+        assert!(cancel_search("test-cancel".to_string()));
+        assert!(registration.is_cancelled());
+        drop(registration);
+        assert!(!cancel_search("test-cancel".to_string()));
+    }
+}
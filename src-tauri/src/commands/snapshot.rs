@@ -0,0 +1,366 @@
+//! スナップショット操作コマンド
+//!
+//! 設定ルート全体のスナップショットの作成・一覧・差分・復元・削除を提供します。
+//! 復元は全ファイルまたは選択したパスのみを対象とし、書き込みに失敗した場合は
+//! 復元前の状態に戻します。
+
+use crate::error::AppResult;
+use crate::git_history;
+use crate::security::AccessPolicy;
+use crate::snapshot_store::{self, SnapshotFile};
+use crate::types::{
+    SnapshotChangeKind, SnapshotDiff, SnapshotInfo, SnapshotRestoreResult, SnapshotTrigger,
+};
+use crate::utils::{write_file_atomic, TEMP_FILE_SUFFIX};
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 復元の1ステップ
+enum RestoreStep {
+    /// 一時ファイルに書き込んだ内容で置き換える
+    Write {
+        relative_path: String,
+        target: PathBuf,
+        staged: PathBuf,
+    },
+    /// スナップショットに存在しないファイルを削除する
+    Delete {
+        relative_path: String,
+        target: PathBuf,
+    },
+}
+
+impl RestoreStep {
+    fn relative_path(&self) -> &str {
+        match self {
+            Self::Write { relative_path, .. } | Self::Delete { relative_path, .. } => relative_path,
+        }
+    }
+
+    fn target(&self) -> &Path {
+        match self {
+            Self::Write { target, .. } | Self::Delete { target, .. } => target,
+        }
+    }
+}
+
+/// スナップショットを作成
+///
+/// # Arguments
+///
+/// * `name` - スナップショットの名前
+///
+/// # Returns
+///
+/// 作成したスナップショットの概要
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn create_snapshot(name: Option<String>) -> AppResult<SnapshotInfo> {
+    snapshot_store::create_snapshot(name.as_deref(), SnapshotTrigger::Manual)
+}
+
+/// 一括操作の前にスナップショットを作成
+///
+/// # Arguments
+///
+/// * `trigger` - 作成した契機
+///
+/// # Returns
+///
+/// 作成したスナップショットのID
+///
+/// # Errors
+///
+/// 作成に失敗した場合はエラーを返します（呼び出し元は操作を中止してください）。
+pub fn create_auto_snapshot(trigger: SnapshotTrigger) -> AppResult<String> {
+    snapshot_store::create_snapshot(None, trigger)
+        .map(|info| info.id)
+        .map_err(|e| format!("Failed to create snapshot before the operation: {e}"))
+}
+
+/// スナップショットの一覧を取得
+///
+/// # Returns
+///
+/// スナップショットの概要（新しい順）
+#[tauri::command]
+pub fn list_snapshots() -> AppResult<Vec<SnapshotInfo>> {
+    snapshot_store::list_snapshots()
+}
+
+/// スナップショットと現在の状態の差分を取得
+///
+/// # Arguments
+///
+/// * `snapshot_id` - スナップショットID
+///
+/// # Returns
+///
+/// 追加・削除・変更されたファイルと同一のファイル数
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn diff_snapshot(snapshot_id: String) -> AppResult<SnapshotDiff> {
+    snapshot_store::diff_with_current(&snapshot_id)
+}
+
+/// スナップショットを削除
+///
+/// # Arguments
+///
+/// * `snapshot_id` - スナップショットID
+///
+/// # Returns
+///
+/// 解放したバイト数
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn delete_snapshot(snapshot_id: String) -> AppResult<u64> {
+    snapshot_store::delete_snapshot(&snapshot_id)
+}
+
+/// スナップショットを復元
+///
+/// 復元前に現在の状態をスナップショットとして保存し、すべての内容を一時ファイルに書き込んでから
+/// 置き換えます。途中で失敗した場合は置き換え済みのファイルを復元前の状態に戻します。
+///
+/// # Arguments
+///
+/// * `snapshot_id` - スナップショットID
+/// * `paths` - 復元する相対パス（`None` の場合はすべて）
+///
+/// # Returns
+///
+/// 書き込んだファイル・削除したファイルと、復元前の状態を保存したスナップショットのID
+///
+/// # Security
+///
+/// 復元先は設定ルート配下のみ許可されます。
+#[tauri::command(async)]
+#[allow(clippy::needless_pass_by_value)]
+pub fn restore_snapshot(
+    snapshot_id: String,
+    paths: Option<Vec<String>>,
+) -> AppResult<SnapshotRestoreResult> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let (_, files) = snapshot_store::load_snapshot(&snapshot_id)?;
+    let (mut changes, _) = snapshot_store::compare_with_current(&files, policy.root());
+
+    if let Some(paths) = paths {
+        let selected: HashSet<String> = paths
+            .iter()
+            .map(|p| normalize_relative(p, policy.root()))
+            .collect();
+        let known: HashSet<&str> = files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .chain(changes.iter().map(|c| c.relative_path.as_str()))
+            .collect();
+        if let Some(unknown) = selected.iter().find(|p| !known.contains(p.as_str())) {
+            return Err(format!(
+                "Path is not in the snapshot or the current config: {unknown}"
+            ));
+        }
+        changes.retain(|c| selected.contains(&c.relative_path));
+    }
+
+    if changes.is_empty() {
+        return Ok(SnapshotRestoreResult::default());
+    }
+
+    let safety_id = create_auto_snapshot(SnapshotTrigger::Restore)?;
+    let snapshot_files: HashMap<&str, &SnapshotFile> = files
+        .iter()
+        .map(|f| (f.relative_path.as_str(), f))
+        .collect();
+
+    // すべての内容を一時ファイルに書き込む（失敗した場合は何も変更しない）
+    let mut steps = Vec::with_capacity(changes.len());
+    for change in &changes {
+        let step = policy
+            .resolve_relative(&change.relative_path)
+            .map_err(|e| e.to_string())
+            .and_then(|target| match change.kind {
+                SnapshotChangeKind::Added => Ok(RestoreStep::Delete {
+                    relative_path: change.relative_path.clone(),
+                    target,
+                }),
+                SnapshotChangeKind::Removed | SnapshotChangeKind::Modified => {
+                    let file = snapshot_files
+                        .get(change.relative_path.as_str())
+                        .ok_or_else(|| format!("{} is not in the snapshot", change.relative_path))?;
+                    let staged = stage_file(file, &target)?;
+                    Ok(RestoreStep::Write {
+                        relative_path: change.relative_path.clone(),
+                        target,
+                        staged,
+                    })
+                }
+            });
+        match step {
+            Ok(step) => steps.push(step),
+            Err(e) => {
+                discard_staged(&steps);
+                return Err(format!("Failed to restore {}: {e}", change.relative_path));
+            }
+        }
+    }
+
+    // 置き換え（失敗した場合は置き換え済みのファイルを元に戻す）
+    let mut result = SnapshotRestoreResult {
+        safety_snapshot_id: Some(safety_id.clone()),
+        ..SnapshotRestoreResult::default()
+    };
+    for (index, step) in steps.iter().enumerate() {
+        let applied = match step {
+            RestoreStep::Write { target, staged, .. } => fs::rename(staged, target),
+            RestoreStep::Delete { target, .. } => fs::remove_file(target),
+        };
+        if let Err(e) = applied {
+            discard_staged(&steps[index..]);
+            rollback(&steps[..index], &safety_id);
+            return Err(format!(
+                "Failed to restore {}: {e}. Restored files were rolled back",
+                step.relative_path()
+            ));
+        }
+        match step {
+            RestoreStep::Write { relative_path, .. } => result.restored.push(relative_path.clone()),
+            RestoreStep::Delete { relative_path, .. } => result.deleted.push(relative_path.clone()),
+        }
+    }
+
+    let paths: Vec<PathBuf> = steps.iter().map(|s| s.target().to_path_buf()).collect();
+    git_history::record_change_or_warn(&paths, &format!("Restore snapshot {snapshot_id}"));
+
+    info!(
+        "Restored snapshot {snapshot_id}: {} written, {} deleted (previous state saved as {safety_id})",
+        result.restored.len(),
+        result.deleted.len()
+    );
+    Ok(result)
+}
+
+/// 絶対パスまたは相対パスを設定ルートからの相対パス（`/` 区切り）に変換
+fn normalize_relative(path: &str, root: &Path) -> String {
+    let path = Path::new(path);
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+/// スナップショットの内容を復元先と同じディレクトリの一時ファイルに書き込む
+fn stage_file(file: &SnapshotFile, target: &Path) -> Result<PathBuf, String> {
+    let bytes = snapshot_store::read_file_content(file)?;
+    let parent = target
+        .parent()
+        .ok_or_else(|| "Target has no parent directory".to_string())?;
+    fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory: {e}"))?;
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let staged = parent.join(format!(
+        ".{file_name}.restore-{}{TEMP_FILE_SUFFIX}",
+        std::process::id()
+    ));
+
+    let written = fs::File::create(&staged).and_then(|mut f| {
+        f.write_all(&bytes)?;
+        f.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&staged);
+        return Err(format!("Failed to write temporary file: {e}"));
+    }
+    Ok(staged)
+}
+
+/// 書き込み済みの一時ファイルを削除
+fn discard_staged(steps: &[RestoreStep]) {
+    for step in steps {
+        if let RestoreStep::Write { staged, .. } = step {
+            let _ = fs::remove_file(staged);
+        }
+    }
+}
+
+/// 置き換え済みのファイルを復元前のスナップショットの状態に戻す
+fn rollback(applied: &[RestoreStep], safety_id: &str) {
+    let previous: HashMap<String, SnapshotFile> = match snapshot_store::load_snapshot(safety_id) {
+        Ok((_, files)) => files
+            .into_iter()
+            .map(|f| (f.relative_path.clone(), f))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to load snapshot {safety_id} for rollback: {e}");
+            return;
+        }
+    };
+
+    for step in applied.iter().rev() {
+        let target = step.target();
+        let restored = match previous.get(step.relative_path()) {
+            Some(file) => snapshot_store::read_file_content(file)
+                .and_then(|bytes| write_file_atomic(target, &bytes).map_err(|e| e.to_string())),
+            None => fs::remove_file(target).map_err(|e| e.to_string()),
+        };
+        if let Err(e) = restored {
+            warn!("Failed to roll back {}: {e}", step.relative_path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_all_and_selected_paths() {
+        let root = crate::utils::TestClaudeDir::new();
+        let path = |relative: &str| root.path().join(relative);
+        fs::write(path("settings.json"), "{}").unwrap();
+        fs::create_dir_all(path("agents")).unwrap();
+        fs::write(path("agents/a.md"), "# a").unwrap();
+
+        let snapshot = create_snapshot(Some("base".to_string())).unwrap();
+
+        fs::write(path("settings.json"), r#"{"broken": true}"#).unwrap();
+        fs::remove_file(path("agents/a.md")).unwrap();
+        fs::write(path("CLAUDE.md"), "# added").unwrap();
+
+        // 選択したパスのみ復元
+        let result =
+            restore_snapshot(snapshot.id.clone(), Some(vec!["settings.json".to_string()])).unwrap();
+        assert_eq!(result.restored, vec!["settings.json"]);
+        assert!(result.deleted.is_empty());
+        assert_eq!(fs::read_to_string(path("settings.json")).unwrap(), "{}");
+        assert!(!path("agents/a.md").exists());
+
+        // 未知のパスはエラー
+        assert!(restore_snapshot(snapshot.id.clone(), Some(vec!["nope.md".to_string()])).is_err());
+
+        // 全体を復元すると追加されたファイルは削除される
+        let result = restore_snapshot(snapshot.id.clone(), None).unwrap();
+        assert_eq!(result.restored, vec!["agents/a.md"]);
+        assert_eq!(result.deleted, vec!["CLAUDE.md"]);
+        assert_eq!(fs::read_to_string(path("agents/a.md")).unwrap(), "# a");
+        assert!(!path("CLAUDE.md").exists());
+
+        // 復元前の状態はスナップショットとして残る
+        let safety = result.safety_snapshot_id.unwrap();
+        let diff = diff_snapshot(safety).unwrap();
+        assert_eq!(diff.changes.len(), 2);
+        assert_eq!(diff.snapshot.trigger, SnapshotTrigger::Restore);
+
+        // 変更がなければ何もしない
+        let result = restore_snapshot(snapshot.id, None).unwrap();
+        assert_eq!(result, SnapshotRestoreResult::default());
+    }
+}
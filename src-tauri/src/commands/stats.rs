@@ -2,8 +2,9 @@
 //!
 //! ~/.claude/ ディレクトリ内の各種統計情報を収集して返します。
 
+use crate::backup_store;
 use crate::error::AppResult;
-use crate::utils::get_claude_dir;
+use crate::utils::{extract_frontmatter_field, get_claude_dir, walk_visible_files};
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -26,9 +27,13 @@ pub struct Stats {
     pub mcp_server_count: u32,
     /// プラグイン数（settings.json の enabledPlugins 配列長）
     pub plugin_count: u32,
-    /// バックアップ数（backups/ 内のファイル数）
+    /// バックアップ数（バックアップストアに記録されたバックアップ数）
     pub backup_count: u32,
-    /// 総ファイル数（~/.claude/ 配下の全ファイル）
+    /// バックアップの論理サイズ（各バックアップの元ファイルサイズの合計）
+    pub backup_logical_size: u64,
+    /// バックアップの物理サイズ（重複排除・圧縮後のディスク使用量）
+    pub backup_physical_size: u64,
+    /// 総ファイル数（~/.claude/ 配下のファイルのうち、除外ディレクトリ・除外globに該当しないもの）
     pub total_file_count: u32,
     /// 最終更新日時（最も新しいファイルの更新日時）
     pub last_updated: Option<String>,
@@ -172,30 +177,19 @@ fn count_plugins(claude_dir: &Path) -> u32 {
         .unwrap_or(0)
 }
 
-/// backups/ 内のファイル数をカウント
-fn count_backups(claude_dir: &Path) -> u32 {
-    let backups_dir = claude_dir.join("backups");
-    if !backups_dir.exists() {
-        return 0;
-    }
-
-    WalkDir::new(&backups_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .count() as u32
+/// バックアップストアの使用状況を取得
+///
+/// 取得に失敗した場合は空の使用状況を返します。
+fn backup_usage() -> backup_store::StoreUsage {
+    backup_store::usage().unwrap_or_default()
 }
 
-/// ~/.claude/ 配下の全ファイル数をカウントし、最新の更新日時も取得
+/// ~/.claude/ 配下の除外されていないファイル数をカウントし、最新の更新日時も取得
 fn count_total_files_and_last_updated(claude_dir: &Path) -> (u32, Option<SystemTime>) {
     let mut count: u32 = 0;
     let mut latest_time: Option<SystemTime> = None;
 
-    for entry in WalkDir::new(claude_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-    {
+    for entry in walk_visible_files(claude_dir) {
         count += 1;
 
         if let Ok(metadata) = entry.metadata() {
@@ -242,7 +236,7 @@ pub fn get_stats() -> AppResult<Stats> {
     let skill_count = count_skills(&claude_dir);
     let mcp_server_count = count_mcp_servers(&claude_dir);
     let plugin_count = count_plugins(&claude_dir);
-    let backup_count = count_backups(&claude_dir);
+    let backup_usage = backup_usage();
     let (total_file_count, last_updated_time) = count_total_files_and_last_updated(&claude_dir);
 
     let last_updated = last_updated_time.map(format_system_time);
@@ -253,7 +247,9 @@ pub fn get_stats() -> AppResult<Stats> {
         skill_count,
         mcp_server_count,
         plugin_count,
-        backup_count,
+        backup_count: backup_usage.record_count as u32,
+        backup_logical_size: backup_usage.logical_size,
+        backup_physical_size: backup_usage.physical_size,
         total_file_count,
         last_updated,
     })
@@ -261,27 +257,7 @@ pub fn get_stats() -> AppResult<Stats> {
 
 /// SKILL.md ファイルから frontmatter の description を抽出
 fn extract_skill_description(content: &str) -> Option<String> {
-    // YAML frontmatter を解析（---で囲まれた部分）
-    if !content.starts_with("---") {
-        return None;
-    }
-
-    let parts: Vec<&str> = content.splitn(3, "---").collect();
-    if parts.len() < 3 {
-        return None;
-    }
-
-    let frontmatter = parts[1];
-    for line in frontmatter.lines() {
-        let line = line.trim();
-        if line.starts_with("description:") {
-            let desc = line.strip_prefix("description:")?.trim();
-            // クォートを除去
-            let desc = desc.trim_matches('"').trim_matches('\'');
-            return Some(desc.to_string());
-        }
-    }
-    None
+    extract_frontmatter_field(content, "description")
 }
 
 /// skills/ 内の SKILL.md ファイル一覧を取得
@@ -519,42 +495,34 @@ fn get_plugins_detail(claude_dir: &Path) -> Vec<StatsDetailItem> {
 
 /// backups/ 内のファイル一覧を取得
 fn get_backups_detail(claude_dir: &Path) -> Vec<StatsDetailItem> {
-    let backups_dir = claude_dir.join("backups");
-    if !backups_dir.exists() {
+    let Ok(records) = backup_store::list_backups() else {
         return Vec::new();
-    }
+    };
 
-    WalkDir::new(&backups_dir)
+    records
         .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .map(|entry| {
-            let path = entry.path();
-            let path_str = path.to_string_lossy().to_string();
-            let name = path
+        .map(|record| {
+            let name = Path::new(&record.relative_path)
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| "unknown".to_string());
 
             let mut metadata = HashMap::new();
-
-            // ファイルサイズを取得
-            if let Ok(meta) = entry.metadata() {
-                metadata.insert("size".to_string(), meta.len().to_string());
-
-                // 更新日時を取得
-                if let Ok(modified) = meta.modified() {
-                    metadata.insert("date".to_string(), format_system_time(modified));
+            metadata.insert("size".to_string(), record.size.to_string());
+            metadata.insert("date".to_string(), record.created_at.clone());
+            if let Ok(operation) = serde_json::to_value(record.operation) {
+                if let Some(operation) = operation.as_str() {
+                    metadata.insert("operation".to_string(), operation.to_string());
                 }
             }
 
             StatsDetailItem {
-                id: path_str.clone(),
+                id: record.id,
                 name,
-                path: Some(path_str),
-                description: None,
+                path: Some(claude_dir.join(&record.relative_path).to_string_lossy().to_string()),
+                description: Some(record.relative_path),
                 category: None,
-                metadata: if metadata.is_empty() { None } else { Some(metadata) },
+                metadata: Some(metadata),
             }
         })
         .collect()
@@ -567,11 +535,7 @@ fn get_total_files_detail(claude_dir: &Path) -> (Vec<StatsDetailItem>, u32) {
     let mut items = Vec::new();
     let mut total_count: u32 = 0;
 
-    for entry in WalkDir::new(claude_dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-    {
+    for entry in walk_visible_files(claude_dir) {
         total_count += 1;
 
         if items.len() < TOTAL_FILES_LIMIT {
@@ -3,7 +3,8 @@
 //! ユーザーが作成したカスタムテンプレートのCRUD操作を提供します。
 //! テンプレートは ~/.claude/templates/ にJSONファイルとして保存されます。
 
-use crate::error::AppResult;
+use crate::error::{AccessDeniedReason, AppError, AppResult};
+use crate::security::AccessPolicy;
 use crate::utils::get_claude_dir;
 use log::info;
 use serde::{Deserialize, Serialize};
@@ -79,6 +80,25 @@ fn get_templates_dir() -> Result<PathBuf, String> {
     Ok(claude_dir.join("templates"))
 }
 
+/// テンプレートIDからファイルパスを取得
+///
+/// IDに `..` や区切り文字を含めて templates/ 外を指すことを防ぎます。
+fn get_template_file_path(id: &str) -> Result<PathBuf, String> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let file_path = policy
+        .resolve_relative(&format!("templates/{id}.json"))
+        .map_err(|e| e.to_string())?;
+
+    if file_path.parent() != Some(policy.root().join("templates").as_path()) {
+        return Err(AppError::AccessDenied(AccessDeniedReason::Other(format!(
+            "invalid template id: {id}"
+        )))
+        .to_string());
+    }
+
+    Ok(file_path)
+}
+
 /// テンプレート名からIDを生成（ファイル名セーフな形式）
 fn generate_template_id(name: &str) -> String {
     let sanitized: String = name
@@ -224,8 +244,7 @@ pub fn get_custom_templates() -> AppResult<Vec<CustomTemplate>> {
 /// * `id` - 削除するテンプレートのID
 #[tauri::command]
 pub fn delete_custom_template(id: String) -> AppResult<()> {
-    let templates_dir = get_templates_dir()?;
-    let file_path = templates_dir.join(format!("{id}.json"));
+    let file_path = get_template_file_path(&id)?;
 
     if !file_path.exists() {
         return Err(format!("Template not found: {id}"));
@@ -246,8 +265,7 @@ pub fn delete_custom_template(id: String) -> AppResult<()> {
 /// * `id` - 取得するテンプレートのID
 #[tauri::command]
 pub fn get_custom_template(id: String) -> AppResult<CustomTemplate> {
-    let templates_dir = get_templates_dir()?;
-    let file_path = templates_dir.join(format!("{id}.json"));
+    let file_path = get_template_file_path(&id)?;
 
     if !file_path.exists() {
         return Err(format!("Template not found: {id}"));
@@ -0,0 +1,342 @@
+//! ファイル監視コマンド
+//!
+//! 設定ルート配下の変更（Claude Code自身による編集や `git pull` など）を監視し、
+//! デバウンスしたうえで `fs:created` / `fs:modified` / `fs:removed` / `fs:renamed`
+//! イベントとしてフロントエンドに通知します。
+//! 除外ディレクトリ（cache, backupsなど）配下の変更は通知しません。
+//! ダッシュボード設定ファイルが変更された場合は設定を再読み込みし、
+//! `dashboard-config:changed` イベントを通知します。
+
+use crate::config::{self, DASHBOARD_CONFIG_FILE};
+use crate::error::AppResult;
+use crate::search_index::{mark_paths_changed, request_full_sync};
+use crate::types::{FsChangeEvent, FsRenameEvent};
+use crate::utils::{get_claude_dir, is_excluded_relative_path, TEMP_FILE_SUFFIX};
+use log::{info, warn};
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// ファイル作成イベント名
+pub const FS_CREATED_EVENT: &str = "fs:created";
+/// ファイル更新イベント名
+pub const FS_MODIFIED_EVENT: &str = "fs:modified";
+/// ファイル削除イベント名
+pub const FS_REMOVED_EVENT: &str = "fs:removed";
+/// ファイル名変更イベント名
+pub const FS_RENAMED_EVENT: &str = "fs:renamed";
+/// ダッシュボード設定変更イベント名
+pub const DASHBOARD_CONFIG_CHANGED_EVENT: &str = "dashboard-config:changed";
+
+/// デバウンス間隔
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// 監視中のウォッチャー
+struct ActiveWatcher {
+    /// 監視対象の設定ルート
+    root: PathBuf,
+    /// デバウンサー（ドロップすると監視が停止する）
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+}
+
+/// グローバルなウォッチャー管理
+static WATCHER: Lazy<Mutex<Option<ActiveWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+/// デバウンス済みイベントを種類別に振り分けた結果
+#[derive(Debug, Default, PartialEq)]
+struct ClassifiedEvents {
+    created: Vec<String>,
+    modified: Vec<String>,
+    removed: Vec<String>,
+    renamed: Vec<FsRenameEvent>,
+}
+
+impl ClassifiedEvents {
+    /// 変更のあったすべてのパス（名前変更は変更前と変更後の両方）
+    fn changed_paths(&self) -> Vec<PathBuf> {
+        self.created
+            .iter()
+            .chain(&self.modified)
+            .chain(&self.removed)
+            .chain(self.renamed.iter().flat_map(|r| [&r.from, &r.to]))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// ダッシュボード設定ファイルが変更されたか
+    fn touches_dashboard_config(&self, root: &Path) -> bool {
+        let config_path = root.join(DASHBOARD_CONFIG_FILE);
+        self.changed_paths().contains(&config_path)
+    }
+}
+
+/// 設定ルート配下かつ除外ディレクトリ外のパスかどうか
+///
+/// アトミック書き込みの一時ファイルは対象外です。
+fn is_watched_path(path: &Path, root: &Path) -> bool {
+    let is_temp_file = path
+        .file_name()
+        .is_some_and(|n| n.to_string_lossy().ends_with(TEMP_FILE_SUFFIX));
+
+    !is_temp_file
+        && path
+            .strip_prefix(root)
+            .map(|relative| {
+                !relative.as_os_str().is_empty() && !is_excluded_relative_path(relative)
+            })
+            .unwrap_or(false)
+}
+
+/// パスを重複なく追加
+fn push_unique(list: &mut Vec<String>, path: &Path) {
+    let path_str = path.to_string_lossy().to_string();
+    if !list.contains(&path_str) {
+        list.push(path_str);
+    }
+}
+
+/// notifyのイベントを作成/更新/削除/名前変更に振り分け
+fn classify_events(events: &[(EventKind, Vec<PathBuf>)], root: &Path) -> ClassifiedEvents {
+    let mut classified = ClassifiedEvents::default();
+
+    for (kind, paths) in events {
+        let watched: Vec<&PathBuf> = paths.iter().filter(|p| is_watched_path(p, root)).collect();
+
+        match kind {
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+                let (from, to) = (&paths[0], &paths[1]);
+                match (is_watched_path(from, root), is_watched_path(to, root)) {
+                    (true, true) => classified.renamed.push(FsRenameEvent {
+                        from: from.to_string_lossy().to_string(),
+                        to: to.to_string_lossy().to_string(),
+                    }),
+                    // 除外ディレクトリとの間の移動は作成/削除として扱う
+                    (true, false) => push_unique(&mut classified.removed, from),
+                    (false, true) => push_unique(&mut classified.created, to),
+                    (false, false) => {}
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) | EventKind::Remove(_) => {
+                for path in watched {
+                    push_unique(&mut classified.removed, path);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) | EventKind::Create(_) => {
+                for path in watched {
+                    push_unique(&mut classified.created, path);
+                }
+            }
+            EventKind::Modify(_) => {
+                for path in watched {
+                    push_unique(&mut classified.modified, path);
+                }
+            }
+            EventKind::Access(_) | EventKind::Any | EventKind::Other => {}
+        }
+    }
+
+    // 同じバッチで作成されたファイルの更新は作成イベントのみ通知
+    classified
+        .modified
+        .retain(|p| !classified.created.contains(p) && !classified.removed.contains(p));
+
+    classified
+}
+
+/// 振り分け済みイベントをフロントエンドに送信
+fn emit_events(app: &AppHandle, classified: ClassifiedEvents) {
+    let batches = [
+        (FS_CREATED_EVENT, classified.created),
+        (FS_MODIFIED_EVENT, classified.modified),
+        (FS_REMOVED_EVENT, classified.removed),
+    ];
+
+    for (event, paths) in batches {
+        if paths.is_empty() {
+            continue;
+        }
+        if let Err(e) = app.emit(event, FsChangeEvent { paths }) {
+            warn!("Failed to emit {event}: {e}");
+        }
+    }
+
+    for rename in classified.renamed {
+        if let Err(e) = app.emit(FS_RENAMED_EVENT, rename) {
+            warn!("Failed to emit {FS_RENAMED_EVENT}: {e}");
+        }
+    }
+}
+
+/// アクティブな設定ルートの監視を開始（既に監視中の場合は再起動）
+///
+/// 設定ルートが切り替わった場合にも呼び出されます。
+///
+/// # Errors
+///
+/// - 設定ルートが存在しない場合
+/// - ウォッチャーの作成に失敗した場合
+pub fn start_watcher(app: &AppHandle) -> AppResult<()> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    if !root.is_dir() {
+        stop_watcher();
+        return Err(format!("Config root not found: {}", root.display()));
+    }
+
+    // シンボリックリンク経由の場合でもイベントのパスと一致させる
+    let root = dunce::canonicalize(&root).unwrap_or(root);
+
+    let handler_app = app.clone();
+    let handler_root = root.clone();
+    let mut debouncer = new_debouncer(
+        DEBOUNCE_TIMEOUT,
+        None,
+        move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let events: Vec<(EventKind, Vec<PathBuf>)> = events
+                    .into_iter()
+                    .map(|e| (e.event.kind, e.event.paths))
+                    .collect();
+                let classified = classify_events(&events, &handler_root);
+                if classified.touches_dashboard_config(&handler_root) {
+                    config::invalidate();
+                    request_full_sync();
+                    if let Err(e) = handler_app.emit(DASHBOARD_CONFIG_CHANGED_EVENT, ()) {
+                        warn!("Failed to emit {DASHBOARD_CONFIG_CHANGED_EVENT}: {e}");
+                    }
+                }
+                mark_paths_changed(classified.changed_paths());
+                emit_events(&handler_app, classified);
+            }
+            Err(errors) => {
+                for e in errors {
+                    warn!("File watcher error: {e}");
+                }
+            }
+        },
+    )
+    .map_err(|e| format!("Failed to create file watcher: {e}"))?;
+
+    debouncer
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+
+    let mut watcher = WATCHER.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    *watcher = Some(ActiveWatcher {
+        root: root.clone(),
+        _debouncer: debouncer,
+    });
+
+    info!("File watcher started: {}", root.display());
+    Ok(())
+}
+
+/// 監視を停止
+pub fn stop_watcher() {
+    let mut watcher = WATCHER.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(active) = watcher.take() {
+        info!("File watcher stopped: {}", active.root.display());
+    }
+}
+
+/// ファイル監視を（再）開始
+///
+/// # Returns
+///
+/// 監視対象の設定ルートのパス
+#[tauri::command]
+#[allow(clippy::needless_pass_by_value)]
+pub fn restart_file_watcher(app: AppHandle) -> AppResult<String> {
+    start_watcher(&app)?;
+    get_watched_root().ok_or_else(|| "File watcher is not running".to_string())
+}
+
+/// 監視中の設定ルートを取得
+///
+/// # Returns
+///
+/// 監視中の場合はそのパス、停止中の場合は`None`
+#[tauri::command]
+pub fn get_watched_root() -> Option<String> {
+    WATCHER
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .as_ref()
+        .map(|active| active.root.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::event::{CreateKind, DataChange, RemoveKind};
+
+    #[test]
+    fn test_classify_events_filters_excluded_dirs() {
+        let root = PathBuf::from("/home/user/.claude");
+        let events = vec![
+            (
+                EventKind::Create(CreateKind::File),
+                vec![root.join("skills/new/SKILL.md"), root.join("backups/a_1")],
+            ),
+            (
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                vec![root.join("settings.json"), root.join("cache/x.json")],
+            ),
+            (
+                EventKind::Remove(RemoveKind::File),
+                vec![root.join("agents/old.md")],
+            ),
+        ];
+
+        let classified = classify_events(&events, &root);
+        assert_eq!(classified.created, vec![root.join("skills/new/SKILL.md").to_string_lossy()]);
+        assert_eq!(classified.modified, vec![root.join("settings.json").to_string_lossy()]);
+        assert_eq!(classified.removed, vec![root.join("agents/old.md").to_string_lossy()]);
+    }
+
+    #[test]
+    fn test_classify_events_renames() {
+        let root = PathBuf::from("/home/user/.claude");
+        let events = vec![
+            (
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                vec![root.join("agents/a.md"), root.join("agents/b.md")],
+            ),
+            (
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                vec![root.join("agents/c.md"), root.join("backups/c.md")],
+            ),
+        ];
+
+        let classified = classify_events(&events, &root);
+        assert_eq!(classified.renamed.len(), 1);
+        assert_eq!(classified.renamed[0].to, root.join("agents/b.md").to_string_lossy());
+        assert_eq!(classified.removed, vec![root.join("agents/c.md").to_string_lossy()]);
+    }
+
+    #[test]
+    fn test_classify_events_ignores_temp_files() {
+        let root = PathBuf::from("/home/user/.claude");
+        let events = vec![
+            (
+                EventKind::Create(CreateKind::File),
+                vec![root.join(".settings.json.1-0.ccsd-tmp")],
+            ),
+            (
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                vec![
+                    root.join(".settings.json.1-0.ccsd-tmp"),
+                    root.join("settings.json"),
+                ],
+            ),
+        ];
+
+        let classified = classify_events(&events, &root);
+        assert!(classified.renamed.is_empty());
+        assert_eq!(classified.created, vec![root.join("settings.json").to_string_lossy()]);
+    }
+}
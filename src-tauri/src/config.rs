@@ -0,0 +1,530 @@
+//! ダッシュボード設定
+//!
+//! 設定ルート直下の `dashboard-config.json` から、除外ディレクトリ・除外glob・
+//! 許可する拡張子・拡張子ごとのエディタモード・バックアップの保持ポリシー・
+//! Gitによる変更履歴・アーカイブのインポートの上限の設定を読み込みます。
+//! ファイルの更新日時とサイズを確認し、変更されていれば自動的に再読み込みします。
+//! ファイルが存在しない場合や解析に失敗した場合はデフォルト値を使用します。
+
+use crate::utils::{get_claude_dir, ALLOWED_EXTENSIONS, EXCLUDED_DIRS};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+/// ダッシュボード設定ファイル名
+pub const DASHBOARD_CONFIG_FILE: &str = "dashboard-config.json";
+
+/// 設定に関わらず常に除外するディレクトリ（アプリ内部で使用）
+pub const ALWAYS_EXCLUDED_DIRS: &[&str] = &["backups", ".git"];
+
+/// 設定ファイルの更新を確認する間隔
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// ダッシュボード設定
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DashboardConfig {
+    /// 除外するディレクトリ名（どの階層でも一致すれば除外）
+    pub exclude_dirs: Vec<String>,
+    /// 除外するパスのglob（設定ルートからの相対パス、例: `**/node_modules`）
+    pub exclude_globs: Vec<String>,
+    /// 表示・編集を許可する拡張子（先頭の `.` は不要）
+    pub allowed_extensions: Vec<String>,
+    /// 拡張子ごとのエディタモード（例: `sh` → `shell`）
+    pub editor_modes: BTreeMap<String, String>,
+    /// バックアップの保持ポリシー
+    pub backup_retention: RetentionPolicy,
+    /// Gitによる変更履歴
+    pub git_history: GitHistoryConfig,
+    /// アーカイブ（ZIP・tar.gz・フォルダー）のインポートの上限
+    ///
+    /// 以前のキー名 `zipImportLimits` も読み込めます。
+    #[serde(alias = "zipImportLimits")]
+    pub archive_import_limits: ArchiveImportLimits,
+}
+
+/// アーカイブのインポートの上限（zip bomb対策）
+///
+/// 展開前にアーカイブ内のエントリを検証し、いずれかの上限を超える場合はインポートしません。
+/// 展開時も実際に読み込んだサイズで上限を確認します。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveImportLimits {
+    /// エントリ数の上限（ディレクトリを含む）
+    pub max_entries: u32,
+    /// 展開後の合計サイズの上限（バイト）
+    pub max_total_bytes: u64,
+    /// 1エントリの展開後のサイズの上限（バイト）
+    pub max_entry_bytes: u64,
+    /// 圧縮率（展開後のサイズ / 圧縮後のサイズ）の上限
+    ///
+    /// 展開後のサイズが1MiB未満のエントリには適用しません。
+    pub max_compression_ratio: u32,
+}
+
+impl Default for ArchiveImportLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+            max_total_bytes: 1024 * 1024 * 1024,
+            max_entry_bytes: 256 * 1024 * 1024,
+            max_compression_ratio: 100,
+        }
+    }
+}
+
+/// Gitによる変更履歴の設定
+///
+/// 有効にすると設定ルートをローカルのGitリポジトリとして管理し、
+/// ダッシュボードからの保存ごとにコミットを作成します。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GitHistoryConfig {
+    /// 有効にするか
+    pub enabled: bool,
+    /// コミットの作成者名
+    pub author_name: String,
+    /// コミットの作成者のメールアドレス
+    pub author_email: String,
+}
+
+impl Default for GitHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            author_name: "Claude Setting Dashboard".to_string(),
+            author_email: "dashboard@localhost".to_string(),
+        }
+    }
+}
+
+/// バックアップの保持ポリシー
+///
+/// いずれかの `keep*` ルールに該当するバックアップを残し、それ以外を削除します。
+/// `keep*` ルールがすべて未設定の場合は期間・世代による削除を行いません。
+/// ピン留めされたバックアップは常に残ります。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    /// 作成から指定日数以内のバックアップを残す
+    pub keep_within_days: Option<u32>,
+    /// ファイルごとに最新の指定数のバックアップを残す
+    pub keep_last: Option<u32>,
+    /// ファイルごとに直近の指定日数分、各日の最新のバックアップを残す
+    pub keep_daily: Option<u32>,
+    /// ファイルごとに直近の指定週数分、各週の最新のバックアップを残す
+    pub keep_weekly: Option<u32>,
+    /// ファイルごとに直近の指定月数分、各月の最新のバックアップを残す
+    pub keep_monthly: Option<u32>,
+    /// バックアップの物理サイズの上限（バイト）
+    ///
+    /// 超過した場合はピン留めされておらず、ファイルの最新でもないバックアップを古い順に削除します。
+    pub max_total_bytes: Option<u64>,
+    /// アプリ起動中にポリシーを適用する間隔（時間、未設定または `0` の場合は起動時のみ）
+    pub interval_hours: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_within_days: Some(30),
+            keep_last: None,
+            keep_daily: None,
+            keep_weekly: None,
+            keep_monthly: None,
+            max_total_bytes: None,
+            interval_hours: Some(24),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// 期間・世代による保持ルールが設定されているか
+    pub fn has_keep_rules(&self) -> bool {
+        self.keep_within_days.is_some()
+            || self.keep_last.is_some()
+            || self.keep_daily.is_some()
+            || self.keep_weekly.is_some()
+            || self.keep_monthly.is_some()
+    }
+
+    /// 定期適用の間隔（未設定または `0` の場合は`None`）
+    pub fn interval(&self) -> Option<std::time::Duration> {
+        self.interval_hours
+            .filter(|hours| *hours > 0)
+            .map(|hours| std::time::Duration::from_secs(u64::from(hours) * 3600))
+    }
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        let editor_modes = [
+            ("md", "markdown"),
+            ("json", "json"),
+            ("jsonl", "json"),
+            ("yaml", "yaml"),
+            ("yml", "yaml"),
+            ("toml", "ini"),
+            ("sh", "shell"),
+            ("py", "python"),
+            ("txt", "plaintext"),
+        ]
+        .into_iter()
+        .map(|(ext, mode)| (ext.to_string(), mode.to_string()))
+        .collect();
+
+        Self {
+            exclude_dirs: EXCLUDED_DIRS.iter().map(|d| (*d).to_string()).collect(),
+            exclude_globs: Vec::new(),
+            allowed_extensions: ALLOWED_EXTENSIONS.iter().map(|e| (*e).to_string()).collect(),
+            editor_modes,
+            backup_retention: RetentionPolicy::default(),
+            git_history: GitHistoryConfig::default(),
+            archive_import_limits: ArchiveImportLimits::default(),
+        }
+    }
+}
+
+impl DashboardConfig {
+    /// 拡張子の表記を揃える（先頭の `.` を除去し小文字化）
+    pub fn normalize(mut self) -> Self {
+        let normalize_ext = |ext: &str| ext.trim().trim_start_matches('.').to_lowercase();
+        self.allowed_extensions = self
+            .allowed_extensions
+            .iter()
+            .map(|e| normalize_ext(e))
+            .filter(|e| !e.is_empty())
+            .collect();
+        self.editor_modes = self
+            .editor_modes
+            .into_iter()
+            .map(|(ext, mode)| (normalize_ext(&ext), mode))
+            .collect();
+        self.exclude_dirs.retain(|d| !d.trim().is_empty());
+        self.exclude_globs.retain(|g| !g.trim().is_empty());
+        self
+    }
+}
+
+/// 除外globをコンパイル（`*` は `/` に一致しない）
+fn compile_glob(pattern: &str) -> Result<Glob, String> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| format!("Invalid exclude glob '{pattern}': {e}"))
+}
+
+/// 判定用に解決済みのダッシュボード設定
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// 元の設定
+    pub config: DashboardConfig,
+    /// コンパイル済みの除外glob
+    exclude_set: GlobSet,
+}
+
+impl Default for ResolvedConfig {
+    fn default() -> Self {
+        Self {
+            config: DashboardConfig::default(),
+            exclude_set: GlobSet::empty(),
+        }
+    }
+}
+
+impl ResolvedConfig {
+    /// 設定を検証して除外globをコンパイル
+    ///
+    /// # Errors
+    ///
+    /// 不正なglobが含まれている場合はエラーを返します。
+    pub fn new(config: DashboardConfig) -> Result<Self, String> {
+        let config = config.normalize();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &config.exclude_globs {
+            let pattern = pattern.trim().trim_start_matches("./");
+            builder.add(compile_glob(pattern)?);
+            // `dir/**` はディレクトリ自体も除外する
+            if let Some(dir) = pattern.strip_suffix("/**") {
+                if !dir.is_empty() {
+                    builder.add(compile_glob(dir)?);
+                }
+            }
+        }
+        let exclude_set = builder
+            .build()
+            .map_err(|e| format!("Failed to build exclude globs: {e}"))?;
+
+        Ok(Self {
+            config,
+            exclude_set,
+        })
+    }
+
+    /// ディレクトリ名が除外対象かどうか
+    pub fn is_excluded_dir_name(&self, name: &str) -> bool {
+        ALWAYS_EXCLUDED_DIRS.contains(&name) || self.config.exclude_dirs.iter().any(|d| d == name)
+    }
+
+    /// 設定ルートからの相対パスが除外対象かどうか
+    ///
+    /// いずれかの階層が除外ディレクトリ名に一致するか、パス自体または
+    /// 祖先ディレクトリが除外globに一致する場合に`true`を返します。
+    pub fn is_excluded_relative(&self, relative: &Path) -> bool {
+        let mut current = String::new();
+        for component in relative.components() {
+            let name = component.as_os_str().to_string_lossy();
+            if self.is_excluded_dir_name(&name) {
+                return true;
+            }
+            if !current.is_empty() {
+                current.push('/');
+            }
+            current.push_str(&name);
+            if self.exclude_set.is_match(&current) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// ファイルの拡張子が許可されているかどうか
+    pub fn is_allowed_extension(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| {
+                self.config
+                    .allowed_extensions
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(ext))
+            })
+    }
+
+    /// ファイルのエディタモードを取得（未設定の場合は `plaintext`）
+    pub fn editor_mode(&self, path: &Path) -> String {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.config.editor_modes.get(&ext.to_lowercase()))
+            .cloned()
+            .unwrap_or_else(|| "plaintext".to_string())
+    }
+}
+
+/// 読み込み済みの設定
+struct CachedConfig {
+    /// 設定ファイルのパス
+    path: PathBuf,
+    /// 設定ファイルの更新日時とサイズ（存在しない場合は`None`）
+    stamp: Option<(SystemTime, u64)>,
+    /// 最後に更新を確認した時刻（`None`の場合は次回必ず確認）
+    checked_at: Option<Instant>,
+    /// 解決済みの設定
+    resolved: Arc<ResolvedConfig>,
+    /// 読み込みエラー（デフォルト値を使用中）
+    error: Option<String>,
+}
+
+/// グローバルな設定キャッシュ
+static CACHE: Lazy<RwLock<Option<CachedConfig>>> = Lazy::new(|| RwLock::new(None));
+
+/// アクティブな設定ルートの設定ファイルのパスを取得
+pub fn get_config_file_path() -> Option<PathBuf> {
+    get_claude_dir()
+        .ok()
+        .map(|dir| dir.join(DASHBOARD_CONFIG_FILE))
+}
+
+/// 設定ファイルの更新日時とサイズを取得
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// 設定ファイルを読み込んで解決
+///
+/// ファイルが存在しない場合はデフォルト値、解析に失敗した場合はデフォルト値とエラーを返します。
+fn load_config(path: &Path) -> (ResolvedConfig, Option<String>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return (ResolvedConfig::default(), None),
+    };
+
+    let parsed = serde_json::from_str::<DashboardConfig>(&content)
+        .map_err(|e| format!("Failed to parse {DASHBOARD_CONFIG_FILE}: {e}"))
+        .and_then(ResolvedConfig::new);
+
+    match parsed {
+        Ok(resolved) => (resolved, None),
+        Err(e) => {
+            warn!("{e}. Falling back to defaults");
+            (ResolvedConfig::default(), Some(e))
+        }
+    }
+}
+
+/// 現在の設定を取得（必要に応じて再読み込み）
+fn current_with_error() -> (Arc<ResolvedConfig>, Option<String>) {
+    let Some(path) = get_config_file_path() else {
+        return (Arc::new(ResolvedConfig::default()), None);
+    };
+
+    {
+        let cache = CACHE.read().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(cached) = cache.as_ref() {
+            let fresh = cached
+                .checked_at
+                .is_some_and(|t| t.elapsed() < RELOAD_CHECK_INTERVAL);
+            if cached.path == path && fresh {
+                return (cached.resolved.clone(), cached.error.clone());
+            }
+        }
+    }
+
+    let stamp = file_stamp(&path);
+    let mut cache = CACHE.write().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    if let Some(cached) = cache.as_mut() {
+        if cached.path == path && cached.stamp == stamp {
+            cached.checked_at = Some(Instant::now());
+            return (cached.resolved.clone(), cached.error.clone());
+        }
+    }
+
+    let reloaded = cache.is_some();
+    let (resolved, error) = load_config(&path);
+    let resolved = Arc::new(resolved);
+    *cache = Some(CachedConfig {
+        path: path.clone(),
+        stamp,
+        checked_at: Some(Instant::now()),
+        resolved: resolved.clone(),
+        error: error.clone(),
+    });
+
+    if reloaded {
+        info!("Dashboard config reloaded: {}", path.display());
+    }
+
+    (resolved, error)
+}
+
+/// 現在のダッシュボード設定を取得
+///
+/// 設定ファイルが変更されていれば再読み込みします（確認は最大1秒に1回）。
+pub fn current() -> Arc<ResolvedConfig> {
+    current_with_error().0
+}
+
+/// 現在の設定と読み込みエラーを取得
+pub fn current_with_status() -> (Arc<ResolvedConfig>, Option<String>) {
+    current_with_error()
+}
+
+/// 次回の取得時に設定ファイルの更新を必ず確認させる
+///
+/// ファイル監視で設定ファイルの変更を検知した場合や、設定を保存した場合に呼び出します。
+pub fn invalidate() {
+    let mut cache = CACHE.write().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(cached) = cache.as_mut() {
+        cached.stamp = None;
+        cached.checked_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_matches_constants() {
+        let resolved = ResolvedConfig::default();
+        assert!(resolved.is_excluded_relative(Path::new("cache/data.json")));
+        assert!(resolved.is_allowed_extension(Path::new("SKILL.md")));
+        assert!(!resolved.is_allowed_extension(Path::new("hook.sh")));
+        assert_eq!(resolved.editor_mode(Path::new("a.jsonl")), "json");
+        assert_eq!(resolved.editor_mode(Path::new("a.unknown")), "plaintext");
+    }
+
+    #[test]
+    fn test_custom_globs_and_extensions() {
+        let config = DashboardConfig {
+            exclude_dirs: vec![],
+            exclude_globs: vec!["**/node_modules/**".to_string(), "projects/*.jsonl".to_string()],
+            allowed_extensions: vec![".SH".to_string(), "md".to_string()],
+            ..DashboardConfig::default()
+        };
+        let resolved = ResolvedConfig::new(config).unwrap();
+
+        assert!(resolved.is_excluded_relative(Path::new("skills/a/node_modules")));
+        assert!(resolved.is_excluded_relative(Path::new("skills/a/node_modules/x/index.md")));
+        assert!(resolved.is_excluded_relative(Path::new("projects/log.jsonl")));
+        assert!(!resolved.is_excluded_relative(Path::new("projects/a/log.jsonl")));
+        // backupsは設定に関わらず除外
+        assert!(resolved.is_excluded_relative(Path::new("backups/a.md")));
+        assert!(!resolved.is_excluded_relative(Path::new("cache/a.md")));
+
+        assert!(resolved.is_allowed_extension(Path::new("hooks/pre.sh")));
+        assert!(!resolved.is_allowed_extension(Path::new("settings.json")));
+    }
+
+    #[test]
+    fn test_invalid_glob_is_rejected() {
+        let config = DashboardConfig {
+            exclude_globs: vec!["a[".to_string()],
+            ..DashboardConfig::default()
+        };
+        assert!(ResolvedConfig::new(config).is_err());
+    }
+
+    #[test]
+    fn test_zero_retention_interval_disables_schedule() {
+        let policy = RetentionPolicy {
+            interval_hours: Some(0),
+            ..RetentionPolicy::default()
+        };
+        assert_eq!(policy.interval(), None);
+        assert_eq!(
+            RetentionPolicy::default().interval(),
+            Some(std::time::Duration::from_secs(24 * 3600))
+        );
+    }
+
+    #[test]
+    fn test_archive_import_limits_accepts_legacy_key() {
+        for key in ["archiveImportLimits", "zipImportLimits"] {
+            let config: DashboardConfig =
+                serde_json::from_str(&format!(r#"{{"{key}": {{"maxEntries": 5}}}}"#)).unwrap();
+            assert_eq!(config.archive_import_limits.max_entries, 5, "{key}");
+            assert_eq!(
+                config.archive_import_limits.max_compression_ratio,
+                ArchiveImportLimits::default().max_compression_ratio
+            );
+        }
+    }
+
+    #[test]
+    fn test_hot_reload() {
+        let claude = crate::utils::TestClaudeDir::new();
+        assert!(!current().is_allowed_extension(Path::new("hook.py")));
+
+        fs::write(
+            claude.path().join(DASHBOARD_CONFIG_FILE),
+            r#"{"allowedExtensions": ["md", "json", "jsonl", "py"]}"#,
+        )
+        .unwrap();
+        invalidate();
+        assert!(current().is_allowed_extension(Path::new("hook.py")));
+
+        claude.write(DASHBOARD_CONFIG_FILE, "{ invalid");
+        invalidate();
+        let (resolved, error) = current_with_status();
+        assert!(error.is_some());
+        assert!(resolved.is_allowed_extension(Path::new("a.json")));
+    }
+}
@@ -0,0 +1,427 @@
+//! 差分エンジン
+//!
+//! 2つのテキストを行単位で比較し、ハンク（行番号・種類付き）または統合diff形式のテキストと
+//! 統計を求めます。JSONの場合はキーの順序と空白を無視した意味的な比較にも対応し、
+//! 変更された値をJSON Pointerで列挙します。
+//! 同じハンク分割を使用して、選択したハンクのみを比較元の内容に戻すこともできます。
+
+use crate::types::{
+    DiffFormat, DiffHunk, DiffLine, DiffLineKind, DiffOptions, DiffResult, DiffStats, JsonChange,
+    JsonChangeKind,
+};
+use serde_json::{Map, Value};
+use similar::{Algorithm, ChangeTag, DiffOp, DiffTag, TextDiff};
+use std::collections::HashSet;
+
+/// ハンクの前後に含める変更のない行数のデフォルト
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// 行単位の差分を計算
+fn line_diff<'a>(old: &'a str, new: &'a str) -> TextDiff<'a, 'a, 'a, str> {
+    TextDiff::configure()
+        .algorithm(Algorithm::Myers)
+        .diff_lines(old, new)
+}
+
+/// 範囲の開始行（1始まり、空の範囲は直前の行）
+fn range_start(range: &std::ops::Range<usize>) -> usize {
+    if range.is_empty() {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
+/// ハンクに含まれる操作から見出しと範囲を求める
+fn hunk_from_ops(index: usize, ops: &[DiffOp]) -> DiffHunk {
+    let (Some(first), Some(last)) = (ops.first(), ops.last()) else {
+        return DiffHunk {
+            index,
+            old_start: 0,
+            old_lines: 0,
+            new_start: 0,
+            new_lines: 0,
+            header: "@@ -0,0 +0,0 @@".to_string(),
+            lines: Vec::new(),
+        };
+    };
+    let old_range = first.old_range().start..last.old_range().end;
+    let new_range = first.new_range().start..last.new_range().end;
+    let (old_start, new_start) = (range_start(&old_range), range_start(&new_range));
+    DiffHunk {
+        index,
+        old_start,
+        old_lines: old_range.len(),
+        new_start,
+        new_lines: new_range.len(),
+        header: format!(
+            "@@ -{old_start},{} +{new_start},{} @@",
+            old_range.len(),
+            new_range.len()
+        ),
+        lines: Vec::new(),
+    }
+}
+
+/// 2つのテキストの差分を計算
+///
+/// # Arguments
+///
+/// * `old` - 比較元の内容
+/// * `new` - 比較先の内容
+/// * `old_label` - 比較元の表示名
+/// * `new_label` - 比較先の表示名
+/// * `options` - 出力形式・前後の行数・JSONとして比較するか
+///
+/// # Returns
+///
+/// ハンク・統計と、指定に応じて統合diff形式のテキストやJSONの値の変更
+pub fn diff_texts(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    options: &DiffOptions,
+) -> DiffResult {
+    let mut json_changes = None;
+    let mut semantic_error = None;
+    let mut canonical = None;
+
+    if options.semantic {
+        match (parse_json(old), parse_json(new)) {
+            (Ok(old_value), Ok(new_value)) => {
+                let mut changes = Vec::new();
+                collect_json_changes(&old_value, &new_value, String::new(), &mut changes);
+                json_changes = Some(changes);
+                canonical = Some((canonical_text(&old_value), canonical_text(&new_value)));
+            }
+            (Err(e), _) => semantic_error = Some(format!("{old_label}: {e}")),
+            (_, Err(e)) => semantic_error = Some(format!("{new_label}: {e}")),
+        }
+    }
+
+    let (old, new) = match &canonical {
+        Some((old, new)) => (old.as_str(), new.as_str()),
+        None => (old, new),
+    };
+    let context = options.context_lines.unwrap_or(DEFAULT_CONTEXT_LINES);
+    let diff = line_diff(old, new);
+
+    let mut stats = DiffStats::default();
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => stats.additions += 1,
+            ChangeTag::Delete => stats.deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let groups = diff.grouped_ops(context);
+    stats.hunks = groups.len();
+
+    let hunks = groups
+        .iter()
+        .enumerate()
+        .map(|(index, ops)| {
+            let mut hunk = hunk_from_ops(index, ops);
+            if options.format == DiffFormat::Structured {
+                hunk.lines = ops
+                    .iter()
+                    .flat_map(|op| diff.iter_changes(op))
+                    .map(|change| DiffLine {
+                        kind: match change.tag() {
+                            ChangeTag::Equal => DiffLineKind::Context,
+                            ChangeTag::Insert => DiffLineKind::Added,
+                            ChangeTag::Delete => DiffLineKind::Removed,
+                        },
+                        content: change
+                            .value()
+                            .trim_end_matches('\n')
+                            .trim_end_matches('\r')
+                            .to_string(),
+                        old_line: change.old_index().map(|i| i + 1),
+                        new_line: change.new_index().map(|i| i + 1),
+                    })
+                    .collect();
+            }
+            hunk
+        })
+        .collect();
+
+    let unified = (options.format == DiffFormat::Unified).then(|| {
+        diff.unified_diff()
+            .context_radius(context)
+            .header(old_label, new_label)
+            .to_string()
+    });
+
+    DiffResult {
+        old_label: old_label.to_string(),
+        new_label: new_label.to_string(),
+        identical: stats.hunks == 0,
+        stats,
+        hunks,
+        unified,
+        json_changes,
+        semantic_error,
+    }
+}
+
+/// 比較先の内容のうち、選択したハンクのみを比較元の内容に戻す
+///
+/// ハンクの番号は同じ `context_lines` で [`diff_texts`] を呼び出した場合の番号です
+/// （意味的な比較ではなくテキストとしての比較）。
+///
+/// # Arguments
+///
+/// * `old` - 戻す内容（バックアップなど）
+/// * `new` - 現在の内容
+/// * `context_lines` - ハンクの前後に含める変更のない行数
+/// * `selected` - 戻すハンクの番号
+///
+/// # Returns
+///
+/// 選択したハンクを戻した内容
+///
+/// # Errors
+///
+/// 存在しないハンクの番号が指定された場合はエラーを返します。
+pub fn revert_hunks(
+    old: &str,
+    new: &str,
+    context_lines: usize,
+    selected: &HashSet<usize>,
+) -> Result<String, String> {
+    let diff = line_diff(old, new);
+    let groups = diff.grouped_ops(context_lines);
+    if let Some(index) = selected.iter().find(|i| **i >= groups.len()) {
+        return Err(format!(
+            "Hunk {index} does not exist (the diff has {} hunk(s))",
+            groups.len()
+        ));
+    }
+
+    // 選択したハンクに含まれる変更を、比較元と比較先の開始位置で識別する
+    let reverted: HashSet<(usize, usize)> = groups
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| selected.contains(index))
+        .flat_map(|(_, ops)| ops.iter())
+        .filter(|op| op.tag() != DiffTag::Equal)
+        .map(|op| (op.old_range().start, op.new_range().start))
+        .collect();
+
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+    let mut result = String::with_capacity(new.len());
+    for op in diff.ops() {
+        let revert = op.tag() != DiffTag::Equal
+            && reverted.contains(&(op.old_range().start, op.new_range().start));
+        let lines = if revert {
+            &old_lines[op.old_range()]
+        } else {
+            &new_lines[op.new_range()]
+        };
+        for line in lines {
+            result.push_str(line);
+        }
+    }
+    Ok(result)
+}
+
+/// JSONとして解析
+fn parse_json(content: &str) -> Result<Value, String> {
+    serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {e}"))
+}
+
+/// キーを並べ替えて整形したJSONのテキスト
+fn canonical_text(value: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(&sort_keys(value)).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// オブジェクトのキーを再帰的に並べ替える
+fn sort_keys(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), sort_keys(v)))
+                    .collect::<Map<String, Value>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(sort_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+/// JSON Pointerのトークンをエスケープ
+pub(crate) fn escape_pointer(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// 2つのJSONの値の変更を再帰的に列挙
+///
+/// オブジェクトはキーの順序を無視して比較し、配列は同じ位置の要素同士を比較します。
+fn collect_json_changes(old: &Value, new: &Value, path: String, changes: &mut Vec<JsonChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = format!("{path}/{}", escape_pointer(key));
+                match (old_map.get(key), new_map.get(key)) {
+                    (Some(o), Some(n)) => collect_json_changes(o, n, child, changes),
+                    (Some(o), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        old_value: Some(o.clone()),
+                        new_value: None,
+                    }),
+                    (None, Some(n)) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Added,
+                        old_value: None,
+                        new_value: Some(n.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items)) => {
+            for index in 0..old_items.len().max(new_items.len()) {
+                let child = format!("{path}/{index}");
+                match (old_items.get(index), new_items.get(index)) {
+                    (Some(o), Some(n)) => collect_json_changes(o, n, child, changes),
+                    (Some(o), None) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Removed,
+                        old_value: Some(o.clone()),
+                        new_value: None,
+                    }),
+                    (None, Some(n)) => changes.push(JsonChange {
+                        path: child,
+                        kind: JsonChangeKind::Added,
+                        old_value: None,
+                        new_value: Some(n.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (o, n) if o != n => changes.push(JsonChange {
+            path,
+            kind: JsonChangeKind::Changed,
+            old_value: Some(o.clone()),
+            new_value: Some(n.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(lines: &[&str]) -> String {
+        lines.iter().map(|l| format!("{l}\n")).collect()
+    }
+
+    #[test]
+    fn test_structured_hunks_and_revert_selected() {
+        let old = numbered(&["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]);
+        let new = numbered(&["a", "B", "c", "d", "e", "f", "g", "h", "i", "j", "k"]);
+        let options = DiffOptions {
+            context_lines: Some(1),
+            ..DiffOptions::default()
+        };
+        let result = diff_texts(&old, &new, "old", "new", &options);
+
+        assert!(!result.identical);
+        assert_eq!(
+            result.stats,
+            DiffStats {
+                additions: 2,
+                deletions: 1,
+                hunks: 2,
+            }
+        );
+        assert_eq!(result.hunks[0].header, "@@ -1,3 +1,3 @@");
+        assert_eq!(
+            result.hunks[0].lines[1],
+            DiffLine {
+                kind: DiffLineKind::Removed,
+                content: "b".to_string(),
+                old_line: Some(2),
+                new_line: None,
+            }
+        );
+        assert_eq!(result.hunks[1].header, "@@ -10,1 +10,2 @@");
+
+        // 1つ目のハンクのみ戻すと、末尾の追加は残る
+        let reverted = revert_hunks(&old, &new, 1, &HashSet::from([0])).unwrap();
+        assert_eq!(
+            reverted,
+            numbered(&["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k"])
+        );
+        assert_eq!(
+            revert_hunks(&old, &new, 1, &HashSet::from([0, 1])).unwrap(),
+            old
+        );
+        assert!(revert_hunks(&old, &new, 1, &HashSet::from([2])).is_err());
+
+        let unified = diff_texts(
+            &old,
+            &new,
+            "old",
+            "new",
+            &DiffOptions {
+                format: DiffFormat::Unified,
+                ..options
+            },
+        );
+        assert!(unified.hunks[0].lines.is_empty());
+        assert!(unified.unified.unwrap().contains("-b\n+B\n"));
+    }
+
+    #[test]
+    fn test_semantic_json_ignores_key_order_and_whitespace() {
+        let old = r#"{"model": "a", "permissions": {"allow": ["x", "y"]}, "env": {"A": "1"}}"#;
+        let new = "{\n  \"env\": {\"A\": \"1\"},\n  \"permissions\": {\"allow\": [\"x\"]},\n  \"model\": \"b\",\n  \"a/b\": true\n}";
+        let options = DiffOptions {
+            semantic: true,
+            ..DiffOptions::default()
+        };
+        let result = diff_texts(old, new, "old", "new", &options);
+
+        let changes = result.json_changes.unwrap();
+        let summary: Vec<(&str, JsonChangeKind)> =
+            changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/a~1b", JsonChangeKind::Added),
+                ("/model", JsonChangeKind::Changed),
+                ("/permissions/allow/1", JsonChangeKind::Removed),
+            ]
+        );
+        assert_eq!(result.stats.deletions, 3);
+
+        // 順序と空白のみ異なる場合は差分なし
+        let reordered = r#"{"env":{"A":"1"},"permissions":{"allow":["x","y"]},"model":"a"}"#;
+        let result = diff_texts(old, reordered, "old", "new", &options);
+        assert!(result.identical);
+        assert_eq!(result.json_changes, Some(Vec::new()));
+
+        // 解析できない場合はテキストとして比較する
+        let result = diff_texts(old, "{", "old", "broken", &options);
+        assert!(result.semantic_error.unwrap().starts_with("broken:"));
+        assert!(result.json_changes.is_none());
+        assert!(!result.identical);
+    }
+}
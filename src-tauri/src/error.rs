@@ -7,6 +7,40 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::io;
 
+/// アクセス拒否の理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessDeniedReason {
+    /// 設定ルート外のパス
+    OutsideRoot(String),
+    /// 相対パスが指定された
+    RelativePath(String),
+    /// 設定ルート外を指すシンボリックリンク
+    SymlinkEscape(String),
+    /// シンボリックリンクが許可されていない
+    SymlinkNotAllowed(String),
+    /// リンク先が存在しないシンボリックリンク
+    BrokenSymlink(String),
+    /// その他の理由
+    Other(String),
+}
+
+impl fmt::Display for AccessDeniedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutsideRoot(path) => {
+                write!(f, "only files in the Claude config directory are allowed: {path}")
+            }
+            Self::RelativePath(path) => write!(f, "relative paths are not allowed: {path}"),
+            Self::SymlinkEscape(path) => {
+                write!(f, "symlink points outside the Claude config directory: {path}")
+            }
+            Self::SymlinkNotAllowed(path) => write!(f, "symlinks are not allowed: {path}"),
+            Self::BrokenSymlink(path) => write!(f, "symlink target does not exist: {path}"),
+            Self::Other(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
 /// アプリケーション全体で使用するエラー型
 #[derive(Debug)]
 pub enum AppError {
@@ -15,7 +49,7 @@ pub enum AppError {
     /// Claudeディレクトリが見つからない
     ClaudeDirNotFound,
     /// アクセス拒否（セキュリティ違反）
-    AccessDenied(String),
+    AccessDenied(AccessDeniedReason),
     /// ファイルが見つからない
     FileNotFound(String),
     /// ファイルが既に存在する
@@ -37,7 +71,7 @@ impl fmt::Display for AppError {
         match self {
             Self::HomeNotFound => write!(f, "Could not find home directory"),
             Self::ClaudeDirNotFound => write!(f, "~/.claude directory not found"),
-            Self::AccessDenied(msg) => write!(f, "Access denied: {msg}"),
+            Self::AccessDenied(reason) => write!(f, "Access denied: {reason}"),
             Self::FileNotFound(path) => write!(f, "File not found: {path}"),
             Self::FileAlreadyExists(path) => {
                 write!(f, "File already exists: {path}. Use write_file to update existing files.")
@@ -47,7 +81,10 @@ impl fmt::Display for AppError {
             Self::PathError(msg) => write!(f, "{msg}"),
             Self::TimeError(msg) => write!(f, "{msg}"),
             Self::InvalidFileType(ext) => {
-                write!(f, "Invalid file type: {ext}. Only .md, .json, .jsonl are allowed")
+                write!(
+                    f,
+                    "Invalid file type: {ext}. Allowed extensions are set by allowedExtensions in dashboard-config.json"
+                )
             }
         }
     }
@@ -0,0 +1,732 @@
+//! Gitによる変更履歴
+//!
+//! ダッシュボード設定の `gitHistory.enabled` が有効な場合、設定ルートをローカルのGitリポジトリとして管理し、
+//! ダッシュボードからの保存（編集・置換・インポート・復元）ごとにコミットを作成します。
+//! 外部の `git` コマンドやネットワークは使用せず、libgit2で直接操作します。
+//!
+//! 追跡対象はダッシュボードに表示されるファイルと同じです。除外ディレクトリ・除外glob・
+//! 許可する拡張子から生成した無視ルールを `.git/info/exclude` の専用ブロックに書き込むため、
+//! 既存のリポジトリでもユーザーが記述した無視ルールや `.gitignore` はそのまま残ります。
+//! 既存のリポジトリで追跡されている表示対象外のファイルはステージしません。
+
+use crate::commands::replace::collect_target_files;
+use crate::config::{self, ResolvedConfig, ALWAYS_EXCLUDED_DIRS};
+use crate::types::{GitChangeKind, GitCommitInfo, GitFileDiff, GitHistoryStatus, GitStatusEntry};
+use crate::utils::{
+    get_claude_dir, is_allowed_extension, is_excluded_relative_path, TEMP_FILE_SUFFIX,
+};
+use chrono::{Local, TimeZone};
+use git2::{
+    Commit, Delta, DiffOptions, Oid, Patch, Repository, Signature, Sort, Status, StatusOptions,
+};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 生成した無視ルールの開始行
+const EXCLUDE_BEGIN: &str = "# >>> claude-setting-dashboard (generated, do not edit)";
+
+/// 生成した無視ルールの終了行
+const EXCLUDE_END: &str = "# <<< claude-setting-dashboard";
+
+/// リポジトリ作成時の最初のコミットメッセージ
+const INITIAL_COMMIT_MESSAGE: &str = "Start tracking configuration history";
+
+/// 履歴の取得件数のデフォルト
+pub const DEFAULT_LOG_LIMIT: usize = 100;
+
+/// インデックスとコミットの更新を直列化するロック
+static GIT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// ダッシュボード設定で変更履歴が有効になっているか
+pub fn is_enabled() -> bool {
+    config::current().config.git_history.enabled
+}
+
+/// 除外設定から `.git/info/exclude` に書き込む無視ルールを生成
+///
+/// すべてを無視したうえでディレクトリと許可する拡張子のファイルを追跡対象に戻し、
+/// 最後に除外ディレクトリと除外globを無視します。
+fn generate_ignore_rules(config: &ResolvedConfig) -> String {
+    let mut lines = vec![
+        EXCLUDE_BEGIN.to_string(),
+        "*".to_string(),
+        "!*/".to_string(),
+    ];
+    lines.extend(
+        config
+            .config
+            .allowed_extensions
+            .iter()
+            .map(|ext| format!("!*.{ext}")),
+    );
+
+    // 除外ディレクトリ名はどの階層でも一致する
+    for dir in ALWAYS_EXCLUDED_DIRS
+        .iter()
+        .map(|d| (*d).to_string())
+        .chain(config.config.exclude_dirs.iter().cloned())
+    {
+        lines.push(format!("{dir}/"));
+    }
+    // 除外globは設定ルートからの相対パスに一致する
+    for pattern in &config.config.exclude_globs {
+        let pattern = pattern.trim().trim_start_matches("./");
+        if pattern.is_empty() {
+            continue;
+        }
+        if pattern.starts_with("**/") {
+            lines.push(pattern.to_string());
+        } else {
+            lines.push(format!("/{pattern}"));
+        }
+    }
+    lines.push(format!("*{TEMP_FILE_SUFFIX}"));
+    lines.push(EXCLUDE_END.to_string());
+    lines.join("\n")
+}
+
+/// `.git/info/exclude` の生成ブロックを現在の設定に合わせて更新
+///
+/// ブロックの外にある内容は保持します。
+fn sync_ignore_rules(repo: &Repository) -> Result<(), String> {
+    let path = repo.path().join("info").join("exclude");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+
+    let mut kept = Vec::new();
+    let mut in_block = false;
+    for line in existing.lines() {
+        if line == EXCLUDE_BEGIN {
+            in_block = true;
+        } else if line == EXCLUDE_END {
+            in_block = false;
+        } else if !in_block {
+            kept.push(line);
+        }
+    }
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+
+    let mut content = kept.join("\n");
+    if !content.is_empty() {
+        content.push_str("\n\n");
+    }
+    content.push_str(&generate_ignore_rules(&config::current()));
+    content.push('\n');
+
+    if content != existing {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create git info directory: {e}"))?;
+        }
+        fs::write(&path, content).map_err(|e| format!("Failed to write git exclude file: {e}"))?;
+    }
+    Ok(())
+}
+
+/// 設定ルートのリポジトリを開く（存在しない場合は`None`）
+///
+/// 親ディレクトリのリポジトリは使用しません。読み込みのみの操作で使用するため、
+/// 無視ルールは更新しません。
+fn open_repository(root: &Path) -> Result<Option<Repository>, String> {
+    if !root.join(".git").exists() {
+        return Ok(None);
+    }
+    Repository::open(root)
+        .map(Some)
+        .map_err(|e| format!("Failed to open git repository: {e}"))
+}
+
+/// 設定ルートのリポジトリを開き、存在しなければ作成して現在の状態をコミット
+///
+/// コミットの前に呼び出すため、無視ルールを現在の設定に合わせて更新します。
+/// 呼び出し元は [`GIT_LOCK`] を保持してください。
+///
+/// # Returns
+///
+/// リポジトリと、作成した場合は最初のコミットのID
+fn open_or_init_repository(root: &Path) -> Result<(Repository, Option<String>), String> {
+    if let Some(repo) = open_repository(root)? {
+        sync_ignore_rules(&repo)?;
+        return Ok((repo, None));
+    }
+
+    let repo =
+        Repository::init(root).map_err(|e| format!("Failed to create git repository: {e}"))?;
+    sync_ignore_rules(&repo)?;
+    stage_all(&repo, root)?;
+    let initial = commit_index(&repo, INITIAL_COMMIT_MESSAGE)?;
+    info!(
+        "Created configuration history repository at {}",
+        root.display()
+    );
+    Ok((repo, initial))
+}
+
+/// ダッシュボードの表示対象のファイルの変更をすべてインデックスに反映
+///
+/// 表示対象のファイルと、インデックスにあるが削除された表示対象のファイルのみを反映します。
+/// 既存のリポジトリで追跡されている表示対象外のファイルには触れません。
+fn stage_all(repo: &Repository, root: &Path) -> Result<(), String> {
+    let mut paths: Vec<PathBuf> = collect_target_files(root, None, None)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+
+    let index = repo.index().map_err(|e| e.to_string())?;
+    for entry in index.iter() {
+        let relative = PathBuf::from(String::from_utf8_lossy(&entry.path).as_ref());
+        let path = root.join(&relative);
+        if !path.exists() && !is_excluded_relative_path(&relative) && is_allowed_extension(&path) {
+            paths.push(path);
+        }
+    }
+
+    stage_paths(repo, root, &paths)
+}
+
+/// 変更されたファイルのみをインデックスに反映
+///
+/// 存在しないファイルや無視ルールに一致するファイルはインデックスから取り除きます。
+fn stage_paths(repo: &Repository, root: &Path, paths: &[PathBuf]) -> Result<(), String> {
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+
+    for path in paths {
+        let Some(relative) = path
+            .strip_prefix(root)
+            .or_else(|_| path.strip_prefix(&canonical_root))
+            .ok()
+        else {
+            continue;
+        };
+        let ignored = repo.is_path_ignored(relative).unwrap_or(true);
+        let result = if path.is_file() && !ignored {
+            index.add_path(relative)
+        } else if index.get_path(relative, 0).is_some() {
+            index.remove_path(relative)
+        } else {
+            Ok(())
+        };
+        result.map_err(|e| format!("Failed to stage {}: {e}", relative.display()))?;
+    }
+
+    index
+        .write()
+        .map_err(|e| format!("Failed to write git index: {e}"))
+}
+
+/// コミットの作成者
+fn signature() -> Result<Signature<'static>, String> {
+    let config = config::current();
+    let settings = &config.config.git_history;
+    Signature::now(&settings.author_name, &settings.author_email)
+        .map_err(|e| format!("Invalid commit author: {e}"))
+}
+
+/// 最新のコミット（コミットがない場合は`None`）
+fn head_commit(repo: &Repository) -> Option<Commit<'_>> {
+    repo.head().ok().and_then(|head| head.peel_to_commit().ok())
+}
+
+/// インデックスの内容をコミット
+///
+/// # Returns
+///
+/// 作成したコミットのID（最新のコミットから変更がない場合は`None`）
+fn commit_index(repo: &Repository, message: &str) -> Result<Option<String>, String> {
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    let tree_id = index
+        .write_tree()
+        .map_err(|e| format!("Failed to write git tree: {e}"))?;
+    let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+
+    let parent = head_commit(repo);
+    let unchanged = match &parent {
+        Some(parent) => parent.tree_id() == tree_id,
+        None => tree.is_empty(),
+    };
+    if unchanged {
+        return Ok(None);
+    }
+
+    let signature = signature()?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|e| format!("Failed to create commit: {e}"))?;
+    Ok(Some(id.to_string()))
+}
+
+/// ダッシュボードから保存したファイルをコミット
+///
+/// 変更履歴が無効な場合は何もしません。リポジトリが存在しなければ作成し、
+/// 現在の状態を最初のコミットとして記録します。
+///
+/// # Arguments
+///
+/// * `paths` - 書き込み・削除したファイルの絶対パス
+/// * `message` - コミットメッセージ
+///
+/// # Returns
+///
+/// 作成したコミットのID（無効な場合や変更がない場合は`None`）
+///
+/// # Errors
+///
+/// リポジトリの操作に失敗した場合はエラーを返します。
+pub fn record_change(paths: &[PathBuf], message: &str) -> Result<Option<String>, String> {
+    if !is_enabled() {
+        return Ok(None);
+    }
+    let _guard = GIT_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let (repo, initial) = open_or_init_repository(&root)?;
+
+    stage_paths(&repo, &root, paths)?;
+    let id = commit_index(&repo, message)?;
+    if let Some(id) = &id {
+        info!("Recorded configuration history {id}: {message}");
+    }
+    // 作成時の最初のコミットに変更が含まれている場合はそのコミットを返す
+    Ok(id.or(initial))
+}
+
+/// 保存処理から呼び出し、失敗しても警告のみ出力してコミットする
+///
+/// 履歴の記録に失敗しても保存自体は成功として扱います。
+pub fn record_change_or_warn(paths: &[PathBuf], message: &str) -> Option<String> {
+    record_change(paths, message).unwrap_or_else(|e| {
+        warn!("Failed to record configuration history: {e}");
+        None
+    })
+}
+
+/// ダッシュボードの外で行われた変更をすべてコミット
+///
+/// # Arguments
+///
+/// * `message` - コミットメッセージ
+///
+/// # Returns
+///
+/// 作成したコミットのID（変更がない場合は`None`）
+///
+/// # Errors
+///
+/// 変更履歴が無効な場合、リポジトリの操作に失敗した場合はエラーを返します。
+pub fn commit_all(message: &str) -> Result<Option<String>, String> {
+    if !is_enabled() {
+        return Err("Git history is disabled in the dashboard config".to_string());
+    }
+    let _guard = GIT_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let (repo, initial) = open_or_init_repository(&root)?;
+
+    stage_all(&repo, &root)?;
+    Ok(commit_index(&repo, message)?.or(initial))
+}
+
+/// 設定ルートからの相対パスをコミットメッセージ用に取得
+pub fn display_path(path: &Path) -> String {
+    get_claude_dir()
+        .ok()
+        .and_then(|root| {
+            path.strip_prefix(&root)
+                .ok()
+                .or_else(|| {
+                    root.canonicalize()
+                        .ok()
+                        .and_then(|canonical| path.strip_prefix(canonical).ok())
+                })
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+        })
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+/// コミットの情報を変換
+fn commit_info(commit: &Commit) -> GitCommitInfo {
+    let id = commit.id().to_string();
+    let time = commit.author().when();
+    GitCommitInfo {
+        short_id: id.chars().take(7).collect(),
+        id,
+        summary: commit.summary().unwrap_or_default().to_string(),
+        message: commit.message().unwrap_or_default().to_string(),
+        author: commit.author().name().unwrap_or_default().to_string(),
+        time: Local
+            .timestamp_opt(time.seconds(), 0)
+            .single()
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
+
+/// ファイルの状態の種類を変換
+fn change_kind(status: Status) -> Option<GitChangeKind> {
+    if status.intersects(Status::WT_NEW | Status::INDEX_NEW) {
+        Some(GitChangeKind::Added)
+    } else if status.intersects(Status::WT_DELETED | Status::INDEX_DELETED) {
+        Some(GitChangeKind::Deleted)
+    } else if status.intersects(Status::WT_RENAMED | Status::INDEX_RENAMED) {
+        Some(GitChangeKind::Renamed)
+    } else if status.intersects(
+        Status::WT_MODIFIED
+            | Status::INDEX_MODIFIED
+            | Status::WT_TYPECHANGE
+            | Status::INDEX_TYPECHANGE,
+    ) {
+        Some(GitChangeKind::Modified)
+    } else {
+        None
+    }
+}
+
+/// 変更履歴の状態とコミットされていない変更を取得
+///
+/// # Errors
+///
+/// リポジトリの読み込みに失敗した場合はエラーを返します。
+pub fn status() -> Result<GitHistoryStatus, String> {
+    let enabled = is_enabled();
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let Some(repo) = open_repository(&root)? else {
+        return Ok(GitHistoryStatus {
+            enabled,
+            ..GitHistoryStatus::default()
+        });
+    };
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        .exclude_submodules(true);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to read git status: {e}"))?;
+
+    let mut changes: Vec<GitStatusEntry> = statuses
+        .iter()
+        .filter_map(|entry| {
+            Some(GitStatusEntry {
+                path: entry.path()?.to_string(),
+                kind: change_kind(entry.status())?,
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(GitHistoryStatus {
+        enabled,
+        initialized: true,
+        head: head_commit(&repo).map(|c| commit_info(&c)),
+        changes,
+    })
+}
+
+/// コミット時点のファイルのblob ID
+fn blob_id_at(commit: &Commit, path: &Path) -> Option<Oid> {
+    commit.tree().ok()?.get_path(path).ok().map(|e| e.id())
+}
+
+/// コミット履歴を取得（新しい順）
+///
+/// # Arguments
+///
+/// * `path` - 指定した場合はこのファイル（設定ルートからの相対パス）を変更したコミットのみ
+/// * `limit` - 最大件数
+/// * `offset` - 読み飛ばす件数
+///
+/// # Errors
+///
+/// リポジトリの読み込みに失敗した場合はエラーを返します。
+pub fn log(path: Option<&str>, limit: usize, offset: usize) -> Result<Vec<GitCommitInfo>, String> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let Some(repo) = open_repository(&root)? else {
+        return Ok(Vec::new());
+    };
+    if head_commit(&repo).is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    walk.push_head()
+        .and_then(|()| walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME))
+        .map_err(|e| format!("Failed to read git history: {e}"))?;
+
+    let path = path.map(Path::new);
+    let mut commits = Vec::new();
+    for id in walk.filter_map(Result::ok) {
+        let Ok(commit) = repo.find_commit(id) else {
+            continue;
+        };
+        if let Some(path) = path {
+            let current = blob_id_at(&commit, path);
+            let previous = commit.parent(0).ok().and_then(|p| blob_id_at(&p, path));
+            if current == previous {
+                continue;
+            }
+        }
+        commits.push(commit_info(&commit));
+        if commits.len() >= offset + limit {
+            break;
+        }
+    }
+    Ok(commits.into_iter().skip(offset).collect())
+}
+
+/// 2つのコミット間、またはコミットと作業ディレクトリ間の差分を取得
+///
+/// # Arguments
+///
+/// * `from` - 比較元のリビジョン
+/// * `to` - 比較先のリビジョン（`None`の場合は現在のファイル）
+/// * `path` - 指定した場合はこのファイル（設定ルートからの相対パス）のみ
+///
+/// # Errors
+///
+/// リビジョンが見つからない場合、差分の計算に失敗した場合はエラーを返します。
+pub fn diff(from: &str, to: Option<&str>, path: Option<&str>) -> Result<Vec<GitFileDiff>, String> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let repo = open_repository(&root)?.ok_or("Git history has not been initialized")?;
+
+    let tree_of = |revision: &str| {
+        repo.revparse_single(revision)
+            .and_then(|object| object.peel_to_tree())
+            .map_err(|e| format!("Revision not found: {revision}: {e}"))
+    };
+    let old_tree = tree_of(from)?;
+
+    let mut options = DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+    let mut diff = match to {
+        Some(to) => {
+            let new_tree = tree_of(to)?;
+            repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut options))
+        }
+        None => {
+            options
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            repo.diff_tree_to_workdir_with_index(Some(&old_tree), Some(&mut options))
+        }
+    }
+    .map_err(|e| format!("Failed to compute diff: {e}"))?;
+    diff.find_similar(None)
+        .map_err(|e| format!("Failed to detect renames: {e}"))?;
+
+    let mut files = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let kind = match delta.status() {
+            Delta::Added | Delta::Untracked | Delta::Copied => GitChangeKind::Added,
+            Delta::Deleted => GitChangeKind::Deleted,
+            Delta::Renamed => GitChangeKind::Renamed,
+            Delta::Modified | Delta::Typechange => GitChangeKind::Modified,
+            _ => continue,
+        };
+        let to_string = |p: Option<&Path>| p.map(|p| p.to_string_lossy().replace('\\', "/"));
+        let new_path = to_string(delta.new_file().path());
+        let old_path = to_string(delta.old_file().path());
+
+        let (patch, additions, deletions) = match Patch::from_diff(&diff, index) {
+            Ok(Some(mut patch)) => {
+                let (_, additions, deletions) = patch.line_stats().unwrap_or((0, 0, 0));
+                let text = patch
+                    .to_buf()
+                    .map(|buf| String::from_utf8_lossy(&buf).to_string())
+                    .unwrap_or_default();
+                (text, additions, deletions)
+            }
+            _ => (String::new(), 0, 0),
+        };
+
+        files.push(GitFileDiff {
+            path: new_path
+                .clone()
+                .or_else(|| old_path.clone())
+                .unwrap_or_default(),
+            old_path: if kind == GitChangeKind::Renamed {
+                old_path
+            } else {
+                None
+            },
+            kind,
+            patch,
+            additions,
+            deletions,
+        });
+    }
+    Ok(files)
+}
+
+/// 指定したリビジョン時点のファイルの内容を取得
+///
+/// # Arguments
+///
+/// * `path` - 設定ルートからの相対パス
+/// * `revision` - リビジョン（コミットIDなど）
+///
+/// # Returns
+///
+/// ファイルの内容とコミットの情報
+///
+/// # Errors
+///
+/// リビジョンが見つからない場合、その時点にファイルが存在しない場合はエラーを返します。
+pub fn read_file_at(path: &str, revision: &str) -> Result<(Vec<u8>, GitCommitInfo), String> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let repo = open_repository(&root)?.ok_or("Git history has not been initialized")?;
+
+    let commit = repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| format!("Revision not found: {revision}: {e}"))?;
+    let id = blob_id_at(&commit, Path::new(path))
+        .ok_or_else(|| format!("{path} does not exist at {revision}"))?;
+    let blob = repo.find_blob(id).map_err(|e| e.to_string())?;
+    Ok((blob.content().to_vec(), commit_info(&commit)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DASHBOARD_CONFIG_FILE;
+    use crate::utils::TestClaudeDir;
+
+    fn enable(root: &Path) {
+        fs::write(
+            root.join(DASHBOARD_CONFIG_FILE),
+            r#"{"excludeGlobs": ["drafts/**"], "gitHistory": {"enabled": true}}"#,
+        )
+        .unwrap();
+        config::invalidate();
+    }
+
+    #[test]
+    fn test_record_change_log_and_diff() {
+        let dir = TestClaudeDir::new();
+        let root = dir.path();
+        dir.write("CLAUDE.md", "v1\n");
+        dir.write("backups/CLAUDE.md_1", "x");
+        dir.write("drafts/a.md", "x");
+        dir.write("notes.txt", "x");
+
+        // 無効な間は何もしない
+        assert_eq!(
+            record_change(&[root.join("CLAUDE.md")], "Update").unwrap(),
+            None
+        );
+        assert!(!root.join(".git").exists());
+
+        enable(root);
+        let first = record_change(&[root.join("CLAUDE.md")], "Update CLAUDE.md")
+            .unwrap()
+            .expect("initial commit");
+        let (content, _) = read_file_at("CLAUDE.md", &first).unwrap();
+        assert_eq!(content, b"v1\n");
+        // 除外ディレクトリ・除外glob・許可されていない拡張子は追跡しない
+        for ignored in ["backups/CLAUDE.md_1", "drafts/a.md", "notes.txt"] {
+            assert!(read_file_at(ignored, &first).is_err(), "{ignored}");
+        }
+
+        dir.write("CLAUDE.md", "v2\n");
+        let second = record_change(&[root.join("CLAUDE.md")], "Update CLAUDE.md")
+            .unwrap()
+            .expect("second commit");
+        // 変更がなければコミットしない
+        assert_eq!(
+            record_change(&[root.join("CLAUDE.md")], "Update").unwrap(),
+            None
+        );
+
+        // 外部の変更はステータスに表示される
+        dir.write("settings.json", "{}");
+        let status = status().unwrap();
+        assert!(status.initialized);
+        assert_eq!(status.head.unwrap().id, second);
+        assert_eq!(
+            status.changes,
+            vec![GitStatusEntry {
+                path: "settings.json".to_string(),
+                kind: GitChangeKind::Added,
+            }]
+        );
+        commit_all("External changes")
+            .unwrap()
+            .expect("third commit");
+
+        assert_eq!(log(None, 10, 0).unwrap().len(), 3);
+        let history = log(Some("CLAUDE.md"), 10, 0).unwrap();
+        assert_eq!(
+            history.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+            vec![second.as_str(), first.as_str()]
+        );
+
+        let files = diff(&first, Some(&second), None).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "CLAUDE.md");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        assert!(files[0].patch.contains("+v2"));
+
+        dir.write(DASHBOARD_CONFIG_FILE, "{}");
+        config::invalidate();
+    }
+
+    #[test]
+    fn test_existing_repository_stages_only_visible_files() {
+        let dir = TestClaudeDir::new();
+        let root = dir.path();
+        dir.write("notes.txt", "v1\n");
+        dir.write("CLAUDE.md", "v1\n");
+
+        // ユーザーが作成したリポジトリで表示対象外のファイルを追跡している
+        let repo = Repository::init(root).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("notes.txt")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let user = Signature::now("user", "user@localhost").unwrap();
+        repo.commit(Some("HEAD"), &user, &user, "Track notes", &tree, &[])
+            .unwrap();
+
+        enable(root);
+        let exclude = repo.path().join("info").join("exclude");
+        // 読み込みのみの操作では無視ルールを書き込まない
+        status().unwrap();
+        log(None, 10, 0).unwrap();
+        assert!(!fs::read_to_string(&exclude)
+            .unwrap_or_default()
+            .contains(EXCLUDE_BEGIN));
+
+        dir.write("notes.txt", "v2\n");
+        let id = commit_all("External changes").unwrap().expect("commit");
+        assert!(fs::read_to_string(&exclude)
+            .unwrap()
+            .contains(EXCLUDE_BEGIN));
+        assert_eq!(read_file_at("CLAUDE.md", &id).unwrap().0, b"v1\n");
+        // 表示対象外のファイルの変更はコミットしない
+        assert_eq!(read_file_at("notes.txt", &id).unwrap().0, b"v1\n");
+
+        dir.write(DASHBOARD_CONFIG_FILE, "{}");
+        config::invalidate();
+    }
+}
@@ -0,0 +1,346 @@
+//! インポート計画
+//!
+//! アーカイブ（ZIP・tar.gz・フォルダー）の各エントリを現在の設定と比較し、
+//! 新規・同一・変更・除外（理由付き）に分類します。
+//! 変更されるテキストファイルには差分を含めます。作成した計画はメモリに保存し、
+//! `import_zip` に計画IDを渡すと、ファイルごとの処理（上書き・スキップ・両方残す・マージ）を
+//! 選択して実行できます。計画の作成後にアーカイブや現在のファイルが変更された場合は検出します。
+
+use crate::archive::{self, ArchiveReader, EntryMeta};
+use crate::archive_safety;
+use crate::commands::export::{
+    is_legacy_manifest, EXPORT_MANIFEST_FILE, LEGACY_EXPORT_MANIFEST_FILE,
+};
+use crate::config;
+use crate::diff_engine;
+use crate::redaction;
+use crate::security::AccessPolicy;
+use crate::types::{
+    DiffOptions, ImportEntryStatus, ImportPlan, ImportPlanEntry, ImportPlanSummary,
+};
+use crate::utils::{content_version_token, is_allowed_extension, is_excluded_zip_entry};
+use chrono::Local;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 保存しておく計画の最大数（超えた場合は古い計画から破棄）
+const MAX_STORED_PLANS: usize = 16;
+
+/// 差分を含めるファイルサイズの上限（バイト）
+const MAX_PLAN_DIFF_BYTES: usize = 1024 * 1024;
+
+/// 以前のバージョンのマニフェストか確認する際に読み込むサイズの上限（バイト）
+const MAX_LEGACY_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+
+/// 作成した計画
+///
+/// キー: 計画ID
+static PLANS: Lazy<Mutex<HashMap<String, StoredPlan>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 計画IDの連番
+static NEXT_PLAN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 計画したエントリ
+#[derive(Debug, Clone)]
+pub struct PlannedEntry {
+    /// 分類
+    pub status: ImportEntryStatus,
+    /// 計画時の現在のファイルのバージョントークン（存在しない場合は`None`）
+    pub local_version: Option<String>,
+}
+
+/// 保存した計画
+#[derive(Debug, Clone)]
+pub struct StoredPlan {
+    /// アーカイブのパス
+    zip_path: String,
+    /// 計画時のアーカイブの変更検出用のトークン
+    fingerprint: String,
+    /// エントリ（キー: アーカイブ内のパス）
+    pub entries: HashMap<String, PlannedEntry>,
+    /// 作成した時刻
+    created: Instant,
+}
+
+/// 現在のファイルのバージョントークン（存在しない場合は`None`）
+pub fn local_version(path: &Path) -> Option<String> {
+    fs::read(path)
+        .ok()
+        .map(|bytes| content_version_token(&bytes))
+}
+
+/// アーカイブのエントリの展開先を検証
+///
+/// # Returns
+///
+/// 展開先のパス。インポートされない場合は理由を返します。
+pub fn resolve_entry(policy: &AccessPolicy, name: &str, is_dir: bool) -> Result<PathBuf, String> {
+    if is_excluded_zip_entry(name) {
+        return Err("excluded by dashboard config".to_string());
+    }
+    // path traversal・シンボリックリンク経由の脱出を防止
+    let dest = policy.resolve_relative(name).map_err(|e| e.to_string())?;
+    if !is_dir && !is_allowed_extension(&dest) {
+        return Err("invalid extension".to_string());
+    }
+    Ok(dest)
+}
+
+/// アーカイブのルートに以前のバージョンのマニフェスト（`manifest.json`）があるか判定
+///
+/// ルートの `manifest.json` は `formatVersion` を含む場合のみマニフェストとして扱い、
+/// それ以外はユーザーのファイルとしてインポートします。
+///
+/// # Arguments
+///
+/// * `archive` - アーカイブ
+/// * `entries` - 検証済みのエントリ一覧（`manifest.json` がない場合は読み込まない）
+///
+/// # Errors
+///
+/// アーカイブを読み込めない場合はエラーを返します。
+pub fn has_legacy_manifest(
+    archive: &mut ArchiveReader,
+    entries: &[EntryMeta],
+) -> Result<bool, String> {
+    let is_legacy_entry = |meta: &EntryMeta| {
+        !meta.is_dir
+            && !meta.is_special
+            && archive_safety::normalize_entry_name(&meta.name)
+                .is_ok_and(|name| name == LEGACY_EXPORT_MANIFEST_FILE)
+    };
+    if !entries.iter().any(is_legacy_entry) {
+        return Ok(false);
+    }
+
+    let mut cursor = archive.entries()?;
+    while let Some((meta, file)) = cursor.next_entry()? {
+        if !is_legacy_entry(&meta) {
+            continue;
+        }
+        let mut contents = Vec::new();
+        file.take(MAX_LEGACY_MANIFEST_BYTES)
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {} from archive: {e}", meta.name))?;
+        return Ok(is_legacy_manifest(&contents));
+    }
+    Ok(false)
+}
+
+/// 展開しないマニフェストのエントリか判定
+///
+/// # Arguments
+///
+/// * `name` - 正規化したエントリ名
+/// * `legacy_manifest` - ルートの `manifest.json` が以前のバージョンのマニフェストか
+pub fn is_manifest_entry(name: &str, legacy_manifest: bool) -> bool {
+    name == EXPORT_MANIFEST_FILE || (legacy_manifest && name == LEGACY_EXPORT_MANIFEST_FILE)
+}
+
+/// 現在のファイルと並べて保存する場合のパス（`<名前>.imported.<拡張子>`）
+pub fn keep_both_path(dest: &Path) -> PathBuf {
+    let stem = dest
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = dest
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = dest.with_file_name(format!("{stem}.imported{extension}"));
+    let mut suffix = 2;
+    while candidate.exists() {
+        candidate = dest.with_file_name(format!("{stem}.imported-{suffix}{extension}"));
+        suffix += 1;
+    }
+    candidate
+}
+
+/// 変更されるファイルの差分（テキストで上限以下の場合のみ）
+fn entry_diff(name: &str, local: &[u8], incoming: &[u8]) -> Option<crate::types::DiffResult> {
+    if local.len() > MAX_PLAN_DIFF_BYTES || incoming.len() > MAX_PLAN_DIFF_BYTES {
+        return None;
+    }
+    let local = std::str::from_utf8(local).ok()?;
+    let incoming = std::str::from_utf8(incoming).ok()?;
+    let options = DiffOptions {
+        semantic: Path::new(name)
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json")),
+        ..DiffOptions::default()
+    };
+    Some(diff_engine::diff_texts(
+        local,
+        incoming,
+        &format!("{name} (current)"),
+        &format!("{name} (archive)"),
+        &options,
+    ))
+}
+
+/// インポート計画を作成して保存
+///
+/// # Arguments
+///
+/// * `zip_path` - アーカイブ（ZIP・tar.gz・フォルダー）のパス
+/// * `secrets` - 秘密情報のプレースホルダーの名前と値（戻した内容で比較）
+///
+/// # Errors
+///
+/// アーカイブを読み込めない場合、上限を超える・安全でないエントリを含む場合、
+/// 現在のファイルを読み込めない場合（存在しない場合を除く）はエラーを返します。
+pub fn create_plan(
+    zip_path: &str,
+    secrets: &HashMap<String, String>,
+) -> Result<ImportPlan, String> {
+    let policy = AccessPolicy::for_claude_dir().map_err(|e| e.to_string())?;
+    let fingerprint = archive::fingerprint(zip_path)?;
+    let mut archive = ArchiveReader::open(zip_path)?;
+    let limits = config::current().config.archive_import_limits.clone();
+    let archive_entries = archive.validate(&limits)?;
+    let legacy_manifest = has_legacy_manifest(&mut archive, &archive_entries)?;
+
+    let mut entries = Vec::new();
+    let mut planned = HashMap::new();
+    let mut summary = ImportPlanSummary::default();
+    let mut cursor = archive.entries()?;
+    while let Some((meta, mut file)) = cursor.next_entry()? {
+        // 検証と同じ正規化（`\` を区切り文字とし、`.` を取り除く）をした名前を使う
+        let name = archive_safety::normalize_entry_name(&meta.name)?;
+        if meta.is_dir || is_manifest_entry(&name, legacy_manifest) {
+            continue;
+        }
+        let size = meta.size;
+
+        let (status, reason, diff, local) = match resolve_entry(&policy, &name, false) {
+            Err(reason) => (ImportEntryStatus::Excluded, Some(reason), None, None),
+            Ok(dest) => {
+                let mut incoming = Vec::new();
+                archive_safety::copy_limited(&mut file, &mut incoming, limits.max_entry_bytes)
+                    .map_err(|e| format!("Failed to read {name} from archive: {e}"))?;
+                if let Ok(text) = std::str::from_utf8(&incoming) {
+                    incoming = redaction::restore_placeholders(&name, text, secrets)
+                        .0
+                        .into_bytes();
+                }
+                match fs::read(&dest) {
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                        (ImportEntryStatus::New, None, None, None)
+                    }
+                    Err(e) => return Err(format!("Failed to read current {name}: {e}")),
+                    Ok(local) if local == incoming => (
+                        ImportEntryStatus::Identical,
+                        None,
+                        None,
+                        Some(content_version_token(&local)),
+                    ),
+                    Ok(local) => (
+                        ImportEntryStatus::Modified,
+                        None,
+                        entry_diff(&name, &local, &incoming),
+                        Some(content_version_token(&local)),
+                    ),
+                }
+            }
+        };
+
+        match status {
+            ImportEntryStatus::New => summary.new_files += 1,
+            ImportEntryStatus::Identical => summary.identical += 1,
+            ImportEntryStatus::Modified => summary.modified += 1,
+            ImportEntryStatus::Excluded => summary.excluded += 1,
+        }
+        planned.insert(
+            name.clone(),
+            PlannedEntry {
+                status,
+                local_version: local,
+            },
+        );
+        entries.push(ImportPlanEntry {
+            path: name,
+            status,
+            size,
+            reason,
+            diff,
+            default_choice: status.default_choice(),
+        });
+    }
+
+    let plan_id = format!(
+        "plan-{}-{}",
+        Local::now().timestamp_millis(),
+        NEXT_PLAN_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut plans = PLANS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    while plans.len() >= MAX_STORED_PLANS {
+        let Some(oldest) = plans
+            .iter()
+            .min_by_key(|(_, plan)| plan.created)
+            .map(|(id, _)| id.clone())
+        else {
+            break;
+        };
+        plans.remove(&oldest);
+    }
+    plans.insert(
+        plan_id.clone(),
+        StoredPlan {
+            zip_path: zip_path.to_string(),
+            fingerprint,
+            entries: planned,
+            created: Instant::now(),
+        },
+    );
+
+    Ok(ImportPlan {
+        plan_id,
+        zip_path: zip_path.to_string(),
+        created_at: Local::now().to_rfc3339(),
+        entries,
+        summary,
+    })
+}
+
+/// 保存した計画を取得
+///
+/// 計画は取り除かないため、インポートが失敗・キャンセルされた場合は同じ計画で再実行できます。
+/// インポートが成功したら [`remove_plan`] で取り除いてください。
+///
+/// # Errors
+///
+/// 計画が見つからない場合、別のアーカイブの計画の場合、
+/// 計画の作成後にアーカイブが変更された場合はエラーを返します。
+pub fn get_plan(plan_id: &str, zip_path: &str) -> Result<StoredPlan, String> {
+    let plan = PLANS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(plan_id)
+        .cloned()
+        .ok_or_else(|| format!("Import plan not found or already used: {plan_id}"))?;
+    if plan.zip_path != zip_path {
+        return Err(format!(
+            "Import plan {plan_id} was created for another archive"
+        ));
+    }
+    if archive::fingerprint(zip_path)? != plan.fingerprint {
+        return Err("The archive has changed since the import plan was created".to_string());
+    }
+    Ok(plan)
+}
+
+/// 実行した計画を取り除く（1つの計画は1回のみ実行できる）
+pub fn remove_plan(plan_id: &str) {
+    PLANS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .remove(plan_id);
+}
@@ -0,0 +1,261 @@
+//! バックグラウンドジョブ
+//!
+//! アーカイブのエクスポート・インポートなどの時間のかかる処理を別スレッドで実行し、ジョブIDで識別します。
+//! 進捗は `job:progress`、完了・キャンセル・失敗は `job:finished` イベントで通知し、
+//! 実行中のジョブには `cancel_job` で中断を要求できます。
+//! 処理側はファイルごとに [`JobContext::check_cancelled`] を呼び出し、キャンセル時は変更を元に戻します。
+
+use crate::types::{ExportProgress, JobFinishedEvent, JobKind, JobStatus};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+/// 進捗を通知するイベント名
+pub const JOB_PROGRESS_EVENT: &str = "job:progress";
+
+/// ジョブの終了を通知するイベント名
+pub const JOB_FINISHED_EVENT: &str = "job:finished";
+
+/// キャンセルされたジョブが返すエラー
+pub const JOB_CANCELLED: &str = "JOB_CANCELLED: The job was cancelled";
+
+/// 実行中のジョブのキャンセルフラグ
+///
+/// キー: ジョブID、値: キャンセル要求フラグ
+static ACTIVE_JOBS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// ジョブIDの連番
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 進捗の通知先
+pub type ProgressSink = Box<dyn Fn(&ExportProgress) + Send>;
+
+/// 実行中のジョブの状態
+///
+/// 作成時にジョブを登録し、破棄時に登録を解除します。
+/// コマンドから同期的に実行する場合も同じ処理を使えるよう、通知先は省略できます。
+pub struct JobContext {
+    /// ジョブID
+    id: String,
+    /// キャンセル要求フラグ
+    cancelled: Arc<AtomicBool>,
+    /// 直近に通知した進捗
+    progress: ExportProgress,
+    /// 進捗の通知先
+    sink: Option<ProgressSink>,
+}
+
+impl JobContext {
+    /// ジョブを登録
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - ジョブの種類
+    /// * `sink` - 進捗の通知先（省略時は通知しない）
+    pub fn new(kind: JobKind, sink: Option<ProgressSink>) -> Self {
+        let id = format!(
+            "{}-{}-{}",
+            kind.as_str(),
+            chrono::Local::now().timestamp_millis(),
+            NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let cancelled = Arc::new(AtomicBool::new(false));
+        ACTIVE_JOBS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(id.clone(), Arc::clone(&cancelled));
+
+        Self {
+            progress: ExportProgress {
+                job_id: id.clone(),
+                kind,
+                total_files: 0,
+                processed_files: 0,
+                current_file: String::new(),
+                total_bytes: 0,
+                processed_bytes: 0,
+            },
+            id,
+            cancelled,
+            sink,
+        }
+    }
+
+    /// ジョブID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// キャンセルが要求されたか
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// キャンセルが要求されていればエラーを返す
+    ///
+    /// # Errors
+    ///
+    /// キャンセルが要求された場合は [`JOB_CANCELLED`] を返します。
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(JOB_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 処理するファイル数とバイト数を設定して通知
+    pub fn set_totals(&mut self, total_files: u32, total_bytes: u64) {
+        self.progress.total_files = total_files;
+        self.progress.total_bytes = total_bytes;
+        self.notify();
+    }
+
+    /// ファイルを1つ処理したことを通知
+    pub fn advance(&mut self, current_file: &str, bytes: u64) {
+        self.progress.processed_files += 1;
+        self.progress.processed_bytes += bytes;
+        current_file.clone_into(&mut self.progress.current_file);
+        self.notify();
+    }
+
+    /// 進捗を通知
+    fn notify(&self) {
+        if let Some(sink) = &self.sink {
+            sink(&self.progress);
+        }
+    }
+}
+
+impl Drop for JobContext {
+    fn drop(&mut self) {
+        ACTIVE_JOBS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&self.id);
+    }
+}
+
+/// ジョブのキャンセルを要求
+///
+/// # Returns
+///
+/// 実行中のジョブが見つかった場合は`true`
+pub fn cancel(job_id: &str) -> bool {
+    let jobs = ACTIVE_JOBS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    match jobs.get(job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// ジョブを別スレッドで開始
+///
+/// 進捗を `job:progress`、終了を `job:finished` イベントで通知します。
+///
+/// # Arguments
+///
+/// * `app` - イベントの送信に使用するアプリケーションハンドル
+/// * `kind` - ジョブの種類
+/// * `run` - ジョブの処理（キャンセル時は [`JOB_CANCELLED`] を返す）
+///
+/// # Returns
+///
+/// ジョブID
+pub fn spawn<T, F>(app: AppHandle, kind: JobKind, run: F) -> String
+where
+    T: Serialize,
+    F: FnOnce(&mut JobContext) -> Result<T, String> + Send + 'static,
+{
+    let progress_app = app.clone();
+    let sink: ProgressSink = Box::new(move |progress| {
+        if let Err(e) = progress_app.emit(JOB_PROGRESS_EVENT, progress.clone()) {
+            warn!("Failed to emit {JOB_PROGRESS_EVENT}: {e}");
+        }
+    });
+    let mut context = JobContext::new(kind, Some(sink));
+    let job_id = context.id().to_string();
+
+    let event_job_id = job_id.clone();
+    std::thread::spawn(move || {
+        let outcome = run(&mut context);
+        let cancelled = context.is_cancelled();
+        // 終了を通知する前に登録を解除する
+        drop(context);
+
+        let event = match outcome {
+            Ok(result) => JobFinishedEvent {
+                job_id: event_job_id,
+                kind,
+                status: JobStatus::Completed,
+                result: serde_json::to_value(result).ok(),
+                error: None,
+            },
+            Err(error) => JobFinishedEvent {
+                job_id: event_job_id,
+                kind,
+                status: if cancelled {
+                    JobStatus::Cancelled
+                } else {
+                    JobStatus::Failed
+                },
+                result: None,
+                error: Some(error),
+            },
+        };
+        info!("Job {} finished: {:?}", event.job_id, event.status);
+        if let Err(e) = app.emit(JOB_FINISHED_EVENT, event) {
+            warn!("Failed to emit {JOB_FINISHED_EVENT}: {e}");
+        }
+    });
+
+    job_id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_progress_and_cancel() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let sink_reported = Arc::clone(&reported);
+        let sink: ProgressSink = Box::new(move |progress| {
+            sink_reported.lock().unwrap().push(progress.clone());
+        });
+
+        let mut context = JobContext::new(JobKind::Export, Some(sink));
+        let id = context.id().to_string();
+        assert!(id.starts_with("export-"));
+
+        context.set_totals(2, 30);
+        context.advance("a.md", 10);
+        assert!(context.check_cancelled().is_ok());
+
+        assert!(cancel(&id));
+        assert!(context
+            .check_cancelled()
+            .unwrap_err()
+            .starts_with("JOB_CANCELLED"));
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[1].processed_files, 1);
+        assert_eq!(reported[1].processed_bytes, 10);
+        assert_eq!(reported[1].current_file, "a.md");
+
+        // 終了したジョブはキャンセルできない
+        drop(context);
+        assert!(!cancel(&id));
+    }
+}
@@ -0,0 +1,263 @@
+//! JSONの構造的なマージ
+//!
+//! インポートする設定ファイルを現在の内容とマージします。
+//! オブジェクトはキーごとに再帰的にマージし、権限ルールなどの配列は重複を除いて結合します。
+//! それ以外の値が異なる場合は衝突として記録し、指定された戦略（インポートする側・現在の値・個別に指定）で解決します。
+
+use crate::diff_engine::escape_pointer;
+use crate::types::{
+    ConflictSide, JsonMergeOptions, JsonMergePreview, MergeConflict, MergeStrategy,
+};
+use serde_json::Value;
+use std::path::Path;
+
+/// 重複を除いて結合する配列の位置（JSON Pointer）
+///
+/// 権限ルールとプロジェクトのMCPサーバーの有効・無効の一覧
+pub const UNION_ARRAY_POINTERS: &[&str] = &[
+    "/permissions/allow",
+    "/permissions/ask",
+    "/permissions/deny",
+    "/permissions/additionalDirectories",
+    "/enabledMcpjsonServers",
+    "/disabledMcpjsonServers",
+];
+
+/// ZIPのインポートで既定でマージする設定ファイルの名前
+const CONFIG_FILE_NAMES: &[&str] = &["settings.json", "settings.local.json", ".mcp.json"];
+
+/// 既定でマージする設定ファイルか
+pub fn is_config_json(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| CONFIG_FILE_NAMES.contains(&n))
+}
+
+/// 2つのJSONの値をマージ
+///
+/// # Arguments
+///
+/// * `ours` - 現在の値（マージ結果で置き換える）
+/// * `theirs` - インポートする値
+/// * `options` - 衝突の解決方法
+///
+/// # Returns
+///
+/// 衝突したキー（未解決の衝突は現在の値を残す）
+pub fn merge_values(
+    ours: &mut Value,
+    theirs: Value,
+    options: &JsonMergeOptions,
+) -> Vec<MergeConflict> {
+    let mut conflicts = Vec::new();
+    merge_at(ours, theirs, "", options, &mut conflicts);
+    conflicts
+}
+
+/// 指定した位置の値をマージ
+fn merge_at(
+    ours: &mut Value,
+    theirs: Value,
+    pointer: &str,
+    options: &JsonMergeOptions,
+    conflicts: &mut Vec<MergeConflict>,
+) {
+    if *ours == theirs {
+        return;
+    }
+    match (ours, theirs) {
+        (Value::Object(ours), Value::Object(theirs)) => {
+            for (key, value) in theirs {
+                let child = format!("{pointer}/{}", escape_pointer(&key));
+                match ours.get_mut(&key) {
+                    Some(existing) => merge_at(existing, value, &child, options, conflicts),
+                    None => {
+                        ours.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(ours), Value::Array(theirs)) if UNION_ARRAY_POINTERS.contains(&pointer) => {
+            for item in theirs {
+                if !ours.contains(&item) {
+                    ours.push(item);
+                }
+            }
+        }
+        (ours, theirs) => {
+            let resolution = options
+                .resolutions
+                .get(pointer)
+                .copied()
+                .or(match options.strategy {
+                    MergeStrategy::Theirs => Some(ConflictSide::Theirs),
+                    MergeStrategy::Ours => Some(ConflictSide::Ours),
+                    MergeStrategy::Ask => None,
+                });
+            conflicts.push(MergeConflict {
+                pointer: pointer.to_string(),
+                ours: ours.clone(),
+                theirs: theirs.clone(),
+                resolution,
+            });
+            if resolution == Some(ConflictSide::Theirs) {
+                *ours = theirs;
+            }
+        }
+    }
+}
+
+/// JSONのテキストをマージし、結果をプレビューとして返す
+///
+/// # Arguments
+///
+/// * `path` - 設定ルートからの相対パス
+/// * `ours` - 現在の内容（ファイルが存在しない場合は`None`）
+/// * `theirs` - インポートする内容
+/// * `options` - 衝突の解決方法
+///
+/// # Errors
+///
+/// どちらかがJSONとして解析できない場合はエラーを返します。
+pub fn merge_json_text(
+    path: &str,
+    ours: Option<&str>,
+    theirs: &str,
+    options: &JsonMergeOptions,
+) -> Result<JsonMergePreview, String> {
+    let theirs_value: Value = serde_json::from_str(theirs)
+        .map_err(|e| format!("Imported file is not valid JSON: {e}"))?;
+    let Some(ours) = ours else {
+        return Ok(JsonMergePreview {
+            path: path.to_string(),
+            exists: false,
+            merged: theirs.to_string(),
+            conflicts: Vec::new(),
+            unresolved: 0,
+            changed: true,
+        });
+    };
+
+    let original: Value =
+        serde_json::from_str(ours).map_err(|e| format!("Current file is not valid JSON: {e}"))?;
+    let mut merged = original.clone();
+    let conflicts = merge_values(&mut merged, theirs_value, options);
+
+    let mut text = serde_json::to_string_pretty(&merged)
+        .map_err(|e| format!("Failed to serialize merged JSON: {e}"))?;
+    text.push('\n');
+    Ok(JsonMergePreview {
+        path: path.to_string(),
+        exists: true,
+        merged: text,
+        unresolved: conflicts.iter().filter(|c| c.resolution.is_none()).count(),
+        conflicts,
+        changed: merged != original,
+    })
+}
+
+/// 書き込むマージ結果を取り出す
+///
+/// # Errors
+///
+/// 解決方法が指定されていない衝突がある場合はエラーを返します。
+pub fn resolved_text(preview: JsonMergePreview) -> Result<String, String> {
+    if preview.unresolved > 0 {
+        let pointers: Vec<&str> = preview
+            .conflicts
+            .iter()
+            .filter(|c| c.resolution.is_none())
+            .map(|c| c.pointer.as_str())
+            .collect();
+        return Err(format!(
+            "{} merge conflict(s) need to be resolved: {}",
+            preview.unresolved,
+            pointers.join(", ")
+        ));
+    }
+    Ok(preview.merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_merge_settings_with_strategies() {
+        let ours = r#"{
+            "model": "opus",
+            "env": {"LOCAL": "1", "SHARED": "local"},
+            "permissions": {"allow": ["Bash(ls)", "Read"], "deny": []}
+        }"#;
+        let theirs = r#"{
+            "model": "sonnet",
+            "env": {"SHARED": "local", "REMOTE": "2"},
+            "permissions": {"allow": ["Read", "Edit"], "deny": ["Bash(rm:*)"]},
+            "hooks": {"Stop": []}
+        }"#;
+
+        let preview = merge_json_text(
+            "settings.json",
+            Some(ours),
+            theirs,
+            &JsonMergeOptions::default(),
+        )
+        .unwrap();
+        let merged: Value = serde_json::from_str(&preview.merged).unwrap();
+        assert_eq!(
+            merged,
+            serde_json::json!({
+                "model": "sonnet",
+                "env": {"LOCAL": "1", "SHARED": "local", "REMOTE": "2"},
+                "permissions": {"allow": ["Bash(ls)", "Read", "Edit"], "deny": ["Bash(rm:*)"]},
+                "hooks": {"Stop": []}
+            })
+        );
+        assert_eq!(preview.conflicts.len(), 1);
+        assert_eq!(preview.conflicts[0].pointer, "/model");
+        assert!(preview.changed);
+
+        let ours_options = JsonMergeOptions {
+            strategy: MergeStrategy::Ours,
+            ..JsonMergeOptions::default()
+        };
+        let preview = merge_json_text("settings.json", Some(ours), theirs, &ours_options).unwrap();
+        assert!(preview.merged.contains("\"opus\""));
+
+        // askでは未指定の衝突があると書き込まない
+        let mut ask = JsonMergeOptions {
+            strategy: MergeStrategy::Ask,
+            ..JsonMergeOptions::default()
+        };
+        let preview = merge_json_text("settings.json", Some(ours), theirs, &ask).unwrap();
+        assert_eq!(preview.unresolved, 1);
+        assert!(resolved_text(preview).unwrap_err().contains("/model"));
+
+        ask.resolutions = HashMap::from([("/model".to_string(), ConflictSide::Theirs)]);
+        let preview = merge_json_text("settings.json", Some(ours), theirs, &ask).unwrap();
+        assert!(resolved_text(preview).unwrap().contains("\"sonnet\""));
+
+        assert!(is_config_json(Path::new("/home/u/.claude/.mcp.json")));
+        assert!(!is_config_json(Path::new("agents/config.json")));
+    }
+
+    #[test]
+    fn test_merge_keeps_key_order() {
+        let ours = "{\n  \"model\": \"opus\",\n  \"env\": {\n    \"Z\": \"1\",\n    \"A\": \"2\"\n  },\n  \"alwaysThinkingEnabled\": true\n}\n";
+        let theirs = r#"{"env": {"M": "3"}, "hooks": {}}"#;
+
+        let preview = merge_json_text(
+            "settings.json",
+            Some(ours),
+            theirs,
+            &JsonMergeOptions::default(),
+        )
+        .unwrap();
+        // 既存のキーの順序は変えず、新しいキーは末尾に追加
+        assert_eq!(
+            preview.merged,
+            "{\n  \"model\": \"opus\",\n  \"env\": {\n    \"Z\": \"1\",\n    \"A\": \"2\",\n    \"M\": \"3\"\n  },\n  \"alwaysThinkingEnabled\": true,\n  \"hooks\": {}\n}\n"
+        );
+    }
+}
@@ -6,45 +6,148 @@
 //! # モジュール構成
 //!
 //! - `commands` - Tauriコマンド（フロントエンドから呼び出される関数）
-//!   - `files` - ファイル操作（読み書き、検索、ツリー取得）
-//!   - `backup` - バックアップ操作（作成、復元、クリーンアップ）
-//!   - `export` - エクスポート操作（単体、ZIP一括）
-//!   - `import` - インポート操作（単体、ZIP復元）
+//!   - `files` - ファイル操作（読み書き、検索、ツリー取得、遅延読み込み）
+//!   - `jsonl` - JSONLリーダー（行インデックス、ページング、行ジャンプ、末尾表示）
+//!   - `fileops` - ファイル管理（削除、名前変更、移動、複製、ゴミ箱）
+//!   - `replace` - ワークスペース一括置換（プレビュー、適用、元に戻す）
+//!   - `search` - 構造化検索（行番号・文脈付き、関連度順、キャンセル）
+//!   - `diff` - 差分操作（任意の2つの内容の比較、JSONの意味的な比較、選択したハンクの復元）
+//!   - `snapshot` - スナップショット操作（作成、一覧、差分、全体・選択したパスの復元）
+//!   - `history` - 変更履歴操作（状態、コミット履歴、差分、リビジョンからの復元）
+//!   - `backup` - バックアップ操作（作成、ファイル別一覧、ID指定の復元、ピン留め、保持ポリシー）
+//!   - `export` - エクスポート操作（単体、ZIP・tar.gz・フォルダーへの一括、glob・種別による選択、マニフェスト、秘密情報の置き換え、ジョブ）
+//!   - `plugin` - プラグインのエクスポート（`plugin.json` のメタデータ、検証、ローカルのマーケットプレイスへの追加）
+//!   - `import` - インポート操作（単体、ZIP・tar.gz・フォルダーからの復元、秘密情報の入力、インポート計画、JSONのマージ、ジョブ）
+//!   - `jobs` - バックグラウンドジョブ操作（キャンセル）
 //!   - `template` - カスタムテンプレート操作（CRUD）
 //!   - `favorites` - お気に入り操作（追加、削除、並べ替え）
+//!   - `dashboard_config` - ダッシュボード設定（除外glob、拡張子、エディタモード）
+//!   - `profiles` - 設定ルート操作（プロファイル管理、切り替え）
+//!   - `watcher` - ファイル監視（外部変更の通知）
+//! - `archive` - アーカイブ形式の抽象化（ZIP・tar.gz・ローカルフォルダーの読み書き）
+//! - `archive_safety` - アーカイブの安全な展開（上限、エントリ名・シンボリックリンク・重複の検証）
+//! - `backup_retention` - バックアップの保持ポリシー（世代数、日次・週次・月次、サイズ上限）
+//! - `backup_store` - バックアップストア（重複排除・圧縮したオブジェクト、メタデータの記録）
+//! - `config` - ダッシュボード設定の読み込み（ホットリロード）
+//! - `diff_engine` - 差分エンジン（行単位のハンク、統計、JSONの意味的な比較）
 //! - `error` - カスタムエラー型
+//! - `git_history` - Gitによる変更履歴（保存ごとのコミット、履歴、差分）
+//! - `import_plan` - インポート計画（エントリの分類、差分、ファイルごとの処理の選択）
+//! - `jobs` - バックグラウンドジョブ（エクスポート・インポートの進捗通知、キャンセル）
+//! - `json_merge` - JSONの構造的なマージ（権限ルールの結合、衝突の解決）
+//! - `redaction` - 秘密情報のマスキング（エクスポート時の置き換え、インポート時の復元）
+//! - `search_index` - 全文検索インデックス（永続化、差分更新、クエリ構文）
+//! - `security` - パスアクセスポリシー（正規化、シンボリックリンク検証）
+//! - `snapshot_store` - スナップショットストア（設定ルート全体のある時点の状態）
 //! - `types` - 共通データ型
 //! - `utils` - ユーティリティ関数
 
+pub mod archive;
+pub mod archive_safety;
+pub mod backup_retention;
+pub mod backup_store;
 pub mod commands;
+pub mod config;
+pub mod diff_engine;
 pub mod error;
+pub mod git_history;
+pub mod import_plan;
+pub mod jobs;
+pub mod json_merge;
+pub mod redaction;
+pub mod search_index;
+pub mod security;
+pub mod snapshot_store;
 pub mod types;
 pub mod utils;
 
-use commands::backup::perform_startup_cleanup;
+use commands::backup::{perform_startup_cleanup, start_retention_scheduler};
+use commands::profiles::restore_active_profile;
+use commands::watcher::start_watcher;
 use commands::{
     // files
     create_file,
     get_file_tree,
+    list_directory,
     read_file,
     search_and_replace_in_file,
     search_files,
     write_file,
+    // jsonl
+    get_jsonl_info,
+    get_jsonl_record,
+    jump_to_jsonl_line,
+    read_jsonl_lines,
+    read_jsonl_tail,
+    // fileops
+    delete_path,
+    duplicate_path,
+    empty_trash,
+    get_trash_entries,
+    move_path,
+    rename_path,
+    restore_from_trash,
+    // replace
+    apply_workspace_replace,
+    preview_workspace_replace,
+    undo_workspace_replace,
+    // search
+    cancel_search,
+    get_search_index_status,
+    query_search_index,
+    rebuild_search_index,
+    search_workspace,
     // backup
+    apply_backup_retention,
     cleanup_old_backups,
     create_backup,
+    get_backup_content,
     get_backups,
-    restore_backup,
+    get_backups_for,
+    preview_backup_retention,
+    restore_backup_by_id,
+    set_backup_pinned,
+    // diff
+    compute_diff,
+    restore_diff_hunks,
+    // snapshot
+    create_snapshot,
+    delete_snapshot,
+    diff_snapshot,
+    list_snapshots,
+    restore_snapshot,
+    // history
+    checkout_git_file,
+    commit_git_changes,
+    get_git_diff,
+    get_git_file_at,
+    get_git_file_history,
+    get_git_history_status,
+    get_git_log,
     // export
     export_all_zip,
     export_file,
+    export_selected_zip,
     get_export_file_count,
+    preview_export_selection,
+    start_export_job,
+    // plugin
+    export_plugin,
+    list_plugin_components,
+    validate_plugin,
     // import
     check_file_exists,
     check_files_exist,
+    get_zip_secret_placeholders,
     import_file,
     import_zip,
+    plan_zip_import,
+    preview_file_merge,
     preview_zip,
+    preview_zip_merge,
+    start_import_job,
+    // jobs
+    cancel_job,
     // template
     delete_custom_template,
     get_custom_template,
@@ -55,6 +158,16 @@ use commands::{
     get_favorites,
     remove_favorite,
     reorder_favorites,
+    // dashboard_config
+    get_dashboard_config,
+    get_editor_mode,
+    save_dashboard_config,
+    // profiles
+    add_config_profile,
+    get_active_config_root,
+    get_config_profiles,
+    remove_config_profile,
+    switch_config_root,
     // window
     close_preview_window,
     is_preview_window_open,
@@ -70,12 +183,15 @@ use commands::{
     resize_terminal,
     spawn_terminal,
     write_terminal,
+    // watcher
+    get_watched_root,
+    restart_file_watcher,
 };
 
 /// アプリケーションのエントリーポイント
 ///
 /// Tauriアプリケーションを初期化し、コマンドハンドラーを登録します。
-/// 起動時に古いバックアップの自動クリーンアップも実行されます。
+/// 起動時に設定ルートのプロファイル復元と古いバックアップの自動クリーンアップも実行されます。
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -85,26 +201,87 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // ファイル操作
             get_file_tree,
+            list_directory,
             read_file,
             write_file,
             create_file,
             search_files,
             search_and_replace_in_file,
+            // JSONLリーダー
+            get_jsonl_info,
+            read_jsonl_lines,
+            jump_to_jsonl_line,
+            read_jsonl_tail,
+            get_jsonl_record,
+            // ファイル管理
+            delete_path,
+            rename_path,
+            move_path,
+            duplicate_path,
+            get_trash_entries,
+            restore_from_trash,
+            empty_trash,
+            // 一括置換
+            preview_workspace_replace,
+            apply_workspace_replace,
+            undo_workspace_replace,
+            // 構造化検索
+            search_workspace,
+            cancel_search,
+            query_search_index,
+            rebuild_search_index,
+            get_search_index_status,
             // バックアップ操作
             create_backup,
             get_backups,
-            restore_backup,
+            get_backups_for,
+            get_backup_content,
+            restore_backup_by_id,
+            set_backup_pinned,
             cleanup_old_backups,
+            preview_backup_retention,
+            apply_backup_retention,
+            // 差分操作
+            compute_diff,
+            restore_diff_hunks,
+            // スナップショット操作
+            create_snapshot,
+            list_snapshots,
+            diff_snapshot,
+            restore_snapshot,
+            delete_snapshot,
+            // 変更履歴操作
+            get_git_history_status,
+            get_git_log,
+            get_git_file_history,
+            get_git_diff,
+            get_git_file_at,
+            checkout_git_file,
+            commit_git_changes,
             // エクスポート操作
             export_file,
             export_all_zip,
+            export_selected_zip,
+            preview_export_selection,
             get_export_file_count,
+            start_export_job,
+            // プラグインのエクスポート
+            list_plugin_components,
+            validate_plugin,
+            export_plugin,
             // インポート操作
             check_file_exists,
             check_files_exist,
             import_file,
+            preview_file_merge,
             preview_zip,
+            preview_zip_merge,
+            get_zip_secret_placeholders,
+            plan_zip_import,
             import_zip,
+            start_import_job,
+            // バックグラウンドジョブ
+            cancel_job,
             // テンプレート操作
             save_custom_template,
             get_custom_templates,
@@ -115,6 +292,16 @@ pub fn run() {
             add_favorite,
             remove_favorite,
             reorder_favorites,
+            // ダッシュボード設定
+            get_dashboard_config,
+            save_dashboard_config,
+            get_editor_mode,
+            // 設定ルート操作
+            get_active_config_root,
+            get_config_profiles,
+            add_config_profile,
+            remove_config_profile,
+            switch_config_root,
             // ウィンドウ操作
             open_preview_window,
             close_preview_window,
@@ -130,6 +317,9 @@ pub fn run() {
             resize_terminal,
             close_terminal,
             get_terminal_session_count,
+            // ファイル監視
+            restart_file_watcher,
+            get_watched_root,
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -140,8 +330,24 @@ pub fn run() {
                 )?;
             }
 
-            // アプリ起動時に古いバックアップを自動クリーンアップ
+            // 前回選択していた設定ルートのプロファイルを復元
+            restore_active_profile();
+
+            // 設定ルートの外部変更を監視
+            if let Err(e) = start_watcher(app.handle()) {
+                log::warn!("Failed to start file watcher: {e}");
+            }
+
+            // 全文検索インデックスをバックグラウンドで読み込み・同期
+            std::thread::spawn(|| {
+                if let Err(e) = search_index::refresh(false) {
+                    log::warn!("Failed to prepare search index: {e}");
+                }
+            });
+
+            // アプリ起動時に保持ポリシーを適用し、以降は設定された間隔で適用
             perform_startup_cleanup();
+            start_retention_scheduler();
 
             Ok(())
         })
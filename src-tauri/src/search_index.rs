@@ -0,0 +1,1040 @@
+//! 全文検索インデックス
+//!
+//! 設定ルート配下のファイルの転置インデックスを作成し、キャッシュディレクトリ
+//! （例: `~/.cache/claude-setting-dashboard/search-index/`）に永続化します。
+//! 起動後の初回検索時にmtime/サイズを比較して差分のみ再インデックスし、
+//! 以降はファイル監視のイベントで変更されたファイルだけを更新します。
+//! 変更後の保存は別スレッドでまとめて行うため、検索はインデックスの書き込みを待ちません。
+//!
+//! # クエリ構文
+//!
+//! - `deploy review` - すべての語を含む（AND）
+//! - `"code review"` - フレーズ検索
+//! - `dep*` - 前方一致
+//! - `name:foo` / `description:"..."` / `body:bar` - フィールド指定
+//! - `kind:skill` - ファイル種別で絞り込み（skill, agent, command, settings, memory, transcript, other）
+
+use crate::commands::replace::collect_target_files;
+use crate::commands::watcher::get_watched_root;
+use crate::error::AppResult;
+use crate::types::FileKind;
+use crate::utils::{
+    classify_file_kind, extract_frontmatter_field, get_claude_dir, is_allowed_extension,
+    is_excluded_relative_path,
+};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// インデックス形式のバージョン（形式を変更した場合は上げる）
+const INDEX_VERSION: u32 = 1;
+
+/// インデックス対象とするファイルサイズの上限
+const MAX_INDEX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// 前方一致で展開する語の上限
+const MAX_PREFIX_EXPANSIONS: usize = 256;
+
+/// 変更後、インデックスを保存するまでの待ち時間（この間の変更はまとめて保存）
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// 読み込み済みのインデックス（保存中のスレッドと共有し、更新時に必要な場合のみ複製）
+static SEARCH_INDEX: Lazy<Mutex<Option<Arc<SearchIndex>>>> = Lazy::new(|| Mutex::new(None));
+
+/// 保存が予約済みか
+static SAVE_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+/// インデックスファイルへの書き込みを直列化するロック
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+/// ファイル監視で検知した未反映の変更パス
+static PENDING_PATHS: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 次回のアクセス時にmtime比較による全体同期を行うか（ダッシュボード設定の変更時など）
+static FULL_SYNC_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// インデックスのフィールド
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexField {
+    /// ファイル名とfrontmatterの `name`
+    Name,
+    /// frontmatterの `description`
+    Description,
+    /// ファイル内容全体
+    Body,
+}
+
+impl IndexField {
+    const ALL: [Self; 3] = [Self::Name, Self::Description, Self::Body];
+
+    /// 関連度計算時の重み
+    fn weight(self) -> f64 {
+        match self {
+            Self::Name => 10.0,
+            Self::Description => 5.0,
+            Self::Body => 1.0,
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "name" => Some(Self::Name),
+            "description" | "desc" => Some(Self::Description),
+            "body" | "content" => Some(Self::Body),
+            _ => None,
+        }
+    }
+}
+
+/// インデックス済みのドキュメント
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IndexedDocument {
+    relative_path: String,
+    kind: FileKind,
+    name: String,
+    description: Option<String>,
+    /// 更新日時（UNIXエポックからのミリ秒）
+    modified: u64,
+    size: u64,
+}
+
+/// 語の出現位置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct Posting {
+    doc: u32,
+    field: IndexField,
+    positions: Vec<u32>,
+}
+
+/// 差分同期の結果
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SyncStats {
+    indexed: usize,
+    removed: usize,
+}
+
+/// 転置インデックス
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SearchIndex {
+    version: u32,
+    root: PathBuf,
+    next_id: u32,
+    documents: HashMap<u32, IndexedDocument>,
+    terms: BTreeMap<String, Vec<Posting>>,
+    /// 相対パスからドキュメントIDへの対応（読み込み時に再構築）
+    #[serde(skip)]
+    by_path: HashMap<String, u32>,
+    /// ドキュメントIDから含まれる語への対応（削除時に該当する語のみ更新する。読み込み時に再構築）
+    #[serde(skip)]
+    doc_terms: HashMap<u32, Vec<String>>,
+}
+
+/// インデックス検索のヒット
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSearchHit {
+    /// 絶対パス
+    pub path: String,
+    /// 設定ルートからの相対パス
+    pub relative_path: String,
+    /// 表示名（frontmatterの `name` またはファイル名）
+    pub name: String,
+    /// ファイル種別
+    pub kind: FileKind,
+    /// frontmatterの `description`
+    pub description: Option<String>,
+    /// 関連度（大きいほど上位）
+    pub score: f64,
+}
+
+/// インデックス検索の結果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSearchResponse {
+    /// 関連度順のヒット（上限まで）
+    pub hits: Vec<IndexSearchHit>,
+    /// ヒット総数
+    pub total_hits: usize,
+    /// 検索に要した時間（ミリ秒、インデックス更新を含む）
+    pub elapsed_ms: u64,
+}
+
+/// インデックスの状態
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndexStatus {
+    /// インデックス対象の設定ルート
+    pub root: String,
+    /// インデックス済みファイル数
+    pub document_count: usize,
+    /// 語の数
+    pub term_count: usize,
+    /// インデックスファイルのパス
+    pub index_path: String,
+}
+
+/// CJK文字（ひらがな、カタカナ、漢字）かどうか
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0xFF66..=0xFF9F
+    )
+}
+
+/// テキストを語に分割
+///
+/// 英数字の連続は小文字化した1語、CJK文字の連続は2文字ずつのbigramとして扱います。
+fn tokenize(text: &str) -> Vec<String> {
+    fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+        if !word.is_empty() {
+            tokens.push(std::mem::take(word));
+        }
+    }
+
+    fn flush_cjk(run: &mut Vec<char>, tokens: &mut Vec<String>) {
+        match run.len() {
+            0 => {}
+            1 => tokens.push(run[0].to_string()),
+            _ => tokens.extend(run.windows(2).map(|w| w.iter().collect::<String>())),
+        }
+        run.clear();
+    }
+
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run = Vec::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+
+    tokens
+}
+
+/// クエリの条件
+#[derive(Debug, PartialEq)]
+enum QueryClause {
+    /// 語・フレーズ（`prefix` の場合は最後の語を前方一致）
+    Text {
+        field: Option<IndexField>,
+        tokens: Vec<String>,
+        prefix: bool,
+    },
+    /// ファイル種別での絞り込み
+    Kind(FileKind),
+}
+
+/// クエリ文字列を条件に分解
+fn parse_query(query: &str) -> Result<Vec<QueryClause>, String> {
+    let mut clauses = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        // `field:` または `"phrase"` または単語を読み取る
+        let mut raw = String::new();
+        let mut field_name = None;
+        while let Some(&c) = chars.peek() {
+            if c == '"' {
+                chars.next();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    raw.push(c);
+                }
+                // 閉じクォート直後の `*` は前方一致
+                if chars.next_if_eq(&'*').is_some() {
+                    raw.push('*');
+                }
+                break;
+            }
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c == ':' && field_name.is_none() && !raw.is_empty() {
+                field_name = Some(std::mem::take(&mut raw));
+                continue;
+            }
+            raw.push(c);
+        }
+
+        let mut field = None;
+        if let Some(name) = field_name {
+            if name.eq_ignore_ascii_case("kind") {
+                let kind = FileKind::parse(raw.trim())
+                    .ok_or_else(|| format!("Unknown kind: {}", raw.trim()))?;
+                clauses.push(QueryClause::Kind(kind));
+                continue;
+            }
+            match IndexField::parse(&name) {
+                Some(f) => field = Some(f),
+                // 未知のフィールド名は検索語の一部として扱う
+                None => raw = format!("{name}:{raw}"),
+            }
+        }
+
+        let prefix = raw.ends_with('*');
+        let tokens = tokenize(raw.trim_end_matches('*'));
+        if tokens.is_empty() {
+            continue;
+        }
+        clauses.push(QueryClause::Text {
+            field,
+            tokens,
+            prefix,
+        });
+    }
+
+    Ok(clauses)
+}
+
+/// ファイルの更新日時（ミリ秒）とサイズを取得
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_millis();
+    Some((u64::try_from(modified).unwrap_or(u64::MAX), metadata.len()))
+}
+
+impl SearchIndex {
+    fn new(root: PathBuf) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            root,
+            ..Self::default()
+        }
+    }
+
+    /// 保存済みのインデックスを読み込み（形式やルートが異なる場合は`None`）
+    fn load(index_path: &Path, root: &Path) -> Option<Self> {
+        let content = fs::read_to_string(index_path).ok()?;
+        let mut index: Self = serde_json::from_str(&content).ok()?;
+        if index.version != INDEX_VERSION || index.root != root {
+            return None;
+        }
+        index.by_path = index
+            .documents
+            .iter()
+            .map(|(id, doc)| (doc.relative_path.clone(), *id))
+            .collect();
+        let mut doc_terms: HashMap<u32, Vec<String>> = HashMap::new();
+        for (term, postings) in &index.terms {
+            for posting in postings {
+                let terms = doc_terms.entry(posting.doc).or_default();
+                // 同じ語のフィールド違いのPostingは連続する
+                if terms.last() != Some(term) {
+                    terms.push(term.clone());
+                }
+            }
+        }
+        index.doc_terms = doc_terms;
+        Some(index)
+    }
+
+    /// インデックスを保存
+    fn save(&self, index_path: &Path) -> Result<(), String> {
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create index directory: {e}"))?;
+        }
+        let json =
+            serde_json::to_string(self).map_err(|e| format!("Failed to serialize index: {e}"))?;
+
+        // 書き込み途中で終了しても壊れたインデックスが残らないよう一時ファイル経由で保存
+        let tmp_path = index_path.with_extension("json.tmp");
+        fs::write(&tmp_path, json).map_err(|e| format!("Failed to write index: {e}"))?;
+        fs::rename(&tmp_path, index_path).map_err(|e| format!("Failed to save index: {e}"))
+    }
+
+    /// ドキュメントを追加（既存の場合は置き換え）
+    fn index_document(&mut self, relative_path: &str, content: &str, modified: u64, size: u64) {
+        if let Some(id) = self.by_path.get(relative_path).copied() {
+            self.remove_documents(&HashSet::from([id]));
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let relative = Path::new(relative_path);
+        let file_name = relative
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(relative_path);
+        let front_name = extract_frontmatter_field(content, "name");
+        let description = extract_frontmatter_field(content, "description");
+
+        // ファイル名に加え、SKILL.mdのように名前が固定のファイルは親ディレクトリ名も名前として扱う
+        let mut name_text = file_name.to_string();
+        if let Some(parent) = relative.parent().and_then(|p| p.file_name()) {
+            if file_name == "SKILL.md" {
+                name_text = format!("{} {name_text}", parent.to_string_lossy());
+            }
+        }
+        if let Some(front_name) = &front_name {
+            name_text = format!("{front_name} {name_text}");
+        }
+
+        let fields = [
+            (IndexField::Name, name_text.as_str()),
+            (
+                IndexField::Description,
+                description.as_deref().unwrap_or(""),
+            ),
+            (IndexField::Body, content),
+        ];
+        let mut doc_terms = HashSet::new();
+        for (field, text) in fields {
+            let mut positions: HashMap<String, Vec<u32>> = HashMap::new();
+            for (position, token) in tokenize(text).into_iter().enumerate() {
+                positions
+                    .entry(token)
+                    .or_default()
+                    .push(u32::try_from(position).unwrap_or(u32::MAX));
+            }
+            for (term, positions) in positions {
+                doc_terms.insert(term.clone());
+                self.terms.entry(term).or_default().push(Posting {
+                    doc: id,
+                    field,
+                    positions,
+                });
+            }
+        }
+
+        self.documents.insert(
+            id,
+            IndexedDocument {
+                relative_path: relative_path.to_string(),
+                kind: classify_file_kind(relative),
+                name: front_name.unwrap_or_else(|| file_name.to_string()),
+                description,
+                modified,
+                size,
+            },
+        );
+        self.by_path.insert(relative_path.to_string(), id);
+        self.doc_terms.insert(id, doc_terms.into_iter().collect());
+    }
+
+    /// ドキュメントを削除（ドキュメントに含まれる語の出現位置のみ更新）
+    fn remove_documents(&mut self, ids: &HashSet<u32>) {
+        for id in ids {
+            if let Some(doc) = self.documents.remove(id) {
+                self.by_path.remove(&doc.relative_path);
+            }
+            for term in self.doc_terms.remove(id).unwrap_or_default() {
+                if let Some(postings) = self.terms.get_mut(&term) {
+                    postings.retain(|p| p.doc != *id);
+                    if postings.is_empty() {
+                        self.terms.remove(&term);
+                    }
+                }
+            }
+        }
+    }
+
+    /// ファイルを読み込んでインデックスに反映（読み込めない場合は削除）
+    fn reindex_file(&mut self, path: &Path, relative_path: &str) -> bool {
+        let content = match file_stamp(path) {
+            Some((modified, size)) if size <= MAX_INDEX_FILE_SIZE => fs::read_to_string(path)
+                .ok()
+                .map(|content| (content, modified, size)),
+            _ => None,
+        };
+
+        match content {
+            Some((content, modified, size)) => {
+                self.index_document(relative_path, &content, modified, size);
+                true
+            }
+            None => {
+                if let Some(id) = self.by_path.get(relative_path).copied() {
+                    self.remove_documents(&HashSet::from([id]));
+                }
+                false
+            }
+        }
+    }
+
+    /// 設定ルート全体をmtime/サイズで比較し、差分のみ反映
+    fn sync(&mut self) -> SyncStats {
+        let mut stats = SyncStats::default();
+        let mut seen = HashSet::new();
+
+        for (path, relative_path) in collect_target_files(&self.root, None, None) {
+            let Some((modified, size)) = file_stamp(&path) else {
+                continue;
+            };
+            seen.insert(relative_path.clone());
+
+            let unchanged = self
+                .by_path
+                .get(&relative_path)
+                .and_then(|id| self.documents.get(id))
+                .is_some_and(|doc| doc.modified == modified && doc.size == size);
+            if !unchanged && self.reindex_file(&path, &relative_path) {
+                stats.indexed += 1;
+            }
+        }
+
+        let removed: HashSet<u32> = self
+            .by_path
+            .iter()
+            .filter(|(relative_path, _)| !seen.contains(*relative_path))
+            .map(|(_, id)| *id)
+            .collect();
+        stats.removed = removed.len();
+        self.remove_documents(&removed);
+
+        stats
+    }
+
+    /// 変更されたパスのみ反映（ディレクトリの削除・移動にも対応）
+    fn update_paths(&mut self, paths: &HashSet<PathBuf>) -> SyncStats {
+        let mut stats = SyncStats::default();
+
+        for path in paths {
+            let Ok(relative) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            if relative.as_os_str().is_empty() || is_excluded_relative_path(relative) {
+                continue;
+            }
+            let relative_path = relative.to_string_lossy().replace('\\', "/");
+
+            if path.is_dir() {
+                // ディレクトリが作成・移動された場合は配下をまとめて反映
+                for (file, file_relative) in collect_target_files(&self.root, None, None) {
+                    if file.starts_with(path) && self.reindex_file(&file, &file_relative) {
+                        stats.indexed += 1;
+                    }
+                }
+            } else if path.is_file() && is_allowed_extension(path) {
+                if self.reindex_file(path, &relative_path) {
+                    stats.indexed += 1;
+                }
+            } else {
+                // 削除されたファイル、またはディレクトリ配下のファイル
+                let dir_prefix = format!("{relative_path}/");
+                let removed: HashSet<u32> = self
+                    .by_path
+                    .iter()
+                    .filter(|(p, _)| **p == relative_path || p.starts_with(&dir_prefix))
+                    .map(|(_, id)| *id)
+                    .collect();
+                stats.removed += removed.len();
+                self.remove_documents(&removed);
+            }
+        }
+
+        stats
+    }
+
+    /// 語（前方一致の場合は展開後の語）の出現位置をドキュメントごとに取得
+    fn slot_positions(
+        &self,
+        token: &str,
+        prefix: bool,
+        field: IndexField,
+    ) -> HashMap<u32, HashSet<u32>> {
+        let mut result: HashMap<u32, HashSet<u32>> = HashMap::new();
+
+        let postings: Vec<&Vec<Posting>> = if prefix {
+            self.terms
+                .range(token.to_string()..)
+                .take_while(|(term, _)| term.starts_with(token))
+                .take(MAX_PREFIX_EXPANSIONS)
+                .map(|(_, postings)| postings)
+                .collect()
+        } else {
+            self.terms.get(token).into_iter().collect()
+        };
+
+        for posting in postings.into_iter().flatten() {
+            if posting.field == field {
+                result
+                    .entry(posting.doc)
+                    .or_default()
+                    .extend(posting.positions.iter().copied());
+            }
+        }
+        result
+    }
+
+    /// 語・フレーズの出現回数をドキュメントごとに取得
+    fn phrase_frequencies(
+        &self,
+        tokens: &[String],
+        prefix: bool,
+        field: IndexField,
+    ) -> HashMap<u32, usize> {
+        let last = tokens.len() - 1;
+        let slots: Vec<HashMap<u32, HashSet<u32>>> = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| self.slot_positions(token, prefix && i == last, field))
+            .collect();
+
+        let mut frequencies = HashMap::new();
+        for (doc, first_positions) in &slots[0] {
+            let count = first_positions
+                .iter()
+                .filter(|&&start| {
+                    slots.iter().enumerate().skip(1).all(|(offset, slot)| {
+                        slot.get(doc).is_some_and(|positions| {
+                            u32::try_from(offset)
+                                .ok()
+                                .and_then(|o| start.checked_add(o))
+                                .is_some_and(|p| positions.contains(&p))
+                        })
+                    })
+                })
+                .count();
+            if count > 0 {
+                frequencies.insert(*doc, count);
+            }
+        }
+        frequencies
+    }
+
+    /// クエリを実行し、関連度順のヒットとヒット総数を返す
+    fn search(&self, clauses: &[QueryClause], limit: usize) -> (Vec<IndexSearchHit>, usize) {
+        let kinds: Vec<FileKind> = clauses
+            .iter()
+            .filter_map(|c| match c {
+                QueryClause::Kind(kind) => Some(*kind),
+                QueryClause::Text { .. } => None,
+            })
+            .collect();
+
+        // 候補ドキュメントとスコア（すべてのテキスト条件を満たすもののみ残す）
+        let mut scores: Option<HashMap<u32, f64>> = None;
+        for clause in clauses {
+            let QueryClause::Text {
+                field,
+                tokens,
+                prefix,
+            } = clause
+            else {
+                continue;
+            };
+
+            let fields = field.map_or(IndexField::ALL.to_vec(), |f| vec![f]);
+            let mut clause_scores: HashMap<u32, f64> = HashMap::new();
+            for field in fields {
+                for (doc, tf) in self.phrase_frequencies(tokens, *prefix, field) {
+                    #[allow(clippy::cast_precision_loss)]
+                    let weight = field.weight() * (1.0 + (tf as f64).ln());
+                    *clause_scores.entry(doc).or_default() += weight;
+                }
+            }
+
+            scores = Some(match scores {
+                None => clause_scores,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(doc, score)| clause_scores.get(&doc).map(|s| (doc, score + s)))
+                    .collect(),
+            });
+        }
+
+        // テキスト条件がない場合（`kind:` のみ）は全ドキュメントが候補
+        let scores = scores.unwrap_or_else(|| self.documents.keys().map(|id| (*id, 0.0)).collect());
+
+        let mut hits: Vec<IndexSearchHit> = scores
+            .into_iter()
+            .filter_map(|(id, score)| {
+                let doc = self.documents.get(&id)?;
+                if !kinds.is_empty() && !kinds.contains(&doc.kind) {
+                    return None;
+                }
+                Some(IndexSearchHit {
+                    path: self
+                        .root
+                        .join(&doc.relative_path)
+                        .to_string_lossy()
+                        .to_string(),
+                    relative_path: doc.relative_path.clone(),
+                    name: doc.name.clone(),
+                    kind: doc.kind,
+                    description: doc.description.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.relative_path.cmp(&b.relative_path))
+        });
+
+        let total = hits.len();
+        hits.truncate(limit);
+        (hits, total)
+    }
+
+    fn status(&self, index_path: &Path) -> SearchIndexStatus {
+        SearchIndexStatus {
+            root: self.root.to_string_lossy().to_string(),
+            document_count: self.documents.len(),
+            term_count: self.terms.len(),
+            index_path: index_path.to_string_lossy().to_string(),
+        }
+    }
+}
+
+/// 設定ルートに対応するインデックスファイルのパスを取得
+fn get_index_file_path(root: &Path) -> Result<PathBuf, String> {
+    let cache_dir =
+        dirs::cache_dir().ok_or_else(|| "Could not find cache directory".to_string())?;
+
+    let mut hasher = DefaultHasher::new();
+    root.hash(&mut hasher);
+
+    Ok(cache_dir
+        .join("claude-setting-dashboard")
+        .join("search-index")
+        .join(format!("{:016x}.json", hasher.finish())))
+}
+
+/// ファイル監視で検知した変更パスを登録
+///
+/// 次回の検索時にインデックスへ反映されます。
+pub fn mark_paths_changed<I: IntoIterator<Item = PathBuf>>(paths: I) {
+    PENDING_PATHS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .extend(paths);
+}
+
+/// 次回の検索時にインデックス全体を同期させる
+///
+/// 除外glob や許可する拡張子が変わり、対象ファイルの集合が変化した場合に呼び出します。
+pub fn request_full_sync() {
+    FULL_SYNC_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// インデックスの保存を予約
+///
+/// 保存は `SAVE_DELAY` 後に別スレッドで行い、その間の変更はまとめて1回で保存します。
+/// シリアライズと書き込みはインデックスのロックを解放してから行います
+/// （保存前に終了した場合も、次回の起動時にmtime比較で同期されます）。
+fn schedule_save() {
+    if SAVE_SCHEDULED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        std::thread::sleep(SAVE_DELAY);
+        SAVE_SCHEDULED.store(false, Ordering::SeqCst);
+        let index = SEARCH_INDEX
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .as_ref()
+            .map(Arc::clone);
+        let Some(index) = index else {
+            return;
+        };
+        let _write = SAVE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Err(e) = get_index_file_path(&index.root).and_then(|path| index.save(&path)) {
+            warn!("Failed to save search index: {e}");
+        }
+    });
+}
+
+/// アクティブな設定ルートのインデックスを最新の状態にして処理を実行
+///
+/// 初回（または設定ルート切り替え後）は保存済みインデックスを読み込んでmtime比較で同期し、
+/// 以降はファイル監視で検知した変更パスのみ反映します。
+/// ファイル監視が動いていない場合は毎回mtime比較で同期します。
+/// 変更があった場合の保存は `schedule_save` で予約します。
+fn with_index<T>(rebuild: bool, f: impl FnOnce(&SearchIndex, &Path) -> T) -> AppResult<T> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let root = dunce::canonicalize(&root)
+        .map_err(|_| format!("Config root not found: {}", root.display()))?;
+    let index_path = get_index_file_path(&root)?;
+
+    let mut guard = SEARCH_INDEX
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    let pending: HashSet<PathBuf> = std::mem::take(
+        &mut *PENDING_PATHS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+
+    let watched = get_watched_root().is_some_and(|w| Path::new(&w) == root)
+        && !FULL_SYNC_REQUESTED.swap(false, Ordering::SeqCst);
+
+    // 保存中のスレッドがインデックスを参照している場合のみ、更新前に複製される
+    let stats = match guard.as_mut() {
+        Some(index) if !rebuild && index.root == root && watched => {
+            if pending.is_empty() {
+                SyncStats::default()
+            } else {
+                Arc::make_mut(index).update_paths(&pending)
+            }
+        }
+        Some(index) if !rebuild && index.root == root => Arc::make_mut(index).sync(),
+        _ => {
+            let mut index = if rebuild {
+                None
+            } else {
+                SearchIndex::load(&index_path, &root)
+            }
+            .unwrap_or_else(|| SearchIndex::new(root.clone()));
+            let stats = index.sync();
+            info!(
+                "Search index loaded: {} document(s), {} updated, {} removed",
+                index.documents.len(),
+                stats.indexed,
+                stats.removed
+            );
+            *guard = Some(Arc::new(index));
+            stats
+        }
+    };
+
+    if stats != SyncStats::default() {
+        schedule_save();
+    }
+
+    let index = guard.as_ref().ok_or("Search index is not loaded")?;
+    Ok(f(index, &index_path))
+}
+
+/// インデックスを使って全文検索
+///
+/// # Arguments
+///
+/// * `query` - クエリ文字列（フレーズ、前方一致、フィールド指定に対応）
+/// * `limit` - 返すヒット数の上限
+///
+/// # Errors
+///
+/// - クエリの構文が不正な場合（未知の `kind:` など）
+/// - 設定ルートが存在しない場合
+pub fn search(query: &str, limit: usize) -> AppResult<IndexSearchResponse> {
+    let started = Instant::now();
+    let clauses = parse_query(query)?;
+    if clauses.is_empty() {
+        return Ok(IndexSearchResponse {
+            hits: Vec::new(),
+            total_hits: 0,
+            elapsed_ms: 0,
+        });
+    }
+
+    let (hits, total_hits) = with_index(false, |index, _| index.search(&clauses, limit))?;
+
+    Ok(IndexSearchResponse {
+        hits,
+        total_hits,
+        elapsed_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+    })
+}
+
+/// インデックスの状態を取得（必要に応じて同期）
+///
+/// # Arguments
+///
+/// * `rebuild` - 保存済みインデックスを破棄して作り直すか
+///
+/// # Errors
+///
+/// 設定ルートが存在しない場合はエラーを返します。
+pub fn refresh(rebuild: bool) -> AppResult<SearchIndexStatus> {
+    with_index(rebuild, SearchIndex::status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_index() -> SearchIndex {
+        let mut index = SearchIndex::new(PathBuf::from("/home/user/.claude"));
+        index.index_document(
+            "skills/deploy-helper/SKILL.md",
+            "---\nname: deploy-helper\ndescription: Deploy the app to production\n---\nRun the deploy script.",
+            1,
+            1,
+        );
+        index.index_document(
+            "agents/code-reviewer.md",
+            "---\nname: code-reviewer\ndescription: コードレビューを行う\n---\nReview code for deploy safety.",
+            1,
+            1,
+        );
+        index.index_document("CLAUDE.md", "Always write code reviews in Japanese.", 1, 1);
+        index
+    }
+
+    fn run(index: &SearchIndex, query: &str) -> Vec<String> {
+        let clauses = parse_query(query).expect("クエリの解析に失敗しました");
+        index
+            .search(&clauses, 10)
+            .0
+            .into_iter()
+            .map(|hit| hit.relative_path)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("Code-Reviewer v2"), vec!["code", "reviewer", "v2"]);
+        assert_eq!(
+            tokenize("コードレビュー"),
+            vec!["コー", "ード", "ドレ", "レビ", "ビュ", "ュー"]
+        );
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let clauses = parse_query("name:deploy* \"code review\" kind:skill").unwrap();
+        assert_eq!(
+            clauses,
+            vec![
+                QueryClause::Text {
+                    field: Some(IndexField::Name),
+                    tokens: vec!["deploy".to_string()],
+                    prefix: true,
+                },
+                QueryClause::Text {
+                    field: None,
+                    tokens: vec!["code".to_string(), "review".to_string()],
+                    prefix: false,
+                },
+                QueryClause::Kind(FileKind::Skill),
+            ]
+        );
+        assert!(parse_query("kind:unknown").is_err());
+    }
+
+    #[test]
+    fn test_search_ranking_and_filters() {
+        let index = build_index();
+
+        // ファイル名/nameの一致が本文の一致より上位
+        let hits = run(&index, "deploy");
+        assert_eq!(hits[0], "skills/deploy-helper/SKILL.md");
+        assert_eq!(hits.len(), 2);
+
+        assert_eq!(
+            run(&index, "deploy kind:agent"),
+            vec!["agents/code-reviewer.md"]
+        );
+        assert_eq!(
+            run(&index, "description:production"),
+            vec!["skills/deploy-helper/SKILL.md"]
+        );
+        assert_eq!(
+            run(&index, "description:レビュー"),
+            vec!["agents/code-reviewer.md"]
+        );
+        assert_eq!(run(&index, "kind:memory"), vec!["CLAUDE.md"]);
+    }
+
+    #[test]
+    fn test_search_phrase_and_prefix() {
+        let index = build_index();
+
+        assert_eq!(run(&index, "\"code reviews\""), vec!["CLAUDE.md"]);
+        assert!(run(&index, "\"reviews code\"").is_empty());
+        assert_eq!(run(&index, "\"code review*\"").len(), 2);
+        assert_eq!(run(&index, "revie*").len(), 2);
+    }
+
+    #[test]
+    fn test_reindex_and_remove_document() {
+        let mut index = build_index();
+        index.index_document("CLAUDE.md", "Nothing relevant here.", 2, 1);
+        assert!(run(&index, "japanese").is_empty());
+
+        let id = index.by_path["agents/code-reviewer.md"];
+        index.remove_documents(&HashSet::from([id]));
+        assert!(run(&index, "reviewer").is_empty());
+        assert!(index.terms.values().flatten().all(|p| p.doc != id));
+    }
+
+    #[test]
+    fn test_sync_detects_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        fs::create_dir_all(root.join("agents")).unwrap();
+        fs::write(root.join("agents/a.md"), "alpha").unwrap();
+        fs::write(root.join("agents/b.md"), "beta").unwrap();
+
+        let mut index = SearchIndex::new(root.clone());
+        assert_eq!(
+            index.sync(),
+            SyncStats {
+                indexed: 2,
+                removed: 0
+            }
+        );
+        assert_eq!(index.sync(), SyncStats::default());
+
+        fs::remove_file(root.join("agents/b.md")).unwrap();
+        assert_eq!(
+            index.sync(),
+            SyncStats {
+                indexed: 0,
+                removed: 1
+            }
+        );
+
+        fs::write(root.join("agents/c.md"), "gamma").unwrap();
+        let stats = index.update_paths(&HashSet::from([root.join("agents/c.md")]));
+        assert_eq!(stats.indexed, 1);
+        assert_eq!(run(&index, "gamma"), vec!["agents/c.md"]);
+
+        // 保存と読み込みで内容が維持される
+        let index_path = root.join("index.json");
+        index.save(&index_path).unwrap();
+        let loaded =
+            SearchIndex::load(&index_path, &root).expect("インデックスの読み込みに失敗しました");
+        assert_eq!(run(&loaded, "alpha"), vec!["agents/a.md"]);
+
+        // 読み込んだインデックスでも削除したドキュメントの語が残らない
+        let mut loaded = loaded;
+        let id = loaded.by_path["agents/a.md"];
+        loaded.remove_documents(&HashSet::from([id]));
+        assert!(run(&loaded, "alpha").is_empty());
+        assert!(!loaded.terms.contains_key("alpha"));
+    }
+}
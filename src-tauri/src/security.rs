@@ -0,0 +1,248 @@
+//! パスアクセスポリシー
+//!
+//! フロントエンドから渡されたパスを正規化し、設定ルート配下に収まっているかを検証します。
+//! `..` を含むパスやルート外を指すシンボリックリンクによる脱出を防ぎます。
+
+use crate::error::{AccessDeniedReason, AppError};
+use crate::utils::get_claude_dir;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// シンボリックリンクの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// シンボリックリンクを経由するパスを拒否
+    Deny,
+    /// リンク先が設定ルート配下に解決される場合のみ許可
+    WithinRoot,
+}
+
+/// 設定ルートに対するアクセスポリシー
+#[derive(Debug, Clone)]
+pub struct AccessPolicy {
+    /// 設定ルート（`.`/`..` のみ解決したパス）
+    lexical_root: PathBuf,
+    /// 設定ルートの正規化済みパス
+    canonical_root: PathBuf,
+    /// シンボリックリンクの扱い
+    symlinks: SymlinkPolicy,
+}
+
+impl AccessPolicy {
+    /// 指定したルートに対するポリシーを作成
+    ///
+    /// # Errors
+    ///
+    /// ルートが存在しない場合はエラーを返します。
+    pub fn new(root: &Path, symlinks: SymlinkPolicy) -> Result<Self, AppError> {
+        let canonical_root = dunce::canonicalize(root).map_err(|_| AppError::ClaudeDirNotFound)?;
+        Ok(Self {
+            lexical_root: lexical_normalize(root),
+            canonical_root,
+            symlinks,
+        })
+    }
+
+    /// アクティブな設定ルートに対するデフォルトポリシーを作成
+    ///
+    /// シンボリックリンクはリンク先が設定ルート配下の場合のみ許可します。
+    ///
+    /// # Errors
+    ///
+    /// 設定ルートが存在しない場合はエラーを返します。
+    pub fn for_claude_dir() -> Result<Self, AppError> {
+        Self::new(&get_claude_dir()?, SymlinkPolicy::WithinRoot)
+    }
+
+    /// 正規化済みの設定ルート
+    pub fn root(&self) -> &Path {
+        &self.canonical_root
+    }
+
+    /// パスを検証し、正規化済みの絶対パスを返す
+    ///
+    /// 存在しないパス（新規作成・インポート先）の場合は、存在する最も近い祖先を
+    /// 正規化したうえで残りの要素を連結します。
+    ///
+    /// # Errors
+    ///
+    /// - 相対パスの場合
+    /// - 設定ルート外を指す場合
+    /// - ポリシーに反するシンボリックリンクを経由する場合
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf, AppError> {
+        if !path.is_absolute() {
+            return Err(AppError::AccessDenied(AccessDeniedReason::RelativePath(
+                path.display().to_string(),
+            )));
+        }
+
+        let lexical = lexical_normalize(path);
+        let relative = lexical
+            .strip_prefix(&self.lexical_root)
+            .or_else(|_| lexical.strip_prefix(&self.canonical_root))
+            .map_err(|_| {
+                AppError::AccessDenied(AccessDeniedReason::OutsideRoot(
+                    path.display().to_string(),
+                ))
+            })?
+            .to_path_buf();
+
+        // ルートから1要素ずつ辿り、シンボリックリンクと既存部分を確認
+        let mut existing = self.canonical_root.clone();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.peek() {
+            let candidate = existing.join(component);
+            match fs::symlink_metadata(&candidate) {
+                Ok(meta) => {
+                    if meta.file_type().is_symlink() {
+                        if self.symlinks == SymlinkPolicy::Deny {
+                            return Err(AppError::AccessDenied(
+                                AccessDeniedReason::SymlinkNotAllowed(
+                                    candidate.display().to_string(),
+                                ),
+                            ));
+                        }
+                        existing = dunce::canonicalize(&candidate).map_err(|_| {
+                            AppError::AccessDenied(AccessDeniedReason::BrokenSymlink(
+                                candidate.display().to_string(),
+                            ))
+                        })?;
+                        if !existing.starts_with(&self.canonical_root) {
+                            return Err(AppError::AccessDenied(
+                                AccessDeniedReason::SymlinkEscape(
+                                    candidate.display().to_string(),
+                                ),
+                            ));
+                        }
+                    } else {
+                        existing = candidate;
+                    }
+                    components.next();
+                }
+                Err(_) => break,
+            }
+        }
+
+        // 存在しない残りの要素を連結（lexical_normalizeにより`..`は含まれない）
+        let resolved = components.fold(existing, |acc, c| acc.join(c));
+
+        if !resolved.starts_with(&self.canonical_root) {
+            return Err(AppError::AccessDenied(AccessDeniedReason::OutsideRoot(
+                path.display().to_string(),
+            )));
+        }
+
+        Ok(resolved)
+    }
+
+    /// 設定ルートからの相対パスを検証し、正規化済みの絶対パスを返す
+    ///
+    /// # Errors
+    ///
+    /// `resolve` と同じ条件でエラーを返します。
+    pub fn resolve_relative(&self, relative: &str) -> Result<PathBuf, AppError> {
+        let relative_path = Path::new(relative);
+        if relative_path.is_absolute() || relative_path.has_root() {
+            return Err(AppError::AccessDenied(AccessDeniedReason::OutsideRoot(
+                relative.to_string(),
+            )));
+        }
+        self.resolve(&self.canonical_root.join(relative_path))
+    }
+}
+
+/// アクティブな設定ルートのデフォルトポリシーでパスを検証
+///
+/// # Errors
+///
+/// `AccessPolicy::resolve` と同じ条件でエラーを返します。
+pub fn resolve_path(path: &Path) -> Result<PathBuf, AppError> {
+    AccessPolicy::for_claude_dir()?.resolve(path)
+}
+
+/// アクティブな設定ルートのデフォルトポリシーで相対パスを検証
+///
+/// # Errors
+///
+/// `AccessPolicy::resolve_relative` と同じ条件でエラーを返します。
+pub fn resolve_relative_path(relative: &str) -> Result<PathBuf, AppError> {
+    AccessPolicy::for_claude_dir()?.resolve_relative(relative)
+}
+
+/// ファイルシステムにアクセスせずに `.` と `..` を解決
+///
+/// ルートより上に遡る `..` は無視されます。
+fn lexical_normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗しました");
+        let root = dir.path().join(".claude");
+        fs::create_dir_all(root.join("skills")).unwrap();
+        fs::write(root.join("settings.json"), "{}").unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn test_resolve_rejects_parent_dir_escape() {
+        let (_dir, root) = setup();
+        let policy = AccessPolicy::new(&root, SymlinkPolicy::WithinRoot).unwrap();
+
+        let escaped = root.join("..").join("secret.txt");
+        assert!(matches!(
+            policy.resolve(&escaped),
+            Err(AppError::AccessDenied(AccessDeniedReason::OutsideRoot(_)))
+        ));
+        assert!(policy.resolve_relative("../secret.txt").is_err());
+        assert!(policy.resolve(&root.join("skills/../settings.json")).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_allows_new_files() {
+        let (_dir, root) = setup();
+        let policy = AccessPolicy::new(&root, SymlinkPolicy::WithinRoot).unwrap();
+
+        let resolved = policy
+            .resolve(&root.join("skills/new-skill/SKILL.md"))
+            .expect("新規ファイルのパスが拒否されました");
+        assert!(resolved.starts_with(policy.root()));
+        assert!(resolved.ends_with("skills/new-skill/SKILL.md"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_symlink_policies() {
+        let (dir, root) = setup();
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), root.join("escape.md")).unwrap();
+        std::os::unix::fs::symlink(root.join("settings.json"), root.join("alias.json")).unwrap();
+
+        let within = AccessPolicy::new(&root, SymlinkPolicy::WithinRoot).unwrap();
+        assert!(matches!(
+            within.resolve(&root.join("escape.md")),
+            Err(AppError::AccessDenied(AccessDeniedReason::SymlinkEscape(_)))
+        ));
+        assert!(within.resolve(&root.join("alias.json")).is_ok());
+
+        let deny = AccessPolicy::new(&root, SymlinkPolicy::Deny).unwrap();
+        assert!(matches!(
+            deny.resolve(&root.join("alias.json")),
+            Err(AppError::AccessDenied(AccessDeniedReason::SymlinkNotAllowed(_)))
+        ));
+    }
+}
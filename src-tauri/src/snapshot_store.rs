@@ -0,0 +1,513 @@
+//! スナップショットストア
+//!
+//! 設定ルート全体（ダッシュボード表示対象のファイル）のある時点の状態を保存します。
+//! 各ファイルの内容はバックアップストアのオブジェクトとして重複排除・圧縮して保存され、
+//! スナップショットごとのファイル一覧は `backups/snapshots/<ID>.json` に記録されます。
+//!
+//! 自動作成したスナップショット（インポート・一括置換・復元の直前）は
+//! 新しい方から [`MAX_AUTO_SNAPSHOTS`] 件まで残し、手動作成したものは削除されるまで残ります。
+
+use crate::backup_store::{self, get_backup_dir, lock_manifest, object_path};
+use crate::types::{
+    SnapshotChange, SnapshotChangeKind, SnapshotDiff, SnapshotInfo, SnapshotTrigger,
+};
+use crate::utils::{
+    content_version_token, get_claude_dir, modified_millis, walk_visible_files, write_file_atomic,
+};
+use chrono::Local;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// スナップショットを保存するディレクトリ名（`backups/` からの相対パス）
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// 自動作成したスナップショットを残す件数
+pub const MAX_AUTO_SNAPSHOTS: usize = 20;
+
+/// スナップショットに含まれるファイル
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotFile {
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub relative_path: String,
+    /// 内容のSHA-256ハッシュ
+    pub hash: String,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// 更新日時（UNIXエポックからのミリ秒、次回作成時に内容の読み込みを省略するために使用）
+    pub modified: Option<u64>,
+}
+
+/// スナップショットのマニフェスト
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotManifest {
+    /// 概要
+    #[serde(flatten)]
+    info: SnapshotInfo,
+    /// 含まれるファイル（相対パス順）
+    files: Vec<SnapshotFile>,
+}
+
+/// スナップショットのディレクトリを取得
+fn snapshots_dir(backup_dir: &Path) -> PathBuf {
+    backup_dir.join(SNAPSHOTS_DIR)
+}
+
+/// スナップショットIDを検証してマニフェストのパスを取得
+fn manifest_path(backup_dir: &Path, id: &str) -> Result<PathBuf, String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!("Invalid snapshot id: {id}"));
+    }
+    Ok(snapshots_dir(backup_dir).join(format!("{id}.json")))
+}
+
+/// すべてのスナップショットのマニフェストのパス（スナップショットがない場合は空）
+fn manifest_paths(backup_dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let dir = snapshots_dir(backup_dir);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read snapshots: {e}")),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read snapshots: {e}"))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// スナップショットのマニフェストを読み込む
+fn read_manifest(path: &Path) -> Result<SnapshotManifest, String> {
+    fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|c| serde_json::from_str(&c).map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to read snapshot {}: {e}", path.display()))
+}
+
+/// すべてのスナップショットのマニフェストを読み込む（作成日時の新しい順）
+///
+/// 読み込めないマニフェストは警告を出してスキップします。
+fn read_manifests(backup_dir: &Path) -> Vec<SnapshotManifest> {
+    let paths = manifest_paths(backup_dir).unwrap_or_else(|e| {
+        warn!("{e}");
+        Vec::new()
+    });
+
+    let mut manifests: Vec<SnapshotManifest> = paths
+        .iter()
+        .filter_map(|path| match read_manifest(path) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                warn!("Skipping unreadable snapshot: {e}");
+                None
+            }
+        })
+        .collect();
+    manifests.sort_by(|a, b| b.info.created_at.cmp(&a.info.created_at));
+    manifests
+}
+
+/// スナップショットが参照しているオブジェクトのハッシュを取得
+///
+/// バックアップストアのガベージコレクションから呼び出されます。
+/// 参照を見落とすとオブジェクトが削除されるため、読み込めないマニフェストはスキップしません。
+///
+/// # Errors
+///
+/// スナップショットのディレクトリ・マニフェストを1つでも読み込めない場合はエラーを返します。
+pub(crate) fn referenced_hashes(backup_dir: &Path) -> Result<HashSet<String>, String> {
+    let mut hashes = HashSet::new();
+    for path in manifest_paths(backup_dir)? {
+        hashes.extend(read_manifest(&path)?.files.into_iter().map(|f| f.hash));
+    }
+    Ok(hashes)
+}
+
+/// 設定ルートの現在の状態をスナップショットとして保存
+///
+/// 直前のスナップショットとサイズ・更新日時が同じファイルは内容を読み込まずにハッシュを再利用します。
+///
+/// # Arguments
+///
+/// * `name` - スナップショットの名前
+/// * `trigger` - 作成した契機
+///
+/// # Returns
+///
+/// 作成したスナップショットの概要
+///
+/// # Errors
+///
+/// ファイルの読み込み、オブジェクトやマニフェストの書き込みに失敗した場合はエラーを返します。
+pub fn create_snapshot(
+    name: Option<&str>,
+    trigger: SnapshotTrigger,
+) -> Result<SnapshotInfo, String> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let backup_dir = get_backup_dir()?;
+
+    // オブジェクトを書き込んでからマニフェストで参照するまでの間に
+    // ガベージコレクションで削除されないようロックを保持する
+    let guard = lock_manifest();
+
+    let previous: HashMap<String, SnapshotFile> = read_manifests(&backup_dir)
+        .into_iter()
+        .next()
+        .map(|m| {
+            m.files
+                .into_iter()
+                .map(|f| (f.relative_path.clone(), f))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut files = Vec::new();
+    for entry in walk_visible_files(&root) {
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(&root) else {
+            continue;
+        };
+        let relative_path = relative.to_string_lossy().replace('\\', "/");
+        let metadata = entry
+            .metadata()
+            .map_err(|e| format!("Failed to read metadata of {relative_path}: {e}"))?;
+        let size = metadata.len();
+        let modified = modified_millis(&metadata);
+
+        let reusable = previous.get(&relative_path).filter(|prev| {
+            prev.size == size
+                && modified.is_some()
+                && prev.modified == modified
+                && object_path(&backup_dir, &prev.hash).is_file()
+        });
+        let hash = if let Some(prev) = reusable {
+            prev.hash.clone()
+        } else {
+            let bytes =
+                fs::read(path).map_err(|e| format!("Failed to read {relative_path}: {e}"))?;
+            let hash = content_version_token(&bytes);
+            backup_store::write_object(&backup_dir, &hash, &bytes)?;
+            hash
+        };
+
+        files.push(SnapshotFile {
+            relative_path,
+            hash,
+            size,
+            modified,
+        });
+    }
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    let now = Local::now();
+    let base_id = format!("snap_{}", now.format("%Y%m%d_%H%M%S_%3f"));
+    let mut id = base_id.clone();
+    let mut counter = 1;
+    while manifest_path(&backup_dir, &id)?.exists() {
+        id = format!("{base_id}_{counter}");
+        counter += 1;
+    }
+    let manifest = SnapshotManifest {
+        info: SnapshotInfo {
+            id: id.clone(),
+            name: name
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(str::to_string),
+            created_at: now.to_rfc3339(),
+            trigger,
+            file_count: files.len(),
+            total_size: files.iter().map(|f| f.size).sum(),
+        },
+        files,
+    };
+
+    let path = manifest_path(&backup_dir, &id)?;
+    fs::create_dir_all(snapshots_dir(&backup_dir))
+        .map_err(|e| format!("Failed to create snapshot directory: {e}"))?;
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize snapshot: {e}"))?;
+    write_file_atomic(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to save snapshot: {e}"))?;
+
+    info!(
+        "Created snapshot {id} ({} files, {} bytes)",
+        manifest.info.file_count, manifest.info.total_size
+    );
+
+    drop(guard);
+
+    if trigger != SnapshotTrigger::Manual && prune_auto_snapshots(&backup_dir) > 0 {
+        match backup_store::collect_unreferenced_objects() {
+            Ok((_, errors)) => errors
+                .iter()
+                .for_each(|e| warn!("Snapshot cleanup error: {e}")),
+            Err(e) => warn!("Snapshot cleanup failed: {e}"),
+        }
+    }
+
+    Ok(manifest.info)
+}
+
+/// 古い自動作成のスナップショットを削除
+///
+/// # Returns
+///
+/// 削除したスナップショットの数
+fn prune_auto_snapshots(backup_dir: &Path) -> usize {
+    let stale: Vec<String> = read_manifests(backup_dir)
+        .into_iter()
+        .filter(|m| m.info.trigger != SnapshotTrigger::Manual)
+        .skip(MAX_AUTO_SNAPSHOTS)
+        .map(|m| m.info.id)
+        .collect();
+
+    let mut removed = 0;
+    for id in &stale {
+        let Ok(path) = manifest_path(backup_dir, id) else {
+            continue;
+        };
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => warn!("Failed to delete old snapshot {id}: {e}"),
+        }
+    }
+    removed
+}
+
+/// スナップショットの一覧を取得（新しい順）
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合はエラーを返します。
+pub fn list_snapshots() -> Result<Vec<SnapshotInfo>, String> {
+    let backup_dir = get_backup_dir()?;
+    Ok(read_manifests(&backup_dir)
+        .into_iter()
+        .map(|m| m.info)
+        .collect())
+}
+
+/// スナップショットの概要とファイル一覧を読み込む
+///
+/// # Errors
+///
+/// IDが不正な場合、スナップショットが見つからない場合はエラーを返します。
+pub fn load_snapshot(id: &str) -> Result<(SnapshotInfo, Vec<SnapshotFile>), String> {
+    let backup_dir = get_backup_dir()?;
+    let path = manifest_path(&backup_dir, id)?;
+    let content = fs::read_to_string(&path).map_err(|_| format!("Snapshot not found: {id}"))?;
+    let manifest: SnapshotManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse snapshot {id}: {e}"))?;
+    Ok((manifest.info, manifest.files))
+}
+
+/// スナップショットに含まれるファイルの内容を読み込む
+///
+/// # Errors
+///
+/// オブジェクトが存在しないか破損している場合はエラーを返します。
+pub fn read_file_content(file: &SnapshotFile) -> Result<Vec<u8>, String> {
+    let backup_dir = get_backup_dir()?;
+    let _guard = lock_manifest();
+    backup_store::read_object(&backup_dir, &file.hash)
+}
+
+/// スナップショットを削除し、参照されなくなったオブジェクトを削除
+///
+/// # Returns
+///
+/// 解放したバイト数
+///
+/// # Errors
+///
+/// IDが不正な場合、スナップショットが見つからない場合、削除に失敗した場合はエラーを返します。
+pub fn delete_snapshot(id: &str) -> Result<u64, String> {
+    let backup_dir = get_backup_dir()?;
+    let path = manifest_path(&backup_dir, id)?;
+    {
+        let _guard = lock_manifest();
+        if !path.is_file() {
+            return Err(format!("Snapshot not found: {id}"));
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot {id}: {e}"))?;
+    }
+
+    let (freed, errors) = backup_store::collect_unreferenced_objects()?;
+    for error in errors {
+        warn!("Snapshot cleanup error: {error}");
+    }
+    info!("Deleted snapshot {id} ({freed} bytes freed)");
+    Ok(freed)
+}
+
+/// 設定ルートの現在のファイル（相対パス → 絶対パスとサイズ）
+fn current_files(root: &Path) -> BTreeMap<String, (PathBuf, u64)> {
+    walk_visible_files(root)
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(root).ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some((
+                relative.to_string_lossy().replace('\\', "/"),
+                (entry.path().to_path_buf(), size),
+            ))
+        })
+        .collect()
+}
+
+/// スナップショットと現在の状態を比較
+///
+/// # Arguments
+///
+/// * `files` - スナップショットに含まれるファイル
+/// * `root` - 設定ルート
+///
+/// # Returns
+///
+/// 異なるファイル（相対パス順）と同一のファイル数
+pub fn compare_with_current(files: &[SnapshotFile], root: &Path) -> (Vec<SnapshotChange>, usize) {
+    let mut current = current_files(root);
+    let mut changes = Vec::new();
+    let mut unchanged_count = 0;
+
+    for file in files {
+        let Some((path, size)) = current.remove(&file.relative_path) else {
+            changes.push(SnapshotChange {
+                relative_path: file.relative_path.clone(),
+                kind: SnapshotChangeKind::Removed,
+                snapshot_size: Some(file.size),
+                current_size: None,
+            });
+            continue;
+        };
+
+        let same = size == file.size
+            && fs::read(&path).is_ok_and(|bytes| content_version_token(&bytes) == file.hash);
+        if same {
+            unchanged_count += 1;
+        } else {
+            changes.push(SnapshotChange {
+                relative_path: file.relative_path.clone(),
+                kind: SnapshotChangeKind::Modified,
+                snapshot_size: Some(file.size),
+                current_size: Some(size),
+            });
+        }
+    }
+
+    changes.extend(current.into_iter().map(|(relative_path, (_, size))| SnapshotChange {
+        relative_path,
+        kind: SnapshotChangeKind::Added,
+        snapshot_size: None,
+        current_size: Some(size),
+    }));
+    changes.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+
+    (changes, unchanged_count)
+}
+
+/// スナップショットと現在の状態の差分を取得
+///
+/// # Errors
+///
+/// スナップショットが見つからない場合はエラーを返します。
+pub fn diff_with_current(id: &str) -> Result<SnapshotDiff, String> {
+    let root = get_claude_dir().map_err(|e| e.to_string())?;
+    let (snapshot, files) = load_snapshot(id)?;
+    let (changes, unchanged_count) = compare_with_current(&files, &root);
+    Ok(SnapshotDiff {
+        snapshot,
+        changes,
+        unchanged_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BackupOperation;
+
+    #[test]
+    fn test_snapshot_diff_and_object_sharing() {
+        let root = crate::utils::TestClaudeDir::new();
+        let settings = root.write("settings.json", "{}");
+        root.write("agents/a.md", "# a");
+        // 除外ディレクトリは含まない
+        root.write("cache/c.json", "{}");
+
+        let snapshot = create_snapshot(Some(" before "), SnapshotTrigger::Manual).unwrap();
+        assert_eq!(snapshot.name.as_deref(), Some("before"));
+        assert_eq!(snapshot.file_count, 2);
+
+        fs::write(&settings, r#"{"model": "x"}"#).unwrap();
+        fs::remove_file(root.path().join("agents/a.md")).unwrap();
+        fs::write(root.path().join("CLAUDE.md"), "# new").unwrap();
+
+        let diff = diff_with_current(&snapshot.id).unwrap();
+        let kinds: Vec<(&str, SnapshotChangeKind)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.relative_path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("CLAUDE.md", SnapshotChangeKind::Added),
+                ("agents/a.md", SnapshotChangeKind::Removed),
+                ("settings.json", SnapshotChangeKind::Modified),
+            ]
+        );
+        assert_eq!(diff.unchanged_count, 0);
+
+        // バックアップを削除してもスナップショットが参照する内容は残る
+        backup_store::store_backup(&settings, "settings.json", BackupOperation::Manual).unwrap();
+        backup_store::remove_backups(|_| true).unwrap();
+        let (_, files) = load_snapshot(&snapshot.id).unwrap();
+        let agent = files.iter().find(|f| f.relative_path == "agents/a.md").unwrap();
+        assert_eq!(read_file_content(agent).unwrap(), b"# a");
+
+        // スナップショットを削除すると参照されなくなった内容も削除される
+        assert!(delete_snapshot(&snapshot.id).unwrap() > 0);
+        assert!(list_snapshots().unwrap().is_empty());
+        assert!(load_snapshot("../manifest").is_err());
+    }
+
+    #[test]
+    fn test_unreadable_snapshot_manifest_keeps_objects() {
+        let root = crate::utils::TestClaudeDir::new();
+        let settings = root.path().join("settings.json");
+        fs::write(&settings, r#"{"model": "a"}"#).unwrap();
+        let first = create_snapshot(None, SnapshotTrigger::Manual).unwrap();
+        fs::write(&settings, r#"{"model": "b"}"#).unwrap();
+        let second = create_snapshot(None, SnapshotTrigger::Manual).unwrap();
+
+        let backup_dir = get_backup_dir().unwrap();
+        let hashes: Vec<String> = [&first.id, &second.id]
+            .iter()
+            .map(|id| load_snapshot(id).unwrap().1[0].hash.clone())
+            .collect();
+        fs::write(manifest_path(&backup_dir, &first.id).unwrap(), "{\"id\":").unwrap();
+
+        // 読み込めないマニフェストがある間は何も削除しない
+        assert!(referenced_hashes(&backup_dir).is_err());
+        assert!(delete_snapshot(&second.id)
+            .unwrap_err()
+            .contains(&first.id));
+        for hash in &hashes {
+            assert!(object_path(&backup_dir, hash).is_file());
+        }
+    }
+}
@@ -3,6 +3,7 @@
 //! アプリケーション全体で使用するデータ構造を定義します。
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// ファイルツリーのノード
 ///
@@ -41,8 +42,49 @@ impl FileNode {
     }
 }
 
+/// ディレクトリ一覧の1エントリ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryEntry {
+    /// ファイル/ディレクトリ名
+    pub name: String,
+    /// 絶対パス
+    pub path: String,
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub relative_path: String,
+    /// ディレクトリかどうか
+    pub is_directory: bool,
+    /// ファイルサイズ（バイト、ディレクトリの場合は`None`）
+    pub size: Option<u64>,
+    /// 更新日時（UNIXエポックからのミリ秒）
+    pub modified: Option<u64>,
+    /// 表示対象の子要素数（ディレクトリのみ）
+    pub child_count: Option<usize>,
+    /// ファイル種別
+    pub kind: FileKind,
+    /// エディタモード（ファイルのみ、`dashboard-config.json` の `editorModes` に基づく）
+    pub editor_mode: Option<String>,
+}
+
+/// ディレクトリ一覧（ページング対応）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryListing {
+    /// 一覧を取得したディレクトリの絶対パス
+    pub path: String,
+    /// 子要素（ディレクトリが先、名前順）
+    pub entries: Vec<DirectoryEntry>,
+    /// 子要素の総数
+    pub total: usize,
+    /// 取得開始位置
+    pub offset: usize,
+    /// 続きがあるかどうか
+    pub has_more: bool,
+}
+
 /// ファイルの内容と名前
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct FileContent {
     /// ファイルパス
     pub path: String,
@@ -50,6 +92,144 @@ pub struct FileContent {
     pub content: String,
     /// ファイル名
     pub name: String,
+    /// バージョントークン（`write_file` の `expected_version` に渡す。検索結果では`None`）
+    #[serde(default)]
+    pub version_token: Option<String>,
+    /// 更新日時（UNIXエポックからのミリ秒）
+    #[serde(default)]
+    pub modified: Option<u64>,
+    /// エディタモード（`read_file` のみ、`dashboard-config.json` の `editorModes` に基づく）
+    #[serde(default)]
+    pub editor_mode: Option<String>,
+}
+
+/// 書き込み後のファイルのバージョン
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVersion {
+    /// バージョントークン
+    pub version_token: String,
+    /// 更新日時（UNIXエポックからのミリ秒）
+    pub modified: Option<u64>,
+}
+
+/// 書き込み競合の詳細
+///
+/// 読み込み後に他のプロセス（Claude Codeなど）がファイルを変更した場合に返されます。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteConflict {
+    /// 対象ファイルのパス
+    pub path: String,
+    /// 呼び出し元が想定していたバージョントークン
+    pub expected_version: String,
+    /// 現在のディスク上の内容（削除されている場合は`None`）
+    pub current: Option<FileContent>,
+}
+
+/// 書き込みの結果
+///
+/// 競合はエラーではなく結果として返し、フロントエンドで `status` により判別します。
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum WriteResult {
+    /// 書き込みに成功した
+    Written(FileVersion),
+    /// 読み込み後にファイルが変更されていたため書き込まなかった
+    Conflict(WriteConflict),
+}
+
+/// バックアップを作成した操作
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupOperation {
+    /// エディタからの保存
+    Write,
+    /// 検索置換（単一ファイル・一括置換）
+    Replace,
+    /// インポートによる上書き
+    Import,
+    /// バックアップからの復元
+    Restore,
+    /// 手動作成
+    Manual,
+    /// 旧形式のバックアップから移行（作成した操作は不明）
+    Unknown,
+}
+
+/// バックアップのメタデータ
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRecord {
+    /// バックアップID
+    pub id: String,
+    /// 元ファイルの設定ルートからの相対パス（`/` 区切り）
+    pub relative_path: String,
+    /// 作成日時（ISO 8601形式）
+    pub created_at: String,
+    /// 元ファイルのサイズ（バイト）
+    pub size: u64,
+    /// 内容のSHA-256ハッシュ
+    pub hash: String,
+    /// バックアップを作成した操作
+    pub operation: BackupOperation,
+    /// ピン留め（保持ポリシーによって削除されない）
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// バックアップ一覧とストアの使用量
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupList {
+    /// 設定ルートの絶対パス（`relativePath` の基準）
+    pub root: String,
+    /// バックアップ一覧（新しい順）
+    pub backups: Vec<BackupRecord>,
+    /// 論理サイズ（各バックアップの元ファイルサイズの合計、バイト）
+    pub logical_size: u64,
+    /// 物理サイズ（重複排除・圧縮後のディスク使用量、バイト）
+    pub physical_size: u64,
+    /// 保存されている内容（オブジェクト）の数
+    pub object_count: usize,
+}
+
+/// 保持ポリシーによってバックアップを削除する理由
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RetentionReason {
+    /// どの保持ルールにも該当しない
+    NotRetained,
+    /// 合計サイズの上限を超えている
+    OverSizeLimit,
+}
+
+/// 保持ポリシーによる削除対象のバックアップ
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionCandidate {
+    /// 削除対象のバックアップ
+    pub record: BackupRecord,
+    /// 削除する理由
+    pub reason: RetentionReason,
+}
+
+/// 保持ポリシーの適用結果（ドライラン）
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPlan {
+    /// 削除対象のバックアップ（古い順）
+    pub delete: Vec<RetentionCandidate>,
+    /// 残るバックアップ数
+    pub kept_count: usize,
+    /// 残るバックアップのうちピン留めされている数
+    pub pinned_count: usize,
+    /// 現在の物理サイズ（バイト）
+    pub physical_size_before: u64,
+    /// 適用後の物理サイズ（バイト）
+    pub physical_size_after: u64,
+    /// ピン留めや最新のバックアップのため、合計サイズの上限まで減らせない場合は`true`
+    pub size_limit_exceeded: bool,
 }
 
 /// バックアップクリーンアップ結果
@@ -61,17 +241,611 @@ pub struct CleanupResult {
     pub deleted_size_bytes: u64,
     /// エラーメッセージ一覧
     pub errors: Vec<String>,
+    /// 内容が失われていたためマニフェストから取り除いた記録
+    #[serde(default)]
+    pub pruned_records: Vec<BackupRecord>,
 }
 
-/// エクスポート進捗情報
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// スナップショットを作成した契機
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotTrigger {
+    /// 手動作成
+    Manual,
+    /// アーカイブのインポートの直前
+    Import,
+    /// ワークスペース一括置換の直前
+    BulkReplace,
+    /// スナップショットからの復元の直前
+    Restore,
+}
+
+/// スナップショットの概要
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    /// スナップショットID
+    pub id: String,
+    /// 名前（自動作成の場合は`None`）
+    pub name: Option<String>,
+    /// 作成日時（ISO 8601形式）
+    pub created_at: String,
+    /// 作成した契機
+    pub trigger: SnapshotTrigger,
+    /// 含まれるファイル数
+    pub file_count: usize,
+    /// 含まれるファイルのサイズの合計（バイト）
+    pub total_size: u64,
+}
+
+/// スナップショットと現在の状態の差分の種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotChangeKind {
+    /// スナップショットの作成後に追加された（復元すると削除される）
+    Added,
+    /// スナップショットの作成後に削除された（復元すると再作成される）
+    Removed,
+    /// スナップショットの作成後に変更された（復元すると元に戻る）
+    Modified,
+}
+
+/// スナップショットと現在の状態で異なるファイル
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotChange {
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub relative_path: String,
+    /// 差分の種類
+    pub kind: SnapshotChangeKind,
+    /// スナップショットでのサイズ（バイト）
+    pub snapshot_size: Option<u64>,
+    /// 現在のサイズ（バイト）
+    pub current_size: Option<u64>,
+}
+
+/// スナップショットと現在の状態の差分
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotDiff {
+    /// 比較したスナップショット
+    pub snapshot: SnapshotInfo,
+    /// 異なるファイル（相対パス順）
+    pub changes: Vec<SnapshotChange>,
+    /// 同一のファイル数
+    pub unchanged_count: usize,
+}
+
+/// スナップショットの復元結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRestoreResult {
+    /// スナップショットの内容を書き込んだファイル（相対パス）
+    pub restored: Vec<String>,
+    /// スナップショットに存在しないため削除したファイル（相対パス）
+    pub deleted: Vec<String>,
+    /// 復元直前の状態を保存したスナップショットのID（変更がない場合は`None`）
+    pub safety_snapshot_id: Option<String>,
+}
+
+/// Gitの変更履歴のコミット
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitInfo {
+    /// コミットID
+    pub id: String,
+    /// 短縮したコミットID
+    pub short_id: String,
+    /// コミットメッセージの1行目
+    pub summary: String,
+    /// コミットメッセージ全体
+    pub message: String,
+    /// 作成者名
+    pub author: String,
+    /// 作成日時（RFC 3339）
+    pub time: String,
+}
+
+/// Gitの変更の種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitChangeKind {
+    /// 追加された
+    Added,
+    /// 変更された
+    Modified,
+    /// 削除された
+    Deleted,
+    /// 名前が変更された
+    Renamed,
+}
+
+/// コミットされていない変更
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusEntry {
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub path: String,
+    /// 変更の種類
+    pub kind: GitChangeKind,
+}
+
+/// Gitの変更履歴の状態
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHistoryStatus {
+    /// ダッシュボード設定で有効になっているか
+    pub enabled: bool,
+    /// 設定ルートにリポジトリが存在するか
+    pub initialized: bool,
+    /// 最新のコミット（コミットがない場合は`None`）
+    pub head: Option<GitCommitInfo>,
+    /// ダッシュボードの外で行われた、コミットされていない変更
+    pub changes: Vec<GitStatusEntry>,
+}
+
+/// 2つのコミット間で変更されたファイルの差分
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileDiff {
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub path: String,
+    /// 名前変更前の相対パス
+    pub old_path: Option<String>,
+    /// 変更の種類
+    pub kind: GitChangeKind,
+    /// 統合diff形式のパッチ（バイナリの場合は空）
+    pub patch: String,
+    /// 追加行数
+    pub additions: usize,
+    /// 削除行数
+    pub deletions: usize,
+}
+
+/// 差分を比較する内容の取得元
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum DiffSource {
+    /// 現在のファイル（存在しない場合は空として扱う）
+    Current {
+        /// ファイルのパス（~/.claude/で始まる形式、設定ルートからの相対パスも可）
+        path: String,
+    },
+    /// バックアップストアのバックアップ
+    Backup {
+        /// バックアップID
+        backup_id: String,
+    },
+    /// スナップショットに含まれるファイル
+    Snapshot {
+        /// スナップショットID
+        snapshot_id: String,
+        /// 設定ルートからの相対パス
+        path: String,
+    },
+    /// Gitの変更履歴のリビジョン時点のファイル
+    GitRevision {
+        /// コミットID
+        revision: String,
+        /// 設定ルートからの相対パス
+        path: String,
+    },
+    /// アーカイブ（ZIP・tar.gz・フォルダー）のエントリ
+    ZipEntry {
+        /// アーカイブのパス
+        zip_path: String,
+        /// エントリ名
+        entry: String,
+    },
+    /// 任意のテキスト（エディタの未保存の内容など）
+    Text {
+        /// 内容
+        content: String,
+        /// 表示名
+        label: Option<String>,
+    },
+}
+
+/// 差分の出力形式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffFormat {
+    /// 行ごとの情報を含むハンク
+    #[default]
+    Structured,
+    /// 統合diff形式のテキスト
+    Unified,
+}
+
+/// 差分の計算オプション
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiffOptions {
+    /// 出力形式
+    pub format: DiffFormat,
+    /// ハンクの前後に含める変更のない行数（省略時は3行）
+    pub context_lines: Option<usize>,
+    /// JSONとして解析し、キーの順序と空白を無視して比較するか
+    pub semantic: bool,
+}
+
+/// 差分の行の種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffLineKind {
+    /// 変更のない行
+    Context,
+    /// 追加された行
+    Added,
+    /// 削除された行
+    Removed,
+}
+
+/// 差分の1行
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    /// 行の種類
+    pub kind: DiffLineKind,
+    /// 行の内容（改行を除く）
+    pub content: String,
+    /// 比較元の行番号（1始まり、追加された行は`None`）
+    pub old_line: Option<usize>,
+    /// 比較先の行番号（1始まり、削除された行は`None`）
+    pub new_line: Option<usize>,
+}
+
+/// 差分のハンク（変更箇所とその前後の行）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// ハンクの番号（0始まり、選択したハンクの復元で指定）
+    pub index: usize,
+    /// 比較元の開始行（1始まり）
+    pub old_start: usize,
+    /// 比較元の行数
+    pub old_lines: usize,
+    /// 比較先の開始行（1始まり）
+    pub new_start: usize,
+    /// 比較先の行数
+    pub new_lines: usize,
+    /// `@@ -1,3 +1,4 @@` 形式の見出し
+    pub header: String,
+    /// 行（統合diff形式では空）
+    pub lines: Vec<DiffLine>,
+}
+
+/// 差分の統計
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStats {
+    /// 追加行数
+    pub additions: usize,
+    /// 削除行数
+    pub deletions: usize,
+    /// ハンク数
+    pub hunks: usize,
+}
+
+/// JSONの値の変更の種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JsonChangeKind {
+    /// 追加されたキー・要素
+    Added,
+    /// 削除されたキー・要素
+    Removed,
+    /// 値が変更された
+    Changed,
+}
+
+/// JSONの値の変更
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonChange {
+    /// 変更された値の位置（JSON Pointer、ルートは空文字列）
+    pub path: String,
+    /// 変更の種類
+    pub kind: JsonChangeKind,
+    /// 比較元の値
+    pub old_value: Option<serde_json::Value>,
+    /// 比較先の値
+    pub new_value: Option<serde_json::Value>,
+}
+
+/// 差分の計算結果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffResult {
+    /// 比較元の表示名
+    pub old_label: String,
+    /// 比較先の表示名
+    pub new_label: String,
+    /// 差分がないか
+    pub identical: bool,
+    /// 統計
+    pub stats: DiffStats,
+    /// ハンク
+    pub hunks: Vec<DiffHunk>,
+    /// 統合diff形式のテキスト（`unified` 形式の場合のみ）
+    pub unified: Option<String>,
+    /// JSONの値の変更（意味的な比較の場合のみ）
+    pub json_changes: Option<Vec<JsonChange>>,
+    /// JSONとして解析できなかった理由（テキストとして比較した場合）
+    pub semantic_error: Option<String>,
+}
+
+/// エクスポートするファイルの選択条件
+///
+/// `includeGlobs` と `kinds` がどちらも空の場合はすべてのファイルを対象とします。
+/// どちらかを指定した場合は、いずれかのglobに一致するか、いずれかの種別に該当するファイルを対象とします。
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportSelection {
+    /// 対象にするファイルのglob（設定ルートからの相対パス）
+    pub include_globs: Vec<String>,
+    /// 対象から除くファイルのglob
+    pub exclude_globs: Vec<String>,
+    /// 対象にするファイル種別
+    pub kinds: Vec<FileKind>,
+    /// 対象のサブエージェントがfrontmatterの `skills` で参照するスキルも含めるか
+    pub include_referenced_skills: bool,
+    /// APIトークンなどの秘密情報をプレースホルダーに置き換えるか（デフォルトは置き換える）
+    pub redact_secrets: bool,
+}
+
+impl Default for ExportSelection {
+    fn default() -> Self {
+        Self {
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            kinds: Vec::new(),
+            include_referenced_skills: false,
+            redact_secrets: true,
+        }
+    }
+}
+
+/// エクスポート・インポートの形式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveFormat {
+    /// ZIPファイル
+    Zip,
+    /// gzipで圧縮したtarファイル（`.tar.gz`・`.tgz`）
+    TarGz,
+    /// ローカルフォルダー
+    Directory,
+}
+
+/// 秘密情報と判定した理由
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RedactionReason {
+    /// キー名（`*_TOKEN`、`Authorization` など）
+    KeyName,
+    /// 値の形式（`ghp_`、`sk-ant-` などで始まるトークン）
+    ValuePattern,
+}
+
+/// エクスポート時にプレースホルダーへ置き換えた秘密情報（値は含まない）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactedSecret {
+    /// 設定ルートからの相対パス
+    pub path: String,
+    /// JSONの場合は値の位置（JSON Pointer）
+    pub pointer: Option<String>,
+    /// テキストの場合は行番号（1始まり）
+    pub line: Option<usize>,
+    /// プレースホルダーの名前（`${REDACTED:NAME}` の `NAME`）
+    pub name: String,
+    /// 秘密情報と判定した理由
+    pub reason: RedactionReason,
+}
+
+/// アーカイブに含まれる秘密情報のプレースホルダー
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretPlaceholder {
+    /// プレースホルダーの名前
+    pub name: String,
+    /// プレースホルダーを含むファイル（相対パス順）
+    pub files: Vec<String>,
+}
+
+/// エクスポートしたファイル
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifestEntry {
+    /// 設定ルートからの相対パス（`/` 区切り）
+    pub path: String,
+    /// ファイル種別
+    pub kind: FileKind,
+    /// サイズ（バイト）
+    pub size: u64,
+    /// 内容のSHA-256ハッシュ（hex）
+    pub sha256: String,
+}
+
+/// エクスポートしたアーカイブのマニフェスト（`.ccsd-export.json`）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    /// マニフェストの形式のバージョン
+    pub format_version: u32,
+    /// エクスポートしたダッシュボードのバージョン
+    pub dashboard_version: String,
+    /// エクスポート時のClaude Codeのバージョン（取得できない場合は`None`）
+    pub claude_code_version: Option<String>,
+    /// エクスポート日時（RFC 3339）
+    pub exported_at: String,
+    /// 含まれるファイル（相対パス順）
+    pub files: Vec<ExportManifestEntry>,
+    /// プレースホルダーに置き換えた秘密情報
+    #[serde(default)]
+    pub redactions: Vec<RedactedSecret>,
+}
+
+/// プラグインの作者
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginAuthor {
+    /// 名前
+    pub name: String,
+    /// メールアドレス
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// プラグインのメタデータ（`.claude-plugin/plugin.json` にそのまま書き込む）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginMetadata {
+    /// プラグイン名（小文字の英数字とハイフン）
+    pub name: String,
+    /// バージョン（セマンティックバージョニング）
+    pub version: String,
+    /// 説明
+    pub description: String,
+    /// 作者
+    pub author: PluginAuthor,
+}
+
+/// プラグインに含める内容
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PluginSelection {
+    /// スキル名（`skills/<name>/` 配下をすべて含める）
+    pub skills: Vec<String>,
+    /// サブエージェント（設定ルートからの相対パス、例: `agents/reviewer.md`）
+    pub agents: Vec<String>,
+    /// スラッシュコマンド（設定ルートからの相対パス、例: `commands/review.md`）
+    pub commands: Vec<String>,
+    /// `settings.json` のフックを `hooks/hooks.json` として含めるか
+    pub include_hooks: bool,
+    /// APIトークンなどの秘密情報をプレースホルダーに置き換えるか（デフォルトは置き換える）
+    pub redact_secrets: bool,
+}
+
+impl Default for PluginSelection {
+    fn default() -> Self {
+        Self {
+            skills: Vec::new(),
+            agents: Vec::new(),
+            commands: Vec::new(),
+            include_hooks: false,
+            redact_secrets: true,
+        }
+    }
+}
+
+/// プラグインに含めることができる内容
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginComponents {
+    /// `SKILL.md` があるスキルの名前
+    pub skills: Vec<String>,
+    /// サブエージェント（設定ルートからの相対パス）
+    pub agents: Vec<String>,
+    /// スラッシュコマンド（設定ルートからの相対パス）
+    pub commands: Vec<String>,
+    /// `settings.json` にフックが設定されているか
+    pub has_hooks: bool,
+}
+
+/// プラグインの検証結果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginValidation {
+    /// エクスポートできない問題
+    pub errors: Vec<String>,
+    /// エクスポートはできるが確認が必要な問題
+    pub warnings: Vec<String>,
+    /// プラグインに含まれるファイル（プラグインのルートからの相対パス順）
+    pub files: Vec<String>,
+}
+
+/// プラグインのエクスポート結果
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginExportResult {
+    /// 書き込んだファイル（プラグインのルートからの相対パス順）
+    pub files: Vec<String>,
+    /// 確認が必要な問題
+    pub warnings: Vec<String>,
+    /// プレースホルダーに置き換えた秘密情報
+    pub redactions: Vec<RedactedSecret>,
+    /// エントリを追加した `marketplace.json` のパス
+    pub marketplace_path: Option<String>,
+}
+
+/// バックグラウンドジョブの種類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    /// アーカイブへのエクスポート
+    Export,
+    /// アーカイブからのインポート
+    Import,
+}
+
+impl JobKind {
+    /// ジョブIDの接頭辞
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobKind::Export => "export",
+            JobKind::Import => "import",
+        }
+    }
+}
+
+/// バックグラウンドジョブの終了状態
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    /// 完了
+    Completed,
+    /// キャンセル（変更は元に戻されている）
+    Cancelled,
+    /// 失敗
+    Failed,
+}
+
+/// エクスポート・インポートの進捗情報（`job:progress` イベント）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 pub struct ExportProgress {
+    /// ジョブID
+    pub job_id: String,
+    /// ジョブの種類
+    pub kind: JobKind,
     /// 総ファイル数
     pub total_files: u32,
     /// 処理済みファイル数
     pub processed_files: u32,
     /// 現在処理中のファイル
     pub current_file: String,
+    /// 総バイト数（展開後のサイズ）
+    pub total_bytes: u64,
+    /// 処理済みバイト数
+    pub processed_bytes: u64,
+}
+
+/// バックグラウンドジョブの終了通知（`job:finished` イベント）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFinishedEvent {
+    /// ジョブID
+    pub job_id: String,
+    /// ジョブの種類
+    pub kind: JobKind,
+    /// 終了状態
+    pub status: JobStatus,
+    /// 完了した場合の結果（エクスポートはマニフェスト、インポートはインポート結果）
+    pub result: Option<serde_json::Value>,
+    /// 失敗した場合のエラーメッセージ
+    pub error: Option<String>,
 }
 
 /// インポート結果
@@ -87,6 +861,190 @@ pub struct ImportResult {
     pub errors: Vec<String>,
     /// バックアップを作成したかどうか
     pub backup_created: bool,
+    /// インポート直前の状態を保存したスナップショットのID
+    #[serde(default)]
+    pub snapshot_id: Option<String>,
+    /// 値が指定されず、プレースホルダーのまま展開した秘密情報の名前
+    #[serde(default)]
+    pub unresolved_secrets: Vec<String>,
+}
+
+/// インポート計画でのエントリの分類
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportEntryStatus {
+    /// 現在存在しないファイル
+    New,
+    /// 現在のファイルと同じ内容
+    Identical,
+    /// 現在のファイルと内容が異なる
+    Modified,
+    /// インポートされない（理由は `reason`）
+    Excluded,
+}
+
+/// インポート計画を実行する際のファイルごとの処理
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportChoice {
+    /// 現在のファイルを置き換える
+    Overwrite,
+    /// インポートしない
+    Skip,
+    /// 現在のファイルを残し、`<名前>.imported.<拡張子>` として保存する
+    KeepBoth,
+    /// JSONファイルを現在の内容とマージする
+    Merge,
+}
+
+impl ImportEntryStatus {
+    /// 処理を指定しなかった場合の処理
+    pub fn default_choice(self) -> ImportChoice {
+        match self {
+            ImportEntryStatus::New | ImportEntryStatus::Modified => ImportChoice::Overwrite,
+            ImportEntryStatus::Identical | ImportEntryStatus::Excluded => ImportChoice::Skip,
+        }
+    }
+}
+
+/// インポート計画のエントリ
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlanEntry {
+    /// アーカイブ内のパス（設定ルートからの相対パス）
+    pub path: String,
+    /// 分類
+    pub status: ImportEntryStatus,
+    /// 展開後のサイズ（バイト）
+    pub size: u64,
+    /// 除外される理由
+    pub reason: Option<String>,
+    /// 変更されるファイルの差分（現在の内容 → アーカイブの内容、テキストの場合のみ）
+    pub diff: Option<DiffResult>,
+    /// 処理を指定しなかった場合の処理
+    pub default_choice: ImportChoice,
+}
+
+/// インポート計画の分類ごとの件数
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlanSummary {
+    /// 新規
+    pub new_files: usize,
+    /// 同一
+    pub identical: usize,
+    /// 変更
+    pub modified: usize,
+    /// 除外
+    pub excluded: usize,
+}
+
+/// インポート計画（`import_zip` に計画IDを渡して実行）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    /// 計画ID
+    pub plan_id: String,
+    /// アーカイブのパス
+    pub zip_path: String,
+    /// 作成日時（RFC 3339）
+    pub created_at: String,
+    /// エントリ（アーカイブ内の順序）
+    pub entries: Vec<ImportPlanEntry>,
+    /// 分類ごとの件数
+    pub summary: ImportPlanSummary,
+}
+
+/// JSONのマージでキーが衝突した場合の解決方法
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// インポートする側の値を採用
+    #[default]
+    Theirs,
+    /// 現在の値を残す
+    Ours,
+    /// 衝突ごとに指定された値を採用（未指定の衝突がある場合は書き込まない）
+    Ask,
+}
+
+/// 衝突したキーで採用する値
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictSide {
+    /// 現在の値
+    Ours,
+    /// インポートする値
+    Theirs,
+}
+
+/// JSONファイルのマージの指定
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonMergeOptions {
+    /// 衝突の解決方法
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+    /// 衝突ごとに採用する値（キー: JSON Pointer、戦略より優先）
+    #[serde(default)]
+    pub resolutions: HashMap<String, ConflictSide>,
+}
+
+/// アーカイブのインポートでのJSONファイルのマージの指定
+///
+/// 指定した場合、計画で処理を選択しなかった既存の設定ファイル
+/// （`settings.json`、`settings.local.json`、`.mcp.json`）は上書きせずにマージします。
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZipMergeOptions {
+    /// 衝突の解決方法
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+    /// ファイルごとの衝突の解決（キー: アーカイブ内のパス → JSON Pointer）
+    #[serde(default)]
+    pub resolutions: HashMap<String, HashMap<String, ConflictSide>>,
+}
+
+impl ZipMergeOptions {
+    /// アーカイブ内のファイルに適用するマージの指定
+    pub fn for_file(&self, name: &str) -> JsonMergeOptions {
+        JsonMergeOptions {
+            strategy: self.strategy,
+            resolutions: self.resolutions.get(name).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// JSONのマージで衝突したキー
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeConflict {
+    /// 値の位置（JSON Pointer）
+    pub pointer: String,
+    /// 現在の値
+    pub ours: serde_json::Value,
+    /// インポートする値
+    pub theirs: serde_json::Value,
+    /// 採用した値（`ask` で未指定の場合は`None`、マージ結果には現在の値が入る）
+    pub resolution: Option<ConflictSide>,
+}
+
+/// JSONファイルのマージ結果のプレビュー
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonMergePreview {
+    /// 設定ルートからの相対パス
+    pub path: String,
+    /// 現在のファイルが存在するか（存在しない場合はインポートする内容をそのまま書き込む）
+    pub exists: bool,
+    /// マージ後の内容
+    pub merged: String,
+    /// 衝突したキー
+    pub conflicts: Vec<MergeConflict>,
+    /// 解決方法が指定されていない衝突の数
+    pub unresolved: usize,
+    /// 現在の内容から変更されるか
+    pub changed: bool,
 }
 
 /// ファイル存在チェック結果
@@ -100,7 +1058,7 @@ pub struct FileExistsInfo {
     pub relative_path: String,
 }
 
-/// ZIPファイル内のファイル情報
+/// アーカイブ内のファイル情報
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ZipFileInfo {
     /// ファイル名
@@ -146,3 +1104,95 @@ impl ReplaceResult {
         }
     }
 }
+
+/// 設定ルートの解決元
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigRootSource {
+    /// アプリ内で明示的に指定されたパス
+    Override,
+    /// 登録済みプロファイル
+    Profile,
+    /// 環境変数 `CLAUDE_CONFIG_DIR`
+    Env,
+    /// デフォルトの `~/.claude`
+    Default,
+}
+
+/// 設定ルートのプロファイル
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProfile {
+    /// プロファイル名（一意）
+    pub name: String,
+    /// 設定ルートの絶対パス
+    pub path: String,
+}
+
+/// アクティブな設定ルートの情報
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRootInfo {
+    /// 設定ルートの絶対パス
+    pub path: String,
+    /// 解決元
+    pub source: ConfigRootSource,
+    /// プロファイル名（プロファイル選択時のみ）
+    pub profile_name: Option<String>,
+    /// ディレクトリが存在するかどうか
+    pub exists: bool,
+}
+
+/// ファイル作成/更新/削除イベントのペイロード
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FsChangeEvent {
+    /// 変更されたファイル/ディレクトリの絶対パス
+    pub paths: Vec<String>,
+}
+
+/// ファイル名変更イベントのペイロード
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FsRenameEvent {
+    /// 変更前の絶対パス
+    pub from: String,
+    /// 変更後の絶対パス
+    pub to: String,
+}
+
+/// 設定ファイルの種別
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FileKind {
+    /// スキル（`skills/` 配下）
+    Skill,
+    /// サブエージェント（`agents/` 配下）
+    Agent,
+    /// スラッシュコマンド（`commands/` 配下）
+    Command,
+    /// 設定ファイル（`settings.json`, `.mcp.json` など）
+    Settings,
+    /// メモリ（`CLAUDE.md` など）
+    Memory,
+    /// 会話履歴（`projects/` 配下の `.jsonl`）
+    Transcript,
+    /// その他
+    Other,
+}
+
+impl FileKind {
+    /// 文字列（`skill`, `agent` など）から種別を取得
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "skill" | "skills" => Some(Self::Skill),
+            "agent" | "agents" => Some(Self::Agent),
+            "command" | "commands" => Some(Self::Command),
+            "settings" | "setting" => Some(Self::Settings),
+            "memory" => Some(Self::Memory),
+            "transcript" | "transcripts" => Some(Self::Transcript),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
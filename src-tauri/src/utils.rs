@@ -3,9 +3,16 @@
 //! アプリケーション全体で使用する共通関数を提供します。
 
 use crate::error::AppError;
+use crate::types::{ConfigRootSource, FileKind};
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
 
-/// 除外するディレクトリ一覧（一元管理）
+/// 除外するディレクトリ一覧（`dashboard-config.json` 未設定時のデフォルト）
 pub const EXCLUDED_DIRS: &[&str] = &[
     "cache",
     "tasks",
@@ -18,21 +25,91 @@ pub const EXCLUDED_DIRS: &[&str] = &[
     "backups",
 ];
 
-/// 許可されるファイル拡張子
+/// 許可されるファイル拡張子（`dashboard-config.json` 未設定時のデフォルト）
 pub const ALLOWED_EXTENSIONS: &[&str] = &["md", "json", "jsonl"];
 
-/// Claudeディレクトリのパスを取得
+/// アトミック書き込みの一時ファイルに付けるサフィックス
+pub const TEMP_FILE_SUFFIX: &str = ".ccsd-tmp";
+
+/// 設定ルートを指定する環境変数名
+pub const CLAUDE_CONFIG_DIR_ENV: &str = "CLAUDE_CONFIG_DIR";
+
+/// アプリ内で選択された設定ルート（プロファイルまたは明示指定）
+///
+/// `None` の場合は環境変数またはデフォルトの `~/.claude` が使用されます。
+static ACTIVE_CLAUDE_DIR: Lazy<RwLock<Option<(PathBuf, ConfigRootSource)>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// アクティブな設定ルートを設定
+///
+/// # Arguments
+///
+/// * `root` - 設定ルートとその解決元。`None` で環境変数/デフォルトに戻す
+pub fn set_active_claude_dir(root: Option<(PathBuf, ConfigRootSource)>) {
+    let mut active = ACTIVE_CLAUDE_DIR
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    *active = root;
+}
+
+/// `~/` で始まるパスをホームディレクトリ基準に展開
+///
+/// # Errors
+///
+/// `~/` 展開時にホームディレクトリが見つからない場合はエラーを返します。
+pub fn expand_home(path: &str) -> Result<PathBuf, AppError> {
+    if path == "~" {
+        return dirs::home_dir().ok_or(AppError::HomeNotFound);
+    }
+    match path.strip_prefix("~/") {
+        Some(suffix) => Ok(dirs::home_dir().ok_or(AppError::HomeNotFound)?.join(suffix)),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// 設定ルートとその解決元を取得
+///
+/// 以下の優先順位で解決します:
+/// 1. アプリ内で選択されたプロファイル/明示指定（`set_active_claude_dir`）
+/// 2. 環境変数 `CLAUDE_CONFIG_DIR`
+/// 3. `~/.claude`
 ///
 /// # Errors
 ///
 /// ホームディレクトリが見つからない場合はエラーを返します。
-pub fn get_claude_dir() -> Result<PathBuf, AppError> {
+pub fn resolve_claude_dir() -> Result<(PathBuf, ConfigRootSource), AppError> {
+    let active = ACTIVE_CLAUDE_DIR
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    if let Some(root) = active {
+        return Ok(root);
+    }
+
+    if let Ok(env_dir) = std::env::var(CLAUDE_CONFIG_DIR_ENV) {
+        if !env_dir.trim().is_empty() {
+            return Ok((expand_home(env_dir.trim())?, ConfigRootSource::Env));
+        }
+    }
+
     let home = dirs::home_dir().ok_or(AppError::HomeNotFound)?;
-    Ok(home.join(".claude"))
+    Ok((home.join(".claude"), ConfigRootSource::Default))
+}
+
+/// Claudeディレクトリ（アクティブな設定ルート）のパスを取得
+///
+/// # Errors
+///
+/// ホームディレクトリが見つからない場合はエラーを返します。
+pub fn get_claude_dir() -> Result<PathBuf, AppError> {
+    resolve_claude_dir().map(|(path, _)| path)
 }
 
 /// パスに除外ディレクトリが含まれているかチェック
 ///
+/// 設定ルート配下のパスはダッシュボード設定の除外ディレクトリ・除外globで判定し、
+/// それ以外のパスは親ディレクトリ名のみで判定します。
+///
 /// # Arguments
 ///
 /// * `path_str` - チェックするパス文字列
@@ -41,11 +118,34 @@ pub fn get_claude_dir() -> Result<PathBuf, AppError> {
 ///
 /// 除外ディレクトリが含まれている場合は`true`
 pub fn is_excluded_path(path_str: &str) -> bool {
-    EXCLUDED_DIRS.iter().any(|dir| {
-        path_str.contains(&format!("/{dir}/"))
+    let config = crate::config::current();
+    let path = Path::new(path_str);
+    if let Some(relative) = get_claude_dir()
+        .ok()
+        .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+    {
+        return config.is_excluded_relative(&relative);
+    }
+    path.parent().is_some_and(|parent| {
+        parent
+            .components()
+            .any(|c| config.is_excluded_dir_name(&c.as_os_str().to_string_lossy()))
     })
 }
 
+/// 設定ルートからの相対パスに除外ディレクトリが含まれているかチェック
+///
+/// # Arguments
+///
+/// * `relative` - 設定ルートからの相対パス
+///
+/// # Returns
+///
+/// いずれかの階層が除外ディレクトリ、または除外globに一致する場合は`true`
+pub fn is_excluded_relative_path(relative: &Path) -> bool {
+    crate::config::current().is_excluded_relative(relative)
+}
+
 /// ファイル拡張子が許可されているかチェック
 ///
 /// # Arguments
@@ -54,30 +154,253 @@ pub fn is_excluded_path(path_str: &str) -> bool {
 ///
 /// # Returns
 ///
-/// 許可された拡張子の場合は`true`
+/// ダッシュボード設定で許可された拡張子の場合は`true`
 pub fn is_allowed_extension(path: &std::path::Path) -> bool {
-    path.extension()
+    crate::config::current().is_allowed_extension(path)
+}
+
+/// 設定ルート配下のダッシュボード表示対象（エクスポート対象）のファイルを列挙
+///
+/// ダッシュボード設定の除外ディレクトリ・除外globに一致するパスは配下ごとスキップし、
+/// 許可されていない拡張子のファイルは含みません。
+///
+/// # Arguments
+///
+/// * `root` - 設定ルート
+pub fn walk_visible_files(root: &Path) -> impl Iterator<Item = walkdir::DirEntry> + '_ {
+    walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(move |e| {
+            e.path()
+                .strip_prefix(root)
+                .map_or(true, |rel| !is_excluded_relative_path(rel))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && is_allowed_extension(e.path()))
+}
+
+/// 設定ルートからの相対パスでファイル種別を判定
+///
+/// # Arguments
+///
+/// * `relative` - 設定ルートからの相対パス
+///
+/// # Returns
+///
+/// ファイル種別（該当しない場合は`FileKind::Other`）
+pub fn classify_file_kind(relative: &Path) -> FileKind {
+    let first = relative
+        .components()
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+        .unwrap_or("");
+    let file_name = relative
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    let extension = relative
+        .extension()
         .and_then(|e| e.to_str())
-        .map(|ext| ALLOWED_EXTENSIONS.contains(&ext))
-        .unwrap_or(false)
+        .unwrap_or("");
+
+    match first {
+        "skills" => return FileKind::Skill,
+        "agents" => return FileKind::Agent,
+        "commands" => return FileKind::Command,
+        _ => {}
+    }
+
+    if file_name == "CLAUDE.md"
+        || file_name == "CLAUDE.local.md"
+        || relative.components().any(|c| c.as_os_str() == "memory")
+    {
+        return FileKind::Memory;
+    }
+
+    if first == "projects" && extension == "jsonl" {
+        return FileKind::Transcript;
+    }
+
+    if matches!(
+        file_name,
+        "settings.json" | "settings.local.json" | ".mcp.json" | "mcp.json"
+    ) {
+        return FileKind::Settings;
+    }
+
+    FileKind::Other
 }
 
-/// パスがClaude配下かどうかをセキュリティチェック
+/// YAML frontmatter（先頭の `---` で囲まれた部分）から値を取得
 ///
 /// # Arguments
 ///
-/// * `path` - チェックするパス
-/// * `claude_dir` - Claudeディレクトリのパス
+/// * `content` - ファイル内容
+/// * `key` - 取得するキー（例: `description`）
+///
+/// # Returns
+///
+/// クォートを除去した値（frontmatterまたはキーが存在しない場合は`None`）
+pub fn extract_frontmatter_field(content: &str, key: &str) -> Option<String> {
+    if !content.starts_with("---") {
+        return None;
+    }
+
+    let parts: Vec<&str> = content.splitn(3, "---").collect();
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let prefix = format!("{key}:");
+    parts[1].lines().find_map(|line| {
+        let value = line.trim().strip_prefix(&prefix)?.trim();
+        Some(value.trim_matches('"').trim_matches('\'').to_string())
+    })
+}
+
+/// YAML frontmatterから一覧の値を取得
+///
+/// `key: a, b`・`key: [a, b]`・次の行からの `- a` 形式のいずれにも対応します。
+///
+/// # Arguments
+///
+/// * `content` - ファイル内容
+/// * `key` - 取得するキー（例: `skills`）
+///
+/// # Returns
+///
+/// クォートを除去した値の一覧（frontmatterまたはキーが存在しない場合は空）
+pub fn extract_frontmatter_list(content: &str, key: &str) -> Vec<String> {
+    let unquote = |value: &str| value.trim().trim_matches('"').trim_matches('\'').to_string();
+
+    let Some(value) = extract_frontmatter_field(content, key) else {
+        return Vec::new();
+    };
+    if !value.is_empty() {
+        return value
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .split(',')
+            .map(unquote)
+            .filter(|v| !v.is_empty())
+            .collect();
+    }
+
+    // 値が空の場合は続く `- item` 行を一覧として読む
+    let frontmatter = content.split("---").nth(1).unwrap_or("");
+    let prefix = format!("{key}:");
+    frontmatter
+        .lines()
+        .skip_while(|line| !line.trim().starts_with(&prefix))
+        .skip(1)
+        .map_while(|line| line.trim().strip_prefix('-').map(unquote))
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+/// 内容からバージョントークン（SHA-256のhex）を生成
+///
+/// # Arguments
+///
+/// * `content` - ファイル内容
+///
+/// # Returns
+///
+/// 楽観的排他制御に使用するトークン
+pub fn content_version_token(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// ファイルの更新日時をUNIXエポックからのミリ秒で取得
+///
+/// # Arguments
+///
+/// * `metadata` - ファイルのメタデータ
+///
+/// # Returns
+///
+/// 更新日時（取得できない場合は`None`）
+pub fn modified_millis(metadata: &fs::Metadata) -> Option<u64> {
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    u64::try_from(modified.as_millis()).ok()
+}
+
+/// 書き込み先と同じディレクトリの一時ファイルのパスを生成
+///
+/// 一時ファイルは隠しファイルとし、末尾に [`TEMP_FILE_SUFFIX`] を付けます
+/// （ファイル監視・変更履歴の対象外）。書き込み後に書き込み先へリネームしてください。
 ///
 /// # Errors
 ///
-/// パスがClaude配下でない場合はエラーを返します。
-pub fn validate_path_security(path: &Path, claude_dir: &Path) -> Result<(), AppError> {
-    if !path.starts_with(claude_dir) {
-        return Err(AppError::AccessDenied(
-            "only files in ~/.claude/ are allowed".to_string(),
-        ));
+/// パスにファイル名が含まれない場合はエラーを返します。
+pub fn temp_path_for(path: &Path) -> io::Result<PathBuf> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+    Ok(parent.join(format!(
+        ".{file_name}.{}-{}{TEMP_FILE_SUFFIX}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )))
+}
+
+/// ファイルをアトミックに書き込む
+///
+/// 同じディレクトリの一時ファイルに書き込んでfsyncした後にリネームするため、
+/// 書き込み途中でクラッシュしても元のファイルが壊れることはありません。
+/// 既存ファイルのパーミッションは維持されます。
+///
+/// # Arguments
+///
+/// * `path` - 書き込み先のパス
+/// * `contents` - 書き込む内容
+///
+/// # Errors
+///
+/// 一時ファイルの作成、書き込み、リネームに失敗した場合はエラーを返します。
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp_path = temp_path_for(path)?;
+    let parent = tmp_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let result = (|| {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&tmp_path, metadata.permissions())?;
+        }
+
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
     }
+
+    // リネーム自体を永続化するためディレクトリもfsync（対応していない環境では無視）
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(parent) {
+        let _ = dir.sync_all();
+    }
+
     Ok(())
 }
 
@@ -95,7 +418,7 @@ pub fn generate_backup_name(file_name: &str) -> String {
     format!("{file_name}_{timestamp}")
 }
 
-/// ZIPエントリ名に除外ディレクトリが含まれているかチェック
+/// ZIPエントリ名に除外ディレクトリが含まれているか、除外globに一致するかチェック
 ///
 /// # Arguments
 ///
@@ -105,12 +428,8 @@ pub fn generate_backup_name(file_name: &str) -> String {
 ///
 /// 除外ディレクトリが含まれている場合は`true`
 pub fn is_excluded_zip_entry(name: &str) -> bool {
-    EXCLUDED_DIRS.iter().any(|dir| {
-        name.contains(&format!("{dir}/"))
-            || name.contains(&format!("{dir}\\"))
-            || name.starts_with(&format!("{dir}/"))
-            || name.starts_with(&format!("{dir}\\"))
-    })
+    let normalized = name.replace('\\', "/");
+    crate::config::current().is_excluded_relative(Path::new(normalized.trim_start_matches('/')))
 }
 
 /// パス文字列を正規化
@@ -175,10 +494,68 @@ pub fn normalize_claude_path_strict(path: &str, claude_dir: &Path) -> Option<Pat
     }
 }
 
+/// テスト用に設定ルートを一時ディレクトリへ切り替えるガード
+///
+/// 設定ルートはプロセス全体で共有されるため、ガードの生存中は
+/// 他のテストが設定ルートを切り替えないようロックを保持します。
+#[cfg(test)]
+pub(crate) struct TestClaudeDir {
+    pub dir: tempfile::TempDir,
+    _guard: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl TestClaudeDir {
+    pub fn new() -> Self {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let guard = LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let dir = tempfile::tempdir().expect("一時ディレクトリの作成に失敗しました");
+        set_active_claude_dir(Some((dir.path().to_path_buf(), ConfigRootSource::Override)));
+        Self { dir, _guard: guard }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// 設定ルートからの相対パスにファイルを書き込む（親ディレクトリは作成する）
+    pub fn write(&self, relative: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.path().join(relative);
+        fs::create_dir_all(path.parent().expect("親ディレクトリがありません"))
+            .expect("ディレクトリの作成に失敗しました");
+        fs::write(&path, contents).expect("ファイルの書き込みに失敗しました");
+        path
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestClaudeDir {
+    fn drop(&mut self) {
+        set_active_claude_dir(None);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_active_claude_dir_override() {
+        let root = TestClaudeDir::new();
+        let (path, source) = resolve_claude_dir().expect("設定ルートの解決に失敗しました");
+        assert_eq!(path, root.path());
+        assert_eq!(source, ConfigRootSource::Override);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = dirs::home_dir().expect("ホームディレクトリが見つかりません");
+        assert_eq!(expand_home("~/work/.claude").unwrap(), home.join("work/.claude"));
+        assert_eq!(expand_home("/tmp/.claude").unwrap(), PathBuf::from("/tmp/.claude"));
+    }
+
     #[test]
     fn test_is_excluded_path() {
         assert!(is_excluded_path("/home/user/.claude/cache/data"));
@@ -187,6 +564,68 @@ mod tests {
         assert!(!is_excluded_path("/home/user/.claude/projects/my-project"));
     }
 
+    #[test]
+    fn test_is_excluded_relative_path() {
+        assert!(is_excluded_relative_path(Path::new("backups/file.md")));
+        assert!(is_excluded_relative_path(Path::new("projects/foo/cache/x.json")));
+        assert!(!is_excluded_relative_path(Path::new("skills/my-skill/SKILL.md")));
+    }
+
+    #[test]
+    fn test_write_file_atomic_preserves_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, "{}").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        write_file_atomic(&path, b"{\"a\":1}").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":1}");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // 一時ファイルが残っていない
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_content_version_token() {
+        assert_eq!(content_version_token(b"abc"), content_version_token(b"abc"));
+        assert_ne!(content_version_token(b"abc"), content_version_token(b"abd"));
+        assert_eq!(content_version_token(b"").len(), 64);
+    }
+
+    #[test]
+    fn test_classify_file_kind() {
+        assert_eq!(classify_file_kind(Path::new("skills/foo/SKILL.md")), FileKind::Skill);
+        assert_eq!(classify_file_kind(Path::new("agents/categories/dev/a.md")), FileKind::Agent);
+        assert_eq!(classify_file_kind(Path::new("commands/review.md")), FileKind::Command);
+        assert_eq!(classify_file_kind(Path::new("settings.json")), FileKind::Settings);
+        assert_eq!(classify_file_kind(Path::new("CLAUDE.md")), FileKind::Memory);
+        assert_eq!(classify_file_kind(Path::new("projects/-home-a/1234.jsonl")), FileKind::Transcript);
+        assert_eq!(classify_file_kind(Path::new("history.jsonl")), FileKind::Other);
+    }
+
+    #[test]
+    fn test_extract_frontmatter_list() {
+        let inline = "---\nname: a\nskills: one, \"two\"\n---\nbody";
+        assert_eq!(extract_frontmatter_list(inline, "skills"), vec!["one", "two"]);
+        let flow = "---\nskills: [one, 'two']\n---\n";
+        assert_eq!(extract_frontmatter_list(flow, "skills"), vec!["one", "two"]);
+        let block = "---\nskills:\n  - one\n  - two\ntools: Read\n---\n";
+        assert_eq!(extract_frontmatter_list(block, "skills"), vec!["one", "two"]);
+        assert!(extract_frontmatter_list("no frontmatter", "skills").is_empty());
+    }
+
     #[test]
     fn test_is_allowed_extension() {
         use std::path::Path;
@@ -2,6 +2,8 @@ import { lazy, Suspense, useCallback, useEffect, useMemo, useRef, useState } fro
 
 import {
   useAppAIReview,
+  useAppConfigRootSync,
+  useAppFileChangeSync,
   useAppKeyboardShortcuts,
   useAppSearchReplace,
   useBackup,
@@ -65,6 +67,8 @@ const TemplateSelector = lazy(() => import('./components/template/TemplateSelect
  * - useAppSearchReplace: 検索＆置換統合フック
  * - useAppAIReview: AIレビュー統合フック
  * - useAppKeyboardShortcuts: 統合キーボードショートカット
+ * - useAppConfigRootSync: 設定ルート切り替え時のファイルツリー・タブの再読み込み
+ * - useAppFileChangeSync: ディスク上で変更・削除された開いているタブの検出
  */
 function App() {
   // ========================================
@@ -135,6 +139,7 @@ function App() {
     hasUnsavedChanges,
     loadFileTree,
     selectFile,
+    clearSelection,
     updateContent,
     updateValidationErrors,
     saveFile,
@@ -253,6 +258,8 @@ function App() {
     openTab,
     updateTabContent,
     markTabAsSaved,
+    reloadTab,
+    markTabDiskChange,
   } = useTabEditor({
     onUnsavedWarning: (unsavedTabs) => {
       const fileNames = unsavedTabs.map(t => t.name).join(', ')
@@ -370,7 +377,7 @@ function App() {
     if (selectedFile) {
       const prev = prevSelectedFileRef.current
       if (!prev || prev.path !== selectedFile.path || prev.name !== selectedFile.name) {
-        openTab(selectedFile.path, selectedFile.name, selectedFile.content)
+        openTab(selectedFile.path, selectedFile.name, selectedFile.content, selectedFile.versionToken)
         prevSelectedFileRef.current = { path: selectedFile.path, name: selectedFile.name }
       }
     } else {
@@ -381,7 +388,7 @@ function App() {
   // ファイル保存後にタブを保存済み状態にマーク
   useEffect(() => {
     if (selectedFile && selectedFile.content === selectedFile.originalContent) {
-      markTabAsSaved(selectedFile.path)
+      markTabAsSaved(selectedFile.path, selectedFile.versionToken)
     }
   }, [selectedFile, markTabAsSaved])
 
@@ -453,6 +460,28 @@ function App() {
     onSetReplaceOptions: setReplaceOptions,
   })
 
+  // ========================================
+  // 設定ルート切り替えの同期
+  // ========================================
+  useAppConfigRootSync({
+    tabs,
+    selectedFile,
+    onReloadFileTree: loadFileTree,
+    onReselectFile: selectFile,
+    onClearSelection: clearSelection,
+    onReloadTab: reloadTab,
+    onCloseTab: closeTab,
+    onSuccess: showSuccess,
+  })
+
+  // ========================================
+  // ファイル変更の同期
+  // ========================================
+  useAppFileChangeSync({
+    tabs,
+    onMarkDiskChange: markTabDiskChange,
+  })
+
   // ========================================
   // その他のイベントハンドラ
   // ========================================
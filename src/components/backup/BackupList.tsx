@@ -1,49 +1,57 @@
 import { useCallback, useEffect, useState, type FC } from 'react'
 
-import { getBackups, isTauri, restoreBackup } from '../../hooks/useTauri'
+import {
+  applyBackupRetention,
+  getBackups,
+  isTauri,
+  previewBackupRetention,
+  restoreBackup,
+  setBackupPinned,
+} from '../../hooks/useTauri'
 import Icon from '../common/Icon'
 import Modal from '../common/Modal'
 
-import type { FileNode } from '../../types'
+import type { BackupRecord } from '../../types'
 
 interface BackupListProps {
   isOpen: boolean
   onClose: () => void
   onRestoreComplete: (success: boolean, message: string) => void
-  onCompare?: (backupPath: string, originalPath: string, backupName: string) => void
+  onCompare?: (backupId: string, originalPath: string, backupName: string) => void
 }
 
 interface BackupItem {
-  node: FileNode
+  record: BackupRecord
+  /** 元ファイルの絶対パス */
+  originalPath: string
+  /** 元ファイルのファイル名 */
+  fileName: string
   date: Date
   displayDate: string
-  isOld: boolean // 30日以上古いかどうか
+  scheduledForDeletion: boolean // 保持ポリシーによる削除対象かどうか
+}
+
+/** バックアップストアの使用量 */
+interface BackupUsage {
+  logicalSize: number
+  physicalSize: number
 }
 
 /**
- * バックアップファイル名から日時を抽出
- * 例: settings.json.backup.2024-01-25T10-30-00 -> Date
+ * 相対パスからファイル名を取得
  */
-function parseDateFromBackupName(name: string): Date | null {
-  // .backup.YYYY-MM-DDTHH-MM-SS 形式を探す
-  const match = name.match(/\.backup\.(\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2})$/)
-  if (match) {
-    // ハイフンをコロンに戻して ISO形式に変換
-    const isoString = match[1].replace(/T(\d{2})-(\d{2})-(\d{2})/, 'T$1:$2:$3')
-    const date = new Date(isoString)
-    if (!isNaN(date.getTime())) {
-      return date
-    }
-  }
-  return null
+function getFileName(relativePath: string): string {
+  const parts = relativePath.split(/[\\/]/)
+  return parts[parts.length - 1] || relativePath
 }
 
 /**
- * バックアップファイルから元のファイルパスを推測
- * 例: /path/to/settings.json.backup.2024-01-25T10-30-00 -> /path/to/settings.json
+ * バイト数を読みやすい形式でフォーマット
  */
-function getOriginalPath(backupPath: string): string {
-  return backupPath.replace(/\.backup\.\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}$/, '')
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`
 }
 
 /**
@@ -59,51 +67,32 @@ function formatDate(date: Date): string {
 }
 
 /**
- * 30日以上前かどうかを判定
- */
-function isOlderThan30Days(date: Date): boolean {
-  const thirtyDaysAgo = new Date()
-  thirtyDaysAgo.setDate(thirtyDaysAgo.getDate() - 30)
-  return date < thirtyDaysAgo
-}
-
-/**
- * FileNode配列をフラット化してバックアップアイテムに変換
+ * バックアップの記録を表示用のアイテムに変換
  */
-function flattenBackups(nodes: FileNode[]): BackupItem[] {
-  const items: BackupItem[] = []
-
-  function traverse(nodeList: FileNode[]) {
-    for (const node of nodeList) {
-      if (node.file_type === 'file') {
-        const date = parseDateFromBackupName(node.name)
-        if (date) {
-          items.push({
-            node,
-            date,
-            displayDate: formatDate(date),
-            isOld: isOlderThan30Days(date),
-          })
-        }
-      }
-      if (node.children) {
-        traverse(node.children)
-      }
-    }
+function toBackupItem(
+  record: BackupRecord,
+  root: string,
+  scheduledForDeletion = false
+): BackupItem {
+  const date = new Date(record.createdAt)
+  return {
+    record,
+    originalPath: `${root}/${record.relativePath}`,
+    fileName: getFileName(record.relativePath),
+    date,
+    displayDate: formatDate(date),
+    scheduledForDeletion,
   }
-
-  traverse(nodes)
-  // 日付の新しい順にソート
-  items.sort((a, b) => b.date.getTime() - a.date.getTime())
-  return items
 }
 
 const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, onCompare }) => {
   const [backups, setBackups] = useState<BackupItem[]>([])
+  const [usage, setUsage] = useState<BackupUsage | null>(null)
   const [loading, setLoading] = useState(false)
   const [error, setError] = useState<string | null>(null)
   const [confirmRestore, setConfirmRestore] = useState<BackupItem | null>(null)
   const [restoring, setRestoring] = useState(false)
+  const [cleaning, setCleaning] = useState(false)
 
   const loadBackups = useCallback(async () => {
     setLoading(true)
@@ -111,39 +100,45 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
 
     try {
       if (isTauri()) {
-        const nodes = await getBackups()
-        const items = flattenBackups(nodes)
+        const list = await getBackups()
+        if (!list) {
+          throw new Error('Failed to load backups')
+        }
+        // 保持ポリシーによる削除対象を表示するためにドライランを取得
+        const plan = await previewBackupRetention()
+        const scheduled = new Set(plan?.delete.map(candidate => candidate.record.id) ?? [])
+        const items = list.backups.map(record =>
+          toBackupItem(record, list.root, scheduled.has(record.id))
+        )
         setBackups(items)
-
-        // 30日以上古いバックアップがある場合に警告
-        // TODO: Rustバックエンドに自動削除機能を追加する場合はここで呼び出す
-        // const oldBackups = items.filter(item => item.isOld)
-        // if (oldBackups.length > 0) {
-        //   await invoke('cleanup_old_backups', { days: 30 })
-        // }
+        setUsage({ logicalSize: list.logicalSize, physicalSize: list.physicalSize })
       } else {
         // デモモード
         const demoBackups: BackupItem[] = [
-          {
-            node: {
-              name: 'settings.json.backup.2024-01-25T10-30-00',
-              path: '~/.claude/backups/settings.json.backup.2024-01-25T10-30-00',
-              file_type: 'file',
+          toBackupItem(
+            {
+              id: 'demo-settings',
+              relativePath: 'settings.json',
+              createdAt: '2024-01-25T10:30:00+09:00',
+              size: 1024,
+              hash: '',
+              operation: 'write',
+              pinned: true,
             },
-            date: new Date('2024-01-25T10:30:00'),
-            displayDate: '2024年1月25日 10:30',
-            isOld: false,
-          },
-          {
-            node: {
-              name: 'CLAUDE.md.backup.2024-01-24T15-45-00',
-              path: '~/.claude/backups/CLAUDE.md.backup.2024-01-24T15-45-00',
-              file_type: 'file',
+            '~/.claude'
+          ),
+          toBackupItem(
+            {
+              id: 'demo-claude-md',
+              relativePath: 'CLAUDE.md',
+              createdAt: '2024-01-24T15:45:00+09:00',
+              size: 2048,
+              hash: '',
+              operation: 'manual',
+              pinned: false,
             },
-            date: new Date('2024-01-24T15:45:00'),
-            displayDate: '2024年1月24日 15:45',
-            isOld: false,
-          },
+            '~/.claude'
+          ),
         ]
         setBackups(demoBackups)
       }
@@ -162,13 +157,12 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
 
   const handleRestore = async (item: BackupItem) => {
     setRestoring(true)
-    const targetPath = getOriginalPath(item.node.path)
 
     try {
       if (isTauri()) {
-        const success = await restoreBackup(item.node.path, targetPath)
+        const success = await restoreBackup(item.record.id)
         if (success) {
-          onRestoreComplete(true, `${item.node.name} を復元しました`)
+          onRestoreComplete(true, `${item.fileName} を復元しました`)
           onClose()
         } else {
           onRestoreComplete(false, '復元に失敗しました')
@@ -185,15 +179,44 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
     }
   }
 
-  const getFileNameFromBackup = (backupName: string): string => {
-    // バックアップ名から元のファイル名を取得
-    return backupName.replace(/\.backup\.\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}$/, '')
+  const handleTogglePin = async (item: BackupItem) => {
+    if (!isTauri()) return
+
+    const updated = await setBackupPinned(item.record.id, !item.record.pinned)
+    if (updated) {
+      await loadBackups()
+    } else {
+      onRestoreComplete(false, 'ピン留めの変更に失敗しました')
+    }
+  }
+
+  const handleApplyRetention = async () => {
+    if (!isTauri()) return
+
+    setCleaning(true)
+    try {
+      const result = await applyBackupRetention()
+      if (result) {
+        const pruned =
+          result.pruned_records.length > 0
+            ? `（内容が失われていた${result.pruned_records.length}件の記録を除去）`
+            : ''
+        onRestoreComplete(true, `${result.deleted_count}件のバックアップを削除しました${pruned}`)
+        await loadBackups()
+      } else {
+        onRestoreComplete(false, 'バックアップの削除に失敗しました')
+      }
+    } finally {
+      setCleaning(false)
+    }
   }
 
   const footer = (
     <div className="flex justify-between items-center">
       <span className="text-sm text-gray-500 dark:text-gray-400">
         {backups.length > 0 && `${backups.length}件のバックアップ`}
+        {backups.length > 0 && usage &&
+          `（${formatSize(usage.logicalSize)} / ディスク使用量 ${formatSize(usage.physicalSize)}）`}
       </span>
       <button
         onClick={onClose}
@@ -240,25 +263,28 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
             </div>
           ) : (
             <div className="space-y-3">
-              {/* 30日以上古いバックアップの警告 */}
-              {backups.some(b => b.isOld) && (
+              {/* 保持ポリシーによる削除対象の警告 */}
+              {backups.some(b => b.scheduledForDeletion) && (
                 <div className="mb-4 p-3 bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200 dark:border-yellow-800 rounded-md">
                   <p className="text-sm text-yellow-700 dark:text-yellow-300">
-                    30日以上前のバックアップがあります。古いバックアップは自動削除の対象です。
+                    {backups.filter(b => b.scheduledForDeletion).length}
+                    件のバックアップが保持ポリシーによる自動削除の対象です。残す場合はピン留めしてください。
                   </p>
-                  {/* TODO: Rustバックエンドに cleanup_old_backups コマンドを追加後、削除ボタンを有効化
-                  <button className="mt-2 text-sm text-yellow-600 dark:text-yellow-400 underline hover:no-underline">
-                    古いバックアップを削除
+                  <button
+                    onClick={handleApplyRetention}
+                    disabled={cleaning}
+                    className="mt-2 text-sm text-yellow-600 dark:text-yellow-400 underline hover:no-underline disabled:opacity-50"
+                  >
+                    {cleaning ? '削除中...' : '今すぐ削除'}
                   </button>
-                  */}
                 </div>
               )}
 
               {backups.map((item) => (
                 <div
-                  key={item.node.path}
+                  key={item.record.id}
                   className={`flex items-center justify-between p-4 rounded-lg border transition-colors ${
-                    item.isOld
+                    item.scheduledForDeletion
                       ? 'bg-gray-50 dark:bg-gray-900/50 border-gray-200 dark:border-gray-700 opacity-75'
                       : 'bg-white dark:bg-gray-800 border-gray-200 dark:border-gray-700 hover:border-blue-300 dark:hover:border-blue-600'
                   }`}
@@ -267,11 +293,16 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
                     <div className="flex items-center gap-2">
                       <Icon name="file" className="size-4 text-gray-400 flex-shrink-0" />
                       <span className="text-sm font-medium text-gray-900 dark:text-gray-100 truncate">
-                        {getFileNameFromBackup(item.node.name)}
+                        {item.fileName}
                       </span>
-                      {item.isOld && (
+                      {item.record.pinned && (
+                        <span className="text-xs px-2 py-0.5 bg-blue-100 dark:bg-blue-900 text-blue-700 dark:text-blue-300 rounded">
+                          ピン留め
+                        </span>
+                      )}
+                      {item.scheduledForDeletion && (
                         <span className="text-xs px-2 py-0.5 bg-yellow-100 dark:bg-yellow-900 text-yellow-700 dark:text-yellow-300 rounded">
-                          古い
+                          削除予定
                         </span>
                       )}
                     </div>
@@ -279,19 +310,26 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
                       {item.displayDate}
                     </div>
                     <div className="mt-1 text-xs text-gray-400 dark:text-gray-500 truncate">
-                      {item.node.path}
+                      {item.record.relativePath}
                     </div>
                   </div>
                   <div className="flex items-center gap-2 ml-4 flex-shrink-0">
+                    <button
+                      onClick={() => handleTogglePin(item)}
+                      className="px-3 py-1.5 text-sm border border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 transition-colors"
+                      title={item.record.pinned ? 'ピン留めを解除' : 'ピン留めして自動削除から除外'}
+                      aria-label={`${item.fileName}のピン留めを${item.record.pinned ? '解除' : '設定'}`}
+                    >
+                      {item.record.pinned ? '解除' : 'ピン'}
+                    </button>
                     {onCompare && (
                       <button
                         onClick={() => {
-                          const originalPath = getOriginalPath(item.node.path)
-                          onCompare(item.node.path, originalPath, item.node.name)
+                          onCompare(item.record.id, item.originalPath, item.displayDate)
                         }}
                         className="px-3 py-1.5 text-sm border border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 transition-colors"
                         title="現在のファイルと比較"
-                        aria-label={`${getFileNameFromBackup(item.node.name)}を現在のファイルと比較`}
+                        aria-label={`${item.fileName}を現在のファイルと比較`}
                       >
                         比較
                       </button>
@@ -299,7 +337,7 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
                     <button
                       onClick={() => setConfirmRestore(item)}
                       className="px-3 py-1.5 text-sm bg-blue-500 text-white rounded-md hover:bg-blue-600 transition-colors"
-                      aria-label={`${getFileNameFromBackup(item.node.name)}を復元`}
+                      aria-label={`${item.fileName}を復元`}
                     >
                       復元
                     </button>
@@ -351,7 +389,7 @@ const BackupList: FC<BackupListProps> = ({ isOpen, onClose, onRestoreComplete, o
             </p>
             <div className="bg-gray-100 dark:bg-gray-700 rounded-md p-3 mb-4">
               <p className="text-sm font-medium text-gray-900 dark:text-gray-100">
-                {getFileNameFromBackup(confirmRestore.node.name)}
+                {confirmRestore.fileName}
               </p>
               <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
                 {confirmRestore.displayDate}
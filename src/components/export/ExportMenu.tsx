@@ -1,9 +1,54 @@
 import { useEffect, useRef, useState, type FC } from 'react'
-import { save } from '@tauri-apps/plugin-dialog'
+import { open, save } from '@tauri-apps/plugin-dialog'
 
 import { MESSAGE_AUTO_CLEAR_DELAY } from '../../constants'
-import { exportAllZip, exportFile, getExportFileCount, isTauri } from '../../hooks/useTauri'
+import {
+  cancelJob,
+  exportFile,
+  getExportFileCount,
+  isTauri,
+  runJob,
+  startExportJob,
+} from '../../hooks/useTauri'
 import { Icon } from '../common'
+import PluginExportDialog from './PluginExportDialog'
+
+import type { ArchiveFormat, ExportManifest } from '../../types'
+
+/** 全設定のエクスポートの形式ごとの表示 */
+const EXPORT_FORMATS: {
+  format: ArchiveFormat
+  label: string
+  description: string
+  icon: 'archive' | 'folder'
+}[] = [
+  { format: 'zip', label: 'ZIP', description: '~/.claude/ 配下の全設定ファイル', icon: 'archive' },
+  { format: 'tarGz', label: 'tar.gz', description: '.tar.gz 形式のアーカイブ', icon: 'archive' },
+  {
+    format: 'directory',
+    label: 'フォルダー',
+    description: 'Gitリポジトリなどのフォルダーに書き出し',
+    icon: 'folder',
+  },
+]
+
+/** エクスポート先を選択するダイアログを表示（キャンセル時はnull） */
+const chooseDestination = async (format: ArchiveFormat): Promise<string | null> => {
+  if (format === 'directory') {
+    const selected = await open({ directory: true, multiple: false })
+    return typeof selected === 'string' ? selected : null
+  }
+  const timestamp = new Date().toISOString().slice(0, 10).replace(/-/g, '')
+  const extension = format === 'tarGz' ? 'tar.gz' : 'zip'
+  return save({
+    defaultPath: `claude-settings-${timestamp}.${extension}`,
+    filters: [
+      format === 'tarGz'
+        ? { name: 'tar.gz Archive', extensions: ['tar.gz', 'tgz'] }
+        : { name: 'ZIP Archive', extensions: ['zip'] },
+    ],
+  })
+}
 
 interface ExportMenuProps {
   /** 現在選択中のファイルパス */
@@ -17,6 +62,8 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
   const [exporting, setExporting] = useState(false)
   const [progress, setProgress] = useState<{ current: number; total: number } | null>(null)
   const [message, setMessage] = useState<{ type: 'success' | 'error'; text: string } | null>(null)
+  const [jobId, setJobId] = useState<string | null>(null)
+  const [pluginDialogOpen, setPluginDialogOpen] = useState(false)
   const menuRef = useRef<HTMLDivElement>(null)
 
   // メニュー外クリックで閉じる
@@ -76,9 +123,10 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
     }
   }
 
-  // 全設定をZIPでエクスポート
-  const handleExportAllZip = async () => {
+  // 全設定をアーカイブ（ZIP・tar.gz・フォルダー）でエクスポート
+  const handleExportAll = async (format: ArchiveFormat) => {
     if (!isTauri()) return
+    const label = EXPORT_FORMATS.find((f) => f.format === format)?.label ?? format
 
     try {
       setExporting(true)
@@ -88,14 +136,8 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
       const totalFiles = await getExportFileCount()
       setProgress({ current: 0, total: totalFiles })
 
-      // 保存ダイアログを表示
-      const timestamp = new Date().toISOString().slice(0, 10).replace(/-/g, '')
-      const defaultFileName = `claude-settings-${timestamp}.zip`
-
-      const destPath = await save({
-        defaultPath: defaultFileName,
-        filters: [{ name: 'ZIP Archive', extensions: ['zip'] }],
-      })
+      // 保存先を選択
+      const destPath = await chooseDestination(format)
 
       if (!destPath) {
         setExporting(false)
@@ -103,19 +145,38 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
         return
       }
 
-      const exportedCount = await exportAllZip(destPath)
-      setProgress({ current: exportedCount, total: totalFiles })
+      // バックグラウンドジョブとして実行し、進捗を表示
+      const finished = await runJob<ExportManifest>(
+        () => startExportJob(destPath, undefined, format),
+        {
+          onStart: setJobId,
+          onProgress: (p) => setProgress({ current: p.processedFiles, total: p.totalFiles }),
+        }
+      )
 
-      setMessage({
-        type: 'success',
-        text: `${exportedCount}件のファイルをZIPエクスポートしました`,
-      })
+      if (finished.status === 'completed' && finished.result) {
+        const exportedCount = finished.result.files.length
+        const redactedCount = finished.result.redactions.length
+        setMessage({
+          type: 'success',
+          text:
+            redactedCount > 0
+              ? `${exportedCount}件のファイルを${label}にエクスポートしました（秘密情報${redactedCount}件を置き換え）`
+              : `${exportedCount}件のファイルを${label}にエクスポートしました`,
+        })
+      } else if (finished.status === 'cancelled') {
+        setMessage({ type: 'error', text: `${label}へのエクスポートをキャンセルしました` })
+      } else {
+        console.error('Export archive error:', finished.error)
+        setMessage({ type: 'error', text: `${label}へのエクスポートに失敗しました` })
+      }
     } catch (error) {
-      console.error('Export ZIP error:', error)
-      setMessage({ type: 'error', text: 'ZIPエクスポートに失敗しました' })
+      console.error('Export archive error:', error)
+      setMessage({ type: 'error', text: `${label}へのエクスポートに失敗しました` })
     } finally {
       setExporting(false)
       setProgress(null)
+      setJobId(null)
     }
   }
 
@@ -148,7 +209,15 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
       {/* 進捗表示 */}
       {progress && (
         <div className="absolute top-full left-0 right-0 mt-1 px-3 py-2 message-badge-info whitespace-nowrap z-50">
-          処理中: {progress.current} / {progress.total} ファイル
+          <span>処理中: {progress.current} / {progress.total} ファイル</span>
+          {jobId && (
+            <button
+              onClick={() => cancelJob(jobId)}
+              className="ml-3 underline hover:no-underline"
+            >
+              キャンセル
+            </button>
+          )}
         </div>
       )}
 
@@ -188,23 +257,51 @@ const ExportMenu: FC<ExportMenuProps> = ({ selectedFilePath, selectedFileName })
             </div>
           </button>
 
-          {/* 全設定ZIPエクスポート */}
+          {/* 全設定エクスポート（ZIP・tar.gz・フォルダー） */}
+          {EXPORT_FORMATS.map(({ format, label, description, icon }) => (
+            <button
+              key={format}
+              role="menuitem"
+              onClick={() => handleExportAll(format)}
+              aria-label={`全設定を${label}にエクスポート`}
+              className="w-full px-4 py-2.5 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-700 flex items-center gap-3 focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-700"
+            >
+              <Icon name={icon} className="size-4 text-gray-500 dark:text-gray-400" />
+              <div>
+                <div className="font-medium">全設定を{label}にエクスポート</div>
+                <div className="text-xs text-gray-500 dark:text-gray-400">{description}</div>
+              </div>
+            </button>
+          ))}
+
+          {/* プラグインとしてエクスポート */}
           <button
             role="menuitem"
-            onClick={handleExportAllZip}
-            aria-label="全設定をZIPファイルでエクスポート"
-            className="w-full px-4 py-2.5 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-700 flex items-center gap-3 focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-700"
+            onClick={() => {
+              setIsOpen(false)
+              setPluginDialogOpen(true)
+            }}
+            aria-label="プラグインとしてエクスポート"
+            className="w-full px-4 py-2.5 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-700 border-t border-gray-100 dark:border-gray-700 flex items-center gap-3 focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-700"
           >
-            <Icon name="archive" className="size-4 text-gray-500 dark:text-gray-400" />
+            <Icon name="lightning" className="size-4 text-gray-500 dark:text-gray-400" />
             <div>
-              <div className="font-medium">全設定をZIPエクスポート</div>
+              <div className="font-medium">プラグインとしてエクスポート</div>
               <div className="text-xs text-gray-500 dark:text-gray-400">
-                ~/.claude/ 配下の全設定ファイル
+                スキル・サブエージェント・コマンドを配布用に書き出し
               </div>
             </div>
           </button>
         </div>
       )}
+
+      {pluginDialogOpen && (
+        <PluginExportDialog
+          isOpen={pluginDialogOpen}
+          onClose={() => setPluginDialogOpen(false)}
+          onExported={(text) => setMessage({ type: 'success', text })}
+        />
+      )}
     </div>
   )
 }
@@ -0,0 +1,332 @@
+import { useEffect, useMemo, useState, type FC } from 'react'
+import { open, save } from '@tauri-apps/plugin-dialog'
+
+import { exportPlugin, listPluginComponents, validatePlugin } from '../../hooks/useTauri'
+import { Icon } from '../common'
+import Modal from '../common/Modal'
+
+import type {
+  ArchiveFormat,
+  PluginComponents,
+  PluginMetadata,
+  PluginSelection,
+  PluginValidation,
+} from '../../types'
+
+/** 入力後に検証するまでの待ち時間（ミリ秒） */
+const VALIDATE_DELAY = 300
+
+/** プラグインの出力形式ごとの表示 */
+const PLUGIN_FORMATS: { format: ArchiveFormat; label: string }[] = [
+  { format: 'directory', label: 'フォルダー' },
+  { format: 'zip', label: 'ZIP' },
+  { format: 'tarGz', label: 'tar.gz' },
+]
+
+const INPUT_CLASS =
+  'w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 placeholder:text-gray-400 dark:placeholder:text-gray-500'
+
+/** 出力先を選択するダイアログを表示（キャンセル時はnull） */
+const chooseDestination = async (format: ArchiveFormat, name: string): Promise<string | null> => {
+  if (format === 'directory') {
+    const selected = await open({ directory: true, multiple: false })
+    return typeof selected === 'string' ? selected : null
+  }
+  const extension = format === 'tarGz' ? 'tar.gz' : 'zip'
+  return save({
+    defaultPath: `${name || 'plugin'}.${extension}`,
+    filters: [
+      format === 'tarGz'
+        ? { name: 'tar.gz Archive', extensions: ['tar.gz', 'tgz'] }
+        : { name: 'ZIP Archive', extensions: ['zip'] },
+    ],
+  })
+}
+
+/** 選択したパスを切り替えた一覧 */
+const toggle = (values: string[], value: string): string[] =>
+  values.includes(value) ? values.filter((v) => v !== value) : [...values, value]
+
+interface PluginExportDialogProps {
+  isOpen: boolean
+  onClose: () => void
+  /** エクスポートが完了したときに呼ばれる（表示するメッセージ） */
+  onExported: (message: string) => void
+}
+
+/**
+ * スキル・サブエージェント・スラッシュコマンドをClaude Codeのプラグインとしてエクスポートするダイアログ
+ */
+const PluginExportDialog: FC<PluginExportDialogProps> = ({ isOpen, onClose, onExported }) => {
+  const [components, setComponents] = useState<PluginComponents | null>(null)
+  const [metadata, setMetadata] = useState<PluginMetadata>({
+    name: '',
+    version: '1.0.0',
+    description: '',
+    author: { name: '', email: '' },
+  })
+  const [selection, setSelection] = useState<PluginSelection>({
+    skills: [],
+    agents: [],
+    commands: [],
+    includeHooks: false,
+    redactSecrets: true,
+  })
+  const [format, setFormat] = useState<ArchiveFormat>('directory')
+  const [addToMarketplace, setAddToMarketplace] = useState(false)
+  const [validation, setValidation] = useState<PluginValidation | null>(null)
+  const [exporting, setExporting] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  // 含めることができる内容を取得
+  useEffect(() => {
+    if (!isOpen) return
+    setError(null)
+    listPluginComponents()
+      .then(setComponents)
+      .catch((e) => setError(`プラグインに含める内容の取得に失敗しました: ${e}`))
+  }, [isOpen])
+
+  // 入力が止まってから検証
+  useEffect(() => {
+    if (!isOpen) return
+    const timer = setTimeout(() => {
+      validatePlugin(metadata, selection)
+        .then(setValidation)
+        .catch((e) => setError(`プラグインの検証に失敗しました: ${e}`))
+    }, VALIDATE_DELAY)
+    return () => clearTimeout(timer)
+  }, [isOpen, metadata, selection])
+
+  const sections = useMemo(
+    () =>
+      components
+        ? ([
+            { key: 'skills', label: 'スキル', items: components.skills },
+            { key: 'agents', label: 'サブエージェント', items: components.agents },
+            { key: 'commands', label: 'スラッシュコマンド', items: components.commands },
+          ] as const)
+        : [],
+    [components]
+  )
+
+  const canExport = !exporting && validation !== null && validation.errors.length === 0
+
+  const handleExport = async () => {
+    try {
+      setError(null)
+      const destPath = await chooseDestination(format, metadata.name)
+      if (!destPath) return
+
+      let marketplacePath: string | undefined
+      if (format === 'directory' && addToMarketplace) {
+        const selected = await save({
+          defaultPath: 'marketplace.json',
+          filters: [{ name: 'JSON', extensions: ['json'] }],
+        })
+        if (!selected) return
+        marketplacePath = selected
+      }
+
+      setExporting(true)
+      const result = await exportPlugin(destPath, metadata, selection, format, marketplacePath)
+      const redacted =
+        result.redactions.length > 0 ? `（秘密情報${result.redactions.length}件を置き換え）` : ''
+      onExported(`プラグイン ${metadata.name} をエクスポートしました${redacted}`)
+      onClose()
+    } catch (e) {
+      console.error('Export plugin error:', e)
+      setError(`プラグインのエクスポートに失敗しました: ${e}`)
+    } finally {
+      setExporting(false)
+    }
+  }
+
+  const footer = (
+    <div className="flex justify-end gap-3">
+      <button
+        onClick={onClose}
+        className="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md transition-colors"
+      >
+        キャンセル
+      </button>
+      <button
+        onClick={handleExport}
+        disabled={!canExport}
+        className="px-4 py-2 text-sm text-white bg-blue-500 hover:bg-blue-600 rounded-md transition-colors disabled:opacity-50 disabled:cursor-not-allowed focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800"
+      >
+        {exporting ? 'エクスポート中...' : 'エクスポート'}
+      </button>
+    </div>
+  )
+
+  return (
+    <Modal
+      isOpen={isOpen}
+      onClose={onClose}
+      title="プラグインとしてエクスポート"
+      subtitle=".claude-plugin/plugin.json と skills/・agents/・commands/・hooks/ を書き出します"
+      icon={<Icon name="archive" className="size-5 text-blue-500" />}
+      size="md"
+      footer={footer}
+      contentClassName="px-6 py-4 space-y-4"
+    >
+      {/* メタデータ */}
+      <div className="grid grid-cols-2 gap-3">
+        {(
+          [
+            ['name', '名前', 'my-plugin'],
+            ['version', 'バージョン', '1.0.0'],
+          ] as const
+        ).map(([field, label, placeholder]) => (
+          <label key={field} className="block text-sm text-gray-700 dark:text-gray-300">
+            {label}
+            <input
+              type="text"
+              value={metadata[field]}
+              onChange={(e) => setMetadata((prev) => ({ ...prev, [field]: e.target.value }))}
+              placeholder={placeholder}
+              className={`${INPUT_CLASS} mt-1`}
+            />
+          </label>
+        ))}
+        <label className="col-span-2 block text-sm text-gray-700 dark:text-gray-300">
+          説明
+          <input
+            type="text"
+            value={metadata.description}
+            onChange={(e) => setMetadata((prev) => ({ ...prev, description: e.target.value }))}
+            className={`${INPUT_CLASS} mt-1`}
+          />
+        </label>
+        <label className="block text-sm text-gray-700 dark:text-gray-300">
+          作者
+          <input
+            type="text"
+            value={metadata.author.name}
+            onChange={(e) =>
+              setMetadata((prev) => ({ ...prev, author: { ...prev.author, name: e.target.value } }))
+            }
+            className={`${INPUT_CLASS} mt-1`}
+          />
+        </label>
+        <label className="block text-sm text-gray-700 dark:text-gray-300">
+          メールアドレス（任意）
+          <input
+            type="email"
+            value={metadata.author.email ?? ''}
+            onChange={(e) =>
+              setMetadata((prev) => ({ ...prev, author: { ...prev.author, email: e.target.value } }))
+            }
+            className={`${INPUT_CLASS} mt-1`}
+          />
+        </label>
+      </div>
+
+      {/* 含める内容 */}
+      {sections.map(({ key, label, items }) => (
+        <fieldset key={key}>
+          <legend className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-1">
+            {label} ({selection[key].length} / {items.length})
+          </legend>
+          {items.length === 0 ? (
+            <p className="text-xs text-gray-500 dark:text-gray-400">ありません</p>
+          ) : (
+            <div className="max-h-32 overflow-y-auto bg-gray-50 dark:bg-gray-900 rounded-md p-2 space-y-1">
+              {items.map((item) => (
+                <label
+                  key={item}
+                  className="flex items-center gap-2 text-sm font-mono text-gray-700 dark:text-gray-300"
+                >
+                  <input
+                    type="checkbox"
+                    checked={selection[key].includes(item)}
+                    onChange={() =>
+                      setSelection((prev) => ({ ...prev, [key]: toggle(prev[key], item) }))
+                    }
+                  />
+                  <span className="truncate">{item}</span>
+                </label>
+              ))}
+            </div>
+          )}
+        </fieldset>
+      ))}
+
+      {/* オプション */}
+      <div className="space-y-1 text-sm text-gray-700 dark:text-gray-300">
+        <label className="flex items-center gap-2">
+          <input
+            type="checkbox"
+            checked={selection.includeHooks}
+            disabled={!components?.hasHooks}
+            onChange={(e) => setSelection((prev) => ({ ...prev, includeHooks: e.target.checked }))}
+          />
+          settings.json のフックを hooks/hooks.json として含める
+        </label>
+        <label className="flex items-center gap-2">
+          <input
+            type="checkbox"
+            checked={selection.redactSecrets ?? true}
+            onChange={(e) => setSelection((prev) => ({ ...prev, redactSecrets: e.target.checked }))}
+          />
+          APIトークンなどの秘密情報をプレースホルダーに置き換える
+        </label>
+        <div className="flex items-center gap-2">
+          出力形式
+          <select
+            value={format}
+            onChange={(e) => setFormat(e.target.value as ArchiveFormat)}
+            className="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm"
+          >
+            {PLUGIN_FORMATS.map(({ format: value, label }) => (
+              <option key={value} value={value}>
+                {label}
+              </option>
+            ))}
+          </select>
+        </div>
+        <label className="flex items-center gap-2">
+          <input
+            type="checkbox"
+            checked={format === 'directory' && addToMarketplace}
+            disabled={format !== 'directory'}
+            onChange={(e) => setAddToMarketplace(e.target.checked)}
+          />
+          ローカルの marketplace.json にエントリを追加する（フォルダーのみ）
+        </label>
+      </div>
+
+      {/* 検証結果 */}
+      {validation && validation.errors.length > 0 && (
+        <ul
+          className="p-3 bg-red-50 dark:bg-red-900/30 border border-red-200 dark:border-red-800 rounded-md text-xs text-red-700 dark:text-red-400 space-y-1"
+          role="alert"
+        >
+          {validation.errors.map((message) => (
+            <li key={message}>{message}</li>
+          ))}
+        </ul>
+      )}
+      {validation && validation.warnings.length > 0 && (
+        <ul className="p-3 bg-yellow-50 dark:bg-yellow-900/30 border border-yellow-200 dark:border-yellow-800 rounded-md text-xs text-yellow-700 dark:text-yellow-400 space-y-1">
+          {validation.warnings.map((message) => (
+            <li key={message}>{message}</li>
+          ))}
+        </ul>
+      )}
+      {validation && validation.errors.length === 0 && (
+        <p className="text-xs text-gray-500 dark:text-gray-400">
+          {validation.files.length} 個のファイルを書き出します
+        </p>
+      )}
+      {error && (
+        <p className="text-sm text-red-600 dark:text-red-400" role="alert">
+          {error}
+        </p>
+      )}
+    </Modal>
+  )
+}
+
+export default PluginExportDialog
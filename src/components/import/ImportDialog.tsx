@@ -1,19 +1,39 @@
-import { useCallback, useState, type FC } from 'react'
+import { useCallback, useMemo, useState, type FC } from 'react'
 import { open } from '@tauri-apps/plugin-dialog'
 
 import {
+  cancelJob,
   checkFileExists,
   checkFilesExist,
+  getZipSecretPlaceholders,
   importFile,
-  importZip,
   isTauri,
+  planZipImport,
+  previewFileMerge,
   previewZip,
+  previewZipMerge,
+  runJob,
+  startImportJob,
 } from '../../hooks/useTauri'
 import { Icon } from '../common'
 import Modal from '../common/Modal'
+import MergePreviewPanel from './MergePreviewPanel'
 import OverwriteConfirmDialog from './OverwriteConfirmDialog'
 
-import type { FileExistsInfo, ImportResult, ZipFileInfo } from '../../types'
+import type {
+  ConflictSide,
+  ExportProgress,
+  FileExistsInfo,
+  ImportChoice,
+  ImportEntryStatus,
+  ImportPlan,
+  ImportPlanEntry,
+  ImportResult,
+  JsonMergePreview,
+  MergeStrategy,
+  SecretPlaceholder,
+  ZipFileInfo,
+} from '../../types'
 
 interface ImportDialogProps {
   isOpen: boolean
@@ -23,29 +43,96 @@ interface ImportDialogProps {
 
 type ImportStep = 'select' | 'preview' | 'confirm' | 'importing' | 'result'
 
+/** 一括インポートするアーカイブ（ZIP・tar.gz）の拡張子 */
+const ARCHIVE_PATTERN = /\.(zip|tar\.gz|tgz)$/i
+
+const STATUS_LABELS: Record<ImportEntryStatus, { label: string; className: string }> = {
+  new: { label: '新規', className: 'bg-green-100 text-green-700 dark:bg-green-900/40 dark:text-green-400' },
+  identical: { label: '同一', className: 'bg-gray-100 text-gray-600 dark:bg-gray-700 dark:text-gray-400' },
+  modified: { label: '変更', className: 'bg-yellow-100 text-yellow-700 dark:bg-yellow-900/40 dark:text-yellow-400' },
+  excluded: { label: '除外', className: 'bg-red-100 text-red-700 dark:bg-red-900/40 dark:text-red-400' },
+}
+
+const CHOICE_LABELS: Record<ImportChoice, string> = {
+  overwrite: '上書き',
+  skip: 'スキップ',
+  keepBoth: '両方残す',
+  merge: 'マージ',
+}
+
+const STRATEGY_LABELS: Record<MergeStrategy, string> = {
+  theirs: 'インポートする値を優先',
+  ours: '現在の値を優先',
+  ask: '衝突ごとに選択',
+}
+
+/** 既定でマージする設定ファイル（settings.json、settings.local.json、.mcp.json） */
+const isConfigJson = (path: string): boolean =>
+  ['settings.json', 'settings.local.json', '.mcp.json'].includes(path.split('/').pop() ?? '')
+
+/** エントリの処理（未選択の場合、変更される設定ファイルはマージ） */
+const effectiveChoice = (
+  entry: ImportPlanEntry,
+  choices: Record<string, ImportChoice>
+): ImportChoice =>
+  choices[entry.path] ??
+  (entry.status === 'modified' && isConfigJson(entry.path) ? 'merge' : entry.defaultChoice)
+
+/** エントリで選択できる処理（両方残す・マージは現在のファイルがある場合のみ、マージはJSONのみ） */
+const availableChoices = (entry: ImportPlanEntry): ImportChoice[] => {
+  if (entry.status === 'new') return ['overwrite', 'skip']
+  const choices: ImportChoice[] = ['overwrite', 'skip', 'keepBoth']
+  if (entry.status === 'modified' && entry.path.toLowerCase().endsWith('.json')) {
+    choices.push('merge')
+  }
+  return choices
+}
+
 const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete }) => {
   const [step, setStep] = useState<ImportStep>('select')
   const [selectedFile, setSelectedFile] = useState<string | null>(null)
   const [importType, setImportType] = useState<'file' | 'zip'>('file')
   const [zipContents, setZipContents] = useState<ZipFileInfo[]>([])
+  const [secretPlaceholders, setSecretPlaceholders] = useState<SecretPlaceholder[]>([])
+  const [secretValues, setSecretValues] = useState<Record<string, string>>({})
+  const [importPlan, setImportPlan] = useState<ImportPlan | null>(null)
+  const [choices, setChoices] = useState<Record<string, ImportChoice>>({})
+  const [mergeFile, setMergeFile] = useState(false)
+  const [mergeStrategy, setMergeStrategy] = useState<MergeStrategy>('theirs')
+  const [mergeResolutions, setMergeResolutions] = useState<
+    Record<string, Record<string, ConflictSide>>
+  >({})
+  const [mergePreview, setMergePreview] = useState<JsonMergePreview | null>(null)
   const [existingFiles, setExistingFiles] = useState<FileExistsInfo[]>([])
   const [showOverwriteDialog, setShowOverwriteDialog] = useState(false)
   const [importResult, setImportResult] = useState<ImportResult | null>(null)
   const [isLoading, setIsLoading] = useState(false)
   const [error, setError] = useState<string | null>(null)
   const [destinationPath, setDestinationPath] = useState<string>('')
+  const [jobId, setJobId] = useState<string | null>(null)
+  const [jobProgress, setJobProgress] = useState<ExportProgress | null>(null)
 
   const resetState = useCallback(() => {
     setStep('select')
     setSelectedFile(null)
     setImportType('file')
     setZipContents([])
+    setSecretPlaceholders([])
+    setSecretValues({})
+    setImportPlan(null)
+    setChoices({})
+    setMergeFile(false)
+    setMergeStrategy('theirs')
+    setMergeResolutions({})
+    setMergePreview(null)
     setExistingFiles([])
     setShowOverwriteDialog(false)
     setImportResult(null)
     setIsLoading(false)
     setError(null)
     setDestinationPath('')
+    setJobId(null)
+    setJobProgress(null)
   }, [])
 
   const handleClose = useCallback(() => {
@@ -53,6 +140,34 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
     onClose()
   }, [resetState, onClose])
 
+  // アーカイブ（ZIP・tar.gz・フォルダー）の内容とインポート計画を読み込む
+  const loadArchive = useCallback(async (archivePath: string) => {
+    setSelectedFile(archivePath)
+    setError(null)
+    setImportType('zip')
+    setIsLoading(true)
+    const contents = await previewZip(archivePath)
+    setZipContents(contents)
+    // エクスポート時に置き換えられた秘密情報
+    setSecretPlaceholders(await getZipSecretPlaceholders(archivePath))
+    setSecretValues({})
+    // 現在の設定と比較したインポート計画
+    setImportPlan(await planZipImport(archivePath))
+    setChoices({})
+    setStep('preview')
+
+    // 既存ファイルのチェック
+    const fileNames = contents
+      .filter((f) => !f.is_directory)
+      .map((f) => f.name)
+    if (fileNames.length > 0) {
+      const existsInfo = await checkFilesExist(fileNames)
+      const existing = existsInfo.filter((f) => f.exists)
+      setExistingFiles(existing)
+    }
+    setIsLoading(false)
+  }, [])
+
   const handleSelectFile = useCallback(async () => {
     if (!isTauri()) {
       setError('Tauriアプリとして起動してください')
@@ -65,36 +180,20 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
         filters: [
           {
             name: 'Claude Settings',
-            extensions: ['md', 'json', 'jsonl', 'zip'],
+            extensions: ['md', 'json', 'jsonl', 'zip', 'gz', 'tgz'],
           },
         ],
       })
 
       if (result) {
         const filePath = result as string
-        setSelectedFile(filePath)
-        setError(null)
 
-        const isZip = filePath.toLowerCase().endsWith('.zip')
-        setImportType(isZip ? 'zip' : 'file')
-
-        if (isZip) {
-          setIsLoading(true)
-          const contents = await previewZip(filePath)
-          setZipContents(contents)
-          setStep('preview')
-
-          // 既存ファイルのチェック
-          const fileNames = contents
-            .filter((f) => !f.is_directory)
-            .map((f) => f.name)
-          if (fileNames.length > 0) {
-            const existsInfo = await checkFilesExist(fileNames)
-            const existing = existsInfo.filter((f) => f.exists)
-            setExistingFiles(existing)
-          }
-          setIsLoading(false)
+        if (ARCHIVE_PATTERN.test(filePath)) {
+          await loadArchive(filePath)
         } else {
+          setSelectedFile(filePath)
+          setError(null)
+          setImportType('file')
           // 単一ファイルの場合、宛先パスを設定
           const fileName = filePath.split('/').pop() || filePath.split('\\').pop() || 'unknown'
           setDestinationPath(fileName)
@@ -110,7 +209,60 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
     } catch (err) {
       setError(`ファイル選択エラー: ${err}`)
     }
-  }, [])
+  }, [loadArchive])
+
+  // エクスポートしたフォルダー（Gitリポジトリなど）からの一括インポート
+  const handleSelectFolder = useCallback(async () => {
+    if (!isTauri()) {
+      setError('Tauriアプリとして起動してください')
+      return
+    }
+
+    try {
+      const result = await open({ directory: true, multiple: false })
+      if (result) {
+        await loadArchive(result as string)
+      }
+    } catch (err) {
+      setError(`フォルダー選択エラー: ${err}`)
+    }
+  }, [loadArchive])
+
+  // 入力された値のみ戻す（未入力はプレースホルダーのまま）
+  const secrets = useMemo(
+    () => Object.fromEntries(Object.entries(secretValues).filter(([, value]) => value !== '')),
+    [secretValues]
+  )
+
+  const loadMergePreview = useCallback(
+    async (path: string, resolutions: Record<string, ConflictSide> = mergeResolutions[path] ?? {}) => {
+      if (!selectedFile) return
+      const options = { strategy: mergeStrategy, resolutions }
+      try {
+        setMergePreview(
+          importType === 'zip'
+            ? await previewZipMerge(selectedFile, path, secrets, options)
+            : await previewFileMerge(selectedFile, path, options)
+        )
+        setError(null)
+      } catch (err) {
+        setMergePreview(null)
+        setError(`マージのプレビューエラー: ${err}`)
+      }
+    },
+    [selectedFile, importType, secrets, mergeStrategy, mergeResolutions]
+  )
+
+  const handleResolveConflict = useCallback(
+    (pointer: string, side: ConflictSide) => {
+      if (!mergePreview) return
+      const path = mergePreview.path
+      const resolutions = { ...(mergeResolutions[path] ?? {}), [pointer]: side }
+      setMergeResolutions((prev) => ({ ...prev, [path]: resolutions }))
+      loadMergePreview(path, resolutions)
+    },
+    [mergePreview, mergeResolutions, loadMergePreview]
+  )
 
   const handleImport = useCallback(
     async (createBackup: boolean) => {
@@ -125,9 +277,44 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
         let result: ImportResult
 
         if (importType === 'zip') {
-          result = await importZip(selectedFile, createBackup)
+          // 画面に表示している処理をそのまま指定する
+          const planChoices = importPlan
+            ? Object.fromEntries(
+                importPlan.entries.map((entry) => [entry.path, effectiveChoice(entry, choices)])
+              )
+            : undefined
+          const merge = { strategy: mergeStrategy, resolutions: mergeResolutions }
+          // バックグラウンドジョブとして実行し、進捗を表示
+          const finished = await runJob<ImportResult>(
+            () =>
+              startImportJob(
+                selectedFile,
+                createBackup,
+                secrets,
+                importPlan?.planId,
+                planChoices,
+                merge
+              ),
+            { onStart: setJobId, onProgress: setJobProgress }
+          )
+          // 計画は1回のみ実行できるため、再実行に備えて作り直す
+          if (finished.status !== 'completed') {
+            setImportPlan(await planZipImport(selectedFile))
+          }
+          if (finished.status === 'cancelled') {
+            setError('インポートをキャンセルしました（ファイルは変更されていません）')
+            setStep('preview')
+            return
+          }
+          if (finished.status !== 'completed' || !finished.result) {
+            throw new Error(finished.error ?? 'unknown error')
+          }
+          result = finished.result
         } else {
-          result = await importFile(selectedFile, destinationPath, createBackup)
+          const merge = mergeFile
+            ? { strategy: mergeStrategy, resolutions: mergeResolutions[destinationPath] ?? {} }
+            : undefined
+          result = await importFile(selectedFile, destinationPath, createBackup, merge)
         }
 
         setImportResult(result)
@@ -141,9 +328,22 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
         setStep('preview')
       } finally {
         setIsLoading(false)
+        setJobId(null)
+        setJobProgress(null)
       }
     },
-    [selectedFile, importType, destinationPath, onImportComplete]
+    [
+      selectedFile,
+      importType,
+      destinationPath,
+      secrets,
+      importPlan,
+      choices,
+      mergeFile,
+      mergeStrategy,
+      mergeResolutions,
+      onImportComplete,
+    ]
   )
 
   const handleStartImport = useCallback(() => {
@@ -180,6 +380,13 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
               setStep('select')
               setSelectedFile(null)
               setZipContents([])
+              setSecretPlaceholders([])
+              setSecretValues({})
+              setImportPlan(null)
+              setChoices({})
+              setMergeFile(false)
+              setMergeResolutions({})
+              setMergePreview(null)
               setExistingFiles([])
             }}
             className="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md transition-colors"
@@ -236,16 +443,24 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
             </div>
             <h3 className="text-lg font-medium mb-2 text-gray-900 dark:text-gray-100">ファイルを選択</h3>
             <p className="text-sm text-gray-500 dark:text-gray-400 mb-6">
-              .md, .json, .jsonl ファイルまたは .zip ファイルを選択してください
+              .md, .json, .jsonl ファイルまたは .zip, .tar.gz ファイルを選択してください
             </p>
-            <button
-              onClick={handleSelectFile}
-              className="px-4 py-2 bg-blue-500 text-white rounded-lg hover:bg-blue-600 transition-colors focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800"
-            >
-              ファイルを選択
-            </button>
+            <div className="flex justify-center gap-2">
+              <button
+                onClick={handleSelectFile}
+                className="px-4 py-2 bg-blue-500 text-white rounded-lg hover:bg-blue-600 transition-colors focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800"
+              >
+                ファイルを選択
+              </button>
+              <button
+                onClick={handleSelectFolder}
+                className="px-4 py-2 bg-gray-200 dark:bg-gray-700 text-gray-700 dark:text-gray-200 rounded-lg hover:bg-gray-300 dark:hover:bg-gray-600 transition-colors focus:outline-none focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 dark:focus:ring-offset-gray-800"
+              >
+                フォルダーを選択
+              </button>
+            </div>
             <p className="text-xs text-gray-400 dark:text-gray-500 mt-4">
-              ZIPファイルの場合、エクスポートした設定を一括でインポートできます
+              ZIP・tar.gzファイルやエクスポートしたフォルダーの場合、設定を一括でインポートできます
             </p>
           </div>
         )}
@@ -277,13 +492,142 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
                 <p className="text-xs text-gray-500 mt-1">
                   サブディレクトリも指定できます（自動作成されます）
                 </p>
+                {existingFiles.length > 0 && destinationPath.toLowerCase().endsWith('.json') && (
+                  <div className="mt-2">
+                    <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                      <input
+                        type="checkbox"
+                        checked={mergeFile}
+                        onChange={(e) => {
+                          setMergeFile(e.target.checked)
+                          setMergePreview(null)
+                        }}
+                      />
+                      上書きせずに現在の内容とマージ
+                    </label>
+                    {mergeFile && (
+                      <button
+                        onClick={() => loadMergePreview(destinationPath)}
+                        className="mt-1 text-xs text-blue-600 dark:text-blue-400 hover:underline"
+                      >
+                        マージ結果をプレビュー
+                      </button>
+                    )}
+                  </div>
+                )}
+              </div>
+            )}
+
+            {importType === 'zip' && importPlan && (
+              <div className="mb-4">
+                <p className="text-sm font-medium mb-2 text-gray-900 dark:text-gray-100">
+                  アーカイブの内容 (新規 {importPlan.summary.newFiles} / 変更 {importPlan.summary.modified} /
+                  同一 {importPlan.summary.identical} / 除外 {importPlan.summary.excluded})
+                </p>
+                <div className="bg-gray-50 dark:bg-gray-900 rounded-md p-3 max-h-60 overflow-y-auto">
+                  <ul className="space-y-1">
+                    {importPlan.entries.map((entry) => (
+                      <li
+                        key={entry.path}
+                        className="text-sm flex items-center justify-between gap-2"
+                      >
+                        <span className="flex items-center gap-2 truncate text-gray-700 dark:text-gray-300">
+                          <span
+                            className={`px-1.5 py-0.5 rounded text-xs flex-shrink-0 ${STATUS_LABELS[entry.status].className}`}
+                          >
+                            {STATUS_LABELS[entry.status].label}
+                          </span>
+                          <span
+                            className="truncate"
+                            title={
+                              entry.reason ??
+                              (entry.diff
+                                ? `+${entry.diff.stats.additions} -${entry.diff.stats.deletions}`
+                                : undefined)
+                            }
+                          >
+                            {entry.path}
+                          </span>
+                        </span>
+                        {entry.status === 'excluded' ? (
+                          <span className="text-xs text-gray-400 flex-shrink-0">{entry.reason}</span>
+                        ) : (
+                          <span className="flex items-center gap-1 flex-shrink-0">
+                            {effectiveChoice(entry, choices) === 'merge' && (
+                              <button
+                                onClick={() => loadMergePreview(entry.path)}
+                                className="text-xs text-blue-600 dark:text-blue-400 hover:underline"
+                              >
+                                プレビュー
+                              </button>
+                            )}
+                            <select
+                              aria-label={`${entry.path} の処理`}
+                              value={effectiveChoice(entry, choices)}
+                              onChange={(e) => {
+                                setChoices((prev) => ({
+                                  ...prev,
+                                  [entry.path]: e.target.value as ImportChoice,
+                                }))
+                                setMergePreview(null)
+                              }}
+                              className="text-xs px-1 py-0.5 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 flex-shrink-0"
+                            >
+                              {availableChoices(entry).map((choice) => (
+                                <option key={choice} value={choice}>
+                                  {CHOICE_LABELS[choice]}
+                                </option>
+                              ))}
+                            </select>
+                          </span>
+                        )}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
               </div>
             )}
 
-            {importType === 'zip' && zipContents.length > 0 && (
+            {((importType === 'zip' &&
+              importPlan?.entries.some((entry) => effectiveChoice(entry, choices) === 'merge')) ||
+              (importType === 'file' && mergeFile)) && (
+              <div className="mb-4">
+                <label className="flex items-center justify-between gap-2 text-sm text-gray-700 dark:text-gray-300">
+                  マージの衝突の解決
+                  <select
+                    value={mergeStrategy}
+                    onChange={(e) => {
+                      setMergeStrategy(e.target.value as MergeStrategy)
+                      setMergePreview(null)
+                    }}
+                    className="text-xs px-1 py-0.5 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                  >
+                    {(Object.keys(STRATEGY_LABELS) as MergeStrategy[]).map((strategy) => (
+                      <option key={strategy} value={strategy}>
+                        {STRATEGY_LABELS[strategy]}
+                      </option>
+                    ))}
+                  </select>
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  オブジェクトはキーごとにマージし、権限ルールは重複を除いて結合します
+                  {mergeStrategy === 'ask' && '（未解決の衝突があるファイルは書き込まれません）'}
+                </p>
+                {mergePreview && (
+                  <MergePreviewPanel
+                    preview={mergePreview}
+                    strategy={mergeStrategy}
+                    resolutions={mergeResolutions[mergePreview.path] ?? {}}
+                    onResolve={handleResolveConflict}
+                  />
+                )}
+              </div>
+            )}
+
+            {importType === 'zip' && !importPlan && zipContents.length > 0 && (
               <div className="mb-4">
                 <p className="text-sm font-medium mb-2 text-gray-900 dark:text-gray-100">
-                  ZIPの内容 ({zipContents.filter((f) => !f.is_directory).length} ファイル)
+                  アーカイブの内容 ({zipContents.filter((f) => !f.is_directory).length} ファイル)
                 </p>
                 <div className="bg-gray-50 dark:bg-gray-900 rounded-md p-3 max-h-60 overflow-y-auto">
                   <ul className="space-y-1">
@@ -312,6 +656,43 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
               </div>
             )}
 
+            {importType === 'zip' && secretPlaceholders.length > 0 && (
+              <div className="mb-4">
+                <p className="text-sm font-medium mb-1 text-gray-900 dark:text-gray-100">
+                  秘密情報 ({secretPlaceholders.length} 件)
+                </p>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mb-2">
+                  エクスポート時に置き換えられた値を入力してください（未入力の項目はプレースホルダーのままインポートされます）
+                </p>
+                <div className="space-y-2 max-h-48 overflow-y-auto">
+                  {secretPlaceholders.map((placeholder) => (
+                    <div key={placeholder.name}>
+                      <label
+                        htmlFor={`secret-${placeholder.name}`}
+                        className="block text-xs font-mono text-gray-700 dark:text-gray-300"
+                        title={placeholder.files.join('\n')}
+                      >
+                        {placeholder.name}
+                        <span className="ml-2 font-sans text-gray-400">
+                          {placeholder.files.join(', ')}
+                        </span>
+                      </label>
+                      <input
+                        id={`secret-${placeholder.name}`}
+                        type="password"
+                        autoComplete="off"
+                        value={secretValues[placeholder.name] ?? ''}
+                        onChange={(e) =>
+                          setSecretValues((prev) => ({ ...prev, [placeholder.name]: e.target.value }))
+                        }
+                        className="w-full px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm focus:outline-none focus:ring-2 focus:ring-blue-500"
+                      />
+                    </div>
+                  ))}
+                </div>
+              </div>
+            )}
+
             {existingFiles.length > 0 && (
               <div className="p-3 bg-yellow-50 dark:bg-yellow-900/30 border border-yellow-200 dark:border-yellow-800 rounded-md" role="alert">
                 <p className="text-sm text-yellow-700 dark:text-yellow-400 flex items-center gap-2">
@@ -330,9 +711,28 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
               <Icon name="spinner" className="size-12 mx-auto text-blue-500 animate-spin" />
             </div>
             <p className="text-lg font-medium text-gray-900 dark:text-gray-100">インポート中...</p>
-            <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">
-              しばらくお待ちください
-            </p>
+            {jobProgress && jobProgress.totalFiles > 0 ? (
+              <>
+                <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">
+                  {jobProgress.processedFiles} / {jobProgress.totalFiles} ファイル
+                </p>
+                <p className="text-xs text-gray-400 dark:text-gray-500 mt-1 truncate">
+                  {jobProgress.currentFile}
+                </p>
+              </>
+            ) : (
+              <p className="text-sm text-gray-500 dark:text-gray-400 mt-2">
+                しばらくお待ちください
+              </p>
+            )}
+            {jobId && (
+              <button
+                onClick={() => cancelJob(jobId)}
+                className="mt-4 px-4 py-2 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md transition-colors"
+              >
+                キャンセル
+              </button>
+            )}
           </div>
         )}
 
@@ -362,7 +762,7 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
                 ) : (
                   <>
                     <Icon name="error" className="size-5" />
-                    インポート失敗
+                    インポート失敗{importType === 'zip' && '（ファイルは変更されていません）'}
                   </>
                 )}
               </p>
@@ -414,6 +814,24 @@ const ImportDialog: FC<ImportDialogProps> = ({ isOpen, onClose, onImportComplete
                 </div>
               )}
 
+              {importResult.unresolved_secrets.length > 0 && (
+                <div>
+                  <p className="text-sm font-medium text-yellow-600 dark:text-yellow-400 mb-1">
+                    未入力の秘密情報 ({importResult.unresolved_secrets.length} 件)
+                  </p>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mb-1">
+                    ファイル内の {'${REDACTED:名前}'} を実際の値に置き換えてください
+                  </p>
+                  <ul className="text-sm font-mono text-gray-500 space-y-1 max-h-32 overflow-y-auto">
+                    {importResult.unresolved_secrets.map((name) => (
+                      <li key={name} className="truncate">
+                        {name}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
+
               {importResult.backup_created && (
                 <p className="text-xs text-gray-500 dark:text-gray-400">
                   * 元のファイルは ~/.claude/backups/ にバックアップされました
@@ -0,0 +1,65 @@
+import { FC } from 'react'
+import type { ConflictSide, JsonMergePreview, MergeStrategy } from '../../types'
+
+interface MergePreviewPanelProps {
+  preview: JsonMergePreview
+  strategy: MergeStrategy
+  resolutions: Record<string, ConflictSide>
+  onResolve: (pointer: string, side: ConflictSide) => void
+}
+
+const formatValue = (value: unknown): string => JSON.stringify(value)
+
+/** JSONのマージ結果と衝突したキーの表示（ask の場合は衝突ごとに採用する値を選択） */
+const MergePreviewPanel: FC<MergePreviewPanelProps> = ({
+  preview,
+  strategy,
+  resolutions,
+  onResolve,
+}) => (
+  <div className="mt-2 border border-gray-200 dark:border-gray-700 rounded-md p-2">
+    <p className="text-xs text-gray-600 dark:text-gray-400 mb-1">
+      {preview.path}: 衝突 {preview.conflicts.length} 件
+      {preview.unresolved > 0 && (
+        <span className="ml-1 text-red-600 dark:text-red-400">（未解決 {preview.unresolved} 件）</span>
+      )}
+      {!preview.changed && <span className="ml-1">（変更なし）</span>}
+    </p>
+
+    {preview.conflicts.length > 0 && (
+      <ul className="space-y-1 mb-2 max-h-32 overflow-y-auto">
+        {preview.conflicts.map((conflict) => (
+          <li key={conflict.pointer} className="text-xs flex items-center justify-between gap-2">
+            <span className="truncate font-mono text-gray-700 dark:text-gray-300" title={conflict.pointer}>
+              {conflict.pointer || '/'}
+            </span>
+            {strategy === 'ask' ? (
+              <select
+                aria-label={`${conflict.pointer} で採用する値`}
+                value={resolutions[conflict.pointer] ?? ''}
+                onChange={(e) => onResolve(conflict.pointer, e.target.value as ConflictSide)}
+                className="text-xs px-1 py-0.5 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 flex-shrink-0 max-w-[60%]"
+              >
+                <option value="" disabled>
+                  選択してください
+                </option>
+                <option value="ours">現在: {formatValue(conflict.ours)}</option>
+                <option value="theirs">インポート: {formatValue(conflict.theirs)}</option>
+              </select>
+            ) : (
+              <span className="truncate text-gray-500 dark:text-gray-400">
+                {formatValue(conflict.resolution === 'ours' ? conflict.ours : conflict.theirs)}
+              </span>
+            )}
+          </li>
+        ))}
+      </ul>
+    )}
+
+    <pre className="text-xs font-mono bg-gray-50 dark:bg-gray-900 text-gray-800 dark:text-gray-200 p-2 rounded max-h-48 overflow-auto whitespace-pre">
+      {preview.merged}
+    </pre>
+  </div>
+)
+
+export default MergePreviewPanel
@@ -45,7 +45,7 @@ interface MainAreaProps {
   scrollSync?: boolean
   onPreviewChanges?: () => void
   hasUnsavedChanges?: boolean
-  onCompareWithBackup?: (backupPath: string, originalPath: string, backupName: string) => void
+  onCompareWithBackup?: (backupId: string, originalPath: string, backupName: string) => void
   // タブ関連のプロパティ
   tabs?: Tab[]
   activeTabId?: string
@@ -320,6 +320,7 @@ const MainArea: FC<MainAreaProps> = memo(({
       fileName: tab.name,
       filePath: tab.path,
       isDirty: tab.content !== tab.originalContent,
+      diskChange: tab.diskChange ?? null,
       isActive: tab.id === activeTabId,
     }))
   }, [tabs, activeTabId])
@@ -1,12 +1,22 @@
 import { memo, useCallback, useRef } from 'react'
 import { Icon } from '../common'
 
+import type { TabDiskChange } from '../../types'
+
 export interface EditorTab {
   id: string
   fileName: string
   filePath: string
   isDirty: boolean
   isActive: boolean
+  /** 開いた後にディスク上で起きた変更 */
+  diskChange?: TabDiskChange | null
+}
+
+/** ディスク上での変更の説明 */
+const DISK_CHANGE_LABELS: Record<TabDiskChange, string> = {
+  modified: 'ディスク上で変更されました',
+  removed: 'ディスク上で削除されました',
 }
 
 interface TabItemProps {
@@ -24,6 +34,7 @@ interface TabItemProps {
  * 個別タブコンポーネント
  * - ファイル名表示
  * - 閉じるボタン（未保存時は●表示）
+ * - ディスク上で変更・削除された場合の警告表示
  * - ドラッグ&ドロップ対応
  * - アクティブ状態のハイライト
  */
@@ -77,12 +88,18 @@ const TabItem = memo<TabItemProps>(({
     return <Icon name="file" className="size-3.5 text-gray-400 flex-shrink-0" />
   }
 
+  const ariaLabel = [
+    tab.fileName,
+    tab.isDirty ? '（未保存）' : '',
+    tab.diskChange ? `（${DISK_CHANGE_LABELS[tab.diskChange]}）` : '',
+  ].join('')
+
   return (
     <div
       ref={tabRef}
       role="tab"
       aria-selected={tab.isActive}
-      aria-label={`${tab.fileName}${tab.isDirty ? '（未保存）' : ''}`}
+      aria-label={ariaLabel}
       tabIndex={tab.isActive ? 0 : -1}
       draggable
       className={`
@@ -109,6 +126,16 @@ const TabItem = memo<TabItemProps>(({
         {tab.fileName}
       </span>
 
+      {/* ディスク上での変更 */}
+      {tab.diskChange && (
+        <span title={DISK_CHANGE_LABELS[tab.diskChange]} className="flex-shrink-0">
+          <Icon
+            name="warning"
+            className={`size-3.5 ${tab.diskChange === 'removed' ? 'text-red-500' : 'text-yellow-500'}`}
+          />
+        </span>
+      )}
+
       {/* 閉じるボタン / 未保存マーク */}
       <button
         type="button"
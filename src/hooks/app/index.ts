@@ -19,3 +19,9 @@ export type {
 
 // 統合キーボードショートカットフック
 export { useAppKeyboardShortcuts } from './useAppKeyboardShortcuts'
+
+// 設定ルート切り替えの同期フック
+export { useAppConfigRootSync } from './useAppConfigRootSync'
+
+// ファイル変更の同期フック
+export { useAppFileChangeSync } from './useAppFileChangeSync'
@@ -0,0 +1,137 @@
+import { useEffect, useRef } from 'react'
+
+import { isTauri, onConfigRootChanged, readFile } from '../useTauri'
+
+import type { UnlistenFn } from '@tauri-apps/api/event'
+import type { ConfigRootInfo, SelectedFile, Tab } from '../../types'
+
+interface UseAppConfigRootSyncOptions {
+  /** 開いているタブ */
+  tabs: Tab[]
+  /** 選択中のファイル */
+  selectedFile: SelectedFile | null
+  /** ファイルツリーを再読み込みするコールバック */
+  onReloadFileTree: () => Promise<void>
+  /** ファイルを選択し直すコールバック */
+  onReselectFile: (path: string, name: string) => Promise<boolean>
+  /** ファイルの選択を解除するコールバック */
+  onClearSelection: () => void
+  /** タブの内容を置き換えるコールバック */
+  onReloadTab: (tabId: string, content: string, versionToken?: string | null) => void
+  /** タブを閉じるコールバック */
+  onCloseTab: (tabId: string) => boolean
+  /** 成功メッセージを表示するコールバック */
+  onSuccess: (message: string) => void
+}
+
+/**
+ * App.tsx用の設定ルート切り替えの同期フック
+ *
+ * config-root:changed を受け取ったら以下を行う:
+ * - ファイルツリーの再読み込み
+ * - 保存済みのタブを新しい設定ルートから読み直す（読めないタブは閉じる）
+ * - 選択中のファイルを読み直す（読めない場合は選択を解除）
+ *
+ * 未保存の変更があるタブと選択中のファイルはそのまま残す
+ */
+export function useAppConfigRootSync({
+  tabs,
+  selectedFile,
+  onReloadFileTree,
+  onReselectFile,
+  onClearSelection,
+  onReloadTab,
+  onCloseTab,
+  onSuccess,
+}: UseAppConfigRootSyncOptions): void {
+  // イベントの購読を張り直さずに最新の状態を参照する
+  const latest = useRef({
+    tabs,
+    selectedFile,
+    onReloadFileTree,
+    onReselectFile,
+    onClearSelection,
+    onReloadTab,
+    onCloseTab,
+    onSuccess,
+  })
+
+  // 最新の状態を保持
+  useEffect(() => {
+    latest.current = {
+      tabs,
+      selectedFile,
+      onReloadFileTree,
+      onReselectFile,
+      onClearSelection,
+      onReloadTab,
+      onCloseTab,
+      onSuccess,
+    }
+  }, [
+    tabs,
+    selectedFile,
+    onReloadFileTree,
+    onReselectFile,
+    onClearSelection,
+    onReloadTab,
+    onCloseTab,
+    onSuccess,
+  ])
+
+  useEffect(() => {
+    if (!isTauri()) return
+
+    let unlisten: UnlistenFn | undefined
+    let isMounted = true
+
+    const handleChange = async (info: ConfigRootInfo) => {
+      const current = latest.current
+      await current.onReloadFileTree()
+
+      for (const tab of current.tabs) {
+        if (tab.content !== tab.originalContent) continue
+        const file = await readFile(tab.path)
+        if (file) {
+          current.onReloadTab(tab.id, file.content, file.versionToken)
+        } else {
+          current.onCloseTab(tab.id)
+        }
+      }
+
+      const selected = current.selectedFile
+      if (selected && selected.content === selected.originalContent) {
+        const success = await current.onReselectFile(selected.path, selected.name)
+        if (!success) {
+          current.onClearSelection()
+        }
+      }
+
+      current.onSuccess(`設定ルートを切り替えました: ${info.path}`)
+    }
+
+    const setupListener = async () => {
+      try {
+        const stop = await onConfigRootChanged((info) => {
+          void handleChange(info)
+        })
+        if (isMounted) {
+          unlisten = stop
+        } else {
+          stop()
+        }
+      } catch {
+        // リスナー設定エラーは無視
+      }
+    }
+
+    setupListener()
+
+    return () => {
+      isMounted = false
+      if (unlisten) {
+        unlisten()
+      }
+    }
+  }, [])
+}
@@ -0,0 +1,96 @@
+import { useEffect, useRef } from 'react'
+
+import { isTauri, onFsCreated, onFsModified, onFsRemoved, onFsRenamed, readFile } from '../useTauri'
+
+import type { UnlistenFn } from '@tauri-apps/api/event'
+import type { Tab, TabDiskChange } from '../../types'
+
+interface UseAppFileChangeSyncOptions {
+  /** 開いているタブ */
+  tabs: Tab[]
+  /** ディスク上での変更を記録するコールバック */
+  onMarkDiskChange: (tabId: string, change: TabDiskChange | null) => void
+}
+
+/**
+ * パスが変更されたパス自身か、その配下にあるか判定
+ */
+function isSameOrInside(path: string, changed: string): boolean {
+  if (path === changed) return true
+  return path.startsWith(`${changed}/`) || path.startsWith(`${changed}\\`)
+}
+
+/**
+ * App.tsx用のファイル変更の同期フック
+ *
+ * fs:created / fs:modified / fs:removed / fs:renamed を受け取ったら、
+ * 該当する開いているタブのファイルを読み直し、開いた時（保存した時）の
+ * バージョントークンと比較してディスク上での変更を記録する
+ */
+export function useAppFileChangeSync({
+  tabs,
+  onMarkDiskChange,
+}: UseAppFileChangeSyncOptions): void {
+  // イベントの購読を張り直さずに最新の状態を参照する
+  const latest = useRef({ tabs, onMarkDiskChange })
+
+  // 最新の状態を保持
+  useEffect(() => {
+    latest.current = { tabs, onMarkDiskChange }
+  }, [tabs, onMarkDiskChange])
+
+  useEffect(() => {
+    if (!isTauri()) return
+
+    const unlisteners: UnlistenFn[] = []
+    let isMounted = true
+
+    const checkTab = async (tab: Tab) => {
+      const file = await readFile(tab.path)
+      if (!file) {
+        latest.current.onMarkDiskChange(tab.id, 'removed')
+        return
+      }
+
+      // バージョントークンがない場合（復元したタブなど）は内容で比較する
+      const changed = tab.versionToken
+        ? file.versionToken !== tab.versionToken
+        : file.content !== tab.originalContent
+      latest.current.onMarkDiskChange(tab.id, changed ? 'modified' : null)
+    }
+
+    const handlePaths = (paths: string[]) => {
+      const affected = latest.current.tabs.filter(tab =>
+        paths.some(changed => isSameOrInside(tab.path, changed))
+      )
+      for (const tab of affected) {
+        void checkTab(tab)
+      }
+    }
+
+    const setupListeners = async () => {
+      try {
+        const stops = await Promise.all([
+          onFsCreated((event) => handlePaths(event.paths)),
+          onFsModified((event) => handlePaths(event.paths)),
+          onFsRemoved((event) => handlePaths(event.paths)),
+          onFsRenamed((event) => handlePaths([event.from, event.to])),
+        ])
+        if (isMounted) {
+          unlisteners.push(...stops)
+        } else {
+          stops.forEach(stop => stop())
+        }
+      } catch {
+        // リスナー設定エラーは無視
+      }
+    }
+
+    setupListeners()
+
+    return () => {
+      isMounted = false
+      unlisteners.forEach(unlisten => unlisten())
+    }
+  }, [])
+}
@@ -86,6 +86,8 @@ export {
   useAppSearchReplace,
   useAppAIReview,
   useAppKeyboardShortcuts,
+  useAppConfigRootSync,
+  useAppFileChangeSync,
 } from './app'
 export type {
   UseAppSearchReplaceReturn,
@@ -5,7 +5,7 @@
 
 import { invokeWithBoolean, invokeWithDefault } from './utils'
 
-import type { FileContent, FileNode } from '../../types'
+import type { BackupList, BackupRecord, CleanupResult, RetentionPlan } from '../../types'
 
 // ============================================================
 // バックアップ取得
@@ -13,20 +13,28 @@ import type { FileContent, FileNode } from '../../types'
 
 /**
  * バックアップ一覧を取得する
- * @returns バックアップファイルノードの配列（エラー時は空配列）
+ * @returns バックアップの一覧と論理・物理サイズ（エラー時はnull）
  */
-export async function getBackups(): Promise<FileNode[]> {
-  return invokeWithDefault<FileNode[]>('get_backups', undefined, [])
+export async function getBackups(): Promise<BackupList | null> {
+  return invokeWithDefault<BackupList | null>('get_backups', undefined, null)
 }
 
 /**
- * バックアップファイルの内容を取得する
- * @param backupPath - バックアップファイルのパス
+ * 指定したファイルのバックアップ一覧を取得する
+ * @param path - 対象ファイルのパス
+ * @returns バックアップの配列（新しい順、エラー時は空配列）
+ */
+export async function getBackupsFor(path: string): Promise<BackupRecord[]> {
+  return invokeWithDefault<BackupRecord[]>('get_backups_for', { path }, [])
+}
+
+/**
+ * バックアップの内容を取得する
+ * @param backupId - バックアップID
  * @returns バックアップの内容（エラー時はnull）
  */
-export async function getBackupContent(backupPath: string): Promise<string | null> {
-  const result = await invokeWithDefault<FileContent | null>('read_file', { path: backupPath }, null)
-  return result?.content ?? null
+export async function getBackupContent(backupId: string): Promise<string | null> {
+  return invokeWithDefault<string | null>('get_backup_content', { backupId }, null)
 }
 
 // ============================================================
@@ -34,11 +42,40 @@ export async function getBackupContent(backupPath: string): Promise<string | nul
 // ============================================================
 
 /**
- * バックアップからファイルを復元する
- * @param backupPath - バックアップファイルのパス
- * @param targetPath - 復元先のパス
+ * バックアップを元のパスに復元する
+ * @param backupId - バックアップID
  * @returns 成功時true、失敗時false
  */
-export async function restoreBackup(backupPath: string, targetPath: string): Promise<boolean> {
-  return invokeWithBoolean('restore_backup', { backupPath, targetPath })
+export async function restoreBackup(backupId: string): Promise<boolean> {
+  return invokeWithBoolean('restore_backup_by_id', { backupId })
+}
+
+// ============================================================
+// ピン留め・保持ポリシー
+// ============================================================
+
+/**
+ * バックアップのピン留めを設定する
+ * @param backupId - バックアップID
+ * @param pinned - ピン留めするかどうか
+ * @returns 更新後のバックアップ（エラー時はnull）
+ */
+export async function setBackupPinned(backupId: string, pinned: boolean): Promise<BackupRecord | null> {
+  return invokeWithDefault<BackupRecord | null>('set_backup_pinned', { backupId, pinned }, null)
+}
+
+/**
+ * 保持ポリシーを適用した場合に削除されるバックアップを取得する（ドライラン）
+ * @returns 削除対象と適用前後のサイズ（エラー時はnull）
+ */
+export async function previewBackupRetention(): Promise<RetentionPlan | null> {
+  return invokeWithDefault<RetentionPlan | null>('preview_backup_retention', undefined, null)
+}
+
+/**
+ * 保持ポリシーを適用する
+ * @returns クリーンアップ結果（エラー時はnull）
+ */
+export async function applyBackupRetention(): Promise<CleanupResult | null> {
+  return invokeWithDefault<CleanupResult | null>('apply_backup_retention', undefined, null)
 }
@@ -0,0 +1,64 @@
+/**
+ * @fileoverview 差分操作関連のTauri API
+ * @module hooks/tauri/diff
+ */
+
+import { invokeWithDefault } from './utils'
+
+import type { DiffOptions, DiffResult, DiffSource, WriteResult } from '../../types'
+
+// ============================================================
+// 差分の計算
+// ============================================================
+
+/**
+ * 2つの内容の差分を計算する
+ * @param oldSource - 比較元（現在のファイル、バックアップ、スナップショットなど）
+ * @param newSource - 比較先
+ * @param options - 出力形式・前後の行数・JSONとして比較するか
+ * @returns 差分（エラー時はnull）
+ */
+export async function computeDiff(
+  oldSource: DiffSource,
+  newSource: DiffSource,
+  options?: DiffOptions
+): Promise<DiffResult | null> {
+  return invokeWithDefault<DiffResult | null>(
+    'compute_diff',
+    { old: oldSource, new: newSource, options: options ?? null },
+    null
+  )
+}
+
+// ============================================================
+// 選択したハンクの復元
+// ============================================================
+
+/**
+ * 選択したハンクのみを比較元の内容に戻す
+ * @param path - 戻すファイルのパス
+ * @param source - 戻す内容の取得元（computeDiffの比較元と同じ）
+ * @param hunks - 戻すハンクの番号
+ * @param contextLines - 差分を計算した際の前後の行数
+ * @param expectedVersion - 差分を計算した時点のバージョントークン
+ * @returns 書き込み後のバージョン、または差分の計算後に変更されていた場合は競合（エラー時はnull）
+ */
+export async function restoreDiffHunks(
+  path: string,
+  source: DiffSource,
+  hunks: number[],
+  contextLines?: number,
+  expectedVersion?: string
+): Promise<WriteResult | null> {
+  return invokeWithDefault<WriteResult | null>(
+    'restore_diff_hunks',
+    {
+      path,
+      source,
+      hunks,
+      contextLines: contextLines ?? null,
+      expectedVersion: expectedVersion ?? null,
+    },
+    null
+  )
+}
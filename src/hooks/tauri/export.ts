@@ -5,6 +5,13 @@
 
 import { invokeOrThrow, invokeWithBoolean, invokeWithDefault } from './utils'
 
+import type {
+  ArchiveFormat,
+  ExportManifest,
+  ExportManifestEntry,
+  ExportSelection,
+} from '../../types'
+
 // ============================================================
 // ファイルエクスポート
 // ============================================================
@@ -20,17 +27,49 @@ export async function exportFile(sourcePath: string, destPath: string): Promise<
 }
 
 // ============================================================
-// ZIPエクスポート
+// アーカイブエクスポート（ZIP・tar.gz・フォルダー）
 // ============================================================
 
 /**
- * ~/.claude/ 全体をZIPでエクスポートする
- * @param destPath - エクスポート先のZIPファイルパス
+ * ~/.claude/ 全体をアーカイブでエクスポートする
+ * @param destPath - エクスポート先のパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param format - 形式（省略時はパスから判定し、判定できない場合はZIP）
  * @returns エクスポートされたファイル数
  * @throws {TauriError} エクスポートに失敗した場合
  */
-export async function exportAllZip(destPath: string): Promise<number> {
-  return invokeOrThrow<number>('export_all_zip', { destPath })
+export async function exportAllZip(destPath: string, format?: ArchiveFormat): Promise<number> {
+  return invokeOrThrow<number>('export_all_zip', { destPath, format: format ?? null })
+}
+
+/**
+ * 選択したファイルをアーカイブでエクスポートする（マニフェスト .ccsd-export.json を含む）
+ * @param destPath - エクスポート先のパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param selection - 対象にするglob・除くglob・ファイル種別
+ * @param format - 形式（省略時はパスから判定し、判定できない場合はZIP）
+ * @returns アーカイブに含めたマニフェスト
+ * @throws {TauriError} エクスポートに失敗した場合
+ */
+export async function exportSelectedZip(
+  destPath: string,
+  selection: ExportSelection,
+  format?: ArchiveFormat
+): Promise<ExportManifest> {
+  return invokeOrThrow<ExportManifest>('export_selected_zip', {
+    destPath,
+    selection,
+    format: format ?? null,
+  })
+}
+
+/**
+ * 選択条件に一致するファイルを確認する
+ * @param selection - 対象にするglob・除くglob・ファイル種別
+ * @returns エクスポートされるファイル（エラー時は空配列）
+ */
+export async function previewExportSelection(
+  selection: ExportSelection
+): Promise<ExportManifestEntry[]> {
+  return invokeWithDefault<ExportManifestEntry[]>('preview_export_selection', { selection }, [], true)
 }
 
 /**
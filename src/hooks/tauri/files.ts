@@ -8,7 +8,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { translateTauriCommandError } from '../../utils/errorMessages'
 import { isTauri } from './utils'
 
-import type { FileContent, FileNode } from '../../types'
+import type { FileContent, FileNode, WriteResult } from '../../types'
 
 // ============================================================
 // 型定義
@@ -124,19 +124,27 @@ export async function readFileWithResult(path: string): Promise<FileOperationRes
  * 指定パスにファイルを書き込む
  * @param path - 書き込み先のファイルパス
  * @param content - 書き込む内容
- * @returns 成功時true、失敗時false
+ * @param expectedVersion - 読み込み時のバージョントークン（指定時は変更を検出）
+ * @returns 書き込み後のバージョン、または読み込み後に変更されていた場合は競合（失敗時はnull）
  */
-export async function writeFile(path: string, content: string): Promise<boolean> {
+export async function writeFile(
+  path: string,
+  content: string,
+  expectedVersion?: string | null
+): Promise<WriteResult | null> {
   if (!isTauri()) {
-    return false
+    return null
   }
 
   try {
-    await invoke('write_file', { path, content })
-    return true
+    return await invoke<WriteResult>('write_file', {
+      path,
+      content,
+      expectedVersion: expectedVersion ?? null,
+    })
   } catch (error) {
     translateTauriCommandError('write_file', error)
-    return false
+    return null
   }
 }
 
@@ -0,0 +1,96 @@
+/**
+ * @fileoverview Gitによる変更履歴関連のTauri API
+ * @module hooks/tauri/history
+ */
+
+import { invokeWithDefault } from './utils'
+
+import type { GitCommitInfo, GitFileDiff, GitHistoryStatus } from '../../types'
+
+// ============================================================
+// 履歴の取得
+// ============================================================
+
+/**
+ * 変更履歴の状態（有効かどうか、コミットされていない変更）を取得する
+ * @returns 状態（エラー時はnull）
+ */
+export async function getGitHistoryStatus(): Promise<GitHistoryStatus | null> {
+  return invokeWithDefault<GitHistoryStatus | null>('get_git_history_status', undefined, null)
+}
+
+/**
+ * コミット履歴を取得する
+ * @param limit - 最大件数（省略時は100件）
+ * @param offset - 読み飛ばす件数
+ * @returns コミットの配列（新しい順、エラー時は空配列）
+ */
+export async function getGitLog(limit?: number, offset?: number): Promise<GitCommitInfo[]> {
+  return invokeWithDefault<GitCommitInfo[]>(
+    'get_git_log',
+    { limit: limit ?? null, offset: offset ?? null },
+    []
+  )
+}
+
+/**
+ * ファイルを変更したコミットの履歴を取得する
+ * @param path - 設定ルートからの相対パス
+ * @param limit - 最大件数（省略時は100件）
+ * @returns コミットの配列（新しい順、エラー時は空配列）
+ */
+export async function getGitFileHistory(path: string, limit?: number): Promise<GitCommitInfo[]> {
+  return invokeWithDefault<GitCommitInfo[]>(
+    'get_git_file_history',
+    { path, limit: limit ?? null },
+    []
+  )
+}
+
+/**
+ * コミット間、またはコミットと現在のファイルの差分を取得する
+ * @param from - 比較元のコミットID
+ * @param to - 比較先のコミットID（省略時は現在のファイル）
+ * @param path - 対象のファイル（省略時はすべて）
+ * @returns ファイルごとの差分（エラー時は空配列）
+ */
+export async function getGitDiff(from: string, to?: string, path?: string): Promise<GitFileDiff[]> {
+  return invokeWithDefault<GitFileDiff[]>(
+    'get_git_diff',
+    { from, to: to ?? null, path: path ?? null },
+    []
+  )
+}
+
+/**
+ * 指定したコミット時点のファイルの内容を取得する
+ * @param path - 設定ルートからの相対パス
+ * @param revision - コミットID
+ * @returns ファイルの内容（エラー時はnull）
+ */
+export async function getGitFileAt(path: string, revision: string): Promise<string | null> {
+  return invokeWithDefault<string | null>('get_git_file_at', { path, revision }, null)
+}
+
+// ============================================================
+// 復元・コミット
+// ============================================================
+
+/**
+ * 指定したコミット時点の内容でファイルを復元する
+ * @param path - 設定ルートからの相対パス
+ * @param revision - コミットID
+ * @returns 復元を記録したコミットのID（変更がない場合やエラー時はnull）
+ */
+export async function checkoutGitFile(path: string, revision: string): Promise<string | null> {
+  return invokeWithDefault<string | null>('checkout_git_file', { path, revision }, null)
+}
+
+/**
+ * ダッシュボードの外で行われた変更をコミットする
+ * @param message - コミットメッセージ（省略時は自動生成）
+ * @returns 作成したコミットのID（変更がない場合やエラー時はnull）
+ */
+export async function commitGitChanges(message?: string): Promise<string | null> {
+  return invokeWithDefault<string | null>('commit_git_changes', { message: message ?? null }, null)
+}
@@ -5,9 +5,19 @@
 
 import { invoke } from '@tauri-apps/api/core'
 
-import { createErrorImportResult, invokeWithDefault } from './utils'
+import { createErrorImportResult, invokeOrThrow, invokeWithDefault } from './utils'
 
-import type { FileExistsInfo, ImportResult, ZipFileInfo } from '../../types'
+import type {
+  FileExistsInfo,
+  ImportChoice,
+  ImportPlan,
+  ImportResult,
+  JsonMergeOptions,
+  JsonMergePreview,
+  SecretPlaceholder,
+  ZipFileInfo,
+  ZipMergeOptions,
+} from '../../types'
 
 // ============================================================
 // ファイル存在チェック
@@ -40,46 +50,137 @@ export async function checkFilesExist(relativePaths: string[]): Promise<FileExis
  * @param sourcePath - インポート元のファイルパス（外部）
  * @param relativeDest - ~/.claude/ からの相対パス
  * @param shouldBackup - 既存ファイルのバックアップを作成するか（デフォルト: true）
+ * @param merge - 既存のJSONファイルとマージする場合の指定（省略時は上書き）
  * @returns インポート結果
  */
 export async function importFile(
   sourcePath: string,
   relativeDest: string,
-  shouldBackup: boolean = true
+  shouldBackup: boolean = true,
+  merge?: JsonMergeOptions
 ): Promise<ImportResult> {
   try {
-    return await invoke<ImportResult>('import_file', { sourcePath, relativeDest, shouldBackup })
+    return await invoke<ImportResult>('import_file', {
+      sourcePath,
+      relativeDest,
+      shouldBackup,
+      merge: merge ?? null,
+    })
   } catch (error) {
     console.error('Failed to import file:', error)
     return createErrorImportResult([String(error)])
   }
 }
 
+/**
+ * 単一ファイルのインポートでのマージ結果をプレビューする（書き込みはしない）
+ * @param sourcePath - インポート元のファイルパス（外部）
+ * @param relativeDest - ~/.claude/ からの相対パス
+ * @param options - マージの指定
+ * @returns マージ結果と衝突したキー
+ * @throws {TauriError} JSONとして解析できない場合など
+ */
+export async function previewFileMerge(
+  sourcePath: string,
+  relativeDest: string,
+  options?: JsonMergeOptions
+): Promise<JsonMergePreview> {
+  return invokeOrThrow<JsonMergePreview>('preview_file_merge', {
+    sourcePath,
+    relativeDest,
+    options: options ?? null,
+  })
+}
+
 // ============================================================
-// ZIPインポート
+// アーカイブインポート（ZIP・tar.gz・フォルダー）
 // ============================================================
 
 /**
- * ZIPファイルの内容をプレビューする
- * @param zipPath - ZIPファイルのパス
- * @returns ZIPファイル内のエントリ情報の配列（エラー時は空配列）
+ * アーカイブの内容をプレビューする
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @returns アーカイブ内のエントリ情報の配列（エラー時は空配列）
  */
 export async function previewZip(zipPath: string): Promise<ZipFileInfo[]> {
   return invokeWithDefault<ZipFileInfo[]>('preview_zip', { zipPath }, [], true)
 }
 
 /**
- * ZIPファイルをインポートする（全設定復元）
- * @param zipPath - ZIPファイルのパス
+ * アーカイブに含まれる秘密情報のプレースホルダーを取得する
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @returns プレースホルダーの配列（エラー時は空配列）
+ */
+export async function getZipSecretPlaceholders(zipPath: string): Promise<SecretPlaceholder[]> {
+  return invokeWithDefault<SecretPlaceholder[]>('get_zip_secret_placeholders', { zipPath }, [])
+}
+
+/**
+ * アーカイブのインポート計画を作成する（各ファイルを新規・同一・変更・除外に分類、書き込みはしない）
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param secrets - 秘密情報のプレースホルダーの名前と値（値を戻した内容で比較）
+ * @returns インポート計画（エラー時はnull）
+ */
+export async function planZipImport(
+  zipPath: string,
+  secrets?: Record<string, string>
+): Promise<ImportPlan | null> {
+  return invokeWithDefault<ImportPlan | null>(
+    'plan_zip_import',
+    { zipPath, secrets: secrets ?? null },
+    null
+  )
+}
+
+/**
+ * アーカイブ内のファイルのマージ結果をプレビューする（書き込みはしない）
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param entryPath - アーカイブ内のパス
+ * @param secrets - 秘密情報のプレースホルダーの名前と値
+ * @param options - マージの指定
+ * @returns マージ結果と衝突したキー
+ * @throws {TauriError} JSONとして解析できない場合など
+ */
+export async function previewZipMerge(
+  zipPath: string,
+  entryPath: string,
+  secrets?: Record<string, string>,
+  options?: JsonMergeOptions
+): Promise<JsonMergePreview> {
+  return invokeOrThrow<JsonMergePreview>('preview_zip_merge', {
+    zipPath,
+    entryPath,
+    secrets: secrets ?? null,
+    options: options ?? null,
+  })
+}
+
+/**
+ * アーカイブをインポートする（全設定復元）
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
  * @param createBackups - 既存ファイルのバックアップを作成するか（デフォルト: true）
+ * @param secrets - 秘密情報のプレースホルダーの名前と値
+ * @param planId - 実行するインポート計画のID（planZipImport で作成、1回のみ使用可）
+ * @param choices - ファイルごとの処理（キー: アーカイブ内のパス、planId を指定した場合のみ）
+ * @param merge - JSONのマージの指定（指定すると既存の設定ファイルは既定でマージ）
  * @returns インポート結果
  */
 export async function importZip(
   zipPath: string,
-  createBackups: boolean = true
+  createBackups: boolean = true,
+  secrets?: Record<string, string>,
+  planId?: string,
+  choices?: Record<string, ImportChoice>,
+  merge?: ZipMergeOptions
 ): Promise<ImportResult> {
   try {
-    return await invoke<ImportResult>('import_zip', { zipPath, createBackups })
+    return await invoke<ImportResult>('import_zip', {
+      zipPath,
+      createBackups,
+      secrets: secrets ?? null,
+      planId: planId ?? null,
+      choices: choices ?? null,
+      merge: merge ?? null,
+    })
   } catch (error) {
     console.error('Failed to import ZIP:', error)
     return createErrorImportResult([String(error)])
@@ -6,9 +6,15 @@
  * - utils: 共通ユーティリティ（isTauri判定、エラーハンドリング）
  * - files: ファイル操作（読み書き、検索）
  * - backup: バックアップ操作（取得、復元）
+ * - snapshot: スナップショット操作（作成、差分、復元）
+ * - diff: 差分操作（任意の2つの内容の比較、選択したハンクの復元）
+ * - history: 変更履歴操作（コミット履歴、差分、リビジョンからの復元）
  * - export: エクスポート操作（単一ファイル、ZIP）
+ * - plugin: プラグインのエクスポート（検証、マーケットプレイスへの追加）
  * - import: インポート操作（単一ファイル、ZIP）
  * - template: カスタムテンプレート操作（CRUD）
+ * - profiles: 設定ルートのプロファイル操作（一覧、切り替え、変更の購読）
+ * - watcher: ファイル変更の監視イベントの購読
  *
  * 型定義は @/types から取得してください。
  */
@@ -17,10 +23,46 @@
 export type {
   FileNode,
   FileContent,
+  FileVersion,
+  WriteConflict,
+  WriteResult,
+  FsChangeEvent,
+  FsRenameEvent,
   BackupInfo,
+  BackupRecord,
+  BackupList,
+  RetentionReason,
+  RetentionCandidate,
+  RetentionPlan,
+  CleanupResult,
+  SnapshotInfo,
+  SnapshotDiff,
+  SnapshotRestoreResult,
+  DiffSource,
+  DiffOptions,
+  DiffResult,
+  GitCommitInfo,
+  GitHistoryStatus,
+  GitFileDiff,
   ImportResult,
   FileExistsInfo,
   ZipFileInfo,
+  SecretPlaceholder,
+  ImportChoice,
+  ImportPlan,
+  JsonMergeOptions,
+  ZipMergeOptions,
+  JsonMergePreview,
+  ArchiveFormat,
+  PluginMetadata,
+  PluginSelection,
+  PluginComponents,
+  PluginValidation,
+  PluginExportResult,
+  ExportProgress,
+  JobFinishedEvent,
+  ConfigProfile,
+  ConfigRootInfo,
   CustomTemplate,
   SaveTemplateInput,
 } from '../../types'
@@ -44,26 +86,98 @@ export type { ReplaceResult, SearchReplaceOptions } from './files'
 // バックアップ操作
 export {
   getBackups,
+  getBackupsFor,
   getBackupContent,
   restoreBackup,
+  setBackupPinned,
+  previewBackupRetention,
+  applyBackupRetention,
 } from './backup'
 
+// スナップショット操作
+export {
+  createSnapshot,
+  listSnapshots,
+  diffSnapshot,
+  restoreSnapshot,
+  deleteSnapshot,
+} from './snapshot'
+
+// 差分操作
+export { computeDiff, restoreDiffHunks } from './diff'
+
+// 変更履歴操作
+export {
+  getGitHistoryStatus,
+  getGitLog,
+  getGitFileHistory,
+  getGitDiff,
+  getGitFileAt,
+  checkoutGitFile,
+  commitGitChanges,
+} from './history'
+
 // エクスポート操作
 export {
   exportFile,
   exportAllZip,
+  exportSelectedZip,
+  previewExportSelection,
   getExportFileCount,
 } from './export'
 
+// プラグインのエクスポート
+export { listPluginComponents, validatePlugin, exportPlugin } from './plugin'
+
 // インポート操作
 export {
   checkFileExists,
   checkFilesExist,
   importFile,
   previewZip,
+  getZipSecretPlaceholders,
+  planZipImport,
+  previewFileMerge,
+  previewZipMerge,
   importZip,
 } from './import'
 
+// バックグラウンドジョブ
+export {
+  startExportJob,
+  startImportJob,
+  cancelJob,
+  onJobProgress,
+  onJobFinished,
+  runJob,
+  JOB_PROGRESS_EVENT,
+  JOB_FINISHED_EVENT,
+} from './jobs'
+export type { RunJobHandlers } from './jobs'
+
+// 設定ルートのプロファイル操作
+export {
+  getActiveConfigRoot,
+  getConfigProfiles,
+  addConfigProfile,
+  removeConfigProfile,
+  switchConfigRoot,
+  onConfigRootChanged,
+  CONFIG_ROOT_CHANGED_EVENT,
+} from './profiles'
+
+// ファイル変更の監視
+export {
+  onFsCreated,
+  onFsModified,
+  onFsRemoved,
+  onFsRenamed,
+  FS_CREATED_EVENT,
+  FS_MODIFIED_EVENT,
+  FS_REMOVED_EVENT,
+  FS_RENAMED_EVENT,
+} from './watcher'
+
 // テンプレート操作
 export {
   saveCustomTemplate,
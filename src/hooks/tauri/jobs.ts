@@ -0,0 +1,162 @@
+/**
+ * @fileoverview バックグラウンドジョブ関連のTauri API
+ * @module hooks/tauri/jobs
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+import { invokeOrThrow, invokeWithDefault } from './utils'
+
+import type {
+  ArchiveFormat,
+  ExportProgress,
+  ExportSelection,
+  ImportChoice,
+  JobFinishedEvent,
+  ZipMergeOptions,
+} from '../../types'
+
+/** 進捗を通知するイベント名 */
+export const JOB_PROGRESS_EVENT = 'job:progress'
+
+/** ジョブの終了を通知するイベント名 */
+export const JOB_FINISHED_EVENT = 'job:finished'
+
+// ============================================================
+// ジョブの開始
+// ============================================================
+
+/**
+ * アーカイブのエクスポートをバックグラウンドジョブとして開始する
+ * @param destPath - エクスポート先のパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param selection - 選択条件（省略時はすべてのファイル）
+ * @param format - 形式（省略時はパスから判定し、判定できない場合はZIP）
+ * @returns ジョブID
+ * @throws {TauriError} ジョブを開始できなかった場合
+ */
+export async function startExportJob(
+  destPath: string,
+  selection?: ExportSelection,
+  format?: ArchiveFormat
+): Promise<string> {
+  return invokeOrThrow<string>('start_export_job', {
+    destPath,
+    selection: selection ?? null,
+    format: format ?? null,
+  })
+}
+
+/**
+ * アーカイブのインポートをバックグラウンドジョブとして開始する
+ * @param zipPath - アーカイブのパス（ZIP・tar.gzファイル、またはフォルダー）
+ * @param createBackups - 既存ファイルのバックアップを作成するか（デフォルト: true）
+ * @param secrets - 秘密情報のプレースホルダーの名前と値
+ * @param planId - 実行するインポート計画のID
+ * @param choices - ファイルごとの処理（キー: アーカイブ内のパス）
+ * @param merge - JSONのマージの指定
+ * @returns ジョブID
+ * @throws {TauriError} ジョブを開始できなかった場合
+ */
+export async function startImportJob(
+  zipPath: string,
+  createBackups: boolean = true,
+  secrets?: Record<string, string>,
+  planId?: string,
+  choices?: Record<string, ImportChoice>,
+  merge?: ZipMergeOptions
+): Promise<string> {
+  return invokeOrThrow<string>('start_import_job', {
+    zipPath,
+    createBackups,
+    secrets: secrets ?? null,
+    planId: planId ?? null,
+    choices: choices ?? null,
+    merge: merge ?? null,
+  })
+}
+
+// ============================================================
+// キャンセル・イベント
+// ============================================================
+
+/**
+ * 実行中のジョブをキャンセルする（インポートは展開済みのファイルが元に戻される）
+ * @param jobId - ジョブID
+ * @returns 実行中のジョブが見つかった場合true
+ */
+export async function cancelJob(jobId: string): Promise<boolean> {
+  return invokeWithDefault<boolean>('cancel_job', { jobId }, false)
+}
+
+/**
+ * ジョブの進捗を購読する
+ * @param handler - 進捗を受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onJobProgress(
+  handler: (progress: ExportProgress) => void
+): Promise<UnlistenFn> {
+  return listen<ExportProgress>(JOB_PROGRESS_EVENT, (event) => handler(event.payload))
+}
+
+/**
+ * ジョブの終了を購読する
+ * @param handler - 終了通知を受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onJobFinished<T = unknown>(
+  handler: (event: JobFinishedEvent<T>) => void
+): Promise<UnlistenFn> {
+  return listen<JobFinishedEvent<T>>(JOB_FINISHED_EVENT, (event) => handler(event.payload))
+}
+
+/** runJob に渡すイベントハンドラー */
+export interface RunJobHandlers {
+  /** ジョブIDが確定した時に呼ばれる（キャンセルに使用） */
+  onStart?: (jobId: string) => void
+  /** 進捗を受け取る */
+  onProgress?: (progress: ExportProgress) => void
+}
+
+/**
+ * ジョブを開始し、終了するまで待つ
+ *
+ * 開始前にイベントを購読するため、すぐに終了したジョブの通知も取りこぼさない
+ * @param start - ジョブを開始する関数（startExportJob など）
+ * @param handlers - ジョブIDと進捗を受け取る関数
+ * @returns 終了通知
+ * @throws {TauriError} ジョブを開始できなかった場合
+ */
+export async function runJob<T>(
+  start: () => Promise<string>,
+  handlers: RunJobHandlers = {}
+): Promise<JobFinishedEvent<T>> {
+  let jobId: string | null = null
+  const early = new Map<string, JobFinishedEvent<T>>()
+  let resolveFinished: (event: JobFinishedEvent<T>) => void = () => {}
+  const finished = new Promise<JobFinishedEvent<T>>((resolve) => {
+    resolveFinished = resolve
+  })
+
+  const unlistenProgress = await onJobProgress((progress) => {
+    if (progress.jobId === jobId) handlers.onProgress?.(progress)
+  })
+  const unlistenFinished = await onJobFinished<T>((event) => {
+    if (event.jobId === jobId) {
+      resolveFinished(event)
+    } else if (jobId === null) {
+      early.set(event.jobId, event)
+    }
+  })
+
+  try {
+    jobId = await start()
+    handlers.onStart?.(jobId)
+    const earlyEvent = early.get(jobId)
+    if (earlyEvent) resolveFinished(earlyEvent)
+    return await finished
+  } finally {
+    unlistenProgress()
+    unlistenFinished()
+  }
+}
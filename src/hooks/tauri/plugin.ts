@@ -0,0 +1,64 @@
+/**
+ * @fileoverview プラグインのエクスポート関連のTauri API
+ * @module hooks/tauri/plugin
+ */
+
+import { invokeOrThrow } from './utils'
+
+import type {
+  ArchiveFormat,
+  PluginComponents,
+  PluginExportResult,
+  PluginMetadata,
+  PluginSelection,
+  PluginValidation,
+} from '../../types'
+
+/**
+ * プラグインに含めることができるスキル・サブエージェント・スラッシュコマンド・フックを取得する
+ * @returns スキル名と、サブエージェント・スラッシュコマンドの相対パス
+ * @throws {TauriError} 取得に失敗した場合
+ */
+export async function listPluginComponents(): Promise<PluginComponents> {
+  return invokeOrThrow<PluginComponents>('list_plugin_components')
+}
+
+/**
+ * プラグインの内容を検証する
+ * @param metadata - プラグイン名・バージョン・説明・作者
+ * @param selection - 含めるスキル・サブエージェント・スラッシュコマンドとフック
+ * @returns エラー・警告と、プラグインに含まれるファイル
+ * @throws {TauriError} 検証に失敗した場合
+ */
+export async function validatePlugin(
+  metadata: PluginMetadata,
+  selection: PluginSelection
+): Promise<PluginValidation> {
+  return invokeOrThrow<PluginValidation>('validate_plugin', { metadata, selection })
+}
+
+/**
+ * プラグインとしてエクスポートする（検証でエラーがある場合は何も書き込まない）
+ * @param destPath - 出力先のパス（フォルダー、またはZIP・tar.gzファイル）
+ * @param metadata - プラグイン名・バージョン・説明・作者
+ * @param selection - 含めるスキル・サブエージェント・スラッシュコマンドとフック
+ * @param format - 形式（省略時はパスから判定し、判定できない場合はフォルダー）
+ * @param marketplacePath - エントリを追加する marketplace.json のパス（フォルダーへの出力のみ）
+ * @returns 書き込んだファイル・警告・置き換えた秘密情報
+ * @throws {TauriError} 検証エラーがある場合、またはエクスポートに失敗した場合
+ */
+export async function exportPlugin(
+  destPath: string,
+  metadata: PluginMetadata,
+  selection: PluginSelection,
+  format?: ArchiveFormat,
+  marketplacePath?: string
+): Promise<PluginExportResult> {
+  return invokeOrThrow<PluginExportResult>('export_plugin', {
+    destPath,
+    metadata,
+    selection,
+    format: format ?? null,
+    marketplacePath: marketplacePath ?? null,
+  })
+}
@@ -0,0 +1,89 @@
+/**
+ * @fileoverview 設定ルートのプロファイル関連のTauri API
+ * @module hooks/tauri/profiles
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+import { invokeOrThrow, invokeWithDefault } from './utils'
+
+import type { ConfigProfile, ConfigRootInfo } from '../../types'
+
+/** 設定ルートの切り替えを通知するイベント名 */
+export const CONFIG_ROOT_CHANGED_EVENT = 'config-root:changed'
+
+// ============================================================
+// 取得
+// ============================================================
+
+/**
+ * 現在の設定ルートを取得する
+ * @returns 設定ルートのパス、解決元、プロファイル名
+ * @throws {TauriError} 取得できなかった場合
+ */
+export async function getActiveConfigRoot(): Promise<ConfigRootInfo> {
+  return invokeOrThrow<ConfigRootInfo>('get_active_config_root')
+}
+
+/**
+ * 登録済みのプロファイル一覧を取得する
+ * @returns プロファイル一覧（登録順）。取得できない場合は空配列
+ */
+export async function getConfigProfiles(): Promise<ConfigProfile[]> {
+  return invokeWithDefault<ConfigProfile[]>('get_config_profiles', undefined, [])
+}
+
+// ============================================================
+// 追加・削除・切り替え
+// ============================================================
+
+/**
+ * プロファイルを追加する（同名の場合はパスを更新）
+ * @param name - プロファイル名
+ * @param path - 設定ルートのパス（`~/` で始まる形式も可）
+ * @returns 追加したプロファイル
+ * @throws {TauriError} 名前が空、またはディレクトリが存在しない場合
+ */
+export async function addConfigProfile(name: string, path: string): Promise<ConfigProfile> {
+  return invokeOrThrow<ConfigProfile>('add_config_profile', { name, path })
+}
+
+/**
+ * プロファイルを削除する（選択中の場合は環境変数/デフォルトに戻る）
+ * @param name - 削除するプロファイル名
+ * @throws {TauriError} プロファイルが存在しない場合
+ */
+export async function removeConfigProfile(name: string): Promise<void> {
+  return invokeOrThrow<void>('remove_config_profile', { name })
+}
+
+/**
+ * 設定ルートを切り替える
+ *
+ * どちらも省略した場合は環境変数 `CLAUDE_CONFIG_DIR` またはデフォルトの `~/.claude` に戻る
+ * @param profile - 切り替え先のプロファイル名（次回起動時も維持）
+ * @param path - 切り替え先のパス（現在のセッションのみ有効）
+ * @returns 切り替え後の設定ルート
+ * @throws {TauriError} 両方を指定した場合、またはプロファイル・ディレクトリが存在しない場合
+ */
+export async function switchConfigRoot(profile?: string, path?: string): Promise<ConfigRootInfo> {
+  return invokeOrThrow<ConfigRootInfo>('switch_config_root', {
+    profile: profile ?? null,
+    path: path ?? null,
+  })
+}
+
+// ============================================================
+// イベント
+// ============================================================
+
+/**
+ * 設定ルートの切り替えを購読する
+ * @param handler - 切り替え後の設定ルートを受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onConfigRootChanged(
+  handler: (info: ConfigRootInfo) => void
+): Promise<UnlistenFn> {
+  return listen<ConfigRootInfo>(CONFIG_ROOT_CHANGED_EVENT, (event) => handler(event.payload))
+}
@@ -0,0 +1,68 @@
+/**
+ * @fileoverview スナップショット操作関連のTauri API
+ * @module hooks/tauri/snapshot
+ */
+
+import { invokeWithDefault } from './utils'
+
+import type { SnapshotDiff, SnapshotInfo, SnapshotRestoreResult } from '../../types'
+
+// ============================================================
+// スナップショット作成・取得
+// ============================================================
+
+/**
+ * 設定全体のスナップショットを作成する
+ * @param name - スナップショットの名前
+ * @returns 作成したスナップショット（エラー時はnull）
+ */
+export async function createSnapshot(name?: string): Promise<SnapshotInfo | null> {
+  return invokeWithDefault<SnapshotInfo | null>('create_snapshot', { name: name ?? null }, null)
+}
+
+/**
+ * スナップショット一覧を取得する
+ * @returns スナップショットの配列（新しい順、エラー時は空配列）
+ */
+export async function listSnapshots(): Promise<SnapshotInfo[]> {
+  return invokeWithDefault<SnapshotInfo[]>('list_snapshots', undefined, [])
+}
+
+/**
+ * スナップショットと現在の状態の差分を取得する
+ * @param snapshotId - スナップショットID
+ * @returns 差分（エラー時はnull）
+ */
+export async function diffSnapshot(snapshotId: string): Promise<SnapshotDiff | null> {
+  return invokeWithDefault<SnapshotDiff | null>('diff_snapshot', { snapshotId }, null)
+}
+
+// ============================================================
+// スナップショット復元・削除
+// ============================================================
+
+/**
+ * スナップショットを復元する
+ * @param snapshotId - スナップショットID
+ * @param paths - 復元する相対パス（省略時はすべて）
+ * @returns 復元結果（エラー時はnull）
+ */
+export async function restoreSnapshot(
+  snapshotId: string,
+  paths?: string[]
+): Promise<SnapshotRestoreResult | null> {
+  return invokeWithDefault<SnapshotRestoreResult | null>(
+    'restore_snapshot',
+    { snapshotId, paths: paths ?? null },
+    null
+  )
+}
+
+/**
+ * スナップショットを削除する
+ * @param snapshotId - スナップショットID
+ * @returns 解放したバイト数（エラー時はnull）
+ */
+export async function deleteSnapshot(snapshotId: string): Promise<number | null> {
+  return invokeWithDefault<number | null>('delete_snapshot', { snapshotId }, null)
+}
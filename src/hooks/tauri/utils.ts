@@ -117,5 +117,7 @@ export function createErrorImportResult(errors: string[]): ImportResult {
     skipped_files: [],
     errors,
     backup_created: false,
+    snapshot_id: null,
+    unresolved_secrets: [],
   }
 }
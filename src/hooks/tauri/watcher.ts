@@ -0,0 +1,68 @@
+/**
+ * @fileoverview ファイル変更の監視イベント関連のTauri API
+ * @module hooks/tauri/watcher
+ */
+
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+
+import type { FsChangeEvent, FsRenameEvent } from '../../types'
+
+/** ファイル作成イベント名 */
+export const FS_CREATED_EVENT = 'fs:created'
+
+/** ファイル更新イベント名 */
+export const FS_MODIFIED_EVENT = 'fs:modified'
+
+/** ファイル削除イベント名 */
+export const FS_REMOVED_EVENT = 'fs:removed'
+
+/** ファイル名変更イベント名 */
+export const FS_RENAMED_EVENT = 'fs:renamed'
+
+// ============================================================
+// イベント
+// ============================================================
+
+/**
+ * ファイルの作成を購読する
+ * @param handler - 作成されたパスを受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onFsCreated(
+  handler: (event: FsChangeEvent) => void
+): Promise<UnlistenFn> {
+  return listen<FsChangeEvent>(FS_CREATED_EVENT, (event) => handler(event.payload))
+}
+
+/**
+ * ファイルの更新を購読する
+ * @param handler - 更新されたパスを受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onFsModified(
+  handler: (event: FsChangeEvent) => void
+): Promise<UnlistenFn> {
+  return listen<FsChangeEvent>(FS_MODIFIED_EVENT, (event) => handler(event.payload))
+}
+
+/**
+ * ファイルの削除を購読する
+ * @param handler - 削除されたパスを受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onFsRemoved(
+  handler: (event: FsChangeEvent) => void
+): Promise<UnlistenFn> {
+  return listen<FsChangeEvent>(FS_REMOVED_EVENT, (event) => handler(event.payload))
+}
+
+/**
+ * ファイル名の変更を購読する
+ * @param handler - 変更前後のパスを受け取る関数
+ * @returns 購読を解除する関数
+ */
+export async function onFsRenamed(
+  handler: (event: FsRenameEvent) => void
+): Promise<UnlistenFn> {
+  return listen<FsRenameEvent>(FS_RENAMED_EVENT, (event) => handler(event.payload))
+}
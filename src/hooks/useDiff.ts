@@ -23,12 +23,12 @@ export function useDiff({ onError, onSuccess }: UseDiffOptions) {
 
   /**
    * バックアップと現在のファイルの差分を表示
-   * @param backupPath バックアップファイルのパス
+   * @param backupId バックアップID
    * @param originalPath 元ファイルのパス
    * @param backupName バックアップの表示名
    */
   const compareWithBackup = useCallback(async (
-    backupPath: string,
+    backupId: string,
     originalPath: string,
     backupName: string
   ) => {
@@ -38,7 +38,7 @@ export function useDiff({ onError, onSuccess }: UseDiffOptions) {
     }
 
     const [backupContent, currentFile] = await Promise.all([
-      getBackupContent(backupPath),
+      getBackupContent(backupId),
       readFile(originalPath),
     ])
 
@@ -115,6 +115,7 @@ export function useFileManager({ readOnly, onSuccess, onError }: UseFileManagerO
           name: fileContent.name,
           content: fileContent.content,
           originalContent: fileContent.content,
+          versionToken: fileContent.versionToken,
         })
         return true
       }
@@ -171,14 +172,22 @@ export function useFileManager({ readOnly, onSuccess, onError }: UseFileManagerO
 
     try {
       if (isTauri()) {
-        const success = await writeFile(selectedFile.path, selectedFile.content)
-        if (success) {
+        const result = await writeFile(
+          selectedFile.path,
+          selectedFile.content,
+          selectedFile.versionToken
+        )
+        if (result?.status === 'written') {
           setSelectedFile({
             ...selectedFile,
             originalContent: selectedFile.content,
+            versionToken: result.versionToken,
           })
           onSuccess('保存しました（バックアップ作成済み）')
           return true
+        } else if (result?.status === 'conflict') {
+          onError('読み込み後に他のプロセスがファイルを変更したため保存できませんでした。ファイルを開き直してください。')
+          return false
         } else {
           onError('保存に失敗しました')
           return false
@@ -228,6 +237,14 @@ export function useFileManager({ readOnly, onSuccess, onError }: UseFileManagerO
   }, [loadFileTree, selectFile, onSuccess, onError])
 
   // 未保存の変更があるかどうか
+  /**
+   * ファイルの選択を解除する
+   */
+  const clearSelection = useCallback(() => {
+    setSelectedFile(null)
+    setValidationErrors([])
+  }, [])
+
   const hasUnsavedChanges = selectedFile
     ? selectedFile.content !== selectedFile.originalContent
     : false
@@ -244,6 +261,7 @@ export function useFileManager({ readOnly, onSuccess, onError }: UseFileManagerO
     // 操作
     loadFileTree,
     selectFile,
+    clearSelection,
     updateContent,
     updateValidationErrors,
     saveFile,
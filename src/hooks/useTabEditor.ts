@@ -3,7 +3,7 @@ import { useCallback, useEffect, useMemo, useState } from 'react'
 import { STORAGE_KEY_TABS } from '../constants'
 import { logWarning } from '../utils/errorMessages'
 
-import type { Tab, TabPersistData, UseTabEditorReturn } from '../types/tabs'
+import type { Tab, TabDiskChange, TabPersistData, UseTabEditorReturn } from '../types/tabs'
 
 /**
  * localStorageからタブ状態を復元
@@ -144,7 +144,12 @@ export function useTabEditor(options: UseTabEditorOptions = {}): UseTabEditorRet
    * ファイルを開く（タブを追加）
    * 既に開いている場合はそのタブをアクティブにする
    */
-  const openTab = useCallback((path: string, name: string, content: string) => {
+  const openTab = useCallback((
+    path: string,
+    name: string,
+    content: string,
+    versionToken?: string | null
+  ) => {
     setTabs(prevTabs => {
       // 既存のタブを検索（パスで一致）
       const existingTab = prevTabs.find(tab => tab.path === path)
@@ -167,6 +172,7 @@ export function useTabEditor(options: UseTabEditorOptions = {}): UseTabEditorRet
         content,
         originalContent: content,
         order: maxOrder + 1,
+        versionToken,
       }
 
       setActiveTabIdState(newTab.id)
@@ -260,12 +266,45 @@ export function useTabEditor(options: UseTabEditorOptions = {}): UseTabEditorRet
 
   /**
    * タブを保存済み状態にマーク
+   * バージョントークンを指定した場合は更新し、ディスク上の変更の記録を解除する
    */
-  const markTabAsSaved = useCallback((tabId: string) => {
+  const markTabAsSaved = useCallback((tabId: string, versionToken?: string | null) => {
     setTabs(prevTabs =>
       prevTabs.map(tab =>
         tab.id === tabId
-          ? { ...tab, originalContent: tab.content }
+          ? versionToken === undefined
+            ? { ...tab, originalContent: tab.content }
+            : { ...tab, originalContent: tab.content, versionToken, diskChange: null }
+          : tab
+      )
+    )
+  }, [])
+
+  /**
+   * タブの内容をディスクから読み直した内容に置き換える（保存済み状態になる）
+   */
+  const reloadTab = useCallback((
+    tabId: string,
+    content: string,
+    versionToken?: string | null
+  ) => {
+    setTabs(prevTabs =>
+      prevTabs.map(tab =>
+        tab.id === tabId
+          ? { ...tab, content, originalContent: content, versionToken, diskChange: null }
+          : tab
+      )
+    )
+  }, [])
+
+  /**
+   * ディスク上での変更を記録（null で解除）
+   */
+  const markTabDiskChange = useCallback((tabId: string, change: TabDiskChange | null) => {
+    setTabs(prevTabs =>
+      prevTabs.map(tab =>
+        tab.id === tabId && (tab.diskChange ?? null) !== change
+          ? { ...tab, diskChange: change }
           : tab
       )
     )
@@ -347,6 +386,8 @@ export function useTabEditor(options: UseTabEditorOptions = {}): UseTabEditorRet
     reorderTabs,
     updateTabContent,
     markTabAsSaved,
+    reloadTab,
+    markTabDiskChange,
     closeAllTabs,
     closeSavedTabs,
     closeOtherTabs,
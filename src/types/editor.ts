@@ -8,6 +8,8 @@ export interface SelectedFile {
   name: string
   content: string
   originalContent: string
+  /** 読み込み時（または最後の保存時）のバージョントークン */
+  versionToken?: string | null
 }
 
 /** 検索ハイライト情報 */
@@ -18,6 +18,47 @@ export interface FileContent {
   path: string
   content: string
   name: string
+  /** バージョントークン（書き込み時に渡して競合を検出する） */
+  versionToken?: string | null
+  /** 更新日時（UNIXエポックからのミリ秒） */
+  modified?: number | null
+}
+
+/** 書き込み後のファイルのバージョン */
+export interface FileVersion {
+  /** バージョントークン */
+  versionToken: string
+  /** 更新日時（UNIXエポックからのミリ秒） */
+  modified: number | null
+}
+
+/** 書き込み競合の詳細（読み込み後に他のプロセスがファイルを変更した） */
+export interface WriteConflict {
+  /** 対象ファイルのパス */
+  path: string
+  /** 想定していたバージョントークン */
+  expectedVersion: string
+  /** 現在のディスク上の内容（削除されている場合はnull） */
+  current: FileContent | null
+}
+
+/** 書き込みの結果（競合時は書き込まれない） */
+export type WriteResult =
+  | ({ status: 'written' } & FileVersion)
+  | ({ status: 'conflict' } & WriteConflict)
+
+/** ファイルの作成・更新・削除イベント（fs:created / fs:modified / fs:removed）のペイロード */
+export interface FsChangeEvent {
+  /** 変更されたファイル/ディレクトリの絶対パス */
+  paths: string[]
+}
+
+/** ファイル名変更イベント（fs:renamed）のペイロード */
+export interface FsRenameEvent {
+  /** 変更前の絶対パス */
+  from: string
+  /** 変更後の絶対パス */
+  to: string
 }
 
 /** バックアップ情報 */
@@ -29,6 +70,317 @@ export interface BackupInfo {
   content: string
 }
 
+/** バックアップを作成した操作 */
+export type BackupOperation = 'write' | 'replace' | 'import' | 'restore' | 'manual' | 'unknown'
+
+/** バックアップストアに記録されたバックアップ */
+export interface BackupRecord {
+  /** バックアップID */
+  id: string
+  /** 元ファイルの設定ルートからの相対パス */
+  relativePath: string
+  /** 作成日時（RFC 3339形式） */
+  createdAt: string
+  /** 元ファイルのサイズ（バイト） */
+  size: number
+  /** 内容のSHA-256ハッシュ */
+  hash: string
+  /** バックアップを作成した操作 */
+  operation: BackupOperation
+  /** ピン留め（保持ポリシーによって削除されない） */
+  pinned: boolean
+}
+
+/** 保持ポリシーによってバックアップを削除する理由 */
+export type RetentionReason = 'notRetained' | 'overSizeLimit'
+
+/** 保持ポリシーによる削除対象のバックアップ */
+export interface RetentionCandidate {
+  /** 削除対象のバックアップ */
+  record: BackupRecord
+  /** 削除する理由 */
+  reason: RetentionReason
+}
+
+/** 保持ポリシーの適用結果（ドライラン） */
+export interface RetentionPlan {
+  /** 削除対象のバックアップ（古い順） */
+  delete: RetentionCandidate[]
+  /** 残るバックアップ数 */
+  keptCount: number
+  /** 残るバックアップのうちピン留めされている数 */
+  pinnedCount: number
+  /** 現在の物理サイズ（バイト） */
+  physicalSizeBefore: number
+  /** 適用後の物理サイズ（バイト） */
+  physicalSizeAfter: number
+  /** 合計サイズの上限まで減らせない場合はtrue */
+  sizeLimitExceeded: boolean
+}
+
+/** バックアップクリーンアップ結果 */
+export interface CleanupResult {
+  /** 削除したバックアップ数 */
+  deleted_count: number
+  /** 解放したサイズ（バイト） */
+  deleted_size_bytes: number
+  /** エラーメッセージ */
+  errors: string[]
+  /** 内容が失われていたため取り除いた記録 */
+  pruned_records: BackupRecord[]
+}
+
+/** バックアップ一覧 */
+export interface BackupList {
+  /** 設定ルートの絶対パス */
+  root: string
+  /** バックアップ（新しい順） */
+  backups: BackupRecord[]
+  /** 論理サイズ（各バックアップの元ファイルサイズの合計） */
+  logicalSize: number
+  /** 物理サイズ（重複排除・圧縮後のディスク使用量） */
+  physicalSize: number
+  /** 保存されている内容の数 */
+  objectCount: number
+}
+
+/** スナップショットを作成した契機 */
+export type SnapshotTrigger = 'manual' | 'import' | 'bulkReplace' | 'restore'
+
+/** スナップショットの概要 */
+export interface SnapshotInfo {
+  /** スナップショットID */
+  id: string
+  /** 名前（自動作成の場合はnull） */
+  name: string | null
+  /** 作成日時（RFC 3339形式） */
+  createdAt: string
+  /** 作成した契機 */
+  trigger: SnapshotTrigger
+  /** 含まれるファイル数 */
+  fileCount: number
+  /** 含まれるファイルのサイズの合計（バイト） */
+  totalSize: number
+}
+
+/** スナップショットと現在の状態の差分の種類 */
+export type SnapshotChangeKind = 'added' | 'removed' | 'modified'
+
+/** スナップショットと現在の状態で異なるファイル */
+export interface SnapshotChange {
+  /** 設定ルートからの相対パス */
+  relativePath: string
+  /** 差分の種類（added: 復元すると削除、removed: 復元すると再作成、modified: 復元すると元に戻る） */
+  kind: SnapshotChangeKind
+  /** スナップショットでのサイズ（バイト） */
+  snapshotSize: number | null
+  /** 現在のサイズ（バイト） */
+  currentSize: number | null
+}
+
+/** スナップショットと現在の状態の差分 */
+export interface SnapshotDiff {
+  /** 比較したスナップショット */
+  snapshot: SnapshotInfo
+  /** 異なるファイル */
+  changes: SnapshotChange[]
+  /** 同一のファイル数 */
+  unchangedCount: number
+}
+
+/** スナップショットの復元結果 */
+export interface SnapshotRestoreResult {
+  /** スナップショットの内容を書き込んだファイル */
+  restored: string[]
+  /** 削除したファイル */
+  deleted: string[]
+  /** 復元前の状態を保存したスナップショットのID */
+  safetySnapshotId: string | null
+}
+
+// ============================================================
+// 差分関連
+// ============================================================
+
+/**
+ * 差分を比較する内容の取得元
+ */
+export type DiffSource =
+  | { kind: 'current'; path: string }
+  | { kind: 'backup'; backupId: string }
+  | { kind: 'snapshot'; snapshotId: string; path: string }
+  | { kind: 'gitRevision'; revision: string; path: string }
+  | { kind: 'zipEntry'; zipPath: string; entry: string }
+  | { kind: 'text'; content: string; label?: string | null }
+
+/**
+ * 差分の計算オプション
+ */
+export interface DiffOptions {
+  /** 出力形式（省略時は structured） */
+  format?: 'structured' | 'unified'
+  /** ハンクの前後に含める変更のない行数（省略時は3行） */
+  contextLines?: number | null
+  /** JSONとしてキーの順序と空白を無視して比較するか */
+  semantic?: boolean
+}
+
+/**
+ * 差分の行の種類
+ */
+export type DiffLineKind = 'context' | 'added' | 'removed'
+
+/**
+ * 差分の1行
+ */
+export interface DiffLine {
+  /** 行の種類 */
+  kind: DiffLineKind
+  /** 行の内容（改行を除く） */
+  content: string
+  /** 比較元の行番号（1始まり） */
+  oldLine: number | null
+  /** 比較先の行番号（1始まり） */
+  newLine: number | null
+}
+
+/**
+ * 差分のハンク
+ */
+export interface DiffHunk {
+  /** ハンクの番号（選択したハンクの復元で指定） */
+  index: number
+  /** 比較元の開始行 */
+  oldStart: number
+  /** 比較元の行数 */
+  oldLines: number
+  /** 比較先の開始行 */
+  newStart: number
+  /** 比較先の行数 */
+  newLines: number
+  /** `@@ -1,3 +1,4 @@` 形式の見出し */
+  header: string
+  /** 行（統合diff形式では空） */
+  lines: DiffLine[]
+}
+
+/**
+ * 差分の統計
+ */
+export interface DiffStats {
+  /** 追加行数 */
+  additions: number
+  /** 削除行数 */
+  deletions: number
+  /** ハンク数 */
+  hunks: number
+}
+
+/**
+ * JSONの値の変更
+ */
+export interface JsonChange {
+  /** 変更された値の位置（JSON Pointer） */
+  path: string
+  /** 変更の種類 */
+  kind: 'added' | 'removed' | 'changed'
+  /** 比較元の値 */
+  oldValue: unknown
+  /** 比較先の値 */
+  newValue: unknown
+}
+
+/**
+ * 差分の計算結果
+ */
+export interface DiffResult {
+  /** 比較元の表示名 */
+  oldLabel: string
+  /** 比較先の表示名 */
+  newLabel: string
+  /** 差分がないか */
+  identical: boolean
+  /** 統計 */
+  stats: DiffStats
+  /** ハンク */
+  hunks: DiffHunk[]
+  /** 統合diff形式のテキスト（unified形式の場合のみ） */
+  unified: string | null
+  /** JSONの値の変更（意味的な比較の場合のみ） */
+  jsonChanges: JsonChange[] | null
+  /** JSONとして解析できなかった理由 */
+  semanticError: string | null
+}
+
+// ============================================================
+// Gitによる変更履歴関連
+// ============================================================
+
+/**
+ * 変更履歴のコミット
+ */
+export interface GitCommitInfo {
+  /** コミットID */
+  id: string
+  /** 短縮したコミットID */
+  shortId: string
+  /** コミットメッセージの1行目 */
+  summary: string
+  /** コミットメッセージ全体 */
+  message: string
+  /** 作成者名 */
+  author: string
+  /** 作成日時（RFC 3339） */
+  time: string
+}
+
+/**
+ * 変更の種類
+ */
+export type GitChangeKind = 'added' | 'modified' | 'deleted' | 'renamed'
+
+/**
+ * コミットされていない変更
+ */
+export interface GitStatusEntry {
+  /** 設定ルートからの相対パス */
+  path: string
+  /** 変更の種類 */
+  kind: GitChangeKind
+}
+
+/**
+ * 変更履歴の状態
+ */
+export interface GitHistoryStatus {
+  /** ダッシュボード設定で有効になっているか */
+  enabled: boolean
+  /** リポジトリが存在するか */
+  initialized: boolean
+  /** 最新のコミット */
+  head: GitCommitInfo | null
+  /** ダッシュボードの外で行われた変更 */
+  changes: GitStatusEntry[]
+}
+
+/**
+ * コミット間で変更されたファイルの差分
+ */
+export interface GitFileDiff {
+  /** 設定ルートからの相対パス */
+  path: string
+  /** 名前変更前の相対パス */
+  oldPath: string | null
+  /** 変更の種類 */
+  kind: GitChangeKind
+  /** 統合diff形式のパッチ */
+  patch: string
+  /** 追加行数 */
+  additions: number
+  /** 削除行数 */
+  deletions: number
+}
+
 // ============================================================
 // インポート/エクスポート関連
 // ============================================================
@@ -45,6 +397,10 @@ export interface ImportResult {
   errors: string[]
   /** バックアップ作成フラグ */
   backup_created: boolean
+  /** インポート前の状態を保存したスナップショットのID */
+  snapshot_id: string | null
+  /** 値が指定されず、プレースホルダーのまま展開した秘密情報の名前 */
+  unresolved_secrets: string[]
 }
 
 /** ファイル存在確認の結果 */
@@ -66,3 +422,314 @@ export interface ZipFileInfo {
   /** ディレクトリかどうか */
   is_directory: boolean
 }
+
+/** 設定ファイルの種別（バックエンドの分類） */
+export type ConfigFileKind =
+  | 'skill'
+  | 'agent'
+  | 'command'
+  | 'settings'
+  | 'memory'
+  | 'transcript'
+  | 'other'
+
+/**
+ * エクスポートするファイルの選択条件
+ *
+ * includeGlobs と kinds がどちらも空の場合はすべてのファイルが対象
+ */
+export interface ExportSelection {
+  /** 対象にするファイルのglob（設定ルートからの相対パス） */
+  includeGlobs?: string[]
+  /** 対象から除くファイルのglob */
+  excludeGlobs?: string[]
+  /** 対象にするファイル種別 */
+  kinds?: ConfigFileKind[]
+  /** サブエージェントが参照するスキルも含めるか */
+  includeReferencedSkills?: boolean
+  /** 秘密情報をプレースホルダーに置き換えるか（デフォルト: true） */
+  redactSecrets?: boolean
+}
+
+/** エクスポート・インポートの形式（zip: ZIPファイル、tarGz: .tar.gz、directory: ローカルフォルダー） */
+export type ArchiveFormat = 'zip' | 'tarGz' | 'directory'
+
+/** バックグラウンドジョブの種類 */
+export type JobKind = 'export' | 'import'
+
+/** バックグラウンドジョブの終了状態（cancelledの場合は変更が元に戻されている） */
+export type JobStatus = 'completed' | 'cancelled' | 'failed'
+
+/** エクスポート・インポートの進捗（job:progress イベント） */
+export interface ExportProgress {
+  /** ジョブID */
+  jobId: string
+  /** ジョブの種類 */
+  kind: JobKind
+  /** 総ファイル数 */
+  totalFiles: number
+  /** 処理済みファイル数 */
+  processedFiles: number
+  /** 現在処理中のファイル */
+  currentFile: string
+  /** 総バイト数 */
+  totalBytes: number
+  /** 処理済みバイト数 */
+  processedBytes: number
+}
+
+/** バックグラウンドジョブの終了通知（job:finished イベント） */
+export interface JobFinishedEvent<T = unknown> {
+  /** ジョブID */
+  jobId: string
+  /** ジョブの種類 */
+  kind: JobKind
+  /** 終了状態 */
+  status: JobStatus
+  /** 完了した場合の結果（エクスポートはExportManifest、インポートはImportResult） */
+  result: T | null
+  /** 失敗した場合のエラーメッセージ */
+  error: string | null
+}
+
+/** 秘密情報と判定した理由（キー名、値の形式） */
+export type RedactionReason = 'keyName' | 'valuePattern'
+
+/** エクスポート時にプレースホルダーへ置き換えた秘密情報（値は含まない） */
+export interface RedactedSecret {
+  /** 設定ルートからの相対パス */
+  path: string
+  /** JSONの場合は値の位置（JSON Pointer） */
+  pointer: string | null
+  /** テキストの場合は行番号（1始まり） */
+  line: number | null
+  /** プレースホルダーの名前（${REDACTED:NAME} の NAME） */
+  name: string
+  /** 秘密情報と判定した理由 */
+  reason: RedactionReason
+}
+
+/** アーカイブに含まれる秘密情報のプレースホルダー */
+export interface SecretPlaceholder {
+  /** プレースホルダーの名前 */
+  name: string
+  /** プレースホルダーを含むファイル */
+  files: string[]
+}
+
+/** インポート計画でのエントリの分類 */
+export type ImportEntryStatus = 'new' | 'identical' | 'modified' | 'excluded'
+
+/** インポート計画を実行する際のファイルごとの処理 */
+export type ImportChoice = 'overwrite' | 'skip' | 'keepBoth' | 'merge'
+
+/** インポート計画のエントリ */
+export interface ImportPlanEntry {
+  /** ZIP内のパス（設定ルートからの相対パス） */
+  path: string
+  /** 分類 */
+  status: ImportEntryStatus
+  /** 展開後のサイズ（バイト） */
+  size: number
+  /** 除外される理由 */
+  reason: string | null
+  /** 変更されるファイルの差分（現在の内容 → アーカイブの内容、テキストの場合のみ） */
+  diff: DiffResult | null
+  /** 処理を指定しなかった場合の処理 */
+  defaultChoice: ImportChoice
+}
+
+/** インポート計画の分類ごとの件数 */
+export interface ImportPlanSummary {
+  newFiles: number
+  identical: number
+  modified: number
+  excluded: number
+}
+
+/** インポート計画（importZip に計画IDを渡して実行） */
+export interface ImportPlan {
+  /** 計画ID */
+  planId: string
+  /** ZIPファイルのパス */
+  zipPath: string
+  /** 作成日時（RFC 3339） */
+  createdAt: string
+  /** エントリ（ZIP内の順序） */
+  entries: ImportPlanEntry[]
+  /** 分類ごとの件数 */
+  summary: ImportPlanSummary
+}
+
+/** JSONのマージでキーが衝突した場合の解決方法（インポートする側・現在の値・個別に指定） */
+export type MergeStrategy = 'theirs' | 'ours' | 'ask'
+
+/** 衝突したキーで採用する値 */
+export type ConflictSide = 'ours' | 'theirs'
+
+/** JSONファイルのマージの指定 */
+export interface JsonMergeOptions {
+  /** 衝突の解決方法 */
+  strategy: MergeStrategy
+  /** 衝突ごとに採用する値（キー: JSON Pointer、戦略より優先） */
+  resolutions?: Record<string, ConflictSide>
+}
+
+/** ZIPのインポートでのJSONファイルのマージの指定 */
+export interface ZipMergeOptions {
+  /** 衝突の解決方法 */
+  strategy: MergeStrategy
+  /** ファイルごとの衝突の解決（キー: ZIP内のパス → JSON Pointer） */
+  resolutions?: Record<string, Record<string, ConflictSide>>
+}
+
+/** JSONのマージで衝突したキー */
+export interface MergeConflict {
+  /** 値の位置（JSON Pointer） */
+  pointer: string
+  /** 現在の値 */
+  ours: unknown
+  /** インポートする値 */
+  theirs: unknown
+  /** 採用した値（ask で未指定の場合はnull） */
+  resolution: ConflictSide | null
+}
+
+/** JSONファイルのマージ結果のプレビュー */
+export interface JsonMergePreview {
+  /** 設定ルートからの相対パス */
+  path: string
+  /** 現在のファイルが存在するか */
+  exists: boolean
+  /** マージ後の内容 */
+  merged: string
+  /** 衝突したキー */
+  conflicts: MergeConflict[]
+  /** 解決方法が指定されていない衝突の数 */
+  unresolved: number
+  /** 現在の内容から変更されるか */
+  changed: boolean
+}
+
+/** エクスポートしたファイル */
+export interface ExportManifestEntry {
+  /** 設定ルートからの相対パス */
+  path: string
+  /** ファイル種別 */
+  kind: ConfigFileKind
+  /** サイズ（バイト） */
+  size: number
+  /** 内容のSHA-256ハッシュ */
+  sha256: string
+}
+
+/** エクスポートしたアーカイブのマニフェスト（.ccsd-export.json） */
+export interface ExportManifest {
+  /** マニフェストの形式のバージョン */
+  formatVersion: number
+  /** エクスポートしたダッシュボードのバージョン */
+  dashboardVersion: string
+  /** エクスポート時のClaude Codeのバージョン */
+  claudeCodeVersion: string | null
+  /** エクスポート日時 */
+  exportedAt: string
+  /** 含まれるファイル */
+  files: ExportManifestEntry[]
+  /** プレースホルダーに置き換えた秘密情報 */
+  redactions: RedactedSecret[]
+}
+
+/** プラグインの作者 */
+export interface PluginAuthor {
+  /** 名前 */
+  name: string
+  /** メールアドレス */
+  email?: string | null
+}
+
+/** プラグインのメタデータ（.claude-plugin/plugin.json） */
+export interface PluginMetadata {
+  /** プラグイン名（小文字の英数字とハイフン） */
+  name: string
+  /** バージョン（セマンティックバージョニング） */
+  version: string
+  /** 説明 */
+  description: string
+  /** 作者 */
+  author: PluginAuthor
+}
+
+/** プラグインに含める内容 */
+export interface PluginSelection {
+  /** スキル名（skills/<name>/ 配下をすべて含める） */
+  skills: string[]
+  /** サブエージェント（設定ルートからの相対パス） */
+  agents: string[]
+  /** スラッシュコマンド（設定ルートからの相対パス） */
+  commands: string[]
+  /** settings.json のフックを hooks/hooks.json として含めるか */
+  includeHooks: boolean
+  /** 秘密情報をプレースホルダーに置き換えるか（省略時は置き換える） */
+  redactSecrets?: boolean
+}
+
+/** プラグインに含めることができる内容 */
+export interface PluginComponents {
+  /** SKILL.md があるスキルの名前 */
+  skills: string[]
+  /** サブエージェント（設定ルートからの相対パス） */
+  agents: string[]
+  /** スラッシュコマンド（設定ルートからの相対パス） */
+  commands: string[]
+  /** settings.json にフックが設定されているか */
+  hasHooks: boolean
+}
+
+/** プラグインの検証結果 */
+export interface PluginValidation {
+  /** エクスポートできない問題 */
+  errors: string[]
+  /** エクスポートはできるが確認が必要な問題 */
+  warnings: string[]
+  /** プラグインに含まれるファイル（プラグインのルートからの相対パス） */
+  files: string[]
+}
+
+/** プラグインのエクスポート結果 */
+export interface PluginExportResult {
+  /** 書き込んだファイル */
+  files: string[]
+  /** 確認が必要な問題 */
+  warnings: string[]
+  /** プレースホルダーに置き換えた秘密情報 */
+  redactions: RedactedSecret[]
+  /** エントリを追加した marketplace.json のパス */
+  marketplacePath: string | null
+}
+
+// ============================================================
+// 設定ルート（プロファイル）関連
+// ============================================================
+
+/** 設定ルートの解決元 */
+export type ConfigRootSource = 'override' | 'profile' | 'env' | 'default'
+
+/** 登録済みの設定ルートのプロファイル */
+export interface ConfigProfile {
+  /** プロファイル名 */
+  name: string
+  /** 設定ルートのパス */
+  path: string
+}
+
+/** 現在の設定ルート（config-root:changed イベントのペイロード） */
+export interface ConfigRootInfo {
+  /** 設定ルートのパス */
+  path: string
+  /** 解決元 */
+  source: ConfigRootSource
+  /** プロファイルから解決した場合のプロファイル名 */
+  profileName?: string | null
+  /** ディレクトリが存在するか */
+  exists: boolean
+}
@@ -9,10 +9,73 @@ export type {
   FileType,
   FileNode,
   FileContent,
+  FileVersion,
+  WriteConflict,
+  WriteResult,
+  FsChangeEvent,
+  FsRenameEvent,
   BackupInfo,
+  BackupOperation,
+  BackupRecord,
+  BackupList,
+  RetentionReason,
+  RetentionCandidate,
+  RetentionPlan,
+  CleanupResult,
+  SnapshotTrigger,
+  SnapshotInfo,
+  SnapshotChangeKind,
+  SnapshotChange,
+  SnapshotDiff,
+  SnapshotRestoreResult,
+  DiffSource,
+  DiffOptions,
+  DiffLineKind,
+  DiffLine,
+  DiffHunk,
+  DiffStats,
+  JsonChange,
+  DiffResult,
+  GitCommitInfo,
+  GitChangeKind,
+  GitStatusEntry,
+  GitHistoryStatus,
+  GitFileDiff,
   ImportResult,
   FileExistsInfo,
   ZipFileInfo,
+  ConfigFileKind,
+  ExportSelection,
+  ArchiveFormat,
+  ExportManifestEntry,
+  ExportManifest,
+  PluginAuthor,
+  PluginMetadata,
+  PluginSelection,
+  PluginComponents,
+  PluginValidation,
+  PluginExportResult,
+  RedactionReason,
+  RedactedSecret,
+  SecretPlaceholder,
+  ImportEntryStatus,
+  ImportChoice,
+  ImportPlanEntry,
+  ImportPlanSummary,
+  ImportPlan,
+  MergeStrategy,
+  ConflictSide,
+  JsonMergeOptions,
+  ZipMergeOptions,
+  MergeConflict,
+  JsonMergePreview,
+  JobKind,
+  JobStatus,
+  ExportProgress,
+  JobFinishedEvent,
+  ConfigRootSource,
+  ConfigProfile,
+  ConfigRootInfo,
 } from './files'
 
 // エディタ関連
@@ -53,6 +116,7 @@ export { STATS_TYPE_INFO } from './stats'
 export type {
   // 現行版
   Tab,
+  TabDiskChange,
   TabPersistData,
   TabEditorState,
   TabEditorActions,
@@ -25,6 +25,10 @@ export interface Stats {
   // システム情報
   /** バックアップ数 */
   backupCount: number
+  /** バックアップの論理サイズ（バイト） */
+  backupLogicalSize: number
+  /** バックアップの物理サイズ（重複排除・圧縮後、バイト） */
+  backupPhysicalSize: number
   /** 総ファイル数 */
   totalFileCount: number
   /** 最終更新日時（ISO 8601形式） */
@@ -8,6 +8,9 @@
 // 基本型（現行版との後方互換性を維持）
 // ============================================================
 
+/** 開いた後にディスク上で起きた変更（更新・削除） */
+export type TabDiskChange = 'modified' | 'removed'
+
 /**
  * 個別のタブ（現行版）
  *
@@ -26,6 +29,10 @@ export interface Tab {
   originalContent: string
   /** タブを開いた順序 */
   order: number
+  /** 読み込み・保存時のバージョントークン */
+  versionToken?: string | null
+  /** 開いた後にディスク上で起きた変更 */
+  diskChange?: TabDiskChange | null
 }
 
 /**
@@ -55,7 +62,7 @@ export interface TabEditorState {
  */
 export interface TabEditorActions {
   /** ファイルを開く（タブを追加） */
-  openTab: (path: string, name: string, content: string) => void
+  openTab: (path: string, name: string, content: string, versionToken?: string | null) => void
   /** タブを閉じる */
   closeTab: (tabId: string) => boolean
   /** アクティブタブを切り替え */
@@ -65,7 +72,11 @@ export interface TabEditorActions {
   /** タブの内容を更新 */
   updateTabContent: (tabId: string, content: string) => void
   /** タブを保存済み状態にマーク */
-  markTabAsSaved: (tabId: string) => void
+  markTabAsSaved: (tabId: string, versionToken?: string | null) => void
+  /** タブの内容をディスクから読み直した内容に置き換える */
+  reloadTab: (tabId: string, content: string, versionToken?: string | null) => void
+  /** ディスク上での変更を記録する（null で解除） */
+  markTabDiskChange: (tabId: string, change: TabDiskChange | null) => void
   /** 全タブを閉じる */
   closeAllTabs: () => boolean
   /** 保存済みタブのみ閉じる */